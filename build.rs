@@ -1,5 +1,23 @@
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tonic_prost_build::compile_protos("proto/asset/v1/asset.proto")?;
-    tonic_prost_build::compile_protos("proto/contract/v1/contract.proto")?;
+    tonic_prost_build::compile_protos("proto/money/v1/money.proto")?;
+    tonic_prost_build::compile_protos("proto/currency/v1/currency.proto")?;
+    // events are encoded by the core, their package is included there, see `core::event_proto`
+    tonic_prost_build::compile_protos("proto/event/v1/event.proto")?;
+    // every package is included in the same module, messages of the money package are referenced from there
+    let configure = || tonic_prost_build::configure().extern_path(".proto.money.v1", "crate::server::grpc::asset");
+    // the services of imported packages are generated again with the importing package, the packages it imports are
+    // compiled after it so that their own output is the one that is kept
+    configure()
+        .extern_path(".proto.contract.v1", "crate::server::grpc::asset")
+        .extern_path(".asset_rpc", "crate::server::grpc::asset")
+        .compile_protos(&["proto/collection/v1/collection.proto"], &["proto"])?;
+    configure().compile_protos(&["proto/asset/v1/asset.proto"], &["proto"])?;
+    configure().compile_protos(&["proto/contract/v1/contract.proto"], &["proto"])?;
+    configure().compile_protos(&["proto/bid/v1/bid.proto"], &["proto"])?;
+    configure().compile_protos(&["proto/ledger/v1/ledger.proto"], &["proto"])?;
+    configure().compile_protos(&["proto/fx/v1/fx.proto"], &["proto"])?;
+    configure().compile_protos(&["proto/escrow/v1/escrow.proto"], &["proto"])?;
+    // migrations are embedded in the binary, see `queries::MIGRATOR`
+    println!("cargo:rerun-if-changed=migrations");
     Ok(())
 }
@@ -0,0 +1,63 @@
+-- Base schema every later migration builds on: assets, their NFC certificates and trails, and contracts.
+-- The tables are created as they were before the first versioned migration, later migrations change them in place.
+-- Databases created before this migration already have the schema, every statement leaves existing objects as they are.
+DO
+$$
+BEGIN
+    CREATE TYPE currency_enum AS ENUM ('USD', 'EUR', 'XRP', 'RUB', 'ARS', 'BRL', 'CNY', 'GBP', 'MXN', 'QAR', 'JPY', 'DOGE', 'XRFQ',
+        'SOL', 'BTC', 'ETH', 'ADA', 'USDT', 'BNB');
+EXCEPTION
+    WHEN duplicate_object THEN NULL;
+END
+$$;
+
+CREATE TABLE IF NOT EXISTS asset
+(
+    id           TEXT PRIMARY KEY,
+    name         TEXT        NOT NULL,
+    symbol       TEXT        NOT NULL,
+    owner_fp     TEXT        NOT NULL,
+    description  TEXT        NOT NULL,
+    organization TEXT        NOT NULL,
+    created_at   TIMESTAMPTZ NOT NULL,
+    updated_at   TIMESTAMPTZ NOT NULL,
+    updated_by   TEXT        NOT NULL,
+    tradable     BOOLEAN     NOT NULL DEFAULT FALSE,
+    listable     BOOLEAN     NOT NULL DEFAULT TRUE
+);
+
+-- certificate of ownership of an asset, an asset has a single NFC
+CREATE TABLE IF NOT EXISTS nfc
+(
+    id         TEXT PRIMARY KEY,
+    cert       TEXT        NOT NULL,
+    asset_id   TEXT        NOT NULL UNIQUE REFERENCES asset (id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+-- owners of the asset of an NFC, in the order the asset was transferred to them
+CREATE TABLE IF NOT EXISTS nfc_asset_trail
+(
+    nfc_id         TEXT        NOT NULL REFERENCES nfc (id) ON DELETE CASCADE,
+    user_fp        TEXT        NOT NULL,
+    asset_id       TEXT        NOT NULL,
+    transferred_on TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS contract
+(
+    id                   TEXT PRIMARY KEY,
+    content              TEXT             NOT NULL,
+    summary              TEXT,
+    version              TEXT             NOT NULL,
+    asset_id             TEXT             NOT NULL UNIQUE REFERENCES asset (id) ON DELETE CASCADE,
+    min_price            DOUBLE PRECISION NOT NULL,
+    created_at           TIMESTAMPTZ      NOT NULL,
+    updated_by           TEXT             NOT NULL,
+    updated_at           TIMESTAMPTZ      NOT NULL,
+    update_count         INTEGER          NOT NULL DEFAULT 0,
+    royalty_receiver     TEXT,
+    accepted_currency    currency_enum[]  NOT NULL,
+    royalty_percentage   DOUBLE PRECISION,
+    anonymous_buyer_only BOOLEAN          NOT NULL DEFAULT FALSE
+);
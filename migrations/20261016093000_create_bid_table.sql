@@ -0,0 +1,20 @@
+-- Bids placed by users on tradable assets
+CREATE TYPE bid_status AS ENUM ('open', 'withdrawn', 'accepted', 'rejected', 'expired');
+
+CREATE TABLE IF NOT EXISTS bid
+(
+    id         TEXT PRIMARY KEY,
+    asset_id   TEXT             NOT NULL REFERENCES asset (id) ON DELETE CASCADE,
+    bidder_fp  TEXT             NOT NULL,
+    bidder_org TEXT             NOT NULL,
+    amount     DOUBLE PRECISION NOT NULL CHECK (amount > 0),
+    currency   currency_enum    NOT NULL,
+    anonymous  BOOLEAN          NOT NULL DEFAULT FALSE,
+    status     bid_status       NOT NULL DEFAULT 'open',
+    expires_at TIMESTAMPTZ      NOT NULL,
+    created_at TIMESTAMPTZ      NOT NULL,
+    updated_at TIMESTAMPTZ      NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_bid_asset_id_status ON bid (asset_id, status);
+CREATE INDEX IF NOT EXISTS idx_bid_bidder_fp ON bid (bidder_fp);
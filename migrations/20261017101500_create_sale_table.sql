@@ -0,0 +1,18 @@
+-- A sale is recorded every time a bid is accepted and the asset changes hands.
+-- bid_id is unique so that retrying a bid acceptance can never record a second sale.
+CREATE TABLE IF NOT EXISTS sale
+(
+    id         TEXT PRIMARY KEY,
+    bid_id     TEXT             NOT NULL UNIQUE REFERENCES bid (id) ON DELETE CASCADE,
+    asset_id   TEXT             NOT NULL REFERENCES asset (id) ON DELETE CASCADE,
+    nfc_id     TEXT             NOT NULL REFERENCES nfc (id) ON DELETE CASCADE,
+    seller_fp  TEXT             NOT NULL,
+    seller_org TEXT             NOT NULL,
+    buyer_fp   TEXT             NOT NULL,
+    buyer_org  TEXT             NOT NULL,
+    price      DOUBLE PRECISION NOT NULL CHECK (price > 0),
+    currency   currency_enum    NOT NULL,
+    created_at TIMESTAMPTZ      NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_sale_asset_id ON sale (asset_id);
@@ -0,0 +1,56 @@
+-- Double-entry ledger: every journal entry is made of postings against per user, per currency accounts.
+-- Credits are positive, debits are negative and the postings of an entry must sum to zero.
+CREATE TYPE posting_kind AS ENUM ('purchase', 'sale', 'royalty');
+
+CREATE TABLE IF NOT EXISTS ledger_account
+(
+    id         TEXT PRIMARY KEY,
+    owner_fp   TEXT          NOT NULL,
+    currency   currency_enum NOT NULL,
+    created_at TIMESTAMPTZ   NOT NULL,
+    UNIQUE (owner_fp, currency)
+);
+
+CREATE TABLE IF NOT EXISTS journal_entry
+(
+    id           TEXT PRIMARY KEY,
+    -- what caused the entry (e.g. a sale id), unique so an event is never posted twice
+    reference_id TEXT          NOT NULL UNIQUE,
+    description  TEXT          NOT NULL,
+    currency     currency_enum NOT NULL,
+    created_at   TIMESTAMPTZ   NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS journal_posting
+(
+    id         BIGSERIAL PRIMARY KEY,
+    entry_id   TEXT             NOT NULL REFERENCES journal_entry (id),
+    account_id TEXT             NOT NULL REFERENCES ledger_account (id),
+    amount     DOUBLE PRECISION NOT NULL CHECK (amount <> 0),
+    kind       posting_kind     NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_journal_posting_entry_id ON journal_posting (entry_id);
+CREATE INDEX IF NOT EXISTS idx_journal_posting_account_id ON journal_posting (account_id);
+
+-- checked at commit time, once all postings of an entry have been written
+CREATE OR REPLACE FUNCTION check_journal_entry_balanced() RETURNS TRIGGER AS
+$$
+DECLARE
+    checked_entry_id TEXT             := COALESCE(NEW.entry_id, OLD.entry_id);
+    total            DOUBLE PRECISION;
+BEGIN
+    SELECT COALESCE(SUM(amount), 0) INTO total FROM journal_posting WHERE entry_id = checked_entry_id;
+    IF abs(total) > 1e-6 THEN
+        RAISE EXCEPTION 'journal entry % is not balanced, postings sum to %', checked_entry_id, total;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE CONSTRAINT TRIGGER journal_posting_balanced
+    AFTER INSERT OR UPDATE OR DELETE
+    ON journal_posting
+    DEFERRABLE INITIALLY DEFERRED
+    FOR EACH ROW
+EXECUTE FUNCTION check_journal_entry_balanced();
@@ -0,0 +1,22 @@
+-- Optional auction configuration of a contract, an asset with an auction is sold to the winner when the auction closes
+CREATE TYPE auction_type AS ENUM ('english', 'dutch', 'sealed_bid');
+
+CREATE TABLE IF NOT EXISTS contract_auction
+(
+    contract_id               TEXT PRIMARY KEY REFERENCES contract (id) ON DELETE CASCADE,
+    asset_id                  TEXT             NOT NULL REFERENCES asset (id) ON DELETE CASCADE,
+    auction_type              auction_type     NOT NULL,
+    start_time                TIMESTAMPTZ      NOT NULL,
+    end_time                  TIMESTAMPTZ      NOT NULL CHECK (end_time > start_time),
+    reserve_price             DOUBLE PRECISION NOT NULL CHECK (reserve_price >= 0),
+    bid_increment             DOUBLE PRECISION NOT NULL CHECK (bid_increment >= 0),
+    dutch_start_price         DOUBLE PRECISION,
+    dutch_decay_amount        DOUBLE PRECISION,
+    dutch_decay_interval_secs BIGINT,
+    closed_at                 TIMESTAMPTZ,
+    winning_bid_id            TEXT REFERENCES bid (id) ON DELETE SET NULL
+);
+
+-- the auction closer only scans auctions that are still running
+CREATE INDEX IF NOT EXISTS idx_contract_auction_open_end_time ON contract_auction (end_time) WHERE closed_at IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_contract_auction_asset_id ON contract_auction (asset_id);
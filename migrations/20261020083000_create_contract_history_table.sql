@@ -0,0 +1,36 @@
+-- Previous revisions of contracts, a row is written every time a contract is updated
+CREATE TABLE IF NOT EXISTS contract_history
+(
+    contract_id          TEXT             NOT NULL REFERENCES contract (id) ON DELETE CASCADE,
+    asset_id             TEXT             NOT NULL,
+    update_count         INTEGER          NOT NULL,
+    content              TEXT             NOT NULL,
+    summary              TEXT,
+    version              TEXT             NOT NULL,
+    min_price            DOUBLE PRECISION NOT NULL,
+    updated_by           TEXT             NOT NULL,
+    royalty_receiver     TEXT,
+    royalty_percentage   DOUBLE PRECISION,
+    accepted_currency    currency_enum[]  NOT NULL,
+    anonymous_buyer_only BOOLEAN          NOT NULL,
+    created_at           TIMESTAMPTZ      NOT NULL,
+    updated_at           TIMESTAMPTZ      NOT NULL,
+    recorded_at          TIMESTAMPTZ      NOT NULL DEFAULT now(),
+    PRIMARY KEY (contract_id, update_count)
+);
+
+CREATE INDEX IF NOT EXISTS idx_contract_history_asset_id ON contract_history (asset_id, update_count);
+
+-- history rows are immutable, they are only removed together with their contract
+CREATE OR REPLACE FUNCTION reject_contract_history_update() RETURNS TRIGGER AS
+$$
+BEGIN
+    RAISE EXCEPTION 'contract history is immutable';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS contract_history_immutable ON contract_history;
+CREATE TRIGGER contract_history_immutable
+    BEFORE UPDATE ON contract_history
+    FOR EACH ROW
+EXECUTE FUNCTION reject_contract_history_update();
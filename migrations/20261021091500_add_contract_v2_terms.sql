@@ -0,0 +1,20 @@
+-- Structured terms of v2 contracts, v1 contracts keep the defaults
+ALTER TABLE contract
+    ADD COLUMN IF NOT EXISTS resale_allowed          BOOLEAN NOT NULL DEFAULT TRUE,
+    ADD COLUMN IF NOT EXISTS resale_min_holding_days INTEGER NOT NULL DEFAULT 0 CHECK (resale_min_holding_days >= 0),
+    ADD COLUMN IF NOT EXISTS expires_at              TIMESTAMPTZ;
+
+ALTER TABLE contract_history
+    ADD COLUMN IF NOT EXISTS resale_allowed          BOOLEAN NOT NULL DEFAULT TRUE,
+    ADD COLUMN IF NOT EXISTS resale_min_holding_days INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS expires_at              TIMESTAMPTZ;
+
+-- Royalty splits of every revision of a contract, the current splits have the update_count of the contract
+CREATE TABLE IF NOT EXISTS contract_royalty_split
+(
+    contract_id  TEXT    NOT NULL REFERENCES contract (id) ON DELETE CASCADE,
+    update_count INTEGER NOT NULL,
+    receiver_fp  TEXT    NOT NULL,
+    basis_points INTEGER NOT NULL CHECK (basis_points > 0 AND basis_points <= 10000),
+    PRIMARY KEY (contract_id, update_count, receiver_fp)
+);
@@ -0,0 +1,24 @@
+-- Royalties are only stored as splits, the single receiver of older contracts and revisions becomes their only split
+INSERT INTO contract_royalty_split (contract_id, update_count, receiver_fp, basis_points)
+SELECT id, update_count, royalty_receiver, LEAST(ROUND(royalty_percentage * 100), 10000)::INTEGER
+FROM contract
+WHERE royalty_receiver IS NOT NULL
+  AND royalty_receiver <> ''
+  AND ROUND(royalty_percentage * 100) > 0
+ON CONFLICT DO NOTHING;
+
+INSERT INTO contract_royalty_split (contract_id, update_count, receiver_fp, basis_points)
+SELECT contract_id, update_count, royalty_receiver, LEAST(ROUND(royalty_percentage * 100), 10000)::INTEGER
+FROM contract_history
+WHERE royalty_receiver IS NOT NULL
+  AND royalty_receiver <> ''
+  AND ROUND(royalty_percentage * 100) > 0
+ON CONFLICT DO NOTHING;
+
+ALTER TABLE contract
+    DROP COLUMN IF EXISTS royalty_receiver,
+    DROP COLUMN IF EXISTS royalty_percentage;
+
+ALTER TABLE contract_history
+    DROP COLUMN IF EXISTS royalty_receiver,
+    DROP COLUMN IF EXISTS royalty_percentage;
@@ -0,0 +1,34 @@
+-- Metadata of the supported currencies. Currencies can be disabled at runtime, contracts only accept enabled currencies.
+-- Amounts are parsed and stored exact to the decimals of their currency, codes and aliases name the currency in requests.
+CREATE TABLE IF NOT EXISTS currency
+(
+    code       currency_enum PRIMARY KEY,
+    name       TEXT          NOT NULL,
+    aliases    TEXT[]        NOT NULL DEFAULT '{}',
+    decimals   INTEGER       NOT NULL CHECK (decimals >= 0),
+    crypto     BOOLEAN       NOT NULL,
+    enabled    BOOLEAN       NOT NULL DEFAULT TRUE,
+    updated_at TIMESTAMPTZ   NOT NULL DEFAULT now()
+);
+
+INSERT INTO currency (code, name, aliases, decimals, crypto)
+VALUES ('USD', 'US Dollar', '{usd,"US Dollar","us dollar"}', 2, FALSE),
+       ('EUR', 'Euro', '{eur,Euro,euro}', 2, FALSE),
+       ('XRP', 'Ripple', '{xrp,Ripple,ripple}', 6, TRUE),
+       ('RUB', 'Russian Ruble', '{rub,"Russian Ruble","russian ruble"}', 2, FALSE),
+       ('ARS', 'Argentine Peso', '{ars,"Argentine Peso","argentine peso"}', 2, FALSE),
+       ('BRL', 'Brazilian Real', '{brl,"Brazilian Real","brazilian real"}', 2, FALSE),
+       ('CNY', 'Chinese Yuan', '{cny,"Chinese Yuan","chinese yuan"}', 2, FALSE),
+       ('GBP', 'British Pound', '{gbp,"British Pound","british pound","Pound Sterling","pound sterling"}', 2, FALSE),
+       ('MXN', 'Mexican Peso', '{mxn,"Mexican Peso","mexican peso"}', 2, FALSE),
+       ('QAR', 'Qatari Rial', '{qar,"Qatari Rial","qatari rial"}', 2, FALSE),
+       ('JPY', 'Japanese Yen', '{jpy,"Japanese Yen","japanese yen"}', 0, FALSE),
+       ('DOGE', 'Dogecoin', '{doge,Dogecoin,dogecoin}', 8, TRUE),
+       ('XRFQ', 'XRFQ', '{xrfq}', 8, TRUE),
+       ('SOL', 'Solana', '{sol,Solana,solana,SOLANA}', 9, TRUE),
+       ('BTC', 'Bitcoin', '{btc,Bitcoin,bitcoin,BITCOIN}', 8, TRUE),
+       ('ETH', 'Ethereum', '{eth,Ethereum,ethereum,ETHEREUM}', 18, TRUE),
+       ('ADA', 'Cardano', '{ada,Cardano,cardano,CARDANO}', 6, TRUE),
+       ('USDT', 'Tether', '{usdt,Tether,tether,TETHER}', 6, TRUE),
+       ('BNB', 'Binance Coin', '{bnb,"Binance Coin","binance coin","BNB Coin",BinanceCoin}', 18, TRUE)
+ON CONFLICT (code) DO NOTHING;
@@ -0,0 +1,88 @@
+-- Amounts are stored exactly as NUMERIC with the number of decimal places of their currency in the currency table
+CREATE FUNCTION pg_temp.currency_decimals(currency_code currency_enum) RETURNS INTEGER AS
+$$
+SELECT decimals
+FROM currency
+WHERE code = currency_code
+$$ LANGUAGE sql STABLE;
+
+-- the min price of a contract is in one of its accepted currencies
+ALTER TABLE contract
+    ADD COLUMN IF NOT EXISTS min_price_currency currency_enum;
+UPDATE contract
+SET min_price_currency = accepted_currency[1]
+WHERE min_price_currency IS NULL;
+ALTER TABLE contract
+    ALTER COLUMN min_price_currency SET NOT NULL,
+    ALTER COLUMN min_price TYPE NUMERIC USING ROUND(min_price::NUMERIC, pg_temp.currency_decimals(min_price_currency));
+
+ALTER TABLE contract_history
+    ADD COLUMN IF NOT EXISTS min_price_currency currency_enum;
+ALTER TABLE contract_history
+    DISABLE TRIGGER contract_history_immutable;
+UPDATE contract_history
+SET min_price_currency = accepted_currency[1]
+WHERE min_price_currency IS NULL;
+ALTER TABLE contract_history
+    ENABLE TRIGGER contract_history_immutable;
+ALTER TABLE contract_history
+    ALTER COLUMN min_price_currency SET NOT NULL,
+    ALTER COLUMN min_price TYPE NUMERIC USING ROUND(min_price::NUMERIC, pg_temp.currency_decimals(min_price_currency));
+
+-- auction amounts are in the currency of the contract's min price when the auction was created
+ALTER TABLE contract_auction
+    ADD COLUMN IF NOT EXISTS currency currency_enum;
+UPDATE contract_auction a
+SET currency = c.min_price_currency
+FROM contract c
+WHERE c.id = a.contract_id
+  AND a.currency IS NULL;
+ALTER TABLE contract_auction
+    ALTER COLUMN currency SET NOT NULL,
+    ALTER COLUMN reserve_price TYPE NUMERIC USING ROUND(reserve_price::NUMERIC, pg_temp.currency_decimals(currency)),
+    ALTER COLUMN bid_increment TYPE NUMERIC USING ROUND(bid_increment::NUMERIC, pg_temp.currency_decimals(currency)),
+    ALTER COLUMN dutch_start_price TYPE NUMERIC USING ROUND(dutch_start_price::NUMERIC, pg_temp.currency_decimals(currency)),
+    ALTER COLUMN dutch_decay_amount TYPE NUMERIC USING ROUND(dutch_decay_amount::NUMERIC, pg_temp.currency_decimals(currency));
+
+ALTER TABLE bid
+    ALTER COLUMN amount TYPE NUMERIC USING ROUND(amount::NUMERIC, pg_temp.currency_decimals(currency));
+
+ALTER TABLE sale
+    ALTER COLUMN price TYPE NUMERIC USING ROUND(price::NUMERIC, pg_temp.currency_decimals(currency));
+
+-- entries are balanced exactly, without a rounding tolerance
+CREATE OR REPLACE FUNCTION check_journal_entry_balanced() RETURNS TRIGGER AS
+$$
+DECLARE
+    checked_entry_id TEXT    := COALESCE(NEW.entry_id, OLD.entry_id);
+    total            NUMERIC;
+BEGIN
+    SELECT COALESCE(SUM(amount), 0) INTO total FROM journal_posting WHERE entry_id = checked_entry_id;
+    IF total <> 0 THEN
+        RAISE EXCEPTION 'journal entry % is not balanced, postings sum to %', checked_entry_id, total;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+ALTER TABLE journal_posting
+    ALTER COLUMN amount TYPE NUMERIC USING amount::NUMERIC;
+-- rounding can leave a residual of a few minor units on an entry, it goes to the seller like the remainder of a sale.
+-- Postings are rounded and corrected in a single statement so that entries are balanced when the statement ends.
+WITH rounded AS (SELECT p.id, p.entry_id, p.kind, ROUND(p.amount, pg_temp.currency_decimals(e.currency)) AS amount
+                 FROM journal_posting p
+                          JOIN journal_entry e ON e.id = p.entry_id),
+     residual AS (SELECT entry_id, SUM(amount) AS residual
+                  FROM rounded
+                  GROUP BY entry_id
+                  HAVING SUM(amount) <> 0),
+     corrected AS (SELECT DISTINCT ON (r.entry_id) r.id, s.residual
+                   FROM rounded r
+                            JOIN residual s ON s.entry_id = r.entry_id
+                   WHERE r.kind <> 'purchase'
+                   ORDER BY r.entry_id, r.kind = 'sale' DESC, r.id)
+UPDATE journal_posting p
+SET amount = r.amount - COALESCE(c.residual, 0)
+FROM rounded r
+         LEFT JOIN corrected c ON c.id = r.id
+WHERE p.id = r.id;
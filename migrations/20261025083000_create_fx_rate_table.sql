@@ -0,0 +1,12 @@
+-- Exchange rates between currencies: one unit of base is worth rate units of quote at as_of.
+-- Rates are kept over time, conversions use the latest rate of a pair in either direction.
+CREATE TABLE IF NOT EXISTS fx_rate
+(
+    base       currency_enum NOT NULL,
+    quote      currency_enum NOT NULL,
+    rate       NUMERIC       NOT NULL CHECK (rate > 0),
+    as_of      TIMESTAMPTZ   NOT NULL,
+    created_at TIMESTAMPTZ   NOT NULL DEFAULT now(),
+    PRIMARY KEY (base, quote, as_of),
+    CHECK (base <> quote)
+);
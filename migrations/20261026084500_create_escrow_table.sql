@@ -0,0 +1,27 @@
+-- Escrow holds the funds of the buyer of an accepted bid until the asset is transferred to them.
+-- A pending hold waits for the buyer to pay, a funded hold for the seller to transfer the asset.
+-- Holds that pass their deadline are cancelled (pending) or refunded (funded) by the escrow sweeper.
+CREATE TYPE escrow_status AS ENUM ('pending', 'funded', 'released', 'refunded', 'cancelled');
+
+CREATE TABLE IF NOT EXISTS escrow
+(
+    id         TEXT PRIMARY KEY,
+    bid_id     TEXT          NOT NULL UNIQUE REFERENCES bid (id) ON DELETE CASCADE,
+    asset_id   TEXT          NOT NULL REFERENCES asset (id) ON DELETE CASCADE,
+    nfc_id     TEXT          NOT NULL REFERENCES nfc (id) ON DELETE CASCADE,
+    buyer_fp   TEXT          NOT NULL,
+    buyer_org  TEXT          NOT NULL,
+    seller_fp  TEXT          NOT NULL,
+    amount     NUMERIC       NOT NULL CHECK (amount > 0),
+    currency   currency_enum NOT NULL,
+    status     escrow_status NOT NULL DEFAULT 'pending',
+    deadline   TIMESTAMPTZ   NOT NULL,
+    funded_at  TIMESTAMPTZ,
+    settled_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ   NOT NULL,
+    updated_at TIMESTAMPTZ   NOT NULL
+);
+
+-- an asset can only be reserved for one buyer at a time
+CREATE UNIQUE INDEX IF NOT EXISTS idx_escrow_active_asset_id ON escrow (asset_id) WHERE status IN ('pending', 'funded');
+CREATE INDEX IF NOT EXISTS idx_escrow_active_deadline ON escrow (deadline) WHERE status IN ('pending', 'funded');
@@ -0,0 +1,44 @@
+-- Every row of the NFC trail records who the asset moved from, who it moved to and why.
+-- previous_owner_fp and previous_org are NULL for the mint, sale_id is only set when the asset was sold.
+CREATE TYPE transfer_reason AS ENUM ('mint', 'transfer', 'sale');
+
+ALTER TABLE nfc_asset_trail
+    ADD COLUMN IF NOT EXISTS organization      TEXT,
+    ADD COLUMN IF NOT EXISTS previous_owner_fp TEXT,
+    ADD COLUMN IF NOT EXISTS previous_org      TEXT,
+    ADD COLUMN IF NOT EXISTS reason            transfer_reason NOT NULL DEFAULT 'transfer',
+    ADD COLUMN IF NOT EXISTS sale_id           TEXT REFERENCES sale (id) ON DELETE SET NULL;
+
+-- backfill existing trails: the first row of an nfc is its mint, the owner before a row is the owner of the row before it
+WITH ordered AS (SELECT ctid,
+                        LAG(user_fp) OVER (PARTITION BY nfc_id ORDER BY transferred_on) AS previous_owner_fp,
+                        ROW_NUMBER() OVER (PARTITION BY nfc_id ORDER BY transferred_on) AS position
+                 FROM nfc_asset_trail)
+UPDATE nfc_asset_trail trail
+SET previous_owner_fp = ordered.previous_owner_fp,
+    reason            = CASE WHEN ordered.position = 1 THEN 'mint'::transfer_reason ELSE 'transfer'::transfer_reason END
+FROM ordered
+WHERE trail.ctid = ordered.ctid;
+
+-- sales were recorded in the same transaction as the transfer to their buyer
+UPDATE nfc_asset_trail trail
+SET reason       = 'sale',
+    sale_id      = sale.id,
+    organization = sale.buyer_org,
+    previous_org = sale.seller_org
+FROM sale
+WHERE trail.reason = 'transfer'
+  AND sale.nfc_id = trail.nfc_id
+  AND sale.buyer_fp = trail.user_fp
+  AND sale.created_at BETWEEN trail.transferred_on - INTERVAL '5 seconds' AND trail.transferred_on + INTERVAL '5 seconds';
+
+-- the organization of the current owner is known from the asset
+UPDATE nfc_asset_trail trail
+SET organization = asset.organization
+FROM asset
+WHERE trail.asset_id = asset.id
+  AND trail.organization IS NULL
+  AND trail.user_fp = asset.owner_fp
+  AND trail.transferred_on = (SELECT MAX(latest.transferred_on) FROM nfc_asset_trail latest WHERE latest.nfc_id = trail.nfc_id);
+
+CREATE INDEX IF NOT EXISTS idx_nfc_asset_trail_asset_id ON nfc_asset_trail (asset_id, transferred_on);
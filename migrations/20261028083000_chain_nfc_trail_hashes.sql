@@ -0,0 +1,50 @@
+-- The trail of an NFC is an append-only hash chain: every row stores the hash of the row before it and its own hash,
+-- the SHA-256 of its content and of the previous hash. Editing, deleting or inserting a row breaks the chain.
+-- The content layout must match `NFCTrail::hash_content`:
+-- xrf1-trail-v1|<previous_hash>|<nfc_id>|<asset_id>|<user_fp>|<organization>|<previous_owner_fp>|<previous_org>|<reason>|<sale_id>|<transferred_on in epoch microseconds>
+-- with missing values as empty strings.
+ALTER TABLE nfc_asset_trail
+    ADD COLUMN IF NOT EXISTS previous_hash TEXT,
+    ADD COLUMN IF NOT EXISTS hash          TEXT;
+
+-- chain the existing trails in the order they were recorded
+WITH RECURSIVE ordered AS (SELECT ctid,
+                                  nfc_id,
+                                  ROW_NUMBER() OVER (PARTITION BY nfc_id ORDER BY transferred_on) AS position,
+                                  CONCAT_WS('|',
+                                            nfc_id,
+                                            asset_id,
+                                            user_fp,
+                                            COALESCE(organization, ''),
+                                            COALESCE(previous_owner_fp, ''),
+                                            COALESCE(previous_org, ''),
+                                            reason::TEXT,
+                                            COALESCE(sale_id, ''),
+                                            (EXTRACT(EPOCH FROM transferred_on) * 1000000)::BIGINT::TEXT) AS content
+                           FROM nfc_asset_trail),
+               chain AS (SELECT ordered.ctid,
+                                ordered.nfc_id,
+                                ordered.position,
+                                NULL::TEXT AS previous_hash,
+                                ENCODE(SHA256(CONVERT_TO('xrf1-trail-v1||' || ordered.content, 'UTF8')), 'hex') AS hash
+                         FROM ordered
+                         WHERE ordered.position = 1
+                         UNION ALL
+                         SELECT ordered.ctid,
+                                ordered.nfc_id,
+                                ordered.position,
+                                chain.hash,
+                                ENCODE(SHA256(CONVERT_TO('xrf1-trail-v1|' || chain.hash || '|' || ordered.content, 'UTF8')), 'hex')
+                         FROM ordered
+                                  JOIN chain ON chain.nfc_id = ordered.nfc_id AND ordered.position = chain.position + 1)
+UPDATE nfc_asset_trail trail
+SET previous_hash = chain.previous_hash,
+    hash          = chain.hash
+FROM chain
+WHERE trail.ctid = chain.ctid;
+
+ALTER TABLE nfc_asset_trail
+    ALTER COLUMN hash SET NOT NULL;
+
+-- a row can only be appended after the last row of its chain, two rows can not follow the same row
+CREATE UNIQUE INDEX IF NOT EXISTS idx_nfc_asset_trail_chain ON nfc_asset_trail (nfc_id, previous_hash) NULLS NOT DISTINCT;
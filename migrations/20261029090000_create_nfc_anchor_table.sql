@@ -0,0 +1,23 @@
+-- An anchor is the Merkle root of the NFC certificates issued in a window, the leaves are in `nfc_anchor_leaf`.
+-- The leaf of an NFC is the SHA-256 of 0x00 || `NFC::anchor_leaf`, inner nodes the SHA-256 of 0x01 || left || right.
+CREATE TABLE IF NOT EXISTS nfc_anchor
+(
+    id           TEXT PRIMARY KEY,
+    window_start TIMESTAMPTZ NOT NULL,
+    window_end   TIMESTAMPTZ NOT NULL CHECK (window_end >= window_start),
+    root         TEXT        NOT NULL,
+    leaf_count   INTEGER     NOT NULL CHECK (leaf_count > 0),
+    created_at   TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_nfc_anchor_window_end ON nfc_anchor (window_end);
+
+-- an NFC is anchored once. Leaves do not reference the nfc table, deleting an asset must not change a published tree
+CREATE TABLE IF NOT EXISTS nfc_anchor_leaf
+(
+    nfc_id    TEXT PRIMARY KEY,
+    anchor_id TEXT    NOT NULL REFERENCES nfc_anchor (id) ON DELETE CASCADE,
+    position  INTEGER NOT NULL CHECK (position >= 0),
+    leaf_hash TEXT    NOT NULL,
+    UNIQUE (anchor_id, position)
+);
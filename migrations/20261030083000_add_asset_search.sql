@@ -0,0 +1,14 @@
+-- Full-text search over the name, symbol and description of assets, name and symbol weigh more than the description.
+-- Trigram indexes back the fuzzy matching of names and symbols with typos.
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+ALTER TABLE asset
+    ADD COLUMN IF NOT EXISTS search_vector TSVECTOR GENERATED ALWAYS AS (
+        SETWEIGHT(TO_TSVECTOR('english', COALESCE(name, '')), 'A') ||
+        SETWEIGHT(TO_TSVECTOR('english', COALESCE(symbol, '')), 'A') ||
+        SETWEIGHT(TO_TSVECTOR('english', COALESCE(description, '')), 'B')
+        ) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_asset_search_vector ON asset USING GIN (search_vector);
+CREATE INDEX IF NOT EXISTS idx_asset_name_trgm ON asset USING GIN (name gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_asset_symbol_trgm ON asset USING GIN (symbol gin_trgm_ops);
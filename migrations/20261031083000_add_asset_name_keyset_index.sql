@@ -0,0 +1,2 @@
+-- Pages of assets are read after a (name, id) cursor instead of skipping rows with OFFSET
+CREATE INDEX IF NOT EXISTS idx_asset_name_id ON asset (name, id);
@@ -0,0 +1,4 @@
+-- ListAssets reads pages after a (field, id) cursor for every sort field
+CREATE INDEX IF NOT EXISTS idx_asset_symbol_id ON asset (symbol, id);
+CREATE INDEX IF NOT EXISTS idx_asset_created_at_id ON asset (created_at, id);
+CREATE INDEX IF NOT EXISTS idx_asset_updated_at_id ON asset (updated_at, id);
@@ -0,0 +1,34 @@
+-- Tags and typed key/value attributes of assets, used to model categories and traits.
+-- The value of an attribute lives in the column of its kind, the other value columns stay NULL.
+CREATE TYPE asset_attribute_kind AS ENUM ('string', 'number', 'bool', 'date');
+
+CREATE TABLE IF NOT EXISTS asset_tag
+(
+    asset_id TEXT NOT NULL REFERENCES asset (id) ON DELETE CASCADE,
+    tag      TEXT NOT NULL,
+    PRIMARY KEY (asset_id, tag)
+);
+
+CREATE INDEX IF NOT EXISTS idx_asset_tag_tag ON asset_tag (tag);
+
+CREATE TABLE IF NOT EXISTS asset_attribute
+(
+    asset_id     TEXT                 NOT NULL REFERENCES asset (id) ON DELETE CASCADE,
+    key          TEXT                 NOT NULL,
+    kind         asset_attribute_kind NOT NULL,
+    string_value TEXT,
+    number_value DOUBLE PRECISION,
+    bool_value   BOOLEAN,
+    date_value   TIMESTAMPTZ,
+    PRIMARY KEY (asset_id, key),
+    CHECK (num_nonnulls(string_value, number_value, bool_value, date_value) = 1),
+    CHECK (kind <> 'string' OR string_value IS NOT NULL),
+    CHECK (kind <> 'number' OR number_value IS NOT NULL),
+    CHECK (kind <> 'bool' OR bool_value IS NOT NULL),
+    CHECK (kind <> 'date' OR date_value IS NOT NULL)
+);
+
+CREATE INDEX IF NOT EXISTS idx_asset_attribute_key_string ON asset_attribute (key, string_value) WHERE kind = 'string';
+CREATE INDEX IF NOT EXISTS idx_asset_attribute_key_number ON asset_attribute (key, number_value) WHERE kind = 'number';
+CREATE INDEX IF NOT EXISTS idx_asset_attribute_key_bool ON asset_attribute (key, bool_value) WHERE kind = 'bool';
+CREATE INDEX IF NOT EXISTS idx_asset_attribute_key_date ON asset_attribute (key, date_value) WHERE kind = 'date';
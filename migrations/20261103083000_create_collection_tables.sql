@@ -0,0 +1,50 @@
+-- Collections group the assets an organization releases as a series.
+-- Assets join a collection when they are created, their symbol starts with the symbol prefix of the collection.
+CREATE TABLE IF NOT EXISTS collection
+(
+    id            TEXT PRIMARY KEY,
+    organization  TEXT        NOT NULL,
+    name          TEXT        NOT NULL,
+    symbol_prefix TEXT        NOT NULL,
+    description   TEXT        NOT NULL,
+    -- maximum number of assets in the collection, unlimited when NULL
+    supply_cap    INTEGER CHECK (supply_cap > 0),
+    created_by    TEXT        NOT NULL,
+    updated_by    TEXT        NOT NULL,
+    created_at    TIMESTAMPTZ NOT NULL,
+    updated_at    TIMESTAMPTZ NOT NULL,
+    UNIQUE (organization, name)
+);
+
+-- an asset is in at most one collection, a collection can only be deleted once it has no assets
+CREATE TABLE IF NOT EXISTS collection_asset
+(
+    asset_id      TEXT PRIMARY KEY REFERENCES asset (id) ON DELETE CASCADE,
+    collection_id TEXT        NOT NULL REFERENCES collection (id) ON DELETE RESTRICT,
+    joined_at     TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_collection_asset_collection_id ON collection_asset (collection_id);
+
+-- contract every new asset of the collection is created with, stored like the v2 contracts it becomes
+CREATE TABLE IF NOT EXISTS collection_default_contract
+(
+    collection_id           TEXT PRIMARY KEY REFERENCES collection (id) ON DELETE CASCADE,
+    content                 TEXT            NOT NULL,
+    summary                 TEXT            NOT NULL,
+    min_price               NUMERIC         NOT NULL,
+    min_price_currency      currency_enum   NOT NULL,
+    accepted_currency       currency_enum[] NOT NULL,
+    anonymous_buyer_only    BOOLEAN         NOT NULL DEFAULT FALSE,
+    resale_allowed          BOOLEAN         NOT NULL DEFAULT TRUE,
+    resale_min_holding_days INTEGER         NOT NULL DEFAULT 0 CHECK (resale_min_holding_days >= 0),
+    expires_at              TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS collection_royalty_split
+(
+    collection_id TEXT    NOT NULL REFERENCES collection_default_contract (collection_id) ON DELETE CASCADE,
+    receiver_fp   TEXT    NOT NULL,
+    basis_points  INTEGER NOT NULL CHECK (basis_points > 0 AND basis_points <= 10000),
+    PRIMARY KEY (collection_id, receiver_fp)
+);
@@ -0,0 +1,21 @@
+-- Events of the changes of assets and contracts, written in the same transaction as the change they record.
+-- The outbox relay publishes pending events in sequence order and marks them dispatched once they were received.
+-- Events outlive their asset, deleting an asset records an event about it.
+CREATE TYPE outbox_event_type AS ENUM ('asset_created', 'asset_updated', 'asset_transferred', 'asset_deleted', 'contract_created');
+
+CREATE TABLE IF NOT EXISTS outbox
+(
+    sequence      BIGSERIAL PRIMARY KEY,
+    id            TEXT              NOT NULL UNIQUE,
+    event_type    outbox_event_type NOT NULL,
+    asset_id      TEXT              NOT NULL,
+    organization  TEXT              NOT NULL,
+    owner_fp      TEXT              NOT NULL,
+    -- protobuf encoded EventPayload, see proto/event/v1/event.proto
+    payload       BYTEA             NOT NULL,
+    created_at    TIMESTAMPTZ       NOT NULL,
+    dispatched_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_outbox_pending_sequence ON outbox (sequence) WHERE dispatched_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_outbox_asset_id_sequence ON outbox (asset_id, sequence);
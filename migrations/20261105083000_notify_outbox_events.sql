@@ -0,0 +1,19 @@
+-- Subscribers of the events LISTEN on the outbox_events channel, the payload of a notification is the sequence of the new event.
+-- Postgres sends the notifications once the transaction that recorded the events commits, none are sent when it rolls back.
+CREATE OR REPLACE FUNCTION notify_outbox_event() RETURNS TRIGGER AS
+$$
+BEGIN
+    PERFORM pg_notify('outbox_events', NEW.sequence::TEXT);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS outbox_event_notify ON outbox;
+CREATE TRIGGER outbox_event_notify
+    AFTER INSERT ON outbox
+    FOR EACH ROW
+EXECUTE FUNCTION notify_outbox_event();
+
+-- subscriptions read the events of an organization or of an owner after the last sequence they received
+CREATE INDEX IF NOT EXISTS idx_outbox_organization_sequence ON outbox (organization, sequence);
+CREATE INDEX IF NOT EXISTS idx_outbox_owner_fp_sequence ON outbox (owner_fp, sequence);
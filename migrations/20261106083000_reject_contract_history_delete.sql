@@ -0,0 +1,16 @@
+-- History rows are only removed together with their contract, the cascade of the contract deletion is the only delete allowed
+CREATE OR REPLACE FUNCTION reject_contract_history_delete() RETURNS TRIGGER AS
+$$
+BEGIN
+    IF EXISTS (SELECT 1 FROM contract WHERE id = OLD.contract_id) THEN
+        RAISE EXCEPTION 'contract history is immutable';
+    END IF;
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS contract_history_undeletable ON contract_history;
+CREATE TRIGGER contract_history_undeletable
+    BEFORE DELETE ON contract_history
+    FOR EACH ROW
+EXECUTE FUNCTION reject_contract_history_delete();
@@ -0,0 +1,12 @@
+-- Transaction that recorded the event, the number of pg_current_xact_id().
+-- Sequences are given when events are recorded but are only visible once their transaction commits, a subscription
+-- resuming after the last sequence it read would skip the events of a transaction that commits later.
+-- Subscriptions read the events in the order of their transaction instead, up to the oldest transaction still running:
+-- transactions that are running or start afterwards have a greater id than every event read.
+ALTER TABLE outbox ADD COLUMN IF NOT EXISTS transaction_id BIGINT NOT NULL DEFAULT pg_current_xact_id()::TEXT::BIGINT;
+
+-- subscriptions read the events of an organization or of an owner after the last event they received
+DROP INDEX IF EXISTS idx_outbox_organization_sequence;
+DROP INDEX IF EXISTS idx_outbox_owner_fp_sequence;
+CREATE INDEX IF NOT EXISTS idx_outbox_organization_transaction_id ON outbox (organization, transaction_id, sequence);
+CREATE INDEX IF NOT EXISTS idx_outbox_owner_fp_transaction_id ON outbox (owner_fp, transaction_id, sequence);
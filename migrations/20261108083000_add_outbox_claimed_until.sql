@@ -0,0 +1,5 @@
+-- A relay claims the pending events it publishes until this time, other relays skip them meanwhile.
+-- The claim is committed before the events are published: a transaction held open while publishing would keep
+-- the events of every later transaction from the subscriptions, see 20261107083000_add_outbox_transaction_id.sql.
+-- The events of a relay that stopped before marking them dispatched are published by the next relay once the claim ends.
+ALTER TABLE outbox ADD COLUMN IF NOT EXISTS claimed_until TIMESTAMPTZ;
@@ -0,0 +1,47 @@
+use anyhow::anyhow;
+use std::process::ExitCode;
+use std::sync::Arc;
+use xrf1::configs::load_config;
+use xrf1::core::repository::PgRepository;
+use xrf1::core::{orchestrator, TrailIntegrityReport};
+use xrf1::startup::get_connection_pool;
+
+const USAGE: &str = "usage: xrf1-admin verify-trail [<asset_id>]
+
+commands:
+  verify-trail    walks the hash chain of the NFC trail of one asset, or of every asset,
+                  and reports the first break of each broken trail";
+
+/// Maintenance commands run against the database of the configured environment (XRF_ENV)
+#[tokio::main]
+async fn main() -> anyhow::Result<ExitCode> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        ["verify-trail"] => verify_trail(None).await,
+        ["verify-trail", asset_id] => verify_trail(Some(asset_id)).await,
+        _ => {
+            eprintln!("{}", USAGE);
+            Ok(ExitCode::from(2))
+        }
+    }
+}
+
+async fn verify_trail(asset_id: Option<&str>) -> anyhow::Result<ExitCode> {
+    let config = load_config()?;
+    let repository = PgRepository::new(Arc::new(get_connection_pool(&config.database)));
+    let report = match asset_id {
+        Some(asset_id) => orchestrator::verify_trail_integrity(asset_id, &repository).await,
+        None => orchestrator::verify_all_trails(&repository).await,
+    }
+        .map_err(|e| anyhow!("failed to verify trail integrity :: {}", e))?;
+    print_report(&report);
+    Ok(if report.is_intact() { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
+
+fn print_report(report: &TrailIntegrityReport) {
+    for trail_break in &report.breaks {
+        println!("BROKEN {}", trail_break);
+    }
+    println!("checked {} asset(s), {} broken trail(s)", report.checked_assets, report.breaks.len());
+}
@@ -6,6 +6,9 @@ use sqlx::postgres::{PgConnectOptions, PgSslMode};
 #[derive(Clone, Debug, Deserialize)]
 pub struct DatabaseConfig {
     pub postgres: Postgres,
+    /// applies the migrations embedded in the binary when the application starts
+    #[serde(default)]
+    pub migrate_on_startup: bool,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -37,12 +37,100 @@ pub struct ServerConfig {
     pub http: HttpServerConfig,
 }
 
+#[derive(Deserialize, Clone)]
+pub struct AuctionWorkerConfig {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub interval_secs: u64,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct EscrowWorkerConfig {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub interval_secs: u64,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct AnchorWorkerConfig {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub interval_secs: u64,
+}
+
+/// Where the outbox relay publishes the events
+#[derive(Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum EventSinkConfig {
+    Stdout,
+    /// events are appended to the file
+    File { path: String },
+    /// events are streamed to the `EventSinkService` at the endpoint
+    Grpc { endpoint: String },
+}
+
+#[derive(Deserialize, Clone)]
+pub struct OutboxWorkerConfig {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub interval_secs: u64,
+    /// maximum number of events published at once
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub batch_size: i64,
+    /// a publication the sink has not received by then fails, its events are published again by the next relay
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub publish_timeout_secs: u64,
+    pub sink: EventSinkConfig,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct WorkersConfig {
+    pub auction: AuctionWorkerConfig,
+    pub escrow: EscrowWorkerConfig,
+    pub anchor: AnchorWorkerConfig,
+    pub outbox: OutboxWorkerConfig,
+}
+
+/// Maximum age of the exchange rates used for conversions
+#[derive(Deserialize, Clone)]
+pub struct FxConfig {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_rate_age_secs: u64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_crypto_rate_age_secs: u64,
+}
+
+/// How long the buyer of an accepted bid has to pay, and the seller to transfer the asset
+#[derive(Deserialize, Clone)]
+pub struct EscrowConfig {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub hold_ttl_secs: u64,
+}
+
+impl OutboxWorkerConfig {
+    pub fn publish_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.publish_timeout_secs)
+    }
+}
+
+impl EscrowConfig {
+    pub fn hold_ttl(&self) -> chrono::Duration {
+        chrono::Duration::seconds(self.hold_ttl_secs as i64)
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub struct AdminConfig {
+    /// fingerprints of the users allowed to call admin RPCs
+    pub fingerprints: Vec<String>,
+}
+
 #[derive(serde::Deserialize, Clone)]
 pub struct Configurations {
     pub log: LogConfig,
     pub app: Application,
     pub server: ServerConfig,
     pub database: DatabaseConfig,
+    pub workers: WorkersConfig,
+    pub fx: FxConfig,
+    pub escrow: EscrowConfig,
+    pub admin: AdminConfig,
 }
 
 pub fn load_config() -> Result<Configurations, config::ConfigError> {
@@ -2,4 +2,7 @@ mod database;
 mod load;
 
 pub use database::DatabaseConfig;
-pub use load::{load_config, Application, Configurations, GrpcServerConfig, HttpServerConfig, LogConfig, ServerConfig};
+pub use load::{
+    load_config, AdminConfig, AnchorWorkerConfig, Application, AuctionWorkerConfig, Configurations, EscrowConfig, EscrowWorkerConfig,
+    EventSinkConfig, FxConfig, GrpcServerConfig, HttpServerConfig, LogConfig, OutboxWorkerConfig, ServerConfig, WorkersConfig,
+};
@@ -3,4 +3,5 @@ pub const REQUEST_ID_KEY: &str = "request-id";
 pub const INVALID_SERVER_ID: &str = "invalid-server-id";
 pub const KEY_PEM_PATH: &str = "XRF_Q1_PEM_KEY_PATH";
 pub const CERT_PEM_PATH: &str = "XRF_Q1_PEM_CERT_PATH";
+pub const NFC_SIGNING_KEY_PATH: &str = "XRF_NFC_SIGNING_KEY_PATH";
 pub const XRF_1_POSTGRES_DB_URL_ENV_KEY: &str = "XRF_1_DB_URL";
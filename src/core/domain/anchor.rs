@@ -0,0 +1,198 @@
+use crate::core::domain::key::{generate_unique_key, DOMAIN_KEY_SIZE};
+use crate::core::NFC;
+use chrono::{DateTime, SubsecRound, Utc};
+use sha2::{Digest, Sha256};
+use std::fmt::{Display, Formatter};
+
+/// Version of the anchored leaves, changes whenever the layout of a leaf changes
+pub const ANCHOR_LEAF_FORMAT: &str = "xrf1-anchor-v1";
+
+/// Prefixes keep a leaf from being passed off as an inner node of the tree
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// Hex encoded SHA-256 of `0x00 || content`
+pub(crate) fn hash_leaf(content: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(content.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// SHA-256 of `0x01 || left || right`, None when a child is not a hex encoded hash
+pub(crate) fn hash_node(left: &str, right: &str) -> Option<String> {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(hex::decode(left).ok()?);
+    hasher.update(hex::decode(right).ok()?);
+    Some(hex::encode(hasher.finalize()))
+}
+
+/// Merkle root of the NFC certificates issued in a window, published so that auditors can check
+/// that a certificate existed when the window closed without access to the database
+#[derive(Debug, Clone)]
+pub struct NfcAnchor {
+    pub id: String,
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    /// hex encoded root of the tree over the leaves of the anchored NFCs, in anchoring order
+    pub root: String,
+    pub leaf_count: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Display for NfcAnchor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "anchorId:{}, root:{}, leafCount={}, windowStart={}, windowEnd={}",
+               self.id, self.root, self.leaf_count, self.window_start, self.window_end)
+    }
+}
+
+impl NfcAnchor {
+    pub fn new(window_start: DateTime<Utc>, window_end: DateTime<Utc>, tree: &MerkleTree) -> Self {
+        Self {
+            window_start,
+            window_end,
+            id: generate_unique_key(DOMAIN_KEY_SIZE),
+            root: tree.root().to_string(),
+            leaf_count: tree.leaf_count() as i32,
+            // timestamps are stored with microseconds
+            created_at: Utc::now().trunc_subsecs(6),
+        }
+    }
+}
+
+/// Side of the sibling of a node on the path from a leaf to the root
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofSide {
+    Left,
+    Right,
+}
+
+/// Sibling of one node on the path from a leaf to the root
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofStep {
+    /// hex encoded hash of the sibling
+    pub sibling: String,
+    pub side: ProofSide,
+}
+
+/// Path from the leaf of an NFC to the root of the anchor it was anchored in
+#[derive(Debug, Clone)]
+pub struct InclusionProof {
+    pub anchor: NfcAnchor,
+    /// the leaf is computed from the NFC, see `NFC::anchor_leaf`
+    pub nfc: NFC,
+    pub leaf_hash: String,
+    /// index of the leaf in the tree, 0 is the first anchored NFC
+    pub leaf_index: usize,
+    /// siblings ordered from the leaf to the root
+    pub steps: Vec<ProofStep>,
+}
+
+/// Binary Merkle tree over hex encoded leaf hashes.
+/// A node without a sibling is carried up to the next level unchanged, nothing is duplicated.
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    /// levels from the leaves to the root, the last level only holds the root
+    levels: Vec<Vec<String>>,
+}
+
+impl MerkleTree {
+    /// Returns None when there are no leaves or a leaf is not a hex encoded hash
+    pub fn build(leaves: Vec<String>) -> Option<Self> {
+        if leaves.is_empty() {
+            return None;
+        }
+        let mut levels = vec![leaves];
+        while levels.last()?.len() > 1 {
+            let level = levels.last()?;
+            let mut parents = Vec::with_capacity(level.len().div_ceil(2));
+            for pair in level.chunks(2) {
+                match pair {
+                    [left, right] => parents.push(hash_node(left, right)?),
+                    [single] => parents.push(single.clone()),
+                    _ => unreachable!("chunks of two"),
+                }
+            }
+            levels.push(parents);
+        }
+        Some(Self { levels })
+    }
+
+    pub fn root(&self) -> &str {
+        &self.levels[self.levels.len() - 1][0]
+    }
+
+    pub fn leaf_count(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Siblings of the leaf at `index`, ordered from the leaf to the root
+    pub fn proof(&self, index: usize) -> Option<Vec<ProofStep>> {
+        if index >= self.leaf_count() {
+            return None;
+        }
+        let mut steps = Vec::new();
+        let mut index = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = index ^ 1;
+            // a node without a sibling is carried up, it adds no step
+            if sibling < level.len() {
+                let side = if sibling < index { ProofSide::Left } else { ProofSide::Right };
+                steps.push(ProofStep { sibling: level[sibling].clone(), side });
+            }
+            index /= 2;
+        }
+        Some(steps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::verify_inclusion_proof;
+
+    fn leaves(count: usize) -> Vec<String> {
+        (0..count).map(|i| hash_leaf(&format!("leaf-{}", i))).collect()
+    }
+
+    #[test]
+    fn test_every_leaf_proves_against_root() {
+        for count in 1..=9 {
+            let tree = MerkleTree::build(leaves(count)).unwrap();
+            assert_eq!(tree.leaf_count(), count);
+            for (index, leaf) in leaves(count).iter().enumerate() {
+                let steps = tree.proof(index).unwrap();
+                assert!(verify_inclusion_proof(leaf, &steps, tree.root()), "count={} index={}", count, index);
+            }
+            assert!(tree.proof(count).is_none());
+        }
+    }
+
+    #[test]
+    fn test_single_leaf_is_root() {
+        let tree = MerkleTree::build(leaves(1)).unwrap();
+        assert_eq!(tree.root(), leaves(1)[0]);
+        assert!(tree.proof(0).unwrap().is_empty());
+        assert!(MerkleTree::build(vec![]).is_none());
+        assert!(MerkleTree::build(vec!["not-hex".to_string(), leaves(1)[0].clone()]).is_none());
+    }
+
+    #[test]
+    fn test_proof_does_not_verify_other_leaf_or_root() {
+        let tree = MerkleTree::build(leaves(5)).unwrap();
+        let steps = tree.proof(2).unwrap();
+        assert!(!verify_inclusion_proof(&leaves(5)[3], &steps, tree.root()));
+
+        let other = MerkleTree::build(leaves(6)).unwrap();
+        assert!(!verify_inclusion_proof(&leaves(5)[2], &steps, other.root()));
+
+        let mut swapped = steps.clone();
+        swapped[0].side = match swapped[0].side {
+            ProofSide::Left => ProofSide::Right,
+            ProofSide::Right => ProofSide::Left,
+        };
+        assert!(!verify_inclusion_proof(&leaves(5)[2], &swapped, tree.root()));
+    }
+}
@@ -1,10 +1,12 @@
+use crate::core::domain::attribute::AssetAttribute;
 use crate::core::domain::error::DomainError;
 use crate::core::domain::key::{generate_unique_key, DOMAIN_KEY_SIZE};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, SecondsFormat, Utc};
 use std::fmt::Display;
+use strum_macros::EnumString;
 use uuid::Uuid;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, sqlx::FromRow)]
 pub struct Asset {
     pub id: String,
     pub name: String,
@@ -47,6 +49,16 @@ impl Asset {
         })
     }
 
+    /// Value of the sort field of the asset, timestamps as RFC 3339 UTC with microseconds
+    pub fn sort_key(&self, field: AssetSortField) -> String {
+        match field {
+            AssetSortField::Name => self.name.clone(),
+            AssetSortField::Symbol => self.symbol.clone(),
+            AssetSortField::CreatedAt => self.created_at.to_rfc3339_opts(SecondsFormat::Micros, true),
+            AssetSortField::UpdatedAt => self.updated_at.to_rfc3339_opts(SecondsFormat::Micros, true),
+        }
+    }
+
     fn validate_name(name: &str) -> Result<(), DomainError> {
         const MIN_LENGTH: usize = 3;
         const MAX_LENGTH: usize = 32;
@@ -88,6 +100,78 @@ impl Asset {
     }
 }
 
+/// Field a list of assets is ordered by, ties are broken by id
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, EnumString)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum AssetSortField {
+    #[default]
+    Name,
+    Symbol,
+    CreatedAt,
+    UpdatedAt,
+}
+
+impl Display for AssetSortField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssetSortField::Name => write!(f, "name"),
+            AssetSortField::Symbol => write!(f, "symbol"),
+            AssetSortField::CreatedAt => write!(f, "created_at"),
+            AssetSortField::UpdatedAt => write!(f, "updated_at"),
+        }
+    }
+}
+
+/// Conditions an asset must all meet to be listed, unset conditions match every asset.
+/// Date ranges include their start and exclude their end.
+#[derive(Debug, Clone, Default)]
+pub struct AssetFilter {
+    pub organization: Option<String>,
+    pub owner_fp: Option<String>,
+    pub tradable: Option<bool>,
+    pub listable: Option<bool>,
+    /// part of the name, matched ignoring case
+    pub name_contains: Option<String>,
+    pub symbol_prefix: Option<String>,
+    pub created_from: Option<DateTime<Utc>>,
+    pub created_until: Option<DateTime<Utc>>,
+    pub updated_from: Option<DateTime<Utc>>,
+    pub updated_until: Option<DateTime<Utc>>,
+    /// normalized tags the asset must all have, see `normalize_tags`
+    pub tags: Vec<String>,
+    /// attributes the asset must all have with the same value
+    pub attributes: Vec<AssetAttribute>,
+    pub collection_id: Option<String>,
+}
+
+impl AssetFilter {
+    pub fn validate(&self) -> Result<(), DomainError> {
+        if let (Some(from), Some(until)) = (self.created_from, self.created_until) {
+            if from > until {
+                return Err(DomainError::InvalidArgument("created range should start before it ends".to_string()));
+            }
+        }
+        if let (Some(from), Some(until)) = (self.updated_from, self.updated_until) {
+            if from > until {
+                return Err(DomainError::InvalidArgument("updated range should start before it ends".to_string()));
+            }
+        }
+        if self.symbol_prefix.as_ref().is_some_and(|prefix| prefix.trim().is_empty()) {
+            return Err(DomainError::InvalidArgument("symbol prefix should not be empty".to_string()));
+        }
+        Ok(())
+    }
+}
+
+impl Display for AssetFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "organization:{:?}, owner:{:?}, tradable:{:?}, listable:{:?}, nameContains:{:?}, symbolPrefix:{:?}, created:{:?}..{:?}, updated:{:?}..{:?}, tags:{:?}, attributes:{}, collection:{:?}",
+               self.organization, self.owner_fp, self.tradable, self.listable, self.name_contains, self.symbol_prefix,
+               self.created_from, self.created_until, self.updated_from, self.updated_until, self.tags,
+               self.attributes.iter().map(AssetAttribute::to_string).collect::<Vec<_>>().join(","), self.collection_id)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct UpdateAssetRequest {
     pub name: Option<String>,
@@ -96,6 +180,12 @@ pub struct UpdateAssetRequest {
     pub tradable: Option<bool>,
     pub description: Option<String>,
     pub organization: Option<String>,
+    /// replaces every tag of the asset when set, normalized
+    pub tags: Option<Vec<String>>,
+    /// added, or replacing the value of the attribute with the same key
+    pub set_attributes: Vec<AssetAttribute>,
+    /// keys of the attributes to remove, normalized
+    pub remove_attributes: Vec<String>,
 }
 
 impl UpdateAssetRequest {
@@ -114,13 +204,83 @@ impl UpdateAssetRequest {
             tradable,
             description,
             organization,
+            tags: None,
+            set_attributes: vec![],
+            remove_attributes: vec![],
         }
     }
+
+    /// Whether the tags or attributes of the asset change
+    pub fn updates_metadata(&self) -> bool {
+        self.tags.is_some() || !self.set_attributes.is_empty() || !self.remove_attributes.is_empty()
+    }
 }
 
 impl Display for UpdateAssetRequest {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "name:{:?}, symbol:{:?}, listable:{:?}, tradable:{:?}",
-               self.name, self.symbol, self.listable, self.tradable)
+        write!(f, "name:{:?}, symbol:{:?}, listable:{:?}, tradable:{:?}, tags:{:?}, setAttributes:{}, removeAttributes:{:?}",
+               self.name, self.symbol, self.listable, self.tradable, self.tags,
+               self.set_attributes.iter().map(AssetAttribute::to_string).collect::<Vec<_>>().join(","),
+               self.remove_attributes)
     }
 }
+
+/// Ranked search over the name, symbol and description of assets, tolerant to typos in the name and symbol
+#[derive(Debug, Clone)]
+pub struct AssetSearch {
+    pub term: String,
+    pub organization: Option<String>,
+    pub tradable: Option<bool>,
+    pub listable: Option<bool>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl AssetSearch {
+    pub const MAX_LIMIT: i64 = 100;
+    const MAX_TERM_LENGTH: usize = 100;
+
+    pub fn new(term: &str,
+               organization: Option<String>,
+               tradable: Option<bool>,
+               listable: Option<bool>,
+               limit: i64,
+               offset: i64) -> Result<Self, DomainError> {
+        let term = term.trim();
+        if term.is_empty() || term.chars().count() > Self::MAX_TERM_LENGTH {
+            let error = format!("search term should be between 1 and {} characters long", Self::MAX_TERM_LENGTH);
+            return Err(DomainError::InvalidArgument(error));
+        }
+        if !(1..=Self::MAX_LIMIT).contains(&limit) {
+            return Err(DomainError::InvalidArgument(format!("limit must be between 1 and {}", Self::MAX_LIMIT)));
+        }
+        if offset < 0 {
+            return Err(DomainError::InvalidArgument("offset must be positive".to_string()));
+        }
+        Ok(Self {
+            term: term.to_string(),
+            organization,
+            tradable,
+            listable,
+            limit,
+            offset,
+        })
+    }
+}
+
+impl Display for AssetSearch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "term:{}, organization:{:?}, tradable:{:?}, listable:{:?}, limit={}, offset={}",
+               self.term, self.organization, self.tradable, self.listable, self.limit, self.offset)
+    }
+}
+
+/// An asset matching a search, best matches have the highest score
+#[derive(Debug, Clone)]
+pub struct AssetSearchHit {
+    pub asset: Asset,
+    /// full-text rank plus the trigram similarity of the name or symbol to the term
+    pub score: f32,
+    /// name and description with the matched words between `<b>` and `</b>`
+    pub snippet: String,
+}
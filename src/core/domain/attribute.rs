@@ -0,0 +1,179 @@
+use crate::core::DomainError;
+use chrono::{DateTime, SubsecRound, Utc};
+use std::fmt::{Display, Formatter};
+use strum_macros::EnumString;
+
+/// Maximum number of tags of an asset
+pub const MAX_ASSET_TAGS: usize = 20;
+/// Maximum number of attributes of an asset
+pub const MAX_ASSET_ATTRIBUTES: usize = 32;
+pub const MAX_TAG_LENGTH: usize = 32;
+pub const MAX_ATTRIBUTE_KEY_LENGTH: usize = 64;
+pub const MAX_ATTRIBUTE_STRING_LENGTH: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, sqlx::Type)]
+#[sqlx(type_name = "asset_attribute_kind", rename_all = "lowercase")]
+#[strum(ascii_case_insensitive)]
+pub enum AttributeKind {
+    String,
+    Number,
+    Bool,
+    Date,
+}
+
+impl Display for AttributeKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AttributeKind::String => write!(f, "string"),
+            AttributeKind::Number => write!(f, "number"),
+            AttributeKind::Bool => write!(f, "bool"),
+            AttributeKind::Date => write!(f, "date"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeValue {
+    String(String),
+    Number(f64),
+    Bool(bool),
+    Date(DateTime<Utc>),
+}
+
+impl AttributeValue {
+    pub fn kind(&self) -> AttributeKind {
+        match self {
+            AttributeValue::String(_) => AttributeKind::String,
+            AttributeValue::Number(_) => AttributeKind::Number,
+            AttributeValue::Bool(_) => AttributeKind::Bool,
+            AttributeValue::Date(_) => AttributeKind::Date,
+        }
+    }
+}
+
+impl Display for AttributeValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AttributeValue::String(value) => write!(f, "{}", value),
+            AttributeValue::Number(value) => write!(f, "{}", value),
+            AttributeValue::Bool(value) => write!(f, "{}", value),
+            AttributeValue::Date(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// Typed key/value trait of an asset, an asset has at most one value per key
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetAttribute {
+    pub key: String,
+    pub value: AttributeValue,
+}
+
+impl Display for AssetAttribute {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}={}", self.key, self.value.kind(), self.value)
+    }
+}
+
+impl AssetAttribute {
+    /// Keys are lower cased, string values trimmed and dates truncated to the microseconds they are stored with
+    pub fn new(key: &str, value: AttributeValue) -> Result<Self, DomainError> {
+        let key = normalize_attribute_key(key)?;
+        let value = match value {
+            AttributeValue::String(value) => {
+                let value = value.trim().to_string();
+                if value.is_empty() || value.chars().count() > MAX_ATTRIBUTE_STRING_LENGTH {
+                    let error = format!("attribute {} should be between 1 and {} characters long", key, MAX_ATTRIBUTE_STRING_LENGTH);
+                    return Err(DomainError::InvalidArgument(error));
+                }
+                AttributeValue::String(value)
+            }
+            AttributeValue::Number(value) if !value.is_finite() => {
+                return Err(DomainError::InvalidArgument(format!("attribute {} should be a finite number", key)));
+            }
+            AttributeValue::Date(value) => AttributeValue::Date(value.trunc_subsecs(6)),
+            value => value,
+        };
+        Ok(Self { key, value })
+    }
+}
+
+/// Tags and attributes of an asset
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AssetMetadata {
+    /// sorted
+    pub tags: Vec<String>,
+    /// sorted by key
+    pub attributes: Vec<AssetAttribute>,
+}
+
+/// Keys start with a letter and only hold lower case letters, digits, `_`, `-` and `.`
+pub fn normalize_attribute_key(key: &str) -> Result<String, DomainError> {
+    let key = key.trim().to_lowercase();
+    if key.is_empty() || key.len() > MAX_ATTRIBUTE_KEY_LENGTH {
+        let error = format!("attribute key should be between 1 and {} characters long", MAX_ATTRIBUTE_KEY_LENGTH);
+        return Err(DomainError::InvalidArgument(error));
+    }
+    let valid = key.starts_with(|c: char| c.is_ascii_lowercase())
+        && key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | '-' | '.'));
+    if !valid {
+        let error = format!("attribute key {} should start with a letter and only contain letters, digits, '_', '-' or '.'", key);
+        return Err(DomainError::InvalidArgument(error));
+    }
+    Ok(key)
+}
+
+/// Tags are lower cased and trimmed, they only hold letters, digits, spaces, `_` and `-`
+pub fn normalize_tag(tag: &str) -> Result<String, DomainError> {
+    let tag = tag.trim().to_lowercase();
+    if tag.is_empty() || tag.chars().count() > MAX_TAG_LENGTH {
+        return Err(DomainError::InvalidArgument(format!("tag should be between 1 and {} characters long", MAX_TAG_LENGTH)));
+    }
+    if !tag.chars().all(|c| c.is_alphanumeric() || matches!(c, ' ' | '_' | '-')) {
+        let error = format!("tag {} should only contain letters, digits, spaces, '_' or '-'", tag);
+        return Err(DomainError::InvalidArgument(error));
+    }
+    Ok(tag)
+}
+
+/// Normalized, sorted and deduplicated tags of an asset
+pub fn normalize_tags(tags: &[String]) -> Result<Vec<String>, DomainError> {
+    let mut tags = tags.iter().map(|tag| normalize_tag(tag)).collect::<Result<Vec<_>, _>>()?;
+    tags.sort();
+    tags.dedup();
+    if tags.len() > MAX_ASSET_TAGS {
+        return Err(DomainError::InvalidArgument(format!("an asset can have at most {} tags", MAX_ASSET_TAGS)));
+    }
+    Ok(tags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tags_are_normalized() {
+        let tags = normalize_tags(&["  Art ".to_string(), "art".to_string(), "Oil-Painting".to_string()]).unwrap();
+        assert_eq!(tags, vec!["art".to_string(), "oil-painting".to_string()]);
+        assert!(normalize_tag("").is_err());
+        assert!(normalize_tag("art;drop").is_err());
+        assert!(normalize_tag(&"a".repeat(MAX_TAG_LENGTH + 1)).is_err());
+
+        let too_many: Vec<String> = (0..=MAX_ASSET_TAGS).map(|i| format!("tag{}", i)).collect();
+        assert!(normalize_tags(&too_many).is_err());
+    }
+
+    #[test]
+    fn test_attributes_are_validated() {
+        let attribute = AssetAttribute::new(" Year ", AttributeValue::Number(1889.0)).unwrap();
+        assert_eq!(attribute.key, "year");
+        assert_eq!(attribute.value.kind(), AttributeKind::Number);
+
+        assert!(AssetAttribute::new("1st", AttributeValue::Bool(true)).is_err());
+        assert!(AssetAttribute::new("some key", AttributeValue::Bool(true)).is_err());
+        assert!(AssetAttribute::new("weight", AttributeValue::Number(f64::NAN)).is_err());
+        assert!(AssetAttribute::new("artist", AttributeValue::String("  ".to_string())).is_err());
+        let long = "a".repeat(MAX_ATTRIBUTE_STRING_LENGTH + 1);
+        assert!(AssetAttribute::new("artist", AttributeValue::String(long)).is_err());
+    }
+}
@@ -0,0 +1,299 @@
+use crate::core::domain::bid::MAX_BID_TTL_DAYS;
+use crate::core::{Bid, Currency, DomainError, Money};
+use chrono::{DateTime, Duration, Utc};
+use std::fmt::{Display, Formatter};
+use strum_macros::EnumString;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, sqlx::Type)]
+#[sqlx(type_name = "auction_type", rename_all = "snake_case")]
+#[strum(ascii_case_insensitive)]
+pub enum AuctionType {
+    /// Open ascending auction, every bid must beat the highest bid by the bid increment
+    English,
+    /// Descending auction, the first bid at or above the current price wins
+    Dutch,
+    /// Bids are hidden until the auction closes, the highest bid wins
+    #[strum(serialize = "sealed_bid", serialize = "sealedbid", serialize = "sealed")]
+    SealedBid,
+}
+
+impl Display for AuctionType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuctionType::English => write!(f, "english"),
+            AuctionType::Dutch => write!(f, "dutch"),
+            AuctionType::SealedBid => write!(f, "sealed_bid"),
+        }
+    }
+}
+
+/// Price schedule of a dutch auction: the price starts at `start_price` and drops by `decay_amount`
+/// every `decay_interval_secs` until it reaches the reserve price of the auction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DutchDecay {
+    pub start_price: Money,
+    pub decay_amount: Money,
+    pub decay_interval_secs: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Auction {
+    pub auction_type: AuctionType,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub reserve_price: Money,
+    pub bid_increment: Money,
+    pub dutch_decay: Option<DutchDecay>,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub winning_bid_id: Option<String>,
+}
+
+impl Display for Auction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "type:{}, start:{}, end:{}, closed:{}", self.auction_type, self.start_time, self.end_time, self.is_closed())
+    }
+}
+
+impl Auction {
+    pub fn new(auction_type: AuctionType,
+               start_time: DateTime<Utc>,
+               end_time: DateTime<Utc>,
+               reserve_price: Money,
+               bid_increment: Money,
+               dutch_decay: Option<DutchDecay>) -> Result<Self, DomainError> {
+        if end_time <= start_time {
+            return Err(DomainError::InvalidArgument("auction must end after it starts".to_string()));
+        }
+        if end_time <= Utc::now() {
+            return Err(DomainError::InvalidArgument("auction end time must be in the future".to_string()));
+        }
+        // bids on an auction stay open until the auction ends
+        if end_time - start_time > Duration::days(MAX_BID_TTL_DAYS) {
+            let error = format!("auction can not run for more than {MAX_BID_TTL_DAYS} days");
+            return Err(DomainError::InvalidArgument(error));
+        }
+        if reserve_price.is_negative() || bid_increment.is_negative() {
+            return Err(DomainError::InvalidArgument("reserve price and bid increment can not be negative".to_string()));
+        }
+        // every amount of the auction is in the currency of its reserve price
+        let currency = reserve_price.currency();
+        let decay_currencies = dutch_decay.iter().flat_map(|d| [d.start_price.currency(), d.decay_amount.currency()]);
+        if std::iter::once(bid_increment.currency()).chain(decay_currencies).any(|c| c != currency) {
+            return Err(DomainError::InvalidArgument(format!("auction amounts must be in {}", currency)));
+        }
+
+        match auction_type {
+            AuctionType::English if !bid_increment.is_positive() => {
+                return Err(DomainError::InvalidArgument("english auctions need a bid increment".to_string()));
+            }
+            AuctionType::Dutch => match &dutch_decay {
+                None => {
+                    return Err(DomainError::InvalidArgument("dutch auctions need a price decay schedule".to_string()));
+                }
+                Some(decay) => {
+                    if decay.start_price.try_cmp(&reserve_price)?.is_le() {
+                        let error = "dutch auction start price must be greater than the reserve price".to_string();
+                        return Err(DomainError::InvalidArgument(error));
+                    }
+                    if !decay.decay_amount.is_positive() || decay.decay_interval_secs <= 0 {
+                        let error = "dutch auction decay amount and interval must be greater than 0".to_string();
+                        return Err(DomainError::InvalidArgument(error));
+                    }
+                }
+            },
+            _ => {}
+        }
+        if auction_type != AuctionType::Dutch && dutch_decay.is_some() {
+            return Err(DomainError::InvalidArgument("price decay is only supported by dutch auctions".to_string()));
+        }
+
+        Ok(Self {
+            end_time,
+            start_time,
+            dutch_decay,
+            auction_type,
+            bid_increment,
+            reserve_price,
+            closed_at: None,
+            winning_bid_id: None,
+        })
+    }
+
+    /// Bids on the auction must be placed in this currency
+    pub fn currency(&self) -> &Currency {
+        self.reserve_price.currency()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed_at.is_some()
+    }
+
+    /// An auction is active from its start until it is closed, even if its end time has passed
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        !self.is_closed() && now >= self.start_time
+    }
+
+    pub fn accepts_bids_at(&self, now: DateTime<Utc>) -> bool {
+        self.is_active(now) && now < self.end_time
+    }
+
+    pub fn is_sealed(&self) -> bool {
+        self.auction_type == AuctionType::SealedBid && !self.is_closed()
+    }
+
+    /// Price of a dutch auction at the given time, never lower than the reserve price.
+    /// Returns `None` for other auction types.
+    pub fn price_at(&self, at: DateTime<Utc>) -> Option<Money> {
+        let decay = self.dutch_decay.as_ref()?;
+        let elapsed_secs = (at - self.start_time).num_seconds().max(0);
+        let steps = elapsed_secs / decay.decay_interval_secs;
+        // a price that would overflow has long decayed to the reserve price
+        let price = decay.decay_amount.checked_mul(steps as i128)
+            .and_then(|decayed| decay.start_price.checked_sub(&decayed))
+            .unwrap_or_else(|_| self.reserve_price.clone());
+        if price.minor_units() < self.reserve_price.minor_units() {
+            return Some(self.reserve_price.clone());
+        }
+        Some(price)
+    }
+
+    /// Price a bid placed at `at` has to reach: the dutch price, or the reserve price for other auctions
+    fn min_price_at(&self, at: DateTime<Utc>) -> Money {
+        self.price_at(at).unwrap_or_else(|| self.reserve_price.clone())
+    }
+
+    /// Checks the auction rules for a new bid. `highest_bid` is the current highest open bid on the asset.
+    pub fn validate_bid(&self, bid: &Bid, highest_bid: Option<&Bid>) -> Result<(), DomainError> {
+        if !self.accepts_bids_at(bid.created_at) {
+            return Err(DomainError::ValidationError("auction is not accepting bids".to_string()));
+        }
+        if bid.amount.currency() != self.currency() {
+            return Err(DomainError::ValidationError(format!("auction bids must be in {}", self.currency())));
+        }
+        match self.auction_type {
+            AuctionType::English => {
+                if let Some(highest_bid) = highest_bid {
+                    let min_amount = highest_bid.amount.checked_add(&self.bid_increment)?;
+                    if bid.amount.try_cmp(&min_amount)?.is_lt() {
+                        return Err(DomainError::ValidationError(format!("bid amount must be at least {}", min_amount)));
+                    }
+                }
+            }
+            AuctionType::Dutch => {
+                let price = self.min_price_at(bid.created_at);
+                if bid.amount.try_cmp(&price)?.is_lt() {
+                    return Err(DomainError::ValidationError(format!("bid amount must be at least {}", price)));
+                }
+            }
+            AuctionType::SealedBid => {}
+        }
+        Ok(())
+    }
+
+    /// Picks the winner among the open bids of the auction, bids below the reserve price or in another currency never win:
+    /// - English and sealed-bid: the highest bid, the earliest bid wins a tie
+    /// - Dutch: the earliest bid at or above the price of the auction when it was placed
+    pub fn pick_winner<'a>(&self, bids: &'a [Bid]) -> Option<&'a Bid> {
+        let eligible = bids.iter()
+            .filter(|bid| bid.amount.try_cmp(&self.min_price_at(bid.created_at)).is_ok_and(|o| o.is_ge()));
+        match self.auction_type {
+            // eligible bids are all in the currency of the auction
+            AuctionType::English | AuctionType::SealedBid => eligible.reduce(|best, bid| {
+                let (amount, best_amount) = (bid.amount.minor_units(), best.amount.minor_units());
+                if amount > best_amount || (amount == best_amount && bid.created_at < best.created_at) {
+                    bid
+                } else {
+                    best
+                }
+            }),
+            AuctionType::Dutch => eligible.min_by_key(|bid| bid.created_at),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{test_currency, BidStatus, Currency};
+
+    fn usd(amount: &str) -> Money {
+        Money::parse(amount, &test_currency(Currency::USD)).unwrap()
+    }
+
+    fn bid(amount: &str, created_at: DateTime<Utc>) -> Bid {
+        Bid {
+            created_at,
+            anonymous: false,
+            amount: usd(amount),
+            updated_at: created_at,
+            status: BidStatus::Open,
+            id: format!("bid-{}", amount),
+            asset_id: "asset_id".to_string(),
+            bidder_fp: "bidder_fp".to_string(),
+            bidder_org: "bidder_org".to_string(),
+            expires_at: created_at + Duration::days(1),
+        }
+    }
+
+    fn auction(auction_type: AuctionType, dutch_decay: Option<DutchDecay>) -> Auction {
+        let start = Utc::now() - Duration::hours(1);
+        Auction::new(auction_type, start, start + Duration::days(1), usd("50"), usd("5"), dutch_decay).unwrap()
+    }
+
+    #[test]
+    fn test_new_auction_validation() {
+        let now = Utc::now();
+        assert!(Auction::new(AuctionType::English, now, now - Duration::hours(1), usd("1"), usd("1"), None).is_err());
+        assert!(Auction::new(AuctionType::English, now, now + Duration::hours(1), usd("1"), usd("0"), None).is_err());
+        assert!(Auction::new(AuctionType::Dutch, now, now + Duration::hours(1), usd("1"), usd("0"), None).is_err());
+        assert!(Auction::new(AuctionType::SealedBid, now, now + Duration::days(MAX_BID_TTL_DAYS + 1), usd("1"), usd("0"), None).is_err());
+        assert!(Auction::new(AuctionType::SealedBid, now, now + Duration::hours(1), usd("1"), usd("0"), None).is_ok());
+        let eur_increment = Money::parse("1", &test_currency(Currency::EUR)).unwrap();
+        assert!(Auction::new(AuctionType::English, now, now + Duration::hours(1), usd("1"), eur_increment, None).is_err());
+    }
+
+    #[test]
+    fn test_dutch_price_decays_to_reserve() {
+        let decay = DutchDecay { start_price: usd("100"), decay_amount: usd("10"), decay_interval_secs: 600 };
+        let auction = auction(AuctionType::Dutch, Some(decay));
+        assert_eq!(auction.price_at(auction.start_time), Some(usd("100")));
+        assert_eq!(auction.price_at(auction.start_time + Duration::minutes(25)), Some(usd("80")));
+        assert_eq!(auction.price_at(auction.start_time + Duration::hours(5)), Some(usd("50")));
+    }
+
+    #[test]
+    fn test_english_bids_must_beat_highest_bid_by_increment() {
+        let auction = auction(AuctionType::English, None);
+        let now = Utc::now();
+        let highest = bid("60", now);
+        assert!(auction.validate_bid(&bid("64.99", now), Some(&highest)).is_err());
+        assert!(auction.validate_bid(&bid("65", now), Some(&highest)).is_ok());
+        assert!(auction.validate_bid(&bid("10", now), None).is_ok());
+
+        let mut eur_bid = bid("100", now);
+        eur_bid.amount = Money::parse("100", &test_currency(Currency::EUR)).unwrap();
+        assert!(auction.validate_bid(&eur_bid, None).is_err());
+    }
+
+    #[test]
+    fn test_pick_winner_by_auction_type() {
+        let now = Utc::now();
+        let bids = vec![bid("40", now), bid("70", now + Duration::seconds(1)), bid("60", now - Duration::seconds(1))];
+
+        let english = auction(AuctionType::English, None);
+        assert_eq!(english.pick_winner(&bids).unwrap().amount, usd("70"));
+        let sealed = auction(AuctionType::SealedBid, None);
+        assert_eq!(sealed.pick_winner(&bids).unwrap().amount, usd("70"));
+
+        let decay = DutchDecay { start_price: usd("100"), decay_amount: usd("1"), decay_interval_secs: 600 };
+        let dutch = auction(AuctionType::Dutch, Some(decay));
+        // price is 94.0 an hour after start, no bid qualifies
+        assert!(dutch.pick_winner(&bids).is_none());
+        let early_bid = bid("100", dutch.start_time + Duration::minutes(5));
+        let late_bid = bid("99", dutch.start_time + Duration::minutes(15));
+        assert_eq!(dutch.pick_winner(&[late_bid, early_bid]).unwrap().amount, usd("100"));
+
+        // bids below the reserve price never win
+        assert!(english.pick_winner(&[bid("49.99", now)]).is_none());
+    }
+}
@@ -0,0 +1,241 @@
+use crate::core::domain::key::{generate_unique_key, DOMAIN_KEY_SIZE};
+use crate::core::{Asset, Contract, DomainError, FxRate, Money};
+use chrono::{DateTime, Duration, Utc};
+use std::fmt::{Display, Formatter};
+use strum_macros::EnumString;
+use uuid::Uuid;
+
+pub const DEFAULT_BID_TTL_DAYS: i64 = 7;
+pub const MAX_BID_TTL_DAYS: i64 = 90;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, sqlx::Type)]
+#[sqlx(type_name = "bid_status", rename_all = "lowercase")]
+#[strum(ascii_case_insensitive)]
+pub enum BidStatus {
+    Open,
+    Withdrawn,
+    Accepted,
+    Rejected,
+    Expired,
+}
+
+impl Display for BidStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BidStatus::Open => write!(f, "open"),
+            BidStatus::Withdrawn => write!(f, "withdrawn"),
+            BidStatus::Accepted => write!(f, "accepted"),
+            BidStatus::Rejected => write!(f, "rejected"),
+            BidStatus::Expired => write!(f, "expired"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Bid {
+    pub id: String,
+    pub amount: Money,
+    pub asset_id: String,
+    pub bidder_fp: String,
+    pub bidder_org: String,
+    pub anonymous: bool,
+    pub status: BidStatus,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Display for Bid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "bidId:{}, assetId:{}, status={}", self.id, self.asset_id, self.status)
+    }
+}
+
+impl Bid {
+    /// Creates a new open bid for `asset`, validated against the asset's `contract`.
+    /// If `expires_at` is not set, the bid expires after `DEFAULT_BID_TTL_DAYS`.
+    /// Bids on an auction always expire at the end of the auction.
+    /// `fx_rate` converts the min price of the contract to the currency of the bid, see `validate_against`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(asset: &Asset,
+               contract: &Contract,
+               bidder_fp: String,
+               bidder_org: String,
+               amount: Money,
+               anonymous: bool,
+               expires_at: Option<DateTime<Utc>>,
+               fx_rate: Option<&FxRate>) -> Result<Self, DomainError> {
+        let now = Utc::now();
+        // bids on an auction stay open until the auction closes
+        let expires_at = match &contract.auction {
+            Some(auction) => auction.end_time,
+            None => expires_at.unwrap_or_else(|| now + Duration::days(DEFAULT_BID_TTL_DAYS)),
+        };
+        if expires_at <= now {
+            return Err(DomainError::InvalidArgument("bid expiry must be in the future".to_string()));
+        }
+        if expires_at > now + Duration::days(MAX_BID_TTL_DAYS) {
+            let error = format!("bid can not expire more than {MAX_BID_TTL_DAYS} days from now");
+            return Err(DomainError::InvalidArgument(error));
+        }
+        if Uuid::parse_str(&bidder_org).is_err() {
+            return Err(DomainError::InvalidArgument("bidder orgId should be a valid UUID".to_string()));
+        }
+
+        let bid = Self {
+            amount,
+            anonymous,
+            bidder_fp,
+            bidder_org,
+            expires_at,
+            created_at: now,
+            updated_at: now,
+            status: BidStatus::Open,
+            asset_id: asset.id.clone(),
+            id: generate_unique_key(DOMAIN_KEY_SIZE),
+        };
+        bid.validate_against(asset, contract, fx_rate)?;
+        Ok(bid)
+    }
+
+    /// Checks that the bid satisfies the terms of the asset's contract.
+    /// A bid in another currency than the min price is compared to the min price converted with `fx_rate`.
+    pub fn validate_against(&self, asset: &Asset, contract: &Contract, fx_rate: Option<&FxRate>) -> Result<(), DomainError> {
+        if contract.asset_id != asset.id || self.asset_id != asset.id {
+            return Err(DomainError::ValidationError("contract does not belong to asset".to_string()));
+        }
+        if !asset.tradable {
+            return Err(DomainError::ValidationError("asset is not tradable".to_string()));
+        }
+        if contract.is_expired(Utc::now()) {
+            return Err(DomainError::ValidationError("contract has expired".to_string()));
+        }
+        if asset.owner_fp == self.bidder_fp {
+            return Err(DomainError::ValidationError("asset owner can not bid on own asset".to_string()));
+        }
+        if !contract.accepted_currency.contains(self.amount.currency()) {
+            let error = format!("currency {} is not accepted for this asset", self.amount.currency());
+            return Err(DomainError::ValidationError(error));
+        }
+        if !self.amount.is_positive() {
+            return Err(DomainError::ValidationError("bid amount must be greater than 0".to_string()));
+        }
+        let min_price = contract.min_price_in(&self.amount, fx_rate)?;
+        if self.amount.try_cmp(&min_price)?.is_lt() {
+            let error = format!("bid amount must be at least {}", min_price);
+            return Err(DomainError::ValidationError(error));
+        }
+        if contract.anonymous_buyer_only && !self.anonymous {
+            return Err(DomainError::ValidationError("asset only accepts anonymous bids".to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.status == BidStatus::Open && self.expires_at > Utc::now()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{test_currency, Currency};
+    use std::collections::HashSet;
+
+    fn tradable_asset() -> Asset {
+        let mut asset = Asset::new("asset-name".to_string(),
+                                   "XRF".to_string(),
+                                   "owner_fp".to_string(),
+                                   "description".to_string(),
+                                   Uuid::new_v4().to_string())
+            .unwrap();
+        asset.tradable = true;
+        asset
+    }
+
+    fn contract_for(asset: &Asset, anonymous_buyer_only: bool) -> Contract {
+        Contract::new(asset.id.clone(),
+                      "details".to_string(),
+                      "summary".to_string(),
+                      "owner_fp".to_string(),
+                      Money::parse("20", &test_currency(Currency::USD)).unwrap(),
+                      anonymous_buyer_only,
+                      vec![],
+                      HashSet::from([Currency::USD, Currency::BTC]))
+            .unwrap()
+    }
+
+    fn place_bid(asset: &Asset, contract: &Contract, amount: &str, currency: Currency, anonymous: bool)
+                 -> Result<Bid, DomainError> {
+        Bid::new(asset, contract, "bidder_fp".to_string(), Uuid::new_v4().to_string(),
+                 Money::parse(amount, &test_currency(currency)).unwrap(), anonymous, None, None)
+    }
+
+    #[test]
+    fn test_new_bid_success() {
+        let asset = tradable_asset();
+        let contract = contract_for(&asset, false);
+        let bid = place_bid(&asset, &contract, "25", Currency::USD, false).unwrap();
+        assert_eq!(bid.status, BidStatus::Open);
+        assert_eq!(bid.asset_id, asset.id);
+        assert!(bid.is_open());
+    }
+
+    #[test]
+    fn test_new_bid_rejects_contract_violations() {
+        let asset = tradable_asset();
+        let contract = contract_for(&asset, false);
+        assert!(place_bid(&asset, &contract, "19.99", Currency::USD, false).is_err());
+        assert!(place_bid(&asset, &contract, "25", Currency::EUR, false).is_err());
+        assert!(place_bid(&asset, &contract, "0", Currency::BTC, false).is_err());
+
+        let anonymous_contract = contract_for(&asset, true);
+        assert!(place_bid(&asset, &anonymous_contract, "25", Currency::USD, false).is_err());
+        assert!(place_bid(&asset, &anonymous_contract, "25", Currency::USD, true).is_ok());
+
+        let mut untradable = asset.clone();
+        untradable.tradable = false;
+        assert!(place_bid(&untradable, &contract, "25", Currency::USD, false).is_err());
+    }
+
+    #[test]
+    fn test_bid_in_other_currency_is_compared_to_converted_min_price() {
+        let asset = tradable_asset();
+        let contract = contract_for(&asset, false);
+        let btc_usd = FxRate::new(Currency::BTC, Currency::USD, 65000.into(), Utc::now()).unwrap();
+        let usd_btc = btc_usd.inverse();
+        let btc_bid = |amount: &str, fx_rate: Option<&FxRate>| {
+            Bid::new(&asset, &contract, "bidder_fp".to_string(), Uuid::new_v4().to_string(),
+                     Money::parse(amount, &test_currency(Currency::BTC)).unwrap(), false, None, fx_rate)
+        };
+        // 0.001 BTC is worth 65 USD, 0.0001 BTC is worth 6.50 USD
+        assert!(btc_bid("0.001", Some(&usd_btc)).is_ok());
+        assert!(btc_bid("0.0001", Some(&usd_btc)).is_err());
+        // the rate must go from the currency of the min price to the currency of the bid
+        assert!(btc_bid("0.001", None).is_err());
+        assert!(btc_bid("0.001", Some(&btc_usd)).is_err());
+    }
+
+    #[test]
+    fn test_new_bid_rejects_invalid_expiry_and_owner() {
+        let asset = tradable_asset();
+        let contract = contract_for(&asset, false);
+        let past = Utc::now() - Duration::minutes(1);
+        let amount = Money::parse("25", &test_currency(Currency::USD)).unwrap();
+        let result = Bid::new(&asset, &contract, "bidder_fp".to_string(), Uuid::new_v4().to_string(),
+                              amount.clone(), false, Some(past), None);
+        assert!(result.is_err());
+
+        let result = Bid::new(&asset, &contract, asset.owner_fp.clone(), Uuid::new_v4().to_string(),
+                              amount, false, None, None);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_bid_status_from_str() {
+        use std::str::FromStr;
+        assert_eq!(BidStatus::from_str("open").unwrap(), BidStatus::Open);
+        assert_eq!(BidStatus::from_str("WITHDRAWN").unwrap(), BidStatus::Withdrawn);
+        assert!(BidStatus::from_str("unknown").is_err());
+    }
+}
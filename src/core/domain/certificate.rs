@@ -0,0 +1,132 @@
+use crate::core::{DomainError, NFC};
+use base64::engine::general_purpose;
+use base64::Engine;
+use chrono::{DateTime, SecondsFormat, Utc};
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+/// Version of the signed message, changes whenever the layout of the message changes
+pub const CERTIFICATE_FORMAT: &str = "xrf1-nfc-v1";
+
+/// Statement that an asset, certified by an NFC, is owned by `owner_fp`.
+/// The certificate of an NFC is the Ed25519 signature of this statement by the server key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnershipClaim {
+    pub asset_id: String,
+    pub nfc_id: String,
+    pub created_at: DateTime<Utc>,
+    pub owner_fp: String,
+}
+
+impl OwnershipClaim {
+    pub fn for_nfc(nfc: &NFC, owner_fp: &str) -> Self {
+        Self {
+            asset_id: nfc.asset_id.clone(),
+            nfc_id: nfc.id.clone(),
+            created_at: nfc.created_at,
+            owner_fp: owner_fp.to_string(),
+        }
+    }
+
+    /// Signed bytes: `xrf1-nfc-v1|<asset_id>|<nfc_id>|<created_at as RFC 3339 UTC with microseconds>|<owner_fp>`
+    pub fn message(&self) -> String {
+        format!("{}|{}|{}|{}|{}",
+                CERTIFICATE_FORMAT,
+                self.asset_id,
+                self.nfc_id,
+                self.created_at.to_rfc3339_opts(SecondsFormat::Micros, true),
+                self.owner_fp)
+    }
+}
+
+/// Signs ownership claims with the server Ed25519 key
+#[derive(Clone)]
+pub struct CertificateSigner {
+    key_pair: Arc<Ed25519KeyPair>,
+}
+
+impl Debug for CertificateSigner {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CertificateSigner(public_key={})", self.public_key_base64())
+    }
+}
+
+impl CertificateSigner {
+    /// Loads a PKCS#8 encoded Ed25519 key, as written by `openssl genpkey -algorithm ed25519 -outform DER`
+    pub fn from_pkcs8(pkcs8: &[u8]) -> Result<Self, DomainError> {
+        let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(pkcs8)
+            .map_err(|e| DomainError::InvalidArgument(format!("invalid Ed25519 signing key: {}", e)))?;
+        Ok(Self { key_pair: Arc::new(key_pair) })
+    }
+
+    /// Generates a new key, certificates signed with it can not be verified once the process stops
+    pub fn generate() -> Result<Self, DomainError> {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .map_err(|e| DomainError::ServerError(format!("failed to generate signing key: {}", e)))?;
+        Self::from_pkcs8(pkcs8.as_ref())
+    }
+
+    pub fn public_key(&self) -> &[u8] {
+        self.key_pair.public_key().as_ref()
+    }
+
+    pub fn public_key_base64(&self) -> String {
+        general_purpose::URL_SAFE_NO_PAD.encode(self.public_key())
+    }
+
+    /// URL-safe base64 encoded signature of the claim
+    pub fn sign(&self, claim: &OwnershipClaim) -> String {
+        let signature = self.key_pair.sign(claim.message().as_bytes());
+        general_purpose::URL_SAFE_NO_PAD.encode(signature.as_ref())
+    }
+
+    pub fn verify(&self, claim: &OwnershipClaim, certificate: &str) -> bool {
+        verify_certificate(self.public_key(), claim, certificate)
+    }
+}
+
+/// Checks a certificate against a public key, which is all a third party needs to verify an ownership proof
+pub fn verify_certificate(public_key: &[u8], claim: &OwnershipClaim, certificate: &str) -> bool {
+    let Ok(signature) = general_purpose::URL_SAFE_NO_PAD.decode(certificate.trim()) else {
+        return false;
+    };
+    UnparsedPublicKey::new(&ED25519, public_key)
+        .verify(claim.message().as_bytes(), &signature)
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claim() -> OwnershipClaim {
+        OwnershipClaim {
+            asset_id: "asset_id".to_string(),
+            nfc_id: "nfc_id".to_string(),
+            created_at: DateTime::parse_from_rfc3339("2026-10-16T09:30:00.123456Z").unwrap().to_utc(),
+            owner_fp: "owner_fp".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_message_layout() {
+        assert_eq!(claim().message(), "xrf1-nfc-v1|asset_id|nfc_id|2026-10-16T09:30:00.123456Z|owner_fp");
+    }
+
+    #[test]
+    fn test_certificate_only_verifies_signed_claim() {
+        let signer = CertificateSigner::generate().unwrap();
+        let certificate = signer.sign(&claim());
+        assert!(signer.verify(&claim(), &certificate));
+        assert!(verify_certificate(signer.public_key(), &claim(), &certificate));
+
+        let transferred = OwnershipClaim { owner_fp: "new_owner_fp".to_string(), ..claim() };
+        assert!(!signer.verify(&transferred, &certificate));
+        assert!(!signer.verify(&claim(), "not-a-certificate"));
+
+        let other_signer = CertificateSigner::generate().unwrap();
+        assert!(!other_signer.verify(&claim(), &certificate));
+    }
+}
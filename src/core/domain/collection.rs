@@ -0,0 +1,290 @@
+use crate::core::domain::key::{generate_unique_key, DOMAIN_KEY_SIZE};
+use crate::core::{Asset, Contract, ContractTerms, Currency, DomainError, Money, RoyaltySplit};
+use chrono::{DateTime, SubsecRound, Utc};
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+const MIN_NAME_LENGTH: usize = 3;
+const MAX_NAME_LENGTH: usize = 64;
+const MAX_SYMBOL_PREFIX_LENGTH: usize = 6;
+const MAX_DESCRIPTION_LENGTH: usize = 2048;
+
+/// Series of assets released by an organization.
+/// Assets join a collection when they are created, see `Collection::admit`.
+#[derive(Debug, Clone)]
+pub struct Collection {
+    pub id: String,
+    pub organization: String,
+    pub name: String,
+    /// upper case, the symbol of every asset of the collection starts with it
+    pub symbol_prefix: String,
+    pub description: String,
+    /// maximum number of assets in the collection, unlimited when not set
+    pub supply_cap: Option<i32>,
+    pub default_contract: Option<DefaultContract>,
+    pub created_by: String,
+    pub updated_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Display for Collection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "collectionId:{}, org:{}, name:{}, symbolPrefix:{}, supplyCap:{:?}",
+               self.id, self.organization, self.name, self.symbol_prefix, self.supply_cap)
+    }
+}
+
+impl Collection {
+    pub fn new(organization: String,
+               name: String,
+               symbol_prefix: String,
+               description: String,
+               supply_cap: Option<i32>,
+               default_contract: Option<DefaultContract>,
+               user_fp: String) -> Result<Self, DomainError> {
+        // timestamps are stored with microseconds
+        let now = Utc::now().trunc_subsecs(6);
+        let collection = Self {
+            organization,
+            description,
+            supply_cap,
+            default_contract,
+            id: generate_unique_key(DOMAIN_KEY_SIZE),
+            name: name.trim().to_string(),
+            symbol_prefix: symbol_prefix.trim().to_uppercase(),
+            created_by: user_fp.clone(),
+            updated_by: user_fp,
+            created_at: now,
+            updated_at: now,
+        };
+        collection.validate()?;
+        Ok(collection)
+    }
+
+    /// Returns the collection with the update applied, the collection itself is left unchanged.
+    /// The symbol prefix never changes, the symbols of the assets already in the collection start with it.
+    pub fn apply_update(&self, update: &UpdateCollectionRequest, user_fp: &str) -> Result<Self, DomainError> {
+        if update.is_empty() {
+            return Err(DomainError::InvalidArgument("no collection field to update".to_string()));
+        }
+        let mut collection = self.clone();
+        if let Some(name) = &update.name {
+            collection.name = name.trim().to_string();
+        }
+        if let Some(description) = &update.description {
+            collection.description = description.clone();
+        }
+        if let Some(supply_cap) = update.supply_cap {
+            collection.supply_cap = supply_cap;
+        }
+        if let Some(default_contract) = &update.default_contract {
+            collection.default_contract = default_contract.clone();
+        }
+        collection.updated_by = user_fp.to_string();
+        collection.updated_at = Utc::now().trunc_subsecs(6);
+        collection.validate()?;
+        Ok(collection)
+    }
+
+    /// Checks that the asset can join the collection while it holds `asset_count` assets
+    pub fn admit(&self, asset: &Asset, asset_count: i64) -> Result<(), DomainError> {
+        if asset.organization != self.organization {
+            return Err(DomainError::InvalidArgument("asset and collection belong to different organizations".to_string()));
+        }
+        if !asset.symbol.starts_with(&self.symbol_prefix) {
+            let error = format!("asset symbol should start with the collection symbol prefix {}", self.symbol_prefix);
+            return Err(DomainError::InvalidArgument(error));
+        }
+        self.check_supply(asset_count + 1)
+    }
+
+    /// Checks that the collection can hold `asset_count` assets
+    pub fn check_supply(&self, asset_count: i64) -> Result<(), DomainError> {
+        match self.supply_cap {
+            Some(supply_cap) if asset_count > supply_cap as i64 => {
+                Err(DomainError::ValidationError(format!("collection supply cap of {} assets is reached", supply_cap)))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn validate(&self) -> Result<(), DomainError> {
+        let name_length = self.name.chars().count();
+        if !(MIN_NAME_LENGTH..=MAX_NAME_LENGTH).contains(&name_length) {
+            let error = format!("name should be between {MIN_NAME_LENGTH} and {MAX_NAME_LENGTH} characters long");
+            return Err(DomainError::InvalidArgument(error));
+        }
+        if self.symbol_prefix.is_empty() || self.symbol_prefix.len() > MAX_SYMBOL_PREFIX_LENGTH {
+            let error = format!("symbol prefix should be between 1 and {MAX_SYMBOL_PREFIX_LENGTH} characters long");
+            return Err(DomainError::InvalidArgument(error));
+        }
+        if !self.symbol_prefix.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(DomainError::InvalidArgument("symbol prefix should only contain letters, digits or '-'".to_string()));
+        }
+        if self.description.chars().count() > MAX_DESCRIPTION_LENGTH {
+            let error = format!("description should be at most {MAX_DESCRIPTION_LENGTH} characters long");
+            return Err(DomainError::InvalidArgument(error));
+        }
+        if self.supply_cap.is_some_and(|supply_cap| supply_cap < 1) {
+            return Err(DomainError::InvalidArgument("supply cap should be at least 1".to_string()));
+        }
+        if self.organization.trim().is_empty() {
+            return Err(DomainError::InvalidArgument("organization is required".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Contract every new asset of a collection is created with, as a V2 contract
+#[derive(Debug, Clone, PartialEq)]
+pub struct DefaultContract {
+    pub details: String,
+    pub summary: String,
+    pub min_price: Money,
+    pub anonymous_buyer_only: bool,
+    pub accepted_currency: HashSet<Currency>,
+    pub royalty_splits: Vec<RoyaltySplit>,
+    pub terms: ContractTerms,
+}
+
+impl DefaultContract {
+    /// Validated like the contracts it becomes
+    pub fn new(details: String,
+               summary: String,
+               min_price: Money,
+               anonymous_buyer_only: bool,
+               royalty_splits: Vec<RoyaltySplit>,
+               terms: ContractTerms,
+               accepted_currency: HashSet<Currency>) -> Result<Self, DomainError> {
+        let default_contract = Self { details, summary, min_price, anonymous_buyer_only, accepted_currency, royalty_splits, terms };
+        default_contract.contract_for(String::new(), String::new())?;
+        Ok(default_contract)
+    }
+
+    /// Contract of a new asset of the collection
+    pub fn contract_for(&self, asset_id: String, user_fp: String) -> Result<Contract, DomainError> {
+        Contract::new_v2(asset_id,
+                         self.details.clone(),
+                         self.summary.clone(),
+                         user_fp,
+                         self.min_price.clone(),
+                         self.anonymous_buyer_only,
+                         self.royalty_splits.clone(),
+                         self.terms.clone(),
+                         self.accepted_currency.clone())
+    }
+}
+
+/// Fields of a collection to update, fields that are not set are left unchanged.
+/// `supply_cap` and `default_contract` are removed when set to None.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateCollectionRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub supply_cap: Option<Option<i32>>,
+    pub default_contract: Option<Option<DefaultContract>>,
+}
+
+impl UpdateCollectionRequest {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.description.is_none() && self.supply_cap.is_none() && self.default_contract.is_none()
+    }
+}
+
+impl Display for UpdateCollectionRequest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "name:{:?}, supplyCap:{:?}, updatesDescription:{}, updatesDefaultContract:{}",
+               self.name, self.supply_cap, self.description.is_some(), self.default_contract.is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_currency;
+    use std::str::FromStr;
+    use uuid::Uuid;
+
+    fn collection(supply_cap: Option<i32>) -> Collection {
+        Collection::new(Uuid::new_v4().to_string(),
+                        "Starry series".to_string(),
+                        "sty".to_string(),
+                        "paintings of the night sky".to_string(),
+                        supply_cap,
+                        None,
+                        "creator".to_string()).unwrap()
+    }
+
+    fn asset(collection: &Collection, symbol: &str) -> Asset {
+        Asset::new("starry night".to_string(), symbol.to_string(), "owner".to_string(), "".to_string(), collection.organization.clone())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_collection_admits_assets_up_to_its_supply_cap() {
+        let collection = collection(Some(2));
+        assert_eq!(collection.symbol_prefix, "STY");
+        let asset = asset(&collection, "sty-01");
+        assert!(collection.admit(&asset, 0).is_ok());
+        assert!(collection.admit(&asset, 1).is_ok());
+        assert!(matches!(collection.admit(&asset, 2), Err(DomainError::ValidationError(_))));
+    }
+
+    #[test]
+    fn test_collection_refuses_assets_of_other_series() {
+        let collection = collection(None);
+        assert!(collection.admit(&asset(&collection, "XRF-01"), 0).is_err());
+
+        let mut other_org = asset(&collection, "STY-01");
+        other_org.organization = Uuid::new_v4().to_string();
+        assert!(collection.admit(&other_org, 0).is_err());
+    }
+
+    #[test]
+    fn test_invalid_collection_is_rejected() {
+        let org = Uuid::new_v4().to_string();
+        let new = |name: &str, prefix: &str, supply_cap| {
+            Collection::new(org.clone(), name.to_string(), prefix.to_string(), "".to_string(), supply_cap, None, "creator".to_string())
+        };
+        assert!(new("ab", "STY", None).is_err());
+        assert!(new("Starry series", "", None).is_err());
+        assert!(new("Starry series", "TOOLONG", None).is_err());
+        assert!(new("Starry series", "S Y", None).is_err());
+        assert!(new("Starry series", "STY", Some(0)).is_err());
+    }
+
+    #[test]
+    fn test_update_can_remove_supply_cap() {
+        let collection = collection(Some(2));
+        let update = UpdateCollectionRequest { supply_cap: Some(None), ..Default::default() };
+        let updated = collection.apply_update(&update, "editor").unwrap();
+        assert_eq!(updated.supply_cap, None);
+        assert_eq!(updated.updated_by, "editor");
+        assert!(collection.apply_update(&UpdateCollectionRequest::default(), "editor").is_err());
+    }
+
+    #[test]
+    fn test_default_contract_becomes_v2_contract_of_asset() {
+        let usd = Currency::from_str("USD").unwrap();
+        let default_contract = DefaultContract::new("details".to_string(),
+                                                    "summary".to_string(),
+                                                    Money::parse("20.00", &test_currency(usd.clone())).unwrap(),
+                                                    false,
+                                                    vec![],
+                                                    ContractTerms::default(),
+                                                    HashSet::from([usd.clone()])).unwrap();
+        let contract = default_contract.contract_for("asset-id".to_string(), "creator".to_string()).unwrap();
+        assert_eq!(contract.asset_id, "asset-id");
+        assert_eq!(contract.version, crate::core::ContractVersion::V2);
+
+        let eur = Currency::from_str("EUR").unwrap();
+        assert!(DefaultContract::new("details".to_string(),
+                                     "summary".to_string(),
+                                     Money::parse("20.00", &test_currency(usd)).unwrap(),
+                                     false,
+                                     vec![],
+                                     ContractTerms::default(),
+                                     HashSet::from([eur])).is_err());
+    }
+}
@@ -1,52 +1,98 @@
 use crate::core::domain::key::{generate_unique_key, DOMAIN_KEY_SIZE};
-use crate::core::{Currency, DomainError};
-use chrono::{DateTime, Utc};
+use crate::core::{Auction, Currency, DomainError, FxRate, Money};
+use bigdecimal::RoundingMode;
+use chrono::{DateTime, Duration, Utc};
 use std::collections::HashSet;
 use std::fmt::Display;
+use std::str::FromStr;
 
-#[derive(Clone, Debug)]
+/// Royalty splits are expressed in basis points, 10000 bps is 100% of the sale price
+pub const MAX_ROYALTY_BASIS_POINTS: u32 = 10_000;
+
+/// Author of the revisions written by `Contract::upgrade_to_v2` when it is not run on behalf of a user
+pub const CONTRACT_UPGRADE_AUTHOR: &str = "system:contract-upgrade";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ContractVersion {
-    V1
+    /// Royalty splits only, no structured terms
+    V1,
+    /// Royalty splits with resale restrictions and an optional expiry
+    V2,
 }
 
 impl Display for ContractVersion {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            ContractVersion::V1 => write!(f, "v1")
+            ContractVersion::V1 => write!(f, "v1"),
+            ContractVersion::V2 => write!(f, "v2"),
         }
     }
 }
 
-impl From<String> for ContractVersion {
-    fn from(value: String) -> Self {
-        match value.as_str() {
-            "V1" => ContractVersion::V1,
-            _ => ContractVersion::V1,
+impl FromStr for ContractVersion {
+    type Err = DomainError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.eq_ignore_ascii_case("v1") {
+            Ok(ContractVersion::V1)
+        } else if value.eq_ignore_ascii_case("v2") {
+            Ok(ContractVersion::V2)
+        } else {
+            Err(DomainError::InvalidArgument(format!("unknown contract version '{}'", value)))
         }
     }
 }
 
+/// Share of the royalty of a sale paid to one receiver
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoyaltySplit {
+    pub receiver_fp: String,
+    pub basis_points: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResaleRestriction {
+    /// An asset that was bought under this contract can only be sold again if resale is allowed
+    pub allowed: bool,
+    /// Number of days the buyer has to hold the asset before selling it again
+    pub min_holding_days: u32,
+}
+
+impl Default for ResaleRestriction {
+    fn default() -> Self {
+        Self { allowed: true, min_holding_days: 0 }
+    }
+}
+
+/// Structured terms of a V2 contract, V1 contracts always have the default terms
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContractTerms {
+    pub resale: ResaleRestriction,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Contract {
     pub id: String,
     pub details: String,
-    pub min_price: f64,
+    pub min_price: Money,
     pub summary: String,
     pub asset_id: String,
     pub update_count: i32,
     pub updated_by: String,
-    pub royalty_percentage: f32,
     pub version: ContractVersion,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub anonymous_buyer_only: bool,
-    pub royalty_receiver_id: String,
     pub accepted_currency: HashSet<Currency>,
+    pub auction: Option<Auction>,
+    pub royalty_splits: Vec<RoyaltySplit>,
+    pub terms: ContractTerms,
 }
 
 impl Display for Contract {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "contractId:{}, assetId:{}, updated_count={}", self.id, self.asset_id, self.update_count)
+        write!(f, "contractId:{}, assetId:{}, version:{}, updated_count={}", self.id, self.asset_id, self.version, self.update_count)
     }
 }
 
@@ -55,43 +101,400 @@ impl Contract {
                details: String,
                summary: String,
                user_fp: String,
-               min_price: f64,
+               min_price: Money,
                anonymous_buyer: bool,
-               royalty_percentage: f32,
-               royalty_receiver_id: String,
+               royalty_splits: Vec<RoyaltySplit>,
                accepted_currency: HashSet<Currency>) -> Result<Self, DomainError> {
-        if royalty_percentage < 0.0 {
-            return Err(DomainError::InvalidArgument("royalty percentage can not be less than 0.0".to_string()));
-        }
-        if !royalty_receiver_id.is_empty() && royalty_percentage == 0.0 {
-            return Err(DomainError::InvalidArgument("royalty percentage can not be less than 0.0 if royalty receiver is specified".to_string()));
-        }
-        if royalty_receiver_id.is_empty() && royalty_percentage > 0.0 {
-            return Err(DomainError::InvalidArgument("if royalty percentage is set, royalty receiver can't be empty".to_string()));
-        }
-        if accepted_currency.is_empty() {
-            return Err(DomainError::InvalidArgument("accepted_currency should contain at least one currency".to_string()));
-        }
-        if min_price <= 0.0 {
-            return Err(DomainError::InvalidArgument("min_price must be greater than 0.0".to_string()));
-        }
-
         let id = generate_unique_key(DOMAIN_KEY_SIZE);
-        Ok(Self {
+        let contract = Self {
             id,
             details,
             summary,
             asset_id,
             min_price,
             update_count: 0,
+            royalty_splits,
             accepted_currency,
-            royalty_percentage,
             updated_by: user_fp,
-            royalty_receiver_id,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             version: ContractVersion::V1,
             anonymous_buyer_only: anonymous_buyer,
-        })
+            auction: None,
+            terms: ContractTerms::default(),
+        };
+        contract.validate()?;
+        Ok(contract)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_v2(asset_id: String,
+                  details: String,
+                  summary: String,
+                  user_fp: String,
+                  min_price: Money,
+                  anonymous_buyer: bool,
+                  royalty_splits: Vec<RoyaltySplit>,
+                  terms: ContractTerms,
+                  accepted_currency: HashSet<Currency>) -> Result<Self, DomainError> {
+        let mut contract = Self::new(asset_id,
+                                     details,
+                                     summary,
+                                     user_fp,
+                                     min_price,
+                                     anonymous_buyer,
+                                     royalty_splits,
+                                     accepted_currency)?;
+        contract.version = ContractVersion::V2;
+        contract.terms = terms;
+        contract.validate()?;
+        Ok(contract)
+    }
+
+    /// Returns the next revision of the contract with the update applied, the contract itself is left unchanged.
+    /// Updating the terms of a V1 contract upgrades it to V2.
+    pub fn apply_update(&self, update: &UpdateContractRequest, user_fp: &str) -> Result<Self, DomainError> {
+        if update.is_empty() {
+            return Err(DomainError::InvalidArgument("no contract field to update".to_string()));
+        }
+        let mut contract = self.clone();
+        if update.terms.is_some() {
+            contract.version = ContractVersion::V2;
+        }
+
+        if let Some(details) = &update.details {
+            contract.details = details.clone();
+        }
+        if let Some(summary) = &update.summary {
+            contract.summary = summary.clone();
+        }
+        if let Some(min_price) = &update.min_price {
+            contract.min_price = min_price.clone();
+        }
+        if let Some(anonymous_buyer_only) = update.anonymous_buyer_only {
+            contract.anonymous_buyer_only = anonymous_buyer_only;
+        }
+        if let Some(accepted_currency) = &update.accepted_currency {
+            contract.accepted_currency = accepted_currency.clone();
+        }
+        if let Some(royalty_splits) = &update.royalty_splits {
+            contract.royalty_splits = royalty_splits.clone();
+        }
+        if let Some(terms) = &update.terms {
+            contract.terms = terms.clone();
+        }
+
+        contract.update_count += 1;
+        contract.updated_at = Utc::now();
+        contract.updated_by = user_fp.to_string();
+        contract.validate()?;
+        Ok(contract)
+    }
+
+    /// Returns the next revision of a V1 contract in the V2 format with the same royalty splits and default terms.
+    pub fn upgrade_to_v2(&self, updated_by: &str) -> Result<Self, DomainError> {
+        if self.version != ContractVersion::V1 {
+            return Err(DomainError::InvalidArgument(format!("contract is already {}", self.version)));
+        }
+        let mut contract = self.clone();
+        contract.version = ContractVersion::V2;
+        contract.update_count += 1;
+        contract.updated_at = Utc::now();
+        contract.updated_by = updated_by.to_string();
+        contract.validate()?;
+        Ok(contract)
+    }
+
+    fn validate(&self) -> Result<(), DomainError> {
+        if self.accepted_currency.is_empty() {
+            return Err(DomainError::InvalidArgument("accepted_currency should contain at least one currency".to_string()));
+        }
+        if !self.min_price.is_positive() {
+            return Err(DomainError::InvalidArgument("min_price must be greater than 0".to_string()));
+        }
+        if !self.accepted_currency.contains(self.min_price.currency()) {
+            return Err(DomainError::InvalidArgument("min_price must be in an accepted currency".to_string()));
+        }
+        validate_royalty_splits(&self.royalty_splits)?;
+        match self.version {
+            ContractVersion::V1 => {
+                if self.terms != ContractTerms::default() {
+                    return Err(DomainError::InvalidArgument("v1 contracts do not support terms".to_string()));
+                }
+            }
+            ContractVersion::V2 => {
+                if self.terms.expires_at.is_some_and(|expires_at| expires_at <= self.updated_at) {
+                    return Err(DomainError::InvalidArgument("contract expiry must be in the future".to_string()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Royalties owed on a sale of `amount`, one entry per receiver. Shares are rounded down to a whole minor unit.
+    pub fn royalty_shares(&self, amount: &Money) -> Result<Vec<(String, Money)>, DomainError> {
+        self.royalty_splits.iter()
+            .map(|split| Ok((split.receiver_fp.clone(), amount.basis_point_share(split.basis_points)?)))
+            .collect()
+    }
+
+    /// The min price in the currency of `amount`. A min price in another currency is converted with `fx_rate`,
+    /// which must go from the currency of the min price to that of `amount`. The converted price is rounded up,
+    /// to the decimals of `amount`, so that a bid never undercuts the min price.
+    pub fn min_price_in(&self, amount: &Money, fx_rate: Option<&FxRate>) -> Result<Money, DomainError> {
+        let currency = amount.currency();
+        if self.min_price.currency() == currency {
+            return Ok(self.min_price.clone());
+        }
+        match fx_rate {
+            Some(rate) if rate.base == *self.min_price.currency() && rate.quote == *currency => {
+                rate.convert(&self.min_price, amount.decimals(), RoundingMode::Up)
+            }
+            _ => {
+                let error = format!("no exchange rate from {} to {}", self.min_price.currency(), currency);
+                Err(DomainError::ValidationError(error))
+            }
+        }
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.terms.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Checks the resale restriction of the contract, `last_sale_at` is the time the asset was last sold
+    pub fn check_resale(&self, last_sale_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Result<(), DomainError> {
+        let Some(last_sale_at) = last_sale_at else {
+            return Ok(());
+        };
+        if !self.terms.resale.allowed {
+            return Err(DomainError::ValidationError("contract does not allow the asset to be resold".to_string()));
+        }
+        let resellable_at = last_sale_at + Duration::days(self.terms.resale.min_holding_days as i64);
+        if now < resellable_at {
+            return Err(DomainError::ValidationError(format!("asset can not be resold before {}", resellable_at)));
+        }
+        Ok(())
+    }
+
+    /// Sells the asset through an auction instead of direct bid acceptance.
+    /// The reserve price of the auction can not be lower than the minimum price of the contract.
+    pub fn with_auction(mut self, auction: Auction) -> Result<Self, DomainError> {
+        if auction.reserve_price.try_cmp(&self.min_price)?.is_lt() {
+            return Err(DomainError::InvalidArgument("auction reserve price can not be less than min_price".to_string()));
+        }
+        self.auction = Some(auction);
+        Ok(self)
+    }
+
+    /// Bids on a contract with a running auction are settled by the auction, not by the owner
+    pub fn has_active_auction(&self) -> bool {
+        self.auction.as_ref().is_some_and(|auction| !auction.is_closed())
+    }
+}
+
+fn validate_royalty_splits(royalty_splits: &[RoyaltySplit]) -> Result<(), DomainError> {
+    let mut receivers = HashSet::new();
+    let mut total_basis_points = 0;
+    for split in royalty_splits {
+        if split.receiver_fp.is_empty() {
+            return Err(DomainError::InvalidArgument("royalty receiver can't be empty".to_string()));
+        }
+        if split.basis_points == 0 {
+            return Err(DomainError::InvalidArgument("royalty split must be greater than 0 bps".to_string()));
+        }
+        if !receivers.insert(split.receiver_fp.as_str()) {
+            return Err(DomainError::InvalidArgument(format!("duplicate royalty receiver {}", split.receiver_fp)));
+        }
+        total_basis_points += split.basis_points;
+    }
+    if total_basis_points > MAX_ROYALTY_BASIS_POINTS {
+        let error = format!("royalty splits can not exceed {} bps :: total={}", MAX_ROYALTY_BASIS_POINTS, total_basis_points);
+        return Err(DomainError::InvalidArgument(error));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UpdateContractRequest {
+    pub details: Option<String>,
+    pub summary: Option<String>,
+    pub min_price: Option<Money>,
+    pub anonymous_buyer_only: Option<bool>,
+    pub accepted_currency: Option<HashSet<Currency>>,
+    pub royalty_splits: Option<Vec<RoyaltySplit>>,
+    pub terms: Option<ContractTerms>,
+}
+
+impl UpdateContractRequest {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        details: Option<String>,
+        summary: Option<String>,
+        min_price: Option<Money>,
+        anonymous_buyer_only: Option<bool>,
+        accepted_currency: Option<HashSet<Currency>>,
+        royalty_splits: Option<Vec<RoyaltySplit>>,
+        terms: Option<ContractTerms>,
+    ) -> Self {
+        Self {
+            terms,
+            details,
+            summary,
+            min_price,
+            royalty_splits,
+            accepted_currency,
+            anonymous_buyer_only,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.details.is_none()
+            && self.summary.is_none()
+            && self.min_price.is_none()
+            && self.anonymous_buyer_only.is_none()
+            && self.accepted_currency.is_none()
+            && self.royalty_splits.is_none()
+            && self.terms.is_none()
+    }
+}
+
+impl Display for UpdateContractRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "min_price:{:?}, anonymous_buyer_only:{:?}, royalty_splits:{:?}, accepted_currency:{:?}, terms:{:?}",
+               self.min_price, self.anonymous_buyer_only, self.royalty_splits, self.accepted_currency, self.terms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_currency;
+
+    fn contract() -> Contract {
+        Contract::new("asset_id".to_string(),
+                      "details".to_string(),
+                      "summary".to_string(),
+                      "owner_fp".to_string(),
+                      usd("10.00"),
+                      false,
+                      vec![],
+                      HashSet::from([Currency::USD]))
+            .unwrap()
+    }
+
+    fn usd(amount: &str) -> Money {
+        Money::parse(amount, &test_currency(Currency::USD)).unwrap()
+    }
+
+    fn split(receiver_fp: &str, basis_points: u32) -> RoyaltySplit {
+        RoyaltySplit { receiver_fp: receiver_fp.to_string(), basis_points }
+    }
+
+    fn contract_v2(royalty_splits: Vec<RoyaltySplit>, terms: ContractTerms) -> Result<Contract, DomainError> {
+        Contract::new_v2("asset_id".to_string(),
+                         "details".to_string(),
+                         "summary".to_string(),
+                         "owner_fp".to_string(),
+                         usd("10.00"),
+                         false,
+                         royalty_splits,
+                         terms,
+                         HashSet::from([Currency::USD]))
+    }
+
+    #[test]
+    fn test_contract_version_parsing() {
+        assert_eq!(ContractVersion::from_str("v1").unwrap(), ContractVersion::V1);
+        assert_eq!(ContractVersion::from_str("V1").unwrap(), ContractVersion::V1);
+        assert_eq!(ContractVersion::from_str("v2").unwrap(), ContractVersion::V2);
+        assert!(ContractVersion::from_str("v3").is_err());
+        assert!(ContractVersion::from_str("").is_err());
+        assert_eq!(ContractVersion::from_str(&ContractVersion::V2.to_string()).unwrap(), ContractVersion::V2);
+    }
+
+    #[test]
+    fn test_apply_update_creates_next_revision() {
+        let contract = contract();
+        let update = UpdateContractRequest {
+            min_price: Some(usd("25.00")),
+            royalty_splits: Some(vec![split("creator_fp", 500)]),
+            ..Default::default()
+        };
+        let updated = contract.apply_update(&update, "new_owner_fp").unwrap();
+        assert_eq!(updated.id, contract.id);
+        assert_eq!(updated.min_price, usd("25.00"));
+        assert_eq!(updated.update_count, 1);
+        assert_eq!(updated.updated_by, "new_owner_fp");
+        assert_eq!(updated.details, contract.details);
+        assert_eq!(contract.update_count, 0);
+    }
+
+    #[test]
+    fn test_apply_update_validates_terms() {
+        let contract = contract();
+        assert!(contract.apply_update(&UpdateContractRequest::default(), "owner_fp").is_err());
+
+        let update = UpdateContractRequest { min_price: Some(usd("0")), ..Default::default() };
+        assert!(contract.apply_update(&update, "owner_fp").is_err());
+        let update = UpdateContractRequest { min_price: Some(Money::parse("10", &test_currency(Currency::EUR)).unwrap()), ..Default::default() };
+        assert!(contract.apply_update(&update, "owner_fp").is_err());
+
+        let update = UpdateContractRequest { royalty_splits: Some(vec![split("", 500)]), ..Default::default() };
+        assert!(contract.apply_update(&update, "owner_fp").is_err());
+    }
+
+    #[test]
+    fn test_royalty_splits_validation() {
+        let terms = ContractTerms::default();
+        assert!(contract_v2(vec![split("a", 5_000), split("b", 5_000)], terms.clone()).is_ok());
+        assert!(contract_v2(vec![split("a", 5_000), split("b", 5_001)], terms.clone()).is_err());
+        assert!(contract_v2(vec![split("a", 100), split("a", 100)], terms.clone()).is_err());
+        assert!(contract_v2(vec![split("a", 0)], terms.clone()).is_err());
+        let update = UpdateContractRequest { royalty_splits: Some(vec![split("a", 9_000), split("b", 1_001)]), ..Default::default() };
+        assert!(contract().apply_update(&update, "owner_fp").is_err());
+
+        let expired = ContractTerms { expires_at: Some(Utc::now() - Duration::hours(1)), ..Default::default() };
+        assert!(contract_v2(vec![], expired).is_err());
+    }
+
+    #[test]
+    fn test_royalty_shares() {
+        let update = UpdateContractRequest { royalty_splits: Some(vec![split("a", 250), split("b", 750)]), ..Default::default() };
+        let contract = contract().apply_update(&update, "owner_fp").unwrap();
+        assert_eq!(contract.version, ContractVersion::V1);
+        let shares = contract.royalty_shares(&usd("200.00")).unwrap();
+        assert_eq!(shares, vec![("a".to_string(), usd("5.00")), ("b".to_string(), usd("15.00"))]);
+    }
+
+    #[test]
+    fn test_upgrade_v1_contract_keeps_royalty() {
+        let update = UpdateContractRequest { royalty_splits: Some(vec![split("creator_fp", 750)]), ..Default::default() };
+        let contract = contract().apply_update(&update, "owner_fp").unwrap();
+        let upgraded = contract.upgrade_to_v2(CONTRACT_UPGRADE_AUTHOR).unwrap();
+        assert_eq!(upgraded.version, ContractVersion::V2);
+        assert_eq!(upgraded.update_count, contract.update_count + 1);
+        assert_eq!(upgraded.royalty_splits, vec![split("creator_fp", 750)]);
+        assert_eq!(upgraded.royalty_shares(&usd("100.00")).unwrap(), contract.royalty_shares(&usd("100.00")).unwrap());
+        assert!(upgraded.upgrade_to_v2(CONTRACT_UPGRADE_AUTHOR).is_err());
+    }
+
+    #[test]
+    fn test_resale_restriction() {
+        let now = Utc::now();
+        let terms = ContractTerms {
+            resale: ResaleRestriction { allowed: true, min_holding_days: 30 },
+            expires_at: None,
+        };
+        let contract = contract_v2(vec![], terms).unwrap();
+        assert!(contract.check_resale(None, now).is_ok());
+        assert!(contract.check_resale(Some(now - Duration::days(10)), now).is_err());
+        assert!(contract.check_resale(Some(now - Duration::days(31)), now).is_ok());
+
+        let terms = ContractTerms {
+            resale: ResaleRestriction { allowed: false, min_holding_days: 0 },
+            expires_at: None,
+        };
+        let contract = contract_v2(vec![], terms).unwrap();
+        assert!(contract.check_resale(Some(now - Duration::days(365)), now).is_err());
     }
 }
@@ -3,9 +3,12 @@ use sqlx::error::BoxDynError;
 use sqlx::postgres::PgTypeInfo;
 use sqlx::{Database, TypeInfo};
 use sqlx::{Decode, Encode, Postgres, Type};
+use crate::core::DomainError;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Display, Formatter};
 use strum_macros::EnumString;
 
+/// Currency codes. Names, aliases and decimals are configured in the `currency` table, see [`CurrencyRegistry`].
 #[derive(
     serde::Deserialize,
     Clone,
@@ -18,126 +21,25 @@ use strum_macros::EnumString;
 )]
 #[sqlx(type_name = "currency_enum")]
 pub enum Currency {
-    #[strum(serialize = "USD", serialize = "usd", serialize = "US Dollar", serialize = "us dollar")]
     USD,
-    #[strum(serialize = "EUR", serialize = "eur", serialize = "Euro", serialize = "euro")]
     EUR,
-    #[strum(serialize = "XRP", serialize = "xrp", serialize = "Ripple", serialize = "ripple")]
     XRP,
-    #[strum(
-        serialize = "RUB",
-        serialize = "rub",
-        serialize = "Russian Ruble",
-        serialize = "russian ruble"
-    )]
     RUB,
-    #[strum(
-        serialize = "ARS",
-        serialize = "ars",
-        serialize = "Argentine Peso",
-        serialize = "argentine peso"
-    )]
     ARS,
-    #[strum(
-        serialize = "BRL",
-        serialize = "brl",
-        serialize = "Brazilian Real",
-        serialize = "brazilian real"
-    )]
     BRL,
-    #[strum(
-        serialize = "CNY",
-        serialize = "cny",
-        serialize = "Chinese Yuan",
-        serialize = "chinese yuan"
-    )]
     CNY,
-    #[strum(
-        serialize = "GBP",
-        serialize = "gbp",
-        serialize = "British Pound",
-        serialize = "british pound",
-        serialize = "Pound Sterling",
-        serialize = "pound sterling"
-    )]
     GBP,
-    #[strum(
-        serialize = "MXN",
-        serialize = "mxn",
-        serialize = "Mexican Peso",
-        serialize = "mexican peso"
-    )]
     MXN,
-    #[strum(
-        serialize = "QAR",
-        serialize = "qar",
-        serialize = "Qatari Rial",
-        serialize = "qatari rial"
-    )]
     QAR,
-    #[strum(
-        serialize = "JPY",
-        serialize = "jpy",
-        serialize = "Japanese Yen",
-        serialize = "japanese yen"
-    )]
     JPY,
     ////////// CRYPTO Currencies
-    #[strum(serialize = "DOGE", serialize = "doge", serialize = "Dogecoin", serialize = "dogecoin")]
     DOGE,
-    #[strum(
-        serialize = "XRFQ",
-        serialize = "xrfq"
-    )] // Assuming this is a made-up or very specific currency code
     XRFQ,
-    #[strum(
-        serialize = "SOL",
-        serialize = "sol",
-        serialize = "Solana",
-        serialize = "solana",
-        serialize = "SOLANA"
-    )]
     SOL,
-    #[strum(
-        serialize = "BTC",
-        serialize = "btc",
-        serialize = "Bitcoin",
-        serialize = "bitcoin",
-        serialize = "BITCOIN"
-    )]
     BTC,
-    #[strum(
-        serialize = "ETH",
-        serialize = "eth",
-        serialize = "Ethereum",
-        serialize = "ethereum",
-        serialize = "ETHEREUM"
-    )]
     ETH,
-    #[strum(
-        serialize = "ADA",
-        serialize = "ada",
-        serialize = "Cardano",
-        serialize = "cardano",
-        serialize = "CARDANO"
-    )]
     ADA,
-    #[strum(
-        serialize = "USDT",
-        serialize = "usdt",
-        serialize = "Tether",
-        serialize = "tether",
-        serialize = "TETHER"
-    )]
     USDT,
-    #[strum(
-        serialize = "BNB",
-        serialize = "bnb",
-        serialize = "Binance Coin",
-        serialize = "binance coin",
-        serialize = "BNB Coin",
-        serialize = "BinanceCoin"
-    )]
     BNB,
 }
 
@@ -238,33 +140,101 @@ impl Currency {
 
 impl Display for Currency {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Currency::USD => write!(f, "USD"),
-            Currency::EUR => write!(f, "EUR"),
-            Currency::XRP => write!(f, "XRP"),
-            Currency::RUB => write!(f, "RUB"),
-            Currency::ARS => write!(f, "ARS"),
-            Currency::BRL => write!(f, "BRL"),
-            Currency::CNY => write!(f, "CNY"),
-            Currency::GBP => write!(f, "GBP"),
-            Currency::MXN => write!(f, "MXN"),
-            Currency::QAR => write!(f, "QAR"),
-            Currency::JPY => write!(f, "JPY"),
-            Currency::DOGE => write!(f, "DOGE"),
-            Currency::XRFQ => write!(f, "XRFQ"),
-            Currency::SOL => write!(f, "SOL"),
-            Currency::BTC => write!(f, "BTC"),
-            Currency::USDT => write!(f, "USDT"),
-            Currency::ADA => write!(f, "ADA"),
-            Currency::ETH => write!(f, "ETH"),
-            Currency::BNB => write!(f, "BNB"),
+        write!(f, "{}", self.db_string())
+    }
+}
+
+/// Metadata of a currency as configured in the `currency` table. Amounts of the currency are exact
+/// to `decimals` decimal places.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurrencyInfo {
+    pub code: Currency,
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub decimals: u32,
+    pub crypto: bool,
+    pub enabled: bool,
+}
+
+/// Currencies known at runtime. A currency that is disabled stays readable on existing records,
+/// but can not be added to contracts.
+#[derive(Debug, Clone, Default)]
+pub struct CurrencyRegistry {
+    currencies: HashMap<Currency, CurrencyInfo>,
+    names: HashMap<String, Currency>,
+}
+
+impl CurrencyRegistry {
+    /// Fails if a code or an alias names more than one currency
+    pub fn new(currencies: Vec<CurrencyInfo>) -> Result<Self, DomainError> {
+        let mut names = HashMap::new();
+        for info in &currencies {
+            let codes = std::iter::once(info.code.db_string()).chain(info.aliases.iter().map(String::as_str));
+            for name in codes {
+                match names.insert(name.to_string(), info.code.clone()) {
+                    Some(other) if other != info.code => {
+                        let error = format!("'{}' names both {} and {}", name, other, info.code);
+                        return Err(DomainError::ValidationError(error));
+                    }
+                    _ => {}
+                }
+            }
         }
+        Ok(Self { currencies: currencies.into_iter().map(|info| (info.code.clone(), info)).collect(), names })
     }
+
+    pub fn get(&self, currency: &Currency) -> Option<&CurrencyInfo> {
+        self.currencies.get(currency)
+    }
+
+    /// Finds a currency by its code or one of its aliases, e.g. "USD", "usd" or "US Dollar"
+    pub fn resolve(&self, name: &str) -> Option<&CurrencyInfo> {
+        self.names.get(name).and_then(|currency| self.get(currency))
+    }
+
+    pub fn is_enabled(&self, currency: &Currency) -> bool {
+        self.get(currency).is_some_and(|info| info.enabled)
+    }
+
+    /// Currencies sorted by code
+    pub fn list(&self, include_disabled: bool) -> Vec<&CurrencyInfo> {
+        let mut currencies: Vec<&CurrencyInfo> = self.currencies.values()
+            .filter(|info| include_disabled || info.enabled)
+            .collect();
+        currencies.sort_by_key(|info| info.code.db_string());
+        currencies
+    }
+
+    pub fn check_enabled(&self, currencies: &HashSet<Currency>) -> Result<(), DomainError> {
+        let mut disabled: Vec<&str> = currencies.iter()
+            .filter(|currency| !self.is_enabled(currency))
+            .map(Currency::db_string)
+            .collect();
+        if disabled.is_empty() {
+            return Ok(());
+        }
+        disabled.sort();
+        Err(DomainError::InvalidArgument(format!("currencies are not enabled :: {}", disabled.join(", "))))
+    }
+}
+
+/// Currencies of unit tests, with the decimals they are seeded with in the `currency` table
+#[cfg(test)]
+pub(crate) fn test_currency(code: Currency) -> CurrencyInfo {
+    let decimals = match code {
+        Currency::JPY => 0,
+        Currency::USD | Currency::EUR => 2,
+        Currency::BTC => 8,
+        Currency::ETH => 18,
+        _ => panic!("no test currency for {}", code),
+    };
+    CurrencyInfo { name: code.to_string(), aliases: vec![], decimals, crypto: code.is_crypto(), enabled: true, code }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::core::Currency;
+    use crate::core::{Currency, CurrencyInfo, CurrencyRegistry};
+    use std::collections::HashSet;
     use std::str::FromStr;
     use strum::ParseError;
 
@@ -272,14 +242,10 @@ mod tests {
     fn test_from_string_valid_values() {
         let test_cases = vec![
             ("USD", Ok(Currency::USD)),
-            ("usd", Ok(Currency::USD)),
             ("EUR", Ok(Currency::EUR)),
-            ("eur", Ok(Currency::EUR)),
             ("XRP", Ok(Currency::XRP)),
-            ("xrp", Ok(Currency::XRP)),
-            ("Ripple", Ok(Currency::XRP)),
-            ("Cardano", Ok(Currency::ADA)),
-            ("Argentine Peso", Ok(Currency::ARS)),
+            ("ADA", Ok(Currency::ADA)),
+            ("ARS", Ok(Currency::ARS)),
         ];
 
         for (input, expected) in test_cases {
@@ -301,6 +267,8 @@ mod tests {
             "EUR-",
             "USSD",
             "UsD",
+            "usd",      // aliases are resolved by the registry
+            "Ripple",
             "123",      // Numbers alone are not valid (unless you add a serialize for them)
             "-$£",      // Symbols alone are not valid (unless you add a serialize for them)
             " USD ", // Leading and trailing whitespace (trimmed by strum)
@@ -320,4 +288,47 @@ mod tests {
             assert!(result.is_err(), "Expected an error for input: \"{}\"", input);
         }
     }
+
+    fn info(code: Currency, decimals: u32, aliases: &[&str], enabled: bool) -> CurrencyInfo {
+        CurrencyInfo {
+            name: code.to_string(),
+            aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+            decimals,
+            crypto: code.is_crypto(),
+            enabled,
+            code,
+        }
+    }
+
+    #[test]
+    fn test_registry_checks_enabled_currencies() {
+        let registry = CurrencyRegistry::new(vec![info(Currency::USD, 2, &[], true), info(Currency::EUR, 2, &[], false), info(Currency::BTC, 8, &[], true)]).unwrap();
+        assert!(registry.check_enabled(&HashSet::from([Currency::USD, Currency::BTC])).is_ok());
+        assert!(registry.check_enabled(&HashSet::from([Currency::USD, Currency::EUR])).is_err());
+        // unknown currencies are not enabled
+        assert!(registry.check_enabled(&HashSet::from([Currency::JPY])).is_err());
+
+        let enabled: Vec<Currency> = registry.list(false).into_iter().map(|info| info.code.clone()).collect();
+        assert_eq!(enabled, vec![Currency::BTC, Currency::USD]);
+        assert_eq!(registry.list(true).len(), 3);
+    }
+
+    #[test]
+    fn test_registry_resolves_codes_and_aliases() {
+        let registry = CurrencyRegistry::new(vec![info(Currency::USD, 2, &["usd", "US Dollar"], true), info(Currency::XRP, 6, &["Ripple"], false)]).unwrap();
+        assert_eq!(registry.resolve("USD").map(|info| &info.code), Some(&Currency::USD));
+        assert_eq!(registry.resolve("US Dollar").map(|info| &info.code), Some(&Currency::USD));
+        // disabled currencies still resolve, contracts check that they are enabled
+        assert_eq!(registry.resolve("Ripple").map(|info| info.decimals), Some(6));
+        assert!(registry.resolve("us dollar").is_none());
+        // known codes that are not in the registry do not resolve
+        assert!(registry.resolve("EUR").is_none());
+    }
+
+    #[test]
+    fn test_registry_rejects_ambiguous_aliases() {
+        let registry = CurrencyRegistry::new(vec![info(Currency::USD, 2, &["dollar"], true), info(Currency::EUR, 2, &["dollar"], true)]);
+        assert!(registry.is_err());
+        assert!(CurrencyRegistry::new(vec![info(Currency::USD, 2, &["EUR"], true), info(Currency::EUR, 2, &[], true)]).is_err());
+    }
 }
@@ -0,0 +1,119 @@
+use crate::core::{Asset, AssetSortField, DomainError};
+use base64::engine::general_purpose;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Version of the cursor token, changes whenever the layout of the token changes
+const CURSOR_FORMAT: &str = "c2";
+
+/// Position in a list of assets ordered by a sort field then id, the next page starts after it.
+/// Clients only see the opaque token, see `encode`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetCursor {
+    /// field the list is ordered by, a cursor only continues a list ordered by the same field
+    pub field: AssetSortField,
+    /// value of the sort field of the last asset of the page, see `Asset::sort_key`
+    pub key: String,
+    pub id: String,
+}
+
+impl Display for AssetCursor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "field:{}, key:{}, id:{}", self.field, self.key, self.id)
+    }
+}
+
+impl AssetCursor {
+    /// Cursor of the page that starts after `asset` in a list ordered by `field`
+    pub fn after(asset: &Asset, field: AssetSortField) -> Self {
+        Self {
+            field,
+            key: asset.sort_key(field),
+            id: asset.id.clone(),
+        }
+    }
+
+    /// Key of a cursor over a timestamp field, None when the key is not a timestamp
+    pub fn key_timestamp(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.key).ok().map(|key| key.with_timezone(&Utc))
+    }
+
+    /// URL-safe base64 of `c2|<field>|<id>|<key>`, fields and ids never contain `|` so the key is
+    /// everything after the third `|`
+    pub fn encode(&self) -> String {
+        general_purpose::URL_SAFE_NO_PAD.encode(format!("{}|{}|{}|{}", CURSOR_FORMAT, self.field, self.id, self.key))
+    }
+
+    pub fn decode(token: &str) -> Result<Self, DomainError> {
+        let invalid = || DomainError::InvalidArgument("cursor is invalid".to_string());
+        let bytes = general_purpose::URL_SAFE_NO_PAD.decode(token.trim()).map_err(|_| invalid())?;
+        let content = String::from_utf8(bytes).map_err(|_| invalid())?;
+        let mut parts = content.splitn(4, '|');
+        let cursor = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(CURSOR_FORMAT), Some(field), Some(id), Some(key)) if !id.is_empty() => Self {
+                field: AssetSortField::from_str(field).map_err(|_| invalid())?,
+                key: key.to_string(),
+                id: id.to_string(),
+            },
+            _ => return Err(invalid()),
+        };
+        match cursor.field {
+            AssetSortField::CreatedAt | AssetSortField::UpdatedAt if cursor.key_timestamp().is_none() => Err(invalid()),
+            _ => Ok(cursor),
+        }
+    }
+
+    /// Decodes the token of a request, an empty or missing token starts from the first page
+    pub fn from_token(token: Option<&str>) -> Result<Option<Self>, DomainError> {
+        match token.map(str::trim) {
+            None | Some("") => Ok(None),
+            Some(token) => Self::decode(token).map(Some),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = AssetCursor {
+            field: AssetSortField::Name,
+            key: "name | with pipes".to_string(),
+            id: "abc123".to_string(),
+        };
+        let token = cursor.encode();
+        assert_eq!(AssetCursor::decode(&token).unwrap(), cursor);
+        assert_eq!(AssetCursor::from_token(Some(&token)).unwrap(), Some(cursor));
+        assert_eq!(AssetCursor::from_token(Some("")).unwrap(), None);
+        assert_eq!(AssetCursor::from_token(None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_timestamp_cursor_round_trip() {
+        let cursor = AssetCursor {
+            field: AssetSortField::CreatedAt,
+            key: "2026-10-16T08:30:00.123456Z".to_string(),
+            id: "abc123".to_string(),
+        };
+        let decoded = AssetCursor::decode(&cursor.encode()).unwrap();
+        assert_eq!(decoded, cursor);
+        assert_eq!(decoded.key_timestamp().unwrap().timestamp_subsec_micros(), 123456);
+    }
+
+    #[test]
+    fn test_invalid_cursor_is_rejected() {
+        assert!(AssetCursor::decode("not a cursor").is_err());
+        let unknown_version = general_purpose::URL_SAFE_NO_PAD.encode("c1|abc123|name");
+        assert!(AssetCursor::decode(&unknown_version).is_err());
+        let missing_id = general_purpose::URL_SAFE_NO_PAD.encode("c2|name||name");
+        assert!(AssetCursor::decode(&missing_id).is_err());
+        let unknown_field = general_purpose::URL_SAFE_NO_PAD.encode("c2|owner|abc123|name");
+        assert!(AssetCursor::decode(&unknown_field).is_err());
+        let invalid_timestamp = general_purpose::URL_SAFE_NO_PAD.encode("c2|created_at|abc123|yesterday");
+        assert!(AssetCursor::decode(&invalid_timestamp).is_err());
+    }
+}
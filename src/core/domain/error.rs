@@ -64,6 +64,8 @@ pub enum DatabaseError {
     WorkerCrashed,
     #[error("`{0}`")]
     InvalidArgument(String),
+    #[error("migration failed: `{0}`")]
+    Migration(String),
     #[error("`{0}`")]
     Unknown(String), // Catch-all for other errors with the error message
 }
@@ -106,6 +108,10 @@ pub enum OrchestrateError {
     NotFoundError(String),
     #[error("`{0}`")]
     InvalidArgument(String),
+    #[error("`{0}`")]
+    InvalidState(String),
+    #[error("`{0}`")]
+    PermissionDenied(String),
     #[error("data store disconnected")]
     DatabaseError(#[from] DatabaseError),
 }
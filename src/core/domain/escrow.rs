@@ -0,0 +1,202 @@
+use crate::core::domain::key::{generate_unique_key, DOMAIN_KEY_SIZE};
+use crate::core::{Asset, Bid, DomainError, Money};
+use chrono::{DateTime, Duration, Utc};
+use std::fmt::{Display, Formatter};
+use strum_macros::EnumString;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, sqlx::Type)]
+#[sqlx(type_name = "escrow_status", rename_all = "lowercase")]
+#[strum(ascii_case_insensitive)]
+pub enum EscrowStatus {
+    /// waiting for the buyer's funds
+    Pending,
+    /// the buyer paid, waiting for the asset to be transferred
+    Funded,
+    /// the asset was transferred and the funds went to the seller
+    Released,
+    /// the asset was not transferred before the deadline, the funds went back to the buyer
+    Refunded,
+    /// the buyer did not pay before the deadline
+    Cancelled,
+}
+
+impl Display for EscrowStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EscrowStatus::Pending => write!(f, "pending"),
+            EscrowStatus::Funded => write!(f, "funded"),
+            EscrowStatus::Released => write!(f, "released"),
+            EscrowStatus::Refunded => write!(f, "refunded"),
+            EscrowStatus::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl EscrowStatus {
+    /// An active hold reserves the asset for its buyer
+    pub fn is_active(&self) -> bool {
+        matches!(self, EscrowStatus::Pending | EscrowStatus::Funded)
+    }
+
+    /// Status of an active hold once its deadline passed
+    pub fn expired(&self) -> Option<EscrowStatus> {
+        match self {
+            EscrowStatus::Pending => Some(EscrowStatus::Cancelled),
+            EscrowStatus::Funded => Some(EscrowStatus::Refunded),
+            _ => None,
+        }
+    }
+}
+
+/// Funds of the buyer of an accepted bid, held until the asset is transferred to them.
+/// The hold is keyed to the bid and to the NFC certificate of the asset.
+#[derive(Debug, Clone)]
+pub struct EscrowHold {
+    pub id: String,
+    pub bid_id: String,
+    pub asset_id: String,
+    pub nfc_id: String,
+    pub buyer_fp: String,
+    pub buyer_org: String,
+    pub seller_fp: String,
+    pub amount: Money,
+    pub status: EscrowStatus,
+    pub deadline: DateTime<Utc>,
+    pub funded_at: Option<DateTime<Utc>>,
+    pub settled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Display for EscrowHold {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "holdId:{}, bidId:{}, assetId:{}, status={}", self.id, self.bid_id, self.asset_id, self.status)
+    }
+}
+
+impl EscrowHold {
+    /// Creates a pending hold for the accepted `bid`, the buyer has `ttl` to pay and the seller to transfer the asset
+    pub fn for_bid(bid: &Bid, asset: &Asset, nfc_id: String, ttl: Duration) -> Result<Self, DomainError> {
+        if ttl <= Duration::zero() {
+            return Err(DomainError::InvalidArgument("escrow hold ttl must be positive".to_string()));
+        }
+        if bid.asset_id != asset.id {
+            return Err(DomainError::ValidationError("bid does not belong to asset".to_string()));
+        }
+        let now = Utc::now();
+        Ok(Self {
+            nfc_id,
+            status: EscrowStatus::Pending,
+            amount: bid.amount.clone(),
+            bid_id: bid.id.clone(),
+            asset_id: asset.id.clone(),
+            buyer_fp: bid.bidder_fp.clone(),
+            buyer_org: bid.bidder_org.clone(),
+            seller_fp: asset.owner_fp.clone(),
+            deadline: now + ttl,
+            funded_at: None,
+            settled_at: None,
+            created_at: now,
+            updated_at: now,
+            id: generate_unique_key(DOMAIN_KEY_SIZE),
+        })
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.deadline <= now
+    }
+
+    /// Only the buyer can fund a pending hold, and only before its deadline
+    pub fn check_fundable(&self, buyer_fp: &str, now: DateTime<Utc>) -> Result<(), DomainError> {
+        if self.buyer_fp != buyer_fp {
+            return Err(DomainError::ValidationError("only the buyer can fund an escrow hold".to_string()));
+        }
+        if self.status != EscrowStatus::Pending {
+            return Err(DomainError::ValidationError(format!("escrow hold is not pending :: status={}", self.status)));
+        }
+        if self.is_expired(now) {
+            return Err(DomainError::ValidationError("escrow hold has expired".to_string()));
+        }
+        Ok(())
+    }
+
+    /// The asset can only be transferred to the buyer of a funded hold, and only before its deadline
+    pub fn check_releasable(&self, new_owner_fp: &str, new_org_id: &str, now: DateTime<Utc>) -> Result<(), DomainError> {
+        if self.status != EscrowStatus::Funded {
+            let error = format!("asset is held in escrow until the buyer pays :: status={}", self.status);
+            return Err(DomainError::ValidationError(error));
+        }
+        if self.buyer_fp != new_owner_fp || self.buyer_org != new_org_id {
+            return Err(DomainError::ValidationError("asset is held in escrow for another buyer".to_string()));
+        }
+        if self.is_expired(now) {
+            return Err(DomainError::ValidationError("escrow hold has expired".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{test_currency, Contract, Currency};
+    use std::collections::HashSet;
+    use uuid::Uuid;
+
+    fn pending_hold() -> EscrowHold {
+        let mut asset = Asset::new("asset-name".to_string(),
+                                   "XRF".to_string(),
+                                   "owner_fp".to_string(),
+                                   "description".to_string(),
+                                   Uuid::new_v4().to_string())
+            .unwrap();
+        asset.tradable = true;
+        let contract = Contract::new(asset.id.clone(),
+                                     "details".to_string(),
+                                     "summary".to_string(),
+                                     "owner_fp".to_string(),
+                                     Money::parse("20", &test_currency(Currency::USD)).unwrap(),
+                                     false,
+                                     vec![],
+                                     HashSet::from([Currency::USD]))
+            .unwrap();
+        let bid = Bid::new(&asset, &contract, "buyer_fp".to_string(), Uuid::new_v4().to_string(),
+                           Money::parse("25", &test_currency(Currency::USD)).unwrap(), false, None, None)
+            .unwrap();
+        EscrowHold::for_bid(&bid, &asset, "nfc_id".to_string(), Duration::hours(1)).unwrap()
+    }
+
+    #[test]
+    fn test_fund_only_pending_hold_by_buyer() {
+        let hold = pending_hold();
+        let now = Utc::now();
+        assert!(hold.check_fundable("buyer_fp", now).is_ok());
+        assert!(hold.check_fundable("owner_fp", now).is_err());
+        assert!(hold.check_fundable("buyer_fp", hold.deadline).is_err());
+
+        let funded = EscrowHold { status: EscrowStatus::Funded, ..hold };
+        assert!(funded.check_fundable("buyer_fp", now).is_err());
+    }
+
+    #[test]
+    fn test_release_only_funded_hold_to_buyer() {
+        let hold = pending_hold();
+        let now = Utc::now();
+        let buyer_org = hold.buyer_org.clone();
+        assert!(hold.check_releasable("buyer_fp", &buyer_org, now).is_err());
+
+        let funded = EscrowHold { status: EscrowStatus::Funded, ..hold };
+        assert!(funded.check_releasable("buyer_fp", &buyer_org, now).is_ok());
+        assert!(funded.check_releasable("other_fp", &buyer_org, now).is_err());
+        assert!(funded.check_releasable("buyer_fp", &Uuid::new_v4().to_string(), now).is_err());
+        assert!(funded.check_releasable("buyer_fp", &buyer_org, funded.deadline).is_err());
+    }
+
+    #[test]
+    fn test_expired_status() {
+        assert_eq!(EscrowStatus::Pending.expired(), Some(EscrowStatus::Cancelled));
+        assert_eq!(EscrowStatus::Funded.expired(), Some(EscrowStatus::Refunded));
+        assert_eq!(EscrowStatus::Released.expired(), None);
+        assert!(!EscrowStatus::Refunded.is_active());
+    }
+}
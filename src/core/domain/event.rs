@@ -0,0 +1,283 @@
+use crate::core::domain::key::{generate_unique_key, DOMAIN_KEY_SIZE};
+use crate::core::event_proto::event_payload::Change;
+use crate::core::event_proto::{AssetCreated, AssetDeleted, AssetTransferred, AssetUpdated, ContractCreated, Event, EventPayload};
+use crate::core::{Asset, Contract, DomainError, UpdateAssetRequest};
+use chrono::{DateTime, SubsecRound, Utc};
+use prost::Message;
+use prost_types::Timestamp;
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, sqlx::Type)]
+#[sqlx(type_name = "outbox_event_type", rename_all = "snake_case")]
+pub enum EventType {
+    AssetCreated,
+    AssetUpdated,
+    AssetTransferred,
+    AssetDeleted,
+    ContractCreated,
+}
+
+impl Display for EventType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventType::AssetCreated => write!(f, "asset_created"),
+            EventType::AssetUpdated => write!(f, "asset_updated"),
+            EventType::AssetTransferred => write!(f, "asset_transferred"),
+            EventType::AssetDeleted => write!(f, "asset_deleted"),
+            EventType::ContractCreated => write!(f, "contract_created"),
+        }
+    }
+}
+
+/// A change of an asset or of its contract, recorded in the outbox in the same transaction as the change.
+/// The payload is the protobuf encoded `EventPayload` of the change.
+/// Events are relayed to downstream services in the order of their sequence, at least once.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutboxEvent {
+    /// assigned when the event is recorded, 0 until then
+    pub sequence: i64,
+    pub id: String,
+    pub event_type: EventType,
+    pub asset_id: String,
+    /// organization and owner of the asset once the change is made
+    pub organization: String,
+    pub owner_fp: String,
+    pub payload: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+    pub dispatched_at: Option<DateTime<Utc>>,
+}
+
+impl Display for OutboxEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "sequence:{}, eventId:{}, type:{}, assetId:{}", self.sequence, self.id, self.event_type, self.asset_id)
+    }
+}
+
+impl OutboxEvent {
+    fn new(asset_id: &str, organization: &str, owner_fp: &str, change: Change) -> Self {
+        let event_type = match &change {
+            Change::AssetCreated(_) => EventType::AssetCreated,
+            Change::AssetUpdated(_) => EventType::AssetUpdated,
+            Change::AssetTransferred(_) => EventType::AssetTransferred,
+            Change::AssetDeleted(_) => EventType::AssetDeleted,
+            Change::ContractCreated(_) => EventType::ContractCreated,
+        };
+        OutboxEvent {
+            sequence: 0,
+            id: generate_unique_key(DOMAIN_KEY_SIZE),
+            event_type,
+            asset_id: asset_id.to_string(),
+            organization: organization.to_string(),
+            owner_fp: owner_fp.to_string(),
+            payload: EventPayload { change: Some(change) }.encode_to_vec(),
+            // stored with microsecond precision
+            created_at: Utc::now().trunc_subsecs(6),
+            dispatched_at: None,
+        }
+    }
+
+    pub fn asset_created(asset: &Asset, nfc_id: &str) -> Self {
+        let change = Change::AssetCreated(AssetCreated {
+            asset_id: asset.id.clone(),
+            name: asset.name.clone(),
+            symbol: asset.symbol.clone(),
+            description: asset.description.clone(),
+            organization: asset.organization.clone(),
+            owner_fp: asset.owner_fp.clone(),
+            listable: asset.listable,
+            nfc_id: nfc_id.to_string(),
+            created_at: Some(to_timestamp(asset.created_at)),
+        });
+        OutboxEvent::new(&asset.id, &asset.organization, &asset.owner_fp, change)
+    }
+
+    pub fn asset_updated(asset_id: &str,
+                         organization: &str,
+                         owner_fp: &str,
+                         updated_by: &str,
+                         update: &UpdateAssetRequest,
+                         updated_at: DateTime<Utc>) -> Self {
+        let change = Change::AssetUpdated(AssetUpdated {
+            asset_id: asset_id.to_string(),
+            updated_by: updated_by.to_string(),
+            name: update.name.clone(),
+            symbol: update.symbol.clone(),
+            description: update.description.clone(),
+            organization: update.organization.clone(),
+            listable: update.listable,
+            tradable: update.tradable,
+            tags: update.tags.clone().unwrap_or_default(),
+            tags_replaced: update.tags.is_some(),
+            set_attribute_keys: update.set_attributes.iter().map(|attribute| attribute.key.clone()).collect(),
+            removed_attribute_keys: update.remove_attributes.clone(),
+            updated_at: Some(to_timestamp(updated_at)),
+        });
+        OutboxEvent::new(asset_id, organization, owner_fp, change)
+    }
+
+    /// `previous` is the asset before the transfer
+    pub fn asset_transferred(previous: &Asset, nfc_id: &str, new_org: &str, new_owner_fp: &str, sale_id: Option<&str>) -> Self {
+        let change = Change::AssetTransferred(AssetTransferred {
+            asset_id: previous.id.clone(),
+            nfc_id: nfc_id.to_string(),
+            from_organization: previous.organization.clone(),
+            from_owner_fp: previous.owner_fp.clone(),
+            to_organization: new_org.to_string(),
+            to_owner_fp: new_owner_fp.to_string(),
+            sale_id: sale_id.map(str::to_string),
+            transferred_at: Some(to_timestamp(Utc::now())),
+        });
+        OutboxEvent::new(&previous.id, new_org, new_owner_fp, change)
+    }
+
+    /// `organization` and `owner_fp` are the ones of the asset when it was deleted
+    pub fn asset_deleted(asset_id: &str, organization: &str, owner_fp: &str) -> Self {
+        let change = Change::AssetDeleted(AssetDeleted {
+            asset_id: asset_id.to_string(),
+            deleted_at: Some(to_timestamp(Utc::now())),
+        });
+        OutboxEvent::new(asset_id, organization, owner_fp, change)
+    }
+
+    pub fn contract_created(contract: &Contract, organization: &str, owner_fp: &str) -> Self {
+        let mut accepted_currency: Vec<String> = contract.accepted_currency.iter().map(|currency| currency.to_string()).collect();
+        accepted_currency.sort();
+        let change = Change::ContractCreated(ContractCreated {
+            contract_id: contract.id.clone(),
+            asset_id: contract.asset_id.clone(),
+            version: contract.version.to_string(),
+            accepted_currency,
+            anonymous_buyer_only: contract.anonymous_buyer_only,
+            auctioned: contract.auction.is_some(),
+            created_at: Some(to_timestamp(contract.created_at)),
+        });
+        OutboxEvent::new(&contract.asset_id, organization, owner_fp, change)
+    }
+
+    /// The change the event records
+    pub fn change(&self) -> Result<Change, DomainError> {
+        EventPayload::decode(self.payload.as_slice())
+            .map_err(|e| DomainError::ValidationError(format!("invalid payload of event {}: {}", self.id, e)))?
+            .change
+            .ok_or_else(|| DomainError::ValidationError(format!("event {} has no change", self.id)))
+    }
+}
+
+impl TryFrom<&OutboxEvent> for Event {
+    type Error = DomainError;
+
+    fn try_from(event: &OutboxEvent) -> Result<Self, Self::Error> {
+        Ok(Event {
+            sequence: event.sequence,
+            id: event.id.clone(),
+            asset_id: event.asset_id.clone(),
+            organization: event.organization.clone(),
+            owner_fp: event.owner_fp.clone(),
+            created_at: Some(to_timestamp(event.created_at)),
+            payload: Some(EventPayload { change: Some(event.change()?) }),
+        })
+    }
+}
+
+/// Events a subscription receives, those of an asset, of an organization or of an owner.
+/// Organization and owner are the ones of the asset once the change was made,
+/// the previous owner of a transferred asset does not receive the transfer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventFilter {
+    AssetId(String),
+    Organization(String),
+    OwnerFp(String),
+}
+
+impl Display for EventFilter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventFilter::AssetId(asset_id) => write!(f, "assetId:{}", asset_id),
+            EventFilter::Organization(organization) => write!(f, "organization:{}", organization),
+            EventFilter::OwnerFp(owner_fp) => write!(f, "ownerFp:{}", owner_fp),
+        }
+    }
+}
+
+/// Destination of the events relayed from the outbox.
+/// Events that were published are marked dispatched, a failed publication is retried with the same events.
+pub trait EventSink: Send + Sync {
+    /// Publishes the events in the order of their sequence, succeeds once every event was received
+    fn publish(&self, events: &[OutboxEvent]) -> impl Future<Output=anyhow::Result<()>> + Send;
+}
+
+fn to_timestamp(time: DateTime<Utc>) -> Timestamp {
+    Timestamp {
+        seconds: time.timestamp(),
+        nanos: time.timestamp_subsec_nanos() as i32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{AssetAttribute, AttributeValue};
+    use uuid::Uuid;
+
+    fn asset() -> Asset {
+        Asset::new("asset-name".to_string(),
+                   "XRF".to_string(),
+                   "owner_fp".to_string(),
+                   "description".to_string(),
+                   Uuid::new_v4().to_string())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_event_payload_decodes_to_its_change() {
+        let asset = asset();
+        let event = OutboxEvent::asset_created(&asset, "nfc-id");
+        assert_eq!(event.event_type, EventType::AssetCreated);
+        assert_eq!(event.asset_id, asset.id);
+        match event.change().unwrap() {
+            Change::AssetCreated(created) => {
+                assert_eq!(created.name, asset.name);
+                assert_eq!(created.nfc_id, "nfc-id");
+                assert_eq!(created.owner_fp, asset.owner_fp);
+            }
+            change => panic!("unexpected change {:?}", change),
+        }
+
+        let proto = Event::try_from(&event).unwrap();
+        assert_eq!(proto.id, event.id);
+        assert!(matches!(proto.payload.unwrap().change, Some(Change::AssetCreated(_))));
+    }
+
+    #[test]
+    fn test_transfer_event_is_addressed_to_the_new_owner() {
+        let asset = asset();
+        let event = OutboxEvent::asset_transferred(&asset, "nfc-id", "new-org", "new-owner-fp", Some("sale-id"));
+        assert_eq!(event.event_type, EventType::AssetTransferred);
+        assert_eq!((event.organization.as_str(), event.owner_fp.as_str()), ("new-org", "new-owner-fp"));
+        let Change::AssetTransferred(transferred) = event.change().unwrap() else { panic!("not a transfer") };
+        assert_eq!(transferred.from_owner_fp, asset.owner_fp);
+        assert_eq!(transferred.from_organization, asset.organization);
+        assert_eq!(transferred.sale_id.as_deref(), Some("sale-id"));
+    }
+
+    #[test]
+    fn test_update_event_only_carries_changed_fields() {
+        let mut update = UpdateAssetRequest::new(Some("renamed".to_string()), None, None, None, None, None);
+        update.set_attributes = vec![AssetAttribute { key: "color".to_string(), value: AttributeValue::String("red".to_string()) }];
+        let event = OutboxEvent::asset_updated("asset-id", "an-org", "a-user-fp", "an-updater", &update, Utc::now());
+        let Change::AssetUpdated(updated) = event.change().unwrap() else { panic!("not an update") };
+        assert_eq!(updated.name.as_deref(), Some("renamed"));
+        assert!(updated.symbol.is_none() && updated.listable.is_none());
+        assert!(!updated.tags_replaced);
+        assert_eq!(updated.set_attribute_keys, vec!["color".to_string()]);
+    }
+
+    #[test]
+    fn test_invalid_payload_is_rejected() {
+        let mut event = OutboxEvent::asset_deleted("asset-id", "an-org", "a-user-fp");
+        event.payload = vec![0xff, 0xff];
+        assert!(event.change().is_err());
+        assert!(Event::try_from(&event).is_err());
+    }
+}
@@ -0,0 +1,130 @@
+use crate::core::{Currency, DomainError, Money};
+use bigdecimal::{BigDecimal, RoundingMode, Zero};
+use chrono::{DateTime, Duration, Utc};
+use std::fmt::{Display, Formatter};
+
+/// Exchange rate at `as_of`: one unit of `base` is worth `rate` units of `quote`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FxRate {
+    pub base: Currency,
+    pub quote: Currency,
+    pub rate: BigDecimal,
+    pub as_of: DateTime<Utc>,
+}
+
+impl Display for FxRate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "1 {} = {} {} at {}", self.base, self.rate, self.quote, self.as_of)
+    }
+}
+
+impl FxRate {
+    pub fn new(base: Currency, quote: Currency, rate: BigDecimal, as_of: DateTime<Utc>) -> Result<Self, DomainError> {
+        if base == quote {
+            return Err(DomainError::InvalidArgument(format!("rate must be between two currencies :: {}", base)));
+        }
+        if rate <= BigDecimal::zero() {
+            return Err(DomainError::InvalidArgument(format!("rate must be greater than 0 :: rate={}", rate)));
+        }
+        if as_of > Utc::now() {
+            return Err(DomainError::InvalidArgument(format!("rate can not be dated in the future :: as_of={}", as_of)));
+        }
+        Ok(Self { base, quote, rate, as_of })
+    }
+
+    /// Rate of the opposite direction, from `quote` to `base`
+    pub fn inverse(&self) -> FxRate {
+        FxRate {
+            base: self.quote.clone(),
+            quote: self.base.clone(),
+            rate: BigDecimal::from(1) / &self.rate,
+            as_of: self.as_of,
+        }
+    }
+
+    /// Converts an amount in the base currency to the quote currency, rounded to a whole minor unit
+    /// of the quote currency, which has `decimals` decimal places
+    pub fn convert(&self, amount: &Money, decimals: u32, rounding: RoundingMode) -> Result<Money, DomainError> {
+        if *amount.currency() != self.base {
+            let error = format!("can not convert {} with a rate from {} to {}", amount, self.base, self.quote);
+            return Err(DomainError::InvalidArgument(error));
+        }
+        let converted = (amount.to_decimal() * &self.rate).with_scale_round(decimals as i64, rounding);
+        Money::from_scaled(&converted, self.quote.clone())
+    }
+
+    fn involves_crypto(&self) -> bool {
+        self.base.is_crypto() || self.quote.is_crypto()
+    }
+}
+
+/// How old a rate can be before conversions refuse to use it.
+/// Crypto currencies move faster, rates involving one of them get their own limit.
+#[derive(Debug, Clone)]
+pub struct FxPolicy {
+    max_rate_age: Duration,
+    max_crypto_rate_age: Duration,
+}
+
+impl FxPolicy {
+    pub fn new(max_rate_age: Duration, max_crypto_rate_age: Duration) -> Self {
+        Self { max_rate_age, max_crypto_rate_age }
+    }
+
+    pub fn max_age(&self, rate: &FxRate) -> Duration {
+        if rate.involves_crypto() { self.max_crypto_rate_age } else { self.max_rate_age }
+    }
+
+    pub fn check_fresh(&self, rate: &FxRate, now: DateTime<Utc>) -> Result<(), DomainError> {
+        if now - rate.as_of > self.max_age(rate) {
+            let error = format!("exchange rate from {} to {} is stale :: as_of={}", rate.base, rate.quote, rate.as_of);
+            return Err(DomainError::ValidationError(error));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_currency;
+    use std::str::FromStr;
+
+    fn rate(base: Currency, quote: Currency, rate: &str, as_of: DateTime<Utc>) -> FxRate {
+        FxRate::new(base, quote, BigDecimal::from_str(rate).unwrap(), as_of).unwrap()
+    }
+
+    #[test]
+    fn test_convert_rounds_to_quote_precision() {
+        let (usd, btc) = (test_currency(Currency::USD), test_currency(Currency::BTC));
+        let btc_usd = rate(Currency::BTC, Currency::USD, "65000.125", Utc::now());
+        let amount = Money::parse("0.001", &btc).unwrap();
+        assert_eq!(btc_usd.convert(&amount, usd.decimals, RoundingMode::Down).unwrap(), Money::parse("65.00", &usd).unwrap());
+        assert_eq!(btc_usd.convert(&amount, usd.decimals, RoundingMode::Up).unwrap(), Money::parse("65.01", &usd).unwrap());
+        assert!(btc_usd.convert(&Money::parse("1", &test_currency(Currency::EUR)).unwrap(), usd.decimals, RoundingMode::Down).is_err());
+
+        let usd_btc = btc_usd.inverse();
+        assert_eq!(usd_btc.base, Currency::USD);
+        let floor = usd_btc.convert(&Money::parse("20.00", &usd).unwrap(), btc.decimals, RoundingMode::Up).unwrap();
+        assert_eq!(floor, Money::parse("0.00030770", &btc).unwrap());
+    }
+
+    #[test]
+    fn test_new_rate_is_validated() {
+        let now = Utc::now();
+        assert!(FxRate::new(Currency::USD, Currency::USD, BigDecimal::from(1), now).is_err());
+        assert!(FxRate::new(Currency::USD, Currency::EUR, BigDecimal::from(0), now).is_err());
+        assert!(FxRate::new(Currency::USD, Currency::EUR, BigDecimal::from(1), now + Duration::hours(1)).is_err());
+    }
+
+    #[test]
+    fn test_policy_limits_crypto_rates_separately() {
+        let policy = FxPolicy::new(Duration::hours(1), Duration::minutes(5));
+        let now = Utc::now();
+        let eur_usd = rate(Currency::EUR, Currency::USD, "1.08", now - Duration::minutes(30));
+        let btc_usd = rate(Currency::BTC, Currency::USD, "65000", now - Duration::minutes(30));
+        assert!(policy.check_fresh(&eur_usd, now).is_ok());
+        assert!(policy.check_fresh(&btc_usd, now).is_err());
+        assert!(policy.check_fresh(&eur_usd, now + Duration::hours(1)).is_err());
+    }
+}
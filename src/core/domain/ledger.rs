@@ -0,0 +1,223 @@
+use crate::core::domain::key::{generate_unique_key, DOMAIN_KEY_SIZE};
+use crate::core::{Contract, Currency, DomainError, Money, Sale};
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{DateTime, Utc};
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, sqlx::Type)]
+#[sqlx(type_name = "posting_kind", rename_all = "lowercase")]
+pub enum PostingKind {
+    Purchase,
+    Sale,
+    Royalty,
+}
+
+impl Display for PostingKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PostingKind::Purchase => write!(f, "purchase"),
+            PostingKind::Sale => write!(f, "sale"),
+            PostingKind::Royalty => write!(f, "royalty"),
+        }
+    }
+}
+
+/// A user's account in one currency. Its balance is the sum of all postings made against it.
+#[derive(Debug, Clone)]
+pub struct LedgerAccount {
+    pub id: String,
+    pub owner_fp: String,
+    pub currency: Currency,
+    pub created_at: DateTime<Utc>,
+}
+
+impl LedgerAccount {
+    pub fn new(owner_fp: String, currency: Currency) -> Self {
+        Self {
+            owner_fp,
+            currency,
+            created_at: Utc::now(),
+            id: generate_unique_key(DOMAIN_KEY_SIZE),
+        }
+    }
+}
+
+/// A single movement on an account. Credits are positive and debits are negative.
+#[derive(Debug, Clone)]
+pub struct Posting {
+    pub amount: Money,
+    pub kind: PostingKind,
+    pub account_owner_fp: String,
+}
+
+impl Posting {
+    pub fn debit(account_owner_fp: String, amount: &Money, kind: PostingKind) -> Self {
+        let amount = amount.with_minor_units(-amount.minor_units().saturating_abs());
+        Self { account_owner_fp, amount, kind }
+    }
+
+    pub fn credit(account_owner_fp: String, amount: &Money, kind: PostingKind) -> Self {
+        let amount = amount.with_minor_units(amount.minor_units().saturating_abs());
+        Self { account_owner_fp, amount, kind }
+    }
+}
+
+/// A balanced set of postings in a single currency, the postings of an entry always sum to zero.
+#[derive(Debug, Clone)]
+pub struct JournalEntry {
+    pub id: String,
+    pub reference_id: String,
+    pub description: String,
+    pub currency: Currency,
+    pub postings: Vec<Posting>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Display for JournalEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "entryId:{}, referenceId:{}, postings={}", self.id, self.reference_id, self.postings.len())
+    }
+}
+
+impl JournalEntry {
+    pub fn new(reference_id: String,
+               description: String,
+               currency: Currency,
+               postings: Vec<Posting>) -> Result<Self, DomainError> {
+        if postings.len() < 2 {
+            return Err(DomainError::InvalidArgument("journal entry needs at least two postings".to_string()));
+        }
+        if postings.iter().any(|p| p.amount.is_zero()) {
+            return Err(DomainError::InvalidArgument("posting amount can not be zero".to_string()));
+        }
+        if postings.iter().any(|p| p.amount.currency() != &currency) {
+            return Err(DomainError::InvalidArgument(format!("postings must be in the currency of the entry {}", currency)));
+        }
+        let entry = Self {
+            currency,
+            postings,
+            reference_id,
+            description,
+            created_at: Utc::now(),
+            id: generate_unique_key(DOMAIN_KEY_SIZE),
+        };
+        let total = entry.total()?;
+        if !total.is_zero() {
+            return Err(DomainError::ValidationError(format!("journal entry is not balanced :: total={}", total)));
+        }
+        Ok(entry)
+    }
+
+    /// The buyer is debited the price of the sale. Each royalty receiver of the contract is credited
+    /// its share of the price and the seller is credited the rest.
+    pub fn for_sale(sale: &Sale, contract: &Contract) -> Result<Self, DomainError> {
+        let royalties: Vec<(String, Money)> = contract.royalty_shares(&sale.price)?.into_iter()
+            .filter(|(_, amount)| amount.is_positive())
+            .collect();
+        let mut seller_amount = sale.price.clone();
+        for (_, amount) in &royalties {
+            seller_amount = seller_amount.checked_sub(amount)?;
+        }
+        let mut postings = vec![Posting::debit(sale.buyer_fp.clone(), &sale.price, PostingKind::Purchase)];
+        // the seller receives nothing when the royalties take the whole price
+        if seller_amount.is_positive() {
+            postings.push(Posting::credit(sale.seller_fp.clone(), &seller_amount, PostingKind::Sale));
+        }
+        for (receiver_fp, amount) in royalties {
+            postings.push(Posting::credit(receiver_fp, &amount, PostingKind::Royalty));
+        }
+        let description = format!("sale of asset {}", sale.asset_id);
+        Self::new(sale.id.clone(), description, sale.price.currency().clone(), postings)
+    }
+
+    pub fn total(&self) -> Result<Money, DomainError> {
+        let zero = Money::from_scaled(&BigDecimal::zero(), self.currency.clone())?;
+        self.postings.iter().try_fold(zero, |total, p| total.checked_add(&p.amount))
+    }
+
+    pub fn is_balanced(&self) -> bool {
+        self.total().is_ok_and(|total| total.is_zero())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{test_currency, Asset, Bid, RoyaltySplit};
+    use std::collections::HashSet;
+    use uuid::Uuid;
+
+    fn usd(amount: &str) -> Money {
+        Money::parse(amount, &test_currency(Currency::USD)).unwrap()
+    }
+
+    fn sale_with_contract(price: &str, royalty_splits: Vec<RoyaltySplit>) -> (Sale, Contract) {
+        let mut asset = Asset::new("asset-name".to_string(),
+                                   "XRF".to_string(),
+                                   "seller_fp".to_string(),
+                                   "description".to_string(),
+                                   Uuid::new_v4().to_string())
+            .unwrap();
+        asset.tradable = true;
+        let contract = Contract::new(asset.id.clone(),
+                                     "details".to_string(),
+                                     "summary".to_string(),
+                                     "seller_fp".to_string(),
+                                     usd("1"),
+                                     false,
+                                     royalty_splits,
+                                     HashSet::from([Currency::USD]))
+            .unwrap();
+        let bid = Bid::new(&asset, &contract, "buyer_fp".to_string(), Uuid::new_v4().to_string(),
+                           usd(price), false, None, None)
+            .unwrap();
+        (Sale::from_bid(&bid, &asset, "nfc_id".to_string()), contract)
+    }
+
+    #[test]
+    fn test_sale_entry_with_royalty_is_balanced() {
+        let (sale, contract) = sale_with_contract("99.99", vec![RoyaltySplit { receiver_fp: "creator_fp".to_string(), basis_points: 750 }]);
+        let entry = JournalEntry::for_sale(&sale, &contract).unwrap();
+        assert!(entry.is_balanced());
+        assert_eq!(entry.postings.len(), 3);
+        assert_eq!(entry.reference_id, sale.id);
+
+        let buyer = entry.postings.iter().find(|p| p.kind == PostingKind::Purchase).unwrap();
+        let royalty = entry.postings.iter().find(|p| p.kind == PostingKind::Royalty).unwrap();
+        let seller = entry.postings.iter().find(|p| p.kind == PostingKind::Sale).unwrap();
+        assert_eq!(buyer.amount, usd("-99.99"));
+        assert_eq!(royalty.account_owner_fp, "creator_fp");
+        // the royalty is rounded down to the cent, the seller keeps the remainder
+        assert_eq!(royalty.amount, usd("7.49"));
+        assert_eq!(seller.amount, usd("92.50"));
+    }
+
+    #[test]
+    fn test_sale_entry_without_royalty() {
+        let (sale, contract) = sale_with_contract("20", vec![]);
+        let entry = JournalEntry::for_sale(&sale, &contract).unwrap();
+        assert!(entry.is_balanced());
+        assert_eq!(entry.postings.len(), 2);
+    }
+
+    #[test]
+    fn test_unbalanced_entry_is_rejected() {
+        let postings = vec![
+            Posting::debit("buyer_fp".to_string(), &usd("10"), PostingKind::Purchase),
+            Posting::credit("seller_fp".to_string(), &usd("9.99"), PostingKind::Sale),
+        ];
+        let result = JournalEntry::new("ref".to_string(), "desc".to_string(), Currency::USD, postings);
+        assert!(result.is_err());
+
+        let postings = vec![
+            Posting::debit("buyer_fp".to_string(), &usd("10"), PostingKind::Purchase),
+            Posting::credit("seller_fp".to_string(), &Money::parse("10", &test_currency(Currency::EUR)).unwrap(), PostingKind::Sale),
+        ];
+        let result = JournalEntry::new("ref".to_string(), "desc".to_string(), Currency::USD, postings);
+        assert!(result.is_err());
+
+        let postings = vec![Posting::debit("buyer_fp".to_string(), &usd("10"), PostingKind::Purchase)];
+        let result = JournalEntry::new("ref".to_string(), "desc".to_string(), Currency::USD, postings);
+        assert!(result.is_err());
+    }
+}
@@ -1,12 +1,48 @@
+mod anchor;
 mod asset;
+mod attribute;
+mod auction;
+mod bid;
+mod certificate;
+mod collection;
 mod error;
 mod key;
 mod contract;
 mod currency;
+mod cursor;
+mod escrow;
+mod event;
+mod fx;
+mod ledger;
+mod money;
 mod nfc;
+mod sale;
+mod trail;
 
-pub use asset::{Asset, UpdateAssetRequest};
-pub use contract::{Contract, ContractVersion};
-pub use currency::{Currency, CurrencyList};
+pub use anchor::{InclusionProof, MerkleTree, NfcAnchor, ProofSide, ProofStep, ANCHOR_LEAF_FORMAT};
+pub use asset::{Asset, AssetFilter, AssetSearch, AssetSearchHit, AssetSortField, UpdateAssetRequest};
+pub use attribute::{
+    normalize_attribute_key, normalize_tag, normalize_tags, AssetAttribute, AssetMetadata, AttributeKind, AttributeValue,
+    MAX_ASSET_ATTRIBUTES, MAX_ASSET_TAGS,
+};
+pub use auction::{Auction, AuctionType, DutchDecay};
+pub use bid::{Bid, BidStatus};
+pub use certificate::{verify_certificate, CertificateSigner, OwnershipClaim, CERTIFICATE_FORMAT};
+pub use collection::{Collection, DefaultContract, UpdateCollectionRequest};
+pub use contract::{
+    Contract, ContractTerms, ContractVersion, ResaleRestriction, RoyaltySplit, UpdateContractRequest, CONTRACT_UPGRADE_AUTHOR,
+    MAX_ROYALTY_BASIS_POINTS,
+};
+pub use currency::{Currency, CurrencyInfo, CurrencyList, CurrencyRegistry};
+#[cfg(test)]
+pub(crate) use currency::test_currency;
+pub use cursor::AssetCursor;
+pub use escrow::{EscrowHold, EscrowStatus};
 pub use error::{DatabaseError, DomainError, OrchestrateError};
-pub use nfc::{NFCTrail, NFC};
+pub use event::{EventFilter, EventSink, EventType, OutboxEvent};
+pub use fx::{FxPolicy, FxRate};
+pub use ledger::{JournalEntry, LedgerAccount, Posting, PostingKind};
+pub use money::Money;
+pub use nfc::{verify_inclusion_proof, NFCTrail, ProvenanceEntry, TransferReason, NFC, TRAIL_HASH_FORMAT};
+pub use sale::Sale;
+pub use trail::{verify_trail_chain, TrailBreak, TrailBreakKind, TrailIntegrityReport};
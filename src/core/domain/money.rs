@@ -0,0 +1,247 @@
+use crate::core::{Currency, CurrencyInfo, DomainError, MAX_ROYALTY_BASIS_POINTS};
+use bigdecimal::num_bigint::BigInt;
+use bigdecimal::{BigDecimal, ToPrimitive};
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::postgres::PgTypeInfo;
+use sqlx::{Database, Encode, Postgres, Type};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// An exact amount of money, stored as an integer number of minor units of its currency
+/// (e.g. cents for USD, satoshis for BTC). Amounts in different currencies never mix.
+///
+/// The decimals of the minor unit come from the [`CurrencyInfo`] the amount was created with. Amounts with
+/// fewer decimals, such as a sum read back as 0, are compared and added exactly to amounts with more decimals.
+#[derive(Debug, Clone)]
+pub struct Money {
+    minor_units: i128,
+    decimals: u32,
+    currency: Currency,
+}
+
+impl Money {
+    pub fn from_minor_units(minor_units: i128, currency: &CurrencyInfo) -> Self {
+        Self { minor_units, decimals: currency.decimals, currency: currency.code.clone() }
+    }
+
+    pub fn zero(currency: &CurrencyInfo) -> Self {
+        Self::from_minor_units(0, currency)
+    }
+
+    /// Fails if the amount has more decimal places than the currency or does not fit in minor units
+    pub fn from_decimal(amount: &BigDecimal, currency: &CurrencyInfo) -> Result<Self, DomainError> {
+        Self::from_decimal_with(amount, currency.decimals, currency.code.clone())
+    }
+
+    /// Parses a decimal amount such as "12.34"
+    pub fn parse(amount: &str, currency: &CurrencyInfo) -> Result<Self, DomainError> {
+        let decimal = BigDecimal::from_str(amount.trim())
+            .map_err(|_| DomainError::InvalidArgument(format!("invalid amount '{}'", amount)))?;
+        Self::from_decimal(&decimal, currency)
+    }
+
+    /// An amount with as many decimals as the scale of `amount`, e.g. a value read back from a NUMERIC column,
+    /// amounts are written with the decimals of their currency
+    pub fn from_scaled(amount: &BigDecimal, currency: Currency) -> Result<Self, DomainError> {
+        let (_, scale) = amount.as_bigint_and_exponent();
+        Self::from_decimal_with(amount, scale.max(0) as u32, currency)
+    }
+
+    fn from_decimal_with(amount: &BigDecimal, decimals: u32, currency: Currency) -> Result<Self, DomainError> {
+        let scaled = amount.with_scale(decimals as i64);
+        if &scaled != amount {
+            let error = format!("{} supports at most {} decimal places :: amount={}", currency, decimals, amount);
+            return Err(DomainError::InvalidArgument(error));
+        }
+        let (minor_units, _) = scaled.as_bigint_and_exponent();
+        let minor_units = minor_units.to_i128()
+            .ok_or_else(|| DomainError::InvalidArgument(format!("amount is too large :: amount={}", amount)))?;
+        Ok(Self { minor_units, decimals, currency })
+    }
+
+    /// An amount in the same currency and with the same decimals
+    pub fn with_minor_units(&self, minor_units: i128) -> Money {
+        Self { minor_units, decimals: self.decimals, currency: self.currency.clone() }
+    }
+
+    pub fn to_decimal(&self) -> BigDecimal {
+        BigDecimal::new(BigInt::from(self.minor_units), self.decimals as i64)
+    }
+
+    pub fn decimals(&self) -> u32 {
+        self.decimals
+    }
+
+    pub fn minor_units(&self) -> i128 {
+        self.minor_units
+    }
+
+    pub fn currency(&self) -> &Currency {
+        &self.currency
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.minor_units == 0
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.minor_units > 0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.minor_units < 0
+    }
+
+    pub fn checked_add(&self, other: &Money) -> Result<Money, DomainError> {
+        let (this, other) = self.aligned(other)?;
+        this.minor_units.checked_add(other.minor_units)
+            .map(|minor_units| this.with_minor_units(minor_units))
+            .ok_or_else(|| DomainError::InvalidArgument(format!("{} + {} overflows", self, other)))
+    }
+
+    pub fn checked_sub(&self, other: &Money) -> Result<Money, DomainError> {
+        let (this, other) = self.aligned(other)?;
+        this.minor_units.checked_sub(other.minor_units)
+            .map(|minor_units| this.with_minor_units(minor_units))
+            .ok_or_else(|| DomainError::InvalidArgument(format!("{} - {} overflows", self, other)))
+    }
+
+    pub fn checked_mul(&self, factor: i128) -> Result<Money, DomainError> {
+        self.minor_units.checked_mul(factor)
+            .map(|minor_units| self.with_minor_units(minor_units))
+            .ok_or_else(|| DomainError::InvalidArgument(format!("{} * {} overflows", self, factor)))
+    }
+
+    pub fn checked_neg(&self) -> Result<Money, DomainError> {
+        self.checked_mul(-1)
+    }
+
+    /// Share of the amount in basis points, rounded towards zero to a whole minor unit
+    pub fn basis_point_share(&self, basis_points: u32) -> Result<Money, DomainError> {
+        let share = self.checked_mul(basis_points as i128)?;
+        Ok(self.with_minor_units(share.minor_units / MAX_ROYALTY_BASIS_POINTS as i128))
+    }
+
+    /// Amounts in different currencies can not be compared
+    pub fn try_cmp(&self, other: &Money) -> Result<Ordering, DomainError> {
+        let (this, other) = self.aligned(other)?;
+        Ok(this.minor_units.cmp(&other.minor_units))
+    }
+
+    /// Both amounts with the decimals of the more precise one
+    fn aligned(&self, other: &Money) -> Result<(Money, Money), DomainError> {
+        if self.currency != other.currency {
+            let error = format!("currency mismatch :: {} and {}", self.currency, other.currency);
+            return Err(DomainError::InvalidArgument(error));
+        }
+        let decimals = self.decimals.max(other.decimals);
+        Ok((self.rescaled(decimals)?, other.rescaled(decimals)?))
+    }
+
+    fn rescaled(&self, decimals: u32) -> Result<Money, DomainError> {
+        10i128.checked_pow(decimals - self.decimals)
+            .and_then(|factor| self.minor_units.checked_mul(factor))
+            .map(|minor_units| Self { minor_units, decimals, currency: self.currency.clone() })
+            .ok_or_else(|| DomainError::InvalidArgument(format!("{} does not fit in {} decimal places", self, decimals)))
+    }
+}
+
+// Equal amounts are equal whatever their decimals, 1.50 USD equals 1.5 USD
+impl PartialEq for Money {
+    fn eq(&self, other: &Self) -> bool {
+        self.try_cmp(other).is_ok_and(Ordering::is_eq)
+    }
+}
+
+impl Eq for Money {}
+
+impl Display for Money {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.to_decimal(), self.currency)
+    }
+}
+
+// Money is written to NUMERIC columns. A NUMERIC value does not carry its currency, rows are read as
+// BigDecimal and turned back into Money with `Money::from_scaled` and the currency column of the row.
+impl Type<Postgres> for Money {
+    fn type_info() -> PgTypeInfo {
+        <BigDecimal as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <BigDecimal as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl<'q> Encode<'q, Postgres> for Money {
+    fn encode_by_ref(&self, buf: &mut <Postgres as Database>::ArgumentBuffer<'q>) -> Result<IsNull, BoxDynError> {
+        <BigDecimal as Encode<'_, Postgres>>::encode(self.to_decimal(), buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_currency;
+
+    fn usd(amount: &str) -> Money {
+        Money::parse(amount, &test_currency(Currency::USD)).unwrap()
+    }
+
+    #[test]
+    fn test_parse_respects_currency_decimals() {
+        assert_eq!(usd("12.34").minor_units(), 1234);
+        assert_eq!(usd("12").minor_units(), 1200);
+        assert!(Money::parse("12.345", &test_currency(Currency::USD)).is_err());
+        assert_eq!(Money::parse("1500", &test_currency(Currency::JPY)).unwrap().minor_units(), 1500);
+        assert!(Money::parse("1500.5", &test_currency(Currency::JPY)).is_err());
+        assert_eq!(Money::parse("0.00000001", &test_currency(Currency::BTC)).unwrap().minor_units(), 1);
+        assert_eq!(Money::parse("1.000000000000000001", &test_currency(Currency::ETH)).unwrap().minor_units(), 1_000_000_000_000_000_001);
+        assert!(Money::parse("abc", &test_currency(Currency::USD)).is_err());
+
+        // the decimals are those of the registry, not of the currency code
+        let mut usd4 = test_currency(Currency::USD);
+        usd4.decimals = 4;
+        assert_eq!(Money::parse("12.345", &usd4).unwrap().minor_units(), 123_450);
+    }
+
+    #[test]
+    fn test_decimal_round_trip() {
+        let money = Money::from_minor_units(-1999, &test_currency(Currency::USD));
+        assert_eq!(money.to_decimal().to_string(), "-19.99");
+        assert_eq!(Money::from_decimal(&money.to_decimal(), &test_currency(Currency::USD)).unwrap(), money);
+        assert_eq!(Money::from_scaled(&money.to_decimal(), Currency::USD).unwrap().decimals(), 2);
+        assert_eq!(Money::from_minor_units(7, &test_currency(Currency::JPY)).to_string(), "7 JPY");
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let a = usd("0.10");
+        let b = usd("0.20");
+        assert_eq!(a.checked_add(&b).unwrap(), usd("0.30"));
+        assert_eq!(a.checked_sub(&b).unwrap().minor_units(), -10);
+        assert!(a.checked_add(&Money::parse("0.10", &test_currency(Currency::EUR)).unwrap()).is_err());
+        assert!(Money::from_minor_units(i128::MAX, &test_currency(Currency::USD)).checked_add(&a).is_err());
+        assert_eq!(a.try_cmp(&b).unwrap(), Ordering::Less);
+        assert!(a.try_cmp(&Money::zero(&test_currency(Currency::BTC))).is_err());
+    }
+
+    #[test]
+    fn test_amounts_with_fewer_decimals_are_aligned() {
+        let zero = Money::from_scaled(&BigDecimal::from(0), Currency::USD).unwrap();
+        assert_eq!(zero.decimals(), 0);
+        assert_eq!(zero, Money::zero(&test_currency(Currency::USD)));
+        let sum = zero.checked_add(&usd("1.25")).unwrap();
+        assert_eq!((sum.minor_units(), sum.decimals()), (125, 2));
+        assert_eq!(usd("1.25").try_cmp(&Money::from_scaled(&BigDecimal::from(2), Currency::USD).unwrap()).unwrap(), Ordering::Less);
+    }
+
+    #[test]
+    fn test_basis_points_rounds_towards_zero() {
+        let price = usd("99.99");
+        assert_eq!(price.basis_point_share(750).unwrap().minor_units(), 749);
+        assert_eq!(price.basis_point_share(10_000).unwrap(), price);
+    }
+}
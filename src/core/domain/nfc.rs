@@ -1,38 +1,69 @@
+use crate::core::domain::anchor::{hash_leaf, hash_node};
 use crate::core::domain::key::{generate_unique_key, DOMAIN_KEY_SIZE};
-use crate::core::DomainError;
-use base64::engine::general_purpose;
-use base64::Engine;
-use chrono::{DateTime, Utc};
-use getrandom;
-use ring::rand::SecureRandom;
-use sha2::{Digest, Sha512};
+use crate::core::{Asset, ProofSide, ProofStep, ANCHOR_LEAF_FORMAT, CertificateSigner, Money, OwnershipClaim};
+use chrono::{DateTime, SubsecRound, Utc};
+use sha2::{Digest, Sha256};
 use std::fmt::{Display, Formatter};
-use std::sync::{Arc, Mutex};
-use std::time::SystemTime;
+use strum_macros::EnumString;
+
+/// Version of the hashed content of a trail, changes whenever the layout of the content changes
+pub const TRAIL_HASH_FORMAT: &str = "xrf1-trail-v1";
 
 #[derive(Debug, Clone)]
 pub struct NFC {
     pub id: String,
+    /// signature of the ownership claim of the current owner, see `OwnershipClaim`
     pub cert: String,
     pub asset_id: String,
     pub created_at: DateTime<Utc>,
 }
 
 impl NFC {
-    pub fn new(asset_id: String) -> Result<Self, DomainError> {
-        let cert_id = generate_unique_key(DOMAIN_KEY_SIZE);
-        let certificate = generate_certificate(&asset_id)
-            .map_err(|err| {
-                return DomainError::ServerError(format!("Failed to generate certificate: {}", err));
-            })?;
-        let now = Utc::now();
-        Ok(Self {
+    pub fn new(asset_id: String, owner_fp: &str, signer: &CertificateSigner) -> Self {
+        // timestamps are stored with microseconds, the signed time must survive the round trip
+        let now = Utc::now().trunc_subsecs(6);
+        let mut nfc = Self {
             asset_id,
-            id: cert_id,
+            id: generate_unique_key(DOMAIN_KEY_SIZE),
             created_at: now,
-            cert: certificate,
-        })
+            cert: String::new(),
+        };
+        nfc.sign_for(owner_fp, signer);
+        nfc
+    }
+
+    /// Certifies that the asset is owned by `owner_fp`, replacing the certificate of the previous owner
+    pub fn sign_for(&mut self, owner_fp: &str, signer: &CertificateSigner) {
+        self.cert = signer.sign(&OwnershipClaim::for_nfc(self, owner_fp));
+    }
+
+    /// Anchored bytes: `xrf1-anchor-v1|<nfc_id>|<asset_id>|<created_at in epoch microseconds>`.
+    /// The leaf only holds what never changes, the certificate is signed again on every transfer.
+    pub fn anchor_leaf(&self) -> String {
+        format!("{}|{}|{}|{}", ANCHOR_LEAF_FORMAT, self.id, self.asset_id, self.created_at.timestamp_micros())
+    }
+
+    /// Hex encoded leaf hash of the NFC in the Merkle tree of its anchor
+    pub fn anchor_leaf_hash(&self) -> String {
+        hash_leaf(&self.anchor_leaf())
+    }
+}
+
+/// Checks that a leaf is in the tree of an anchor by hashing it with its siblings, ordered from the leaf, up to
+/// the root. Inner nodes are the hex encoded SHA-256 of `0x01 || left || right`, which is all a third party needs.
+pub fn verify_inclusion_proof(leaf_hash: &str, steps: &[ProofStep], root: &str) -> bool {
+    let mut node = leaf_hash.to_lowercase();
+    for step in steps {
+        let parent = match step.side {
+            ProofSide::Left => hash_node(&step.sibling, &node),
+            ProofSide::Right => hash_node(&node, &step.sibling),
+        };
+        let Some(parent) = parent else {
+            return false;
+        };
+        node = parent;
     }
+    node == root.to_lowercase()
 }
 
 impl Display for NFC {
@@ -42,171 +73,172 @@ impl Display for NFC {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, sqlx::Type)]
+#[sqlx(type_name = "transfer_reason", rename_all = "lowercase")]
+#[strum(ascii_case_insensitive)]
+pub enum TransferReason {
+    /// the asset was created, the trail starts with its first owner
+    Mint,
+    /// the owner moved the asset without a sale
+    Transfer,
+    /// the asset went to the buyer of an accepted bid
+    Sale,
+}
+
+impl Display for TransferReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransferReason::Mint => write!(f, "mint"),
+            TransferReason::Transfer => write!(f, "transfer"),
+            TransferReason::Sale => write!(f, "sale"),
+        }
+    }
+}
+
+/// One change of ownership of an asset, the trail of an NFC is the ordered list of its owners.
 #[derive(Debug, Clone)]
 pub struct NFCTrail {
     pub nfc_id: String,
+    /// owner after the change
     pub user_fp: String,
     pub asset_id: String,
     pub transferred_on: DateTime<Utc>,
+    /// organization of the owner after the change, unknown for some trails recorded before it was tracked
+    pub organization: Option<String>,
+    /// owner before the change, None for the mint
+    pub previous_owner_fp: Option<String>,
+    pub previous_org: Option<String>,
+    pub reason: TransferReason,
+    /// set when the asset changed hands through a sale
+    pub sale_id: Option<String>,
+    /// hash of the row before this one in the trail of the NFC, None for the first row
+    pub previous_hash: Option<String>,
+    /// hash of the content of this row, see `hash_content`
+    pub hash: String,
 }
 
 impl Display for NFCTrail {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "nfc '{}' transferred on - '{}'",
-            self.nfc_id, self.transferred_on
+            "nfc '{}' transferred on - '{}' :: reason={}",
+            self.nfc_id, self.transferred_on, self.reason
         )
     }
 }
 
 impl NFCTrail {
-    pub fn new(
-        nfc_id: String, user_fp: String, asset_id: String) -> Self {
-        let now = Utc::now();
+    /// First row of the trail, when the asset is created with its NFC
+    pub fn mint(nfc: &NFC, user_fp: String, organization: String) -> Self {
+        Self {
+            user_fp,
+            nfc_id: nfc.id.clone(),
+            asset_id: nfc.asset_id.clone(),
+            // timestamps are stored with microseconds, the hashed time must survive the round trip
+            transferred_on: Utc::now().trunc_subsecs(6),
+            organization: Some(organization),
+            previous_owner_fp: None,
+            previous_org: None,
+            reason: TransferReason::Mint,
+            sale_id: None,
+            previous_hash: None,
+            hash: String::new(),
+        }
+    }
+
+    /// `asset` is the asset as it was before the transfer, the reason is a sale when `sale_id` is set
+    pub fn transfer(nfc_id: String,
+                    asset: &Asset,
+                    user_fp: String,
+                    organization: String,
+                    sale_id: Option<String>) -> Self {
+        let reason = if sale_id.is_some() { TransferReason::Sale } else { TransferReason::Transfer };
         Self {
             nfc_id,
             user_fp,
-            asset_id,
-            transferred_on: now,
+            reason,
+            sale_id,
+            asset_id: asset.id.clone(),
+            // timestamps are stored with microseconds, the hashed time must survive the round trip
+            transferred_on: Utc::now().trunc_subsecs(6),
+            organization: Some(organization),
+            previous_owner_fp: Some(asset.owner_fp.clone()),
+            previous_org: Some(asset.organization.clone()),
+            previous_hash: None,
+            hash: String::new(),
         }
     }
-}
 
-/// Generates a unique, non-fungible string.
-///
-/// This function combines multiple sources of entropy, including:
-/// 1.  A high-precision timestamp (nanoseconds).
-/// 2.  Random bytes from a cryptographically secure random bytes generator (using ring).
-/// 3.  A thread-safe, globally unique counter (incremented for each call).
-///
-/// The combined data is then hashed using SHA-256, and the resulting hash is encoded
-/// using Base64 URL-safe encoding to produce the final string.
-///
-/// # Thread Safety
-///
-/// The `GLOBAL_NFT_COUNTER` is protected by an `Arc<Mutex<>>`, ensuring that only one thread
-/// can increment the counter at a time, preventing race conditions and guaranteeing
-/// uniqueness across multiple threads.
-///
-/// # Security Considerations
-///
-/// *   **ring:** Use the ring CSPRNG for strong random bytes' generation.
-/// *   **SHA-256:** Employs the SHA-256 hashing algorithm for collision resistance.  While SHA-256
-///     is currently considered secure, it is recommended to monitor cryptographic best practices and
-///     potentially migrate to stronger hashing algorithms (like SHA-3) as they become available.
-/// *   **Timestamp Precision:** Uses nanosecond-precision timestamps for increased entropy.
-///     This reduces (but does not completely eliminate) the tiny risk of collision
-///     if two tokens are generated within the same nanosecond on different threads, *and* the
-///     random bytes happen to be identical (extraordinarily unlikely).
-/// * **Global Counter:** The global counter adds process-wide (or even machine wide if combined w/
-///     a unique machine identifier) uniqueness.
-/// * **Base64 Encoding:** Base64 URL-safe encoding makes the generated string suitable for use in
-///     URLs and other contexts where special characters are not allowed.
-fn generate_certificate(asset_id: &str) -> Result<String, String> {
-    // Use an Arc<Mutex<>> to make the counter thread-safe.
-    lazy_static::lazy_static! {
-        static ref GLOBAL_NFT_COUNTER: Arc<Mutex<u128>> = Arc::new(Mutex::new(0));
+    /// Hashed bytes: `xrf1-trail-v1|<previous_hash>|<nfc_id>|<asset_id>|<user_fp>|<organization>|<previous_owner_fp>|
+    /// <previous_org>|<reason>|<sale_id>|<transferred_on in epoch microseconds>`, missing values are empty
+    pub fn hash_content(&self) -> String {
+        format!("{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}",
+                TRAIL_HASH_FORMAT,
+                self.previous_hash.as_deref().unwrap_or_default(),
+                self.nfc_id,
+                self.asset_id,
+                self.user_fp,
+                self.organization.as_deref().unwrap_or_default(),
+                self.previous_owner_fp.as_deref().unwrap_or_default(),
+                self.previous_org.as_deref().unwrap_or_default(),
+                self.reason,
+                self.sale_id.as_deref().unwrap_or_default(),
+                self.transferred_on.timestamp_micros())
     }
 
-    // 1. Get a high-precision timestamp (nanoseconds).
-    let now = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .map_err(|err| {
-            return format!("Failed to get system date: err={}", err);
-        });
-    let timestamp_nanos = now?.as_nanos();
-
-    // 2. Generate secure random bytes using ring.
-    let mut random_bytes = vec![0u8; 64];
-    let rng = ring::rand::SystemRandom::new();
-    rng.fill(&mut random_bytes).map_err(|err| {
-        return format!("Failed to generate random bytes with ring: err={}", err);
-    })?;
-
-    let mut salt = vec![0u8; 32];
-    let rng = ring::rand::SystemRandom::new();
-    rng.fill(&mut salt).map_err(|err| {
-        return format!("Failed to generate random bytes with for salt: err={}", err);
-    })?;
-
-    let mut counter_guard = GLOBAL_NFT_COUNTER
-        .lock()
-        .map_err(|err| {
-            return format!("Failed to lock global nft: err={}", err);
-        })?;
-
-    let counter_val = *counter_guard;
-    *counter_guard += 1;
-
-    // 2. Combine timestamp, random bytes, and asset_id.
-    let salt = generate_salt(asset_id)?; // Salt to make data more unique
-    let combined_data = format!(
-        "{}*{}**{}*{}",
-        timestamp_nanos,
-        hex::encode(random_bytes), //hex encode for better formatting
-        counter_val,
-        salt
-    );
-
-    // 3. Hash using SHA-256 (SHA-512 is slow and best for 64-bit OS).
-    let mut hasher = Sha512::new();
-    hasher.update(combined_data);
-    let hash_result = hasher.finalize();
-
-    // 4. encode using Base64 for URL-safety.
-    Ok(general_purpose::URL_SAFE_NO_PAD.encode(hash_result))
+    /// Hex encoded SHA-256 of the content
+    pub fn compute_hash(&self) -> String {
+        hex::encode(Sha256::digest(self.hash_content().as_bytes()))
+    }
+
+    /// Appends the row after the row hashed `previous_hash`, None when it starts the trail
+    pub fn chain_to(&mut self, previous_hash: Option<String>) {
+        self.previous_hash = previous_hash;
+        self.hash = self.compute_hash();
+    }
 }
 
-fn generate_salt(asset_id: &str) -> Result<String, String> {
-    let mut salt_bytes = [0u8; 16];
-    getrandom::fill(&mut salt_bytes).map_err(|err| {
-        return format!("Failed to generate salt: err={}", err);
-    })?;
-    Ok(format!("{}_{}", asset_id, general_purpose::URL_SAFE_NO_PAD.encode(salt_bytes)))
+/// A row of the trail of an asset with the price it was sold for, if it was sold
+#[derive(Debug, Clone)]
+pub struct ProvenanceEntry {
+    pub trail: NFCTrail,
+    pub price: Option<Money>,
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::core::domain::nfc::generate_certificate;
-    use std::collections::HashSet;
-    use std::sync::mpsc;
-    use std::thread;
+    use super::*;
+    use uuid::Uuid;
 
     #[test]
-    fn test_generate_certificate() {
-        let asset_id = "1234";
-        let cert_1 = generate_certificate(asset_id);
-        assert!(cert_1.is_ok());
-        let cert_2 = generate_certificate(asset_id);
-        assert!(cert_2.is_ok());
-        assert_ne!(cert_1, cert_2, "IDs should be different");
+    fn test_certificate_follows_owner() {
+        let signer = CertificateSigner::generate().unwrap();
+        let mut nfc = NFC::new("asset_id".to_string(), "owner_fp", &signer);
+        assert_eq!(nfc.created_at.timestamp_subsec_nanos() % 1000, 0);
+        assert!(signer.verify(&OwnershipClaim::for_nfc(&nfc, "owner_fp"), &nfc.cert));
+
+        nfc.sign_for("new_owner_fp", &signer);
+        assert!(signer.verify(&OwnershipClaim::for_nfc(&nfc, "new_owner_fp"), &nfc.cert));
+        assert!(!signer.verify(&OwnershipClaim::for_nfc(&nfc, "owner_fp"), &nfc.cert));
     }
 
     #[test]
-    fn test_thread_safety() {
-        let num_threads = 10;
-        let asset_id = "1234";
-        let ids_per_thread = 100;
-        let (tx, rx) = mpsc::channel();
-
-        for _ in 0..num_threads {
-            let tx = tx.clone();
-            thread::spawn(move || {
-                for _ in 0..ids_per_thread {
-                    let cert_id = generate_certificate(asset_id);
-                    assert!(cert_id.is_ok());
-                    tx.send(cert_id.unwrap()).unwrap();
-                }
-            });
-        }
-
-        let mut all_ids = HashSet::new();
-        for _ in 0..(num_threads * ids_per_thread) {
-            let id = rx.recv().unwrap();
-            assert!(!all_ids.contains(&id), "Duplicate ID found: {}", id);
-            all_ids.insert(id);
-        }
-        assert_eq!(all_ids.len(), num_threads * ids_per_thread);
+    fn test_transfer_trail_records_previous_owner() {
+        let owner_org = Uuid::new_v4().to_string();
+        let asset = Asset::new("asset-name".to_string(),
+                               "XRF".to_string(),
+                               "owner_fp".to_string(),
+                               "description".to_string(),
+                               owner_org.clone())
+            .unwrap();
+        let transfer = NFCTrail::transfer("nfc_id".to_string(), &asset, "new_owner_fp".to_string(), "new_org".to_string(),
+                                          None);
+        assert_eq!(transfer.reason, TransferReason::Transfer);
+        assert_eq!(transfer.previous_owner_fp.as_deref(), Some("owner_fp"));
+        assert_eq!(transfer.previous_org.as_deref(), Some(owner_org.as_str()));
+
+        let sale = NFCTrail::transfer("nfc_id".to_string(), &asset, "new_owner_fp".to_string(), "new_org".to_string(),
+                                      Some("sale_id".to_string()));
+        assert_eq!(sale.reason, TransferReason::Sale);
     }
 }
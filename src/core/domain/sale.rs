@@ -0,0 +1,44 @@
+use crate::core::domain::key::{generate_unique_key, DOMAIN_KEY_SIZE};
+use crate::core::{Asset, Bid, Money};
+use chrono::{DateTime, Utc};
+use std::fmt::{Display, Formatter};
+
+/// Record of an asset changing hands for a price, created when its owner accepts a bid.
+#[derive(Debug, Clone)]
+pub struct Sale {
+    pub id: String,
+    pub price: Money,
+    pub bid_id: String,
+    pub nfc_id: String,
+    pub asset_id: String,
+    pub buyer_fp: String,
+    pub buyer_org: String,
+    pub seller_fp: String,
+    pub seller_org: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Sale {
+    /// `asset` is the asset as it was before it was transferred to the bidder
+    pub fn from_bid(bid: &Bid, asset: &Asset, nfc_id: String) -> Self {
+        Self {
+            nfc_id,
+            price: bid.amount.clone(),
+            bid_id: bid.id.clone(),
+            created_at: Utc::now(),
+            asset_id: asset.id.clone(),
+            buyer_fp: bid.bidder_fp.clone(),
+            buyer_org: bid.bidder_org.clone(),
+            seller_fp: asset.owner_fp.clone(),
+            seller_org: asset.organization.clone(),
+            id: generate_unique_key(DOMAIN_KEY_SIZE),
+        }
+    }
+}
+
+impl Display for Sale {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "saleId:{}, assetId:{}, bidId:{}, price={}",
+               self.id, self.asset_id, self.bid_id, self.price)
+    }
+}
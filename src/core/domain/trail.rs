@@ -0,0 +1,130 @@
+use crate::core::NFCTrail;
+use chrono::{DateTime, Utc};
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrailBreakKind {
+    /// the content of the row does not match its hash, the row was edited
+    ContentChanged,
+    /// the row does not follow the row before it, rows were removed, inserted or reordered
+    ChainBroken,
+}
+
+impl Display for TrailBreakKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrailBreakKind::ContentChanged => write!(f, "content_changed"),
+            TrailBreakKind::ChainBroken => write!(f, "chain_broken"),
+        }
+    }
+}
+
+/// First row of the trail of an asset that does not verify
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrailBreak {
+    pub asset_id: String,
+    pub nfc_id: String,
+    /// index of the row in the trail, 0 is the mint
+    pub position: usize,
+    pub transferred_on: DateTime<Utc>,
+    pub kind: TrailBreakKind,
+}
+
+impl Display for TrailBreak {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "assetId:{}, nfcId:{}, position={}, transferredOn={}, kind={}",
+               self.asset_id, self.nfc_id, self.position, self.transferred_on, self.kind)
+    }
+}
+
+impl TrailBreak {
+    fn at(trail: &NFCTrail, position: usize, kind: TrailBreakKind) -> Self {
+        Self {
+            position,
+            kind,
+            asset_id: trail.asset_id.clone(),
+            nfc_id: trail.nfc_id.clone(),
+            transferred_on: trail.transferred_on,
+        }
+    }
+}
+
+/// Outcome of walking the trails of one or more assets
+#[derive(Debug, Clone, Default)]
+pub struct TrailIntegrityReport {
+    pub checked_assets: usize,
+    /// the first break of every asset whose trail does not verify
+    pub breaks: Vec<TrailBreak>,
+}
+
+impl TrailIntegrityReport {
+    pub fn is_intact(&self) -> bool {
+        self.breaks.is_empty()
+    }
+}
+
+/// Walks the trail of an NFC, ordered from its first row, and returns the first row that does not verify
+pub fn verify_trail_chain(trails: &[NFCTrail]) -> Option<TrailBreak> {
+    let mut previous_hash: Option<&str> = None;
+    for (position, trail) in trails.iter().enumerate() {
+        if trail.previous_hash.as_deref() != previous_hash {
+            return Some(TrailBreak::at(trail, position, TrailBreakKind::ChainBroken));
+        }
+        if trail.compute_hash() != trail.hash {
+            return Some(TrailBreak::at(trail, position, TrailBreakKind::ContentChanged));
+        }
+        previous_hash = Some(&trail.hash);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{CertificateSigner, TransferReason, NFC};
+
+    fn chain() -> Vec<NFCTrail> {
+        let nfc = NFC::new("asset_id".to_string(), "owner_fp", &CertificateSigner::generate().unwrap());
+        let mut mint = NFCTrail::mint(&nfc, "owner_fp".to_string(), "owner_org".to_string());
+        mint.chain_to(None);
+        let mut transfer = NFCTrail {
+            user_fp: "new_owner_fp".to_string(),
+            previous_owner_fp: Some("owner_fp".to_string()),
+            reason: TransferReason::Transfer,
+            ..mint.clone()
+        };
+        transfer.chain_to(Some(mint.hash.clone()));
+        vec![mint, transfer]
+    }
+
+    #[test]
+    fn test_intact_chain_verifies() {
+        assert_eq!(verify_trail_chain(&chain()), None);
+        assert_eq!(verify_trail_chain(&[]), None);
+    }
+
+    #[test]
+    fn test_edited_row_breaks_chain() {
+        let mut trails = chain();
+        trails[1].user_fp = "thief_fp".to_string();
+        let broken = verify_trail_chain(&trails).unwrap();
+        assert_eq!(broken.position, 1);
+        assert_eq!(broken.kind, TrailBreakKind::ContentChanged);
+
+        // the hash of the edited row was recomputed, the next row no longer follows it
+        let mut trails = chain();
+        trails[0].user_fp = "thief_fp".to_string();
+        trails[0].chain_to(None);
+        let broken = verify_trail_chain(&trails).unwrap();
+        assert_eq!(broken.position, 1);
+        assert_eq!(broken.kind, TrailBreakKind::ChainBroken);
+    }
+
+    #[test]
+    fn test_removed_row_breaks_chain() {
+        let trails = chain();
+        let broken = verify_trail_chain(&trails[1..]).unwrap();
+        assert_eq!(broken.position, 0);
+        assert_eq!(broken.kind, TrailBreakKind::ChainBroken);
+    }
+}
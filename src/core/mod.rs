@@ -4,3 +4,9 @@ mod domain;
 pub use domain::*;
 pub mod orchestrator;
 pub mod queries;
+pub mod repository;
+
+/// Messages of the events recorded in the outbox, see `proto/event/v1/event.proto`
+pub mod event_proto {
+    tonic::include_proto!("proto.event.v1");
+}
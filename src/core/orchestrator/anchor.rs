@@ -0,0 +1,88 @@
+use crate::core::queries::UnitOfWork;
+use crate::core::repository::NfcRepository;
+use crate::core::{queries, DatabaseError, InclusionProof, MerkleTree, NfcAnchor, OrchestrateError};
+use chrono::{SubsecRound, Utc};
+use sqlx::PgPool;
+use tracing::{error, info};
+
+/// Maximum number of NFCs anchored by a single run of `anchor_nfc_certificates`, the rest wait for the next run
+const ANCHOR_BATCH_SIZE: i64 = 10_000;
+
+/// Anchors the NFCs issued since the last anchor, in a single transaction:
+/// 1. The NFCs created before now that are not anchored yet become the leaves of a Merkle tree, oldest first
+/// 2. The root is stored as a new anchor with the leaves it covers
+///
+/// Returns None when there is nothing to anchor. NFCs committed after a run, even when created before its
+/// window ended, are anchored by the next run.
+pub async fn anchor_nfc_certificates(pg_pool: &PgPool) -> Result<Option<NfcAnchor>, OrchestrateError> {
+    let mut transaction = UnitOfWork::begin(pg_pool).await?;
+    queries::lock_nfc_anchoring(&mut transaction).await?;
+
+    // 1. Collect the leaves
+    let now = Utc::now().trunc_subsecs(6);
+    let nfcs = queries::find_unanchored_nfcs(now, ANCHOR_BATCH_SIZE, &mut *transaction).await?;
+    let (Some(first), Some(last)) = (nfcs.first(), nfcs.last()) else {
+        return Ok(None);
+    };
+    let last_anchor = queries::find_last_nfc_anchor(&mut *transaction).await?;
+    let window_start = match last_anchor {
+        Some(anchor) => anchor.window_end.min(first.created_at),
+        None => first.created_at,
+    };
+    // a full batch only covers the NFCs up to its last one
+    let window_end = if nfcs.len() as i64 == ANCHOR_BATCH_SIZE { last.created_at } else { now };
+
+    let leaves: Vec<(String, String)> = nfcs.iter().map(|nfc| (nfc.id.clone(), nfc.anchor_leaf_hash())).collect();
+    let tree = MerkleTree::build(leaves.iter().map(|(_, leaf_hash)| leaf_hash.clone()).collect())
+        .ok_or_else(|| OrchestrateError::ServerError("failed to build anchor tree".to_string()))?;
+
+    // 2. Store the root and its leaves
+    let anchor = NfcAnchor::new(window_start, window_end, &tree);
+    let created = queries::create_nfc_anchor(&mut transaction, &anchor, &leaves).await?;
+    if !created {
+        return Err(OrchestrateError::ServerError("failed to create nfc anchor".to_string()));
+    }
+
+    transaction.commit().await?;
+    info!("anchored nfc certificates :: {}", anchor);
+    Ok(Some(anchor))
+}
+
+/// Sibling path from the leaf of an NFC to the root of its anchor, rebuilt from the stored leaves.
+/// Fails with `InvalidState` while the NFC is not anchored yet.
+pub async fn find_inclusion_proof<R: NfcRepository>(nfc_id: &str, repository: &R) -> Result<InclusionProof, OrchestrateError> {
+    info!("finding inclusion proof :: nfc_id={}", nfc_id);
+    let nfc = repository.get_nfc_by_id(nfc_id)
+        .await
+        .map_err(|e| match e {
+            DatabaseError::NotFound => OrchestrateError::NotFoundError("nfc not found".to_string()),
+            _ => OrchestrateError::DatabaseError(e),
+        })?;
+    let anchor = repository.find_nfc_anchor_by_nfc_id(nfc_id)
+        .await
+        .map_err(|e| match e {
+            DatabaseError::NotFound => OrchestrateError::InvalidState("nfc is not anchored yet".to_string()),
+            _ => OrchestrateError::DatabaseError(e),
+        })?;
+
+    let leaves = repository.find_nfc_anchor_leaves(&anchor.id).await?;
+    let leaf_index = leaves.iter()
+        .position(|(leaf_nfc_id, _)| leaf_nfc_id == nfc_id)
+        .ok_or_else(|| OrchestrateError::ServerError("anchor leaf not found".to_string()))?;
+    let leaf_hash = leaves[leaf_index].1.clone();
+    if leaf_hash != nfc.anchor_leaf_hash() {
+        error!("anchor leaf does not match its nfc :: anchor_id={} :: nfc_id={}", anchor.id, nfc_id);
+        return Err(OrchestrateError::ServerError("anchor leaf does not match its nfc".to_string()));
+    }
+    let tree = MerkleTree::build(leaves.into_iter().map(|(_, leaf_hash)| leaf_hash).collect())
+        .ok_or_else(|| OrchestrateError::ServerError("failed to build anchor tree".to_string()))?;
+    // the stored leaves must still hash to the published root
+    if tree.root() != anchor.root {
+        error!("anchor leaves do not match its root :: anchor_id={}", anchor.id);
+        return Err(OrchestrateError::ServerError("anchor leaves do not match its root".to_string()));
+    }
+    let steps = tree.proof(leaf_index)
+        .ok_or_else(|| OrchestrateError::ServerError("anchor leaf not found".to_string()))?;
+
+    Ok(InclusionProof { anchor, nfc, leaf_hash, leaf_index, steps })
+}
@@ -1,24 +1,37 @@
-use crate::core::{queries, DatabaseError, OrchestrateError, NFC};
-use sqlx::PgPool;
+use crate::core::repository::{AssetRepository, ContractRepository, NfcRepository};
+use crate::core::{CertificateSigner, DatabaseError, OrchestrateError, OwnershipClaim, NFC};
+use chrono::Utc;
 use tracing::info;
 
 /// Transferring an asset should only happen if
 /// 1. It is being transferred from one user in the same org to another
 /// 2. If it is being transferred from one org to another.
-pub async fn transfer_asset(org_id: &str,
-                            asset_id: &str,
-                            new_org_id: &str,
-                            new_asset_owner: &str,
-                            pg_pool: &PgPool)
-                            -> Result<NFC, OrchestrateError> {
+///
+/// An asset held in escrow can only be transferred to the buyer of the hold once it is funded,
+/// the transfer then completes the sale and releases the hold in the same unit of work.
+/// The asset and its contract are locked until the transfer commits, so the sale settles against the current terms.
+/// The certificate of the NFC is signed again for the new owner.
+pub async fn transfer_asset<R>(org_id: &str,
+                               asset_id: &str,
+                               new_org_id: &str,
+                               new_asset_owner: &str,
+                               signer: &CertificateSigner,
+                               repository: &R) -> Result<NFC, OrchestrateError>
+where
+    R: AssetRepository + ContractRepository + NfcRepository,
+{
     info!("starting asset transfer :: asset_id={}", asset_id);
-    // 1. Get asset information
-    let asset = queries::find_asset_by_id_and_org_id(&asset_id, &org_id, &pg_pool)
+    // 1. Lock the asset, every change of ownership goes through this lock
+    let mut transaction = repository.begin().await?;
+    let asset = repository.lock_asset(asset_id, &mut transaction)
         .await
         .map_err(|e| match e {
             DatabaseError::NotFound => OrchestrateError::NotFoundError("asset not found in specified org".to_string()),
             _ => OrchestrateError::DatabaseError(e),
         })?;
+    if asset.organization != org_id {
+        return Err(OrchestrateError::NotFoundError("asset not found in specified org".to_string()));
+    }
 
     // 2. Do not transfer an asset if it's the same org, and it's the same user
     if asset.organization == new_org_id && asset.updated_by == new_asset_owner {
@@ -26,20 +39,76 @@ pub async fn transfer_asset(org_id: &str,
     }
 
     // 3. get contract information about the asset
-    let _ = queries::find_contract_by_asset_id(asset_id, &pg_pool)
+    let contract = repository.lock_contract(asset_id, &mut transaction)
         .await
         .map_err(|e| match e {
             DatabaseError::NotFound => OrchestrateError::NotFoundError(asset_id.to_string()),
             _ => OrchestrateError::DatabaseError(e),
         })?;
 
-    // 4. Transfer asset and get NFC for asset back
-    let nfc = queries::transfer_asset_query(new_org_id, asset_id, new_asset_owner, &pg_pool)
+    // 4. Transfer asset and get NFC for asset back, completing the sale of a funded hold
+    let nfc = match repository.find_active_escrow_hold(asset_id, &mut transaction).await? {
+        None => repository.transfer_asset(asset_id, new_org_id, new_asset_owner, signer, &mut transaction)
+            .await
+            .map_err(|e| match e {
+                DatabaseError::NotFound => OrchestrateError::NotFoundError(asset_id.to_string()),
+                DatabaseError::InvalidRecordState(msg) => OrchestrateError::InvalidState(msg),
+                _ => OrchestrateError::DatabaseError(e),
+            })?,
+        Some(hold) => {
+            hold.check_releasable(new_asset_owner, new_org_id, Utc::now())
+                .map_err(|e| OrchestrateError::InvalidState(e.to_string()))?;
+            let (nfc, sale) = repository.sell_to_bidder(&hold.bid_id, &asset, &contract, signer, &mut transaction)
+                .await
+                .map_err(|e| match e {
+                    DatabaseError::TransactionStepError(msg) => OrchestrateError::ServerError(msg),
+                    _ => OrchestrateError::DatabaseError(e),
+                })?;
+            if !repository.release_escrow_hold(&hold.id, &mut transaction).await? {
+                return Err(OrchestrateError::InvalidState("escrow hold is no longer funded".to_string()));
+            }
+            info!("escrow hold released :: hold_id={} :: sale_id={}", hold.id, sale.id);
+            nfc
+        }
+    };
+
+    repository.commit(transaction).await?;
+    Ok(nfc)
+}
+
+/// Certificate of the current owner of an asset, returned with the owner it certifies.
+/// Certificates that do not verify for the current owner, issued before certificates were signed
+/// or with a previous server key, are signed again.
+pub async fn find_owner_certificate<R>(asset_id: &str,
+                                       signer: &CertificateSigner,
+                                       repository: &R) -> Result<(NFC, String), OrchestrateError>
+where
+    R: AssetRepository + NfcRepository,
+{
+    // 1. Lock the asset so that the owner can not change while the certificate is checked
+    let mut transaction = repository.begin().await?;
+    let asset = repository.lock_asset(asset_id, &mut transaction)
         .await
         .map_err(|e| match e {
-            DatabaseError::NotFound => OrchestrateError::NotFoundError(asset_id.to_string()),
+            DatabaseError::NotFound => OrchestrateError::NotFoundError("asset not found".to_string()),
+            _ => OrchestrateError::DatabaseError(e),
+        })?;
+    let mut nfc = repository.get_nfc_by_asset_id_in_transaction(asset_id, &mut transaction)
+        .await
+        .map_err(|e| match e {
+            DatabaseError::NotFound => OrchestrateError::InvalidState("asset has no NFC".to_string()),
             _ => OrchestrateError::DatabaseError(e),
         })?;
 
-    Ok(nfc)
+    // 2. Sign the certificate again if it does not certify the current owner
+    if !signer.verify(&OwnershipClaim::for_nfc(&nfc, &asset.owner_fp), &nfc.cert) {
+        info!("signing certificate of current owner again :: asset_id={} :: nfc_id={}", asset_id, nfc.id);
+        nfc.sign_for(&asset.owner_fp, signer);
+        if !repository.update_nfc_cert(&nfc.id, &nfc.cert, &mut transaction).await? {
+            return Err(OrchestrateError::ServerError("failed to update certificate".to_string()));
+        }
+    }
+
+    repository.commit(transaction).await?;
+    Ok((nfc, asset.owner_fp))
 }
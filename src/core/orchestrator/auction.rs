@@ -0,0 +1,95 @@
+use crate::core::orchestrator::bid::reserve_for_bidder;
+use crate::core::queries::UnitOfWork;
+use crate::core::{queries, AuctionType, DatabaseError, EscrowHold, OrchestrateError};
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use tracing::{error, info, warn};
+
+/// Maximum number of auctions closed by a single run of `close_due_auctions`
+const CLOSE_BATCH_SIZE: i64 = 100;
+
+/// Closing an auction reserves the asset for the winning bid. All steps run in a single transaction:
+/// 1. The asset and its contract are locked, same as when a bid is accepted
+/// 2. The winner is picked among the open bids according to the auction type
+/// 3. The winning bid is accepted and held in escrow like an accepted bid, if there is no winner all open bids are rejected
+/// 4. The auction is marked as closed with its winning bid
+///
+/// English and sealed-bid auctions can only be closed once they ended,
+/// a dutch auction is closed as soon as a bid meets its current price.
+/// Returns `None` if the auction closed without a winner.
+pub async fn close_auction(asset_id: &str, hold_ttl: Duration, pg_pool: &PgPool) -> Result<Option<EscrowHold>, OrchestrateError> {
+    info!("closing auction :: asset_id={}", asset_id);
+    let mut transaction = UnitOfWork::begin(pg_pool).await?;
+
+    // 1. Lock the asset, every change of ownership goes through this lock
+    let asset = queries::find_asset_by_id_for_update(asset_id, &mut transaction)
+        .await
+        .map_err(|e| match e {
+            DatabaseError::NotFound => OrchestrateError::NotFoundError("asset not found".to_string()),
+            _ => OrchestrateError::DatabaseError(e),
+        })?;
+    // the contract is read under its lock, its terms can not change until the auction is closed
+    let contract = queries::find_contract_by_asset_id_for_update(asset_id, &mut transaction)
+        .await
+        .map_err(|e| match e {
+            DatabaseError::NotFound => OrchestrateError::InvalidState("asset has no contract".to_string()),
+            _ => OrchestrateError::DatabaseError(e),
+        })?;
+    let auction = contract.auction.clone()
+        .ok_or_else(|| OrchestrateError::InvalidState("asset is not being auctioned".to_string()))?;
+    if auction.is_closed() {
+        return Err(OrchestrateError::InvalidState("auction is already closed".to_string()));
+    }
+
+    // 2. Pick the winner
+    let now = Utc::now();
+    let bids = queries::find_open_bids_for_asset(asset_id, &mut *transaction).await?;
+    let mut winner = auction.pick_winner(&bids);
+    let closes_early = auction.auction_type == AuctionType::Dutch && winner.is_some();
+    if now < auction.end_time && !closes_early {
+        return Err(OrchestrateError::InvalidState("auction is still running".to_string()));
+    }
+    // the asset or the contract may have changed since the bid was placed
+    if let Some(bid) = winner {
+        // auction bids are in the currency of the reserve price, which is the currency of the min price
+        if let Err(e) = bid.validate_against(&asset, &contract, None) {
+            warn!("winning bid no longer satisfies the contract :: bid_id={} :: err={}", bid.id, e);
+            winner = None;
+        }
+    }
+
+    // 3. Reserve the asset for the winner or reject all bids
+    let hold = match winner {
+        Some(bid) => Some(reserve_for_bidder(bid, &asset, &contract, hold_ttl, &mut transaction).await?),
+        None => {
+            let rejected = queries::reject_open_bids_for_asset(asset_id, "", &mut *transaction).await?;
+            info!("auction closed without a winner :: asset_id={} :: rejected={}", asset_id, rejected);
+            None
+        }
+    };
+
+    // 4. Close the auction, fails if a concurrent close already did
+    let winning_bid_id = winner.map(|bid| bid.id.as_str());
+    let closed = queries::close_contract_auction(&contract.id, winning_bid_id, now, &mut transaction).await?;
+    if !closed {
+        return Err(OrchestrateError::InvalidState("auction is already closed".to_string()));
+    }
+
+    transaction.commit().await?;
+    info!("auction closed :: asset_id={} :: winning_bid_id={:?}", asset_id, winning_bid_id);
+    Ok(hold)
+}
+
+/// Closes the auctions that ended, returns the number of auctions that were closed.
+/// A failure to close one auction does not prevent the others from being closed.
+pub async fn close_due_auctions(hold_ttl: Duration, pg_pool: &PgPool) -> Result<usize, OrchestrateError> {
+    let asset_ids = queries::find_due_auction_asset_ids(Utc::now(), CLOSE_BATCH_SIZE, pg_pool).await?;
+    let mut closed = 0;
+    for asset_id in asset_ids {
+        match close_auction(&asset_id, hold_ttl, pg_pool).await {
+            Ok(_) => closed += 1,
+            Err(e) => error!("failed to close auction :: asset_id={} :: err={}", asset_id, e),
+        }
+    }
+    Ok(closed)
+}
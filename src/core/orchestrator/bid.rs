@@ -0,0 +1,186 @@
+use crate::core::queries::UnitOfWork;
+use crate::core::orchestrator::find_fx_rate;
+use crate::core::{queries, Asset, Bid, BidStatus, Contract, DatabaseError, DomainError, EscrowHold, FxPolicy, Money, OrchestrateError};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+/// Placing a bid checks it against the asset, its contract, its escrow hold and the highest bid of a running auction.
+/// The checks and the insert run in a single transaction that holds the lock on the asset, the one `accept_bid` takes,
+/// so a bid is not placed on an asset that was just sold or reserved, nor under a bid that was just placed.
+/// Returns the bid with the contract it was placed under.
+#[allow(clippy::too_many_arguments)]
+pub async fn place_bid(asset_id: &str,
+                       bidder_fp: String,
+                       bidder_org: String,
+                       amount: Money,
+                       anonymous: bool,
+                       expires_at: Option<DateTime<Utc>>,
+                       fx_policy: &FxPolicy,
+                       pg_pool: &PgPool) -> Result<(Bid, Contract), OrchestrateError> {
+    info!("placing bid :: asset_id={}", asset_id);
+    let mut transaction = UnitOfWork::begin(pg_pool).await?;
+
+    // 1. Lock the asset, bids are placed one at a time and not while the asset changes hands
+    let asset = queries::find_asset_by_id_for_update(asset_id, &mut transaction)
+        .await
+        .map_err(|e| match e {
+            DatabaseError::NotFound => OrchestrateError::NotFoundError("asset not found".to_string()),
+            _ => OrchestrateError::DatabaseError(e),
+        })?;
+    let contract = queries::find_contract_by_asset_id_for_update(&asset.id, &mut transaction)
+        .await
+        .map_err(|e| match e {
+            DatabaseError::NotFound => OrchestrateError::InvalidState("asset has no contract".to_string()),
+            _ => OrchestrateError::DatabaseError(e),
+        })?;
+
+    // 2. Check the bid against the contract, a bid in another currency is compared to the min price at the current exchange rate
+    let fx_rate = find_fx_rate(contract.min_price.currency(), amount.currency(), fx_policy, &mut *transaction).await?;
+    let bid = Bid::new(&asset, &contract, bidder_fp, bidder_org, amount, anonymous, expires_at, fx_rate.as_ref())
+        .map_err(|e| match e {
+            DomainError::ValidationError(msg) => OrchestrateError::InvalidState(msg),
+            _ => OrchestrateError::InvalidArgument(e.to_string()),
+        })?;
+    let last_sale_at = queries::find_last_sale_time_by_asset_id(&asset.id, &mut *transaction).await?;
+    contract.check_resale(last_sale_at, bid.created_at)
+        .map_err(|e| OrchestrateError::InvalidState(e.to_string()))?;
+    // the asset is reserved for the buyer of an accepted bid until the hold is settled
+    if queries::find_active_escrow_hold_for_asset(&asset.id, &mut *transaction).await?.is_some() {
+        return Err(OrchestrateError::InvalidState("asset is held in escrow".to_string()));
+    }
+    if let Some(auction) = &contract.auction {
+        let highest_bid = queries::find_highest_open_bid(&asset.id, &mut *transaction).await?;
+        auction.validate_bid(&bid, highest_bid.as_ref())
+            .map_err(|e| OrchestrateError::InvalidState(e.to_string()))?;
+    }
+
+    // 3. Place the bid
+    let bid_created = queries::create_bid(&mut *transaction, &bid).await?;
+    if !bid_created {
+        return Err(OrchestrateError::ServerError("bid not created, something went wrong".to_string()));
+    }
+    transaction.commit().await?;
+    info!("bid placed :: bid_id={} :: asset_id={}", bid.id, asset_id);
+    Ok((bid, contract))
+}
+
+/// Accepting a bid reserves the asset for the bidder until they pay. All steps run in a single transaction:
+/// 1. The winning bid is marked as accepted and all competing open bids are rejected
+/// 2. A pending escrow hold is created for the amount of the bid, keyed to the bid and the NFC of the asset
+///
+/// The sale is only completed once the hold is funded and the asset is transferred to the bidder, see `transfer_asset`.
+/// The asset and contract rows are locked for the duration of the transaction, so concurrent acceptances and
+/// contract updates are serialized.
+/// Retrying a bid that was already accepted returns its hold instead of reserving the asset twice.
+/// Bids on an asset with a running auction can not be accepted, the auction picks the winner when it closes.
+/// A bid in another currency than the min price is checked against the min price at the current exchange rate.
+pub async fn accept_bid(bid_id: &str,
+                        seller_fp: &str,
+                        fx_policy: &FxPolicy,
+                        hold_ttl: Duration,
+                        pg_pool: &PgPool) -> Result<EscrowHold, OrchestrateError> {
+    info!("accepting bid :: bid_id={}", bid_id);
+    let bid = queries::find_bid_by_id(bid_id, pg_pool)
+        .await
+        .map_err(|e| match e {
+            DatabaseError::NotFound => OrchestrateError::NotFoundError("bid not found".to_string()),
+            _ => OrchestrateError::DatabaseError(e),
+        })?;
+
+    let mut transaction = UnitOfWork::begin(pg_pool).await?;
+
+    // 1. Lock the asset, every change of ownership goes through this lock
+    let asset = queries::find_asset_by_id_for_update(&bid.asset_id, &mut transaction)
+        .await
+        .map_err(|e| match e {
+            DatabaseError::NotFound => OrchestrateError::NotFoundError("asset not found".to_string()),
+            _ => OrchestrateError::DatabaseError(e),
+        })?;
+
+    // 2. Re-read the bid now that the asset is locked, it may have been accepted by a previous attempt
+    let bid = queries::find_bid_by_id(bid_id, &mut *transaction).await?;
+    if bid.status == BidStatus::Accepted {
+        let hold = queries::find_escrow_hold_by_bid_id(bid_id, &mut *transaction)
+            .await
+            .map_err(|e| match e {
+                DatabaseError::NotFound => OrchestrateError::InvalidState("accepted bid has no escrow hold".to_string()),
+                _ => OrchestrateError::DatabaseError(e),
+            })?;
+        if hold.seller_fp != seller_fp {
+            return Err(OrchestrateError::PermissionDenied("only the asset owner can accept bids".to_string()));
+        }
+        warn!("bid already accepted, returning escrow hold :: bid_id={} :: hold_id={}", bid_id, hold.id);
+        return Ok(hold);
+    }
+
+    if asset.owner_fp != seller_fp {
+        return Err(OrchestrateError::PermissionDenied("only the asset owner can accept bids".to_string()));
+    }
+    if !bid.is_open() {
+        return Err(OrchestrateError::InvalidState(format!("bid is not open :: status={}", bid.status)));
+    }
+
+    // 3. The contract may have changed since the bid was placed, it is locked so the sale settles against its current terms
+    let contract = queries::find_contract_by_asset_id_for_update(&asset.id, &mut transaction)
+        .await
+        .map_err(|e| match e {
+            DatabaseError::NotFound => OrchestrateError::InvalidState("asset has no contract".to_string()),
+            _ => OrchestrateError::DatabaseError(e),
+        })?;
+    if contract.has_active_auction() {
+        return Err(OrchestrateError::InvalidState("asset is being auctioned, the auction picks the winning bid".to_string()));
+    }
+    let fx_rate = find_fx_rate(contract.min_price.currency(), bid.amount.currency(), fx_policy, &mut *transaction).await?;
+    bid.validate_against(&asset, &contract, fx_rate.as_ref())
+        .map_err(|e| match e {
+            DomainError::ValidationError(msg) => OrchestrateError::InvalidState(msg),
+            _ => OrchestrateError::InvalidArgument(e.to_string()),
+        })?;
+
+    let hold = reserve_for_bidder(&bid, &asset, &contract, hold_ttl, &mut transaction).await?;
+
+    transaction.commit().await?;
+    info!("bid accepted :: bid_id={} :: hold_id={}", bid_id, hold.id);
+    Ok(hold)
+}
+
+/// Steps 4 and 5 of `accept_bid`, the caller holds the lock on the asset and commits the transaction
+pub(super) async fn reserve_for_bidder(bid: &Bid,
+                                       asset: &Asset,
+                                       contract: &Contract,
+                                       hold_ttl: Duration,
+                                       transaction: &mut UnitOfWork<'_>) -> Result<EscrowHold, OrchestrateError> {
+    let bid_id = bid.id.as_str();
+    // the previous buyer may still be bound by the resale restriction of the contract
+    let last_sale_at = queries::find_last_sale_time_by_asset_id(&asset.id, &mut **transaction).await?;
+    contract.check_resale(last_sale_at, Utc::now())
+        .map_err(|e| OrchestrateError::InvalidState(e.to_string()))?;
+    // an asset is reserved for one buyer at a time
+    if let Some(hold) = queries::find_active_escrow_hold_for_asset(&asset.id, &mut **transaction).await? {
+        return Err(OrchestrateError::InvalidState(format!("asset is held in escrow :: hold_id={}", hold.id)));
+    }
+
+    // 4. Accept the winning bid and reject the others
+    let accepted = queries::update_bid_status(bid_id, BidStatus::Open, BidStatus::Accepted, &mut **transaction).await?;
+    if !accepted {
+        return Err(OrchestrateError::InvalidState("bid is no longer open".to_string()));
+    }
+    let rejected = queries::reject_open_bids_for_asset(&asset.id, bid_id, &mut **transaction).await?;
+    info!("rejected competing bids :: asset_id={} :: count={}", asset.id, rejected);
+
+    // 5. Hold the funds of the bidder until the asset is transferred
+    let nfc = queries::get_nfc_by_asset_id(&asset.id, &mut **transaction)
+        .await
+        .map_err(|e| match e {
+            DatabaseError::NotFound => OrchestrateError::InvalidState("asset has no NFC".to_string()),
+            _ => OrchestrateError::DatabaseError(e),
+        })?;
+    let hold = EscrowHold::for_bid(bid, asset, nfc.id, hold_ttl)
+        .map_err(|e| OrchestrateError::ServerError(format!("failed to create escrow hold: {}", e)))?;
+    let hold_created = queries::create_escrow_hold(transaction, &hold).await?;
+    if !hold_created {
+        return Err(OrchestrateError::ServerError("failed to create escrow hold".to_string()));
+    }
+    Ok(hold)
+}
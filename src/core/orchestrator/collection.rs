@@ -0,0 +1,155 @@
+use crate::core::queries::UnitOfWork;
+use crate::core::repository::{AssetRepository, ContractRepository};
+use crate::core::{queries, Asset, CertificateSigner, Collection, DatabaseError, DomainError, OrchestrateError, UpdateCollectionRequest};
+use sqlx::PgPool;
+use tracing::info;
+
+/// Creates a collection, the currencies of its default contract must be enabled
+pub async fn create_collection(collection: &Collection, pg_pool: &PgPool) -> Result<(), OrchestrateError> {
+    info!("creating collection :: {}", collection);
+    let mut transaction = UnitOfWork::begin(pg_pool).await?;
+    if let Some(default_contract) = &collection.default_contract {
+        let registry = queries::find_currency_registry(&mut *transaction).await?;
+        registry.check_enabled(&default_contract.accepted_currency).map_err(to_orchestrate_error)?;
+    }
+    queries::create_collection(collection, &mut transaction)
+        .await
+        .map_err(|e| match e {
+            DatabaseError::UniqueViolation => {
+                OrchestrateError::InvalidState(format!("organization already has a collection named {}", collection.name))
+            }
+            _ => OrchestrateError::DatabaseError(e),
+        })?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+/// Creates an asset in a collection of its organization.
+/// The collection row is locked for the duration of the transaction so that concurrent creations can not exceed
+/// the supply cap. The asset is created with a contract of its own, made from the default contract of the collection.
+pub async fn create_collection_asset<R>(asset: &Asset,
+                                        collection_id: &str,
+                                        user_fp: &str,
+                                        signer: &CertificateSigner,
+                                        repository: &R) -> Result<(), OrchestrateError>
+where
+    R: AssetRepository + ContractRepository,
+{
+    info!("creating collection asset :: asset_id={} :: collection_id={}", asset.id, collection_id);
+    let mut transaction = repository.begin().await?;
+
+    // 1. Lock the collection and check that the asset can join it
+    let collection = repository.lock_collection(collection_id, &mut transaction)
+        .await
+        .map_err(collection_not_found)
+        .and_then(|collection| in_org(collection, &asset.organization))?;
+    let asset_count = repository.count_collection_assets(collection_id, &mut transaction).await?;
+    collection.admit(asset, asset_count).map_err(|e| match e {
+        DomainError::ValidationError(msg) => OrchestrateError::InvalidState(msg),
+        _ => to_orchestrate_error(e),
+    })?;
+
+    // 2. The default contract may accept currencies that were disabled since
+    let contract = match &collection.default_contract {
+        None => None,
+        Some(default_contract) => {
+            let registry = repository.find_currency_registry().await?;
+            registry.check_enabled(&default_contract.accepted_currency).map_err(to_orchestrate_error)?;
+            Some(default_contract.contract_for(asset.id.clone(), user_fp.to_string()).map_err(to_orchestrate_error)?)
+        }
+    };
+
+    // 3. Create the asset with its NFC, add it to the collection and give it the inherited contract
+    if !repository.create_asset_in_transaction(asset, user_fp, signer, &mut transaction).await? {
+        return Err(OrchestrateError::ServerError("failed to create asset".to_string()));
+    }
+    if !repository.add_asset_to_collection(collection_id, &asset.id, asset.created_at, &mut transaction).await? {
+        return Err(OrchestrateError::ServerError("failed to add asset to collection".to_string()));
+    }
+    if let Some(contract) = contract {
+        if !repository.create_contract_in_transaction(contract, &mut transaction).await? {
+            return Err(OrchestrateError::ServerError("failed to create asset contract".to_string()));
+        }
+    }
+
+    repository.commit(transaction).await?;
+    info!("collection asset created :: asset_id={} :: collection_id={} :: asset_count={}", asset.id, collection_id, asset_count + 1);
+    Ok(())
+}
+
+/// Updates a collection of the organization, a supply cap can not be lowered below the number of assets of the collection.
+/// A new default contract only applies to the assets created afterward.
+pub async fn update_collection(collection_id: &str,
+                               org_id: &str,
+                               user_fp: &str,
+                               update: &UpdateCollectionRequest,
+                               pg_pool: &PgPool) -> Result<Collection, OrchestrateError> {
+    info!("updating collection :: collection_id={} :: update={}", collection_id, update);
+    let mut transaction = UnitOfWork::begin(pg_pool).await?;
+    let collection = lock_collection(collection_id, org_id, &mut transaction).await?;
+    let updated = collection.apply_update(update, user_fp).map_err(to_orchestrate_error)?;
+
+    let asset_count = queries::count_collection_assets(collection_id, &mut *transaction).await?;
+    updated.check_supply(asset_count).map_err(|e| OrchestrateError::InvalidState(e.to_string()))?;
+    if let Some(Some(default_contract)) = &update.default_contract {
+        let registry = queries::find_currency_registry(&mut *transaction).await?;
+        registry.check_enabled(&default_contract.accepted_currency).map_err(to_orchestrate_error)?;
+    }
+
+    queries::update_collection(&updated, &mut transaction)
+        .await
+        .map_err(|e| match e {
+            DatabaseError::UniqueViolation => {
+                OrchestrateError::InvalidState(format!("organization already has a collection named {}", updated.name))
+            }
+            _ => OrchestrateError::DatabaseError(e),
+        })?;
+    transaction.commit().await?;
+    Ok(updated)
+}
+
+/// Deletes a collection of the organization, only once it has no asset left
+pub async fn delete_collection(collection_id: &str, org_id: &str, pg_pool: &PgPool) -> Result<(), OrchestrateError> {
+    info!("deleting collection :: collection_id={}", collection_id);
+    let deleted = queries::delete_collection(collection_id, org_id, pg_pool)
+        .await
+        .map_err(|e| match e {
+            DatabaseError::ForeignKeyViolation => OrchestrateError::InvalidState("collection still has assets".to_string()),
+            _ => OrchestrateError::DatabaseError(e),
+        })?;
+    if !deleted {
+        return Err(OrchestrateError::NotFoundError("collection not found in specified org".to_string()));
+    }
+    Ok(())
+}
+
+async fn lock_collection(collection_id: &str,
+                         org_id: &str,
+                         transaction: &mut UnitOfWork<'_>) -> Result<Collection, OrchestrateError> {
+    let collection = queries::find_collection_for_update(collection_id, transaction)
+        .await
+        .map_err(collection_not_found)?;
+    in_org(collection, org_id)
+}
+
+fn collection_not_found(e: DatabaseError) -> OrchestrateError {
+    match e {
+        DatabaseError::NotFound => OrchestrateError::NotFoundError("collection not found in specified org".to_string()),
+        _ => OrchestrateError::DatabaseError(e),
+    }
+}
+
+/// Collections of other organizations are not found
+fn in_org(collection: Collection, org_id: &str) -> Result<Collection, OrchestrateError> {
+    if collection.organization != org_id {
+        return Err(OrchestrateError::NotFoundError("collection not found in specified org".to_string()));
+    }
+    Ok(collection)
+}
+
+fn to_orchestrate_error(e: DomainError) -> OrchestrateError {
+    match e {
+        DomainError::InvalidArgument(msg) | DomainError::ValidationError(msg) => OrchestrateError::InvalidArgument(msg),
+        _ => OrchestrateError::ServerError(e.to_string()),
+    }
+}
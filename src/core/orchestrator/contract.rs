@@ -0,0 +1,96 @@
+use crate::core::queries::UnitOfWork;
+use crate::core::repository::{AssetRepository, ContractRepository};
+use crate::core::{queries, Contract, ContractVersion, DatabaseError, DomainError, OrchestrateError, UpdateContractRequest,
+                  CONTRACT_UPGRADE_AUTHOR};
+use sqlx::PgPool;
+use tracing::info;
+
+/// Updating a contract creates its next revision, the previous revision is kept in the contract history.
+/// Only the asset owner can update the contract, and only while no open bid, running auction or escrow hold relies on its terms.
+/// The asset row is locked for the duration of the transaction, same as when a bid is accepted.
+pub async fn update_contract<R>(asset_id: &str,
+                                user_fp: &str,
+                                update: &UpdateContractRequest,
+                                repository: &R) -> Result<Contract, OrchestrateError>
+where
+    R: AssetRepository + ContractRepository,
+{
+    info!("updating contract :: asset_id={} :: update={}", asset_id, update);
+    let mut transaction = repository.begin().await?;
+
+    // 1. Lock the asset, bids can not be accepted while the contract changes
+    let asset = repository.lock_asset(asset_id, &mut transaction)
+        .await
+        .map_err(|e| match e {
+            DatabaseError::NotFound => OrchestrateError::NotFoundError("asset not found".to_string()),
+            _ => OrchestrateError::DatabaseError(e),
+        })?;
+    if asset.owner_fp != user_fp {
+        return Err(OrchestrateError::PermissionDenied("only the asset owner can update the contract".to_string()));
+    }
+    let contract = repository.lock_contract(asset_id, &mut transaction)
+        .await
+        .map_err(|e| match e {
+            DatabaseError::NotFound => OrchestrateError::NotFoundError("contract not found".to_string()),
+            _ => OrchestrateError::DatabaseError(e),
+        })?;
+
+    // 2. Bidders placed their bids under the current terms
+    if contract.has_active_auction() {
+        return Err(OrchestrateError::InvalidState("contract can not be updated during an auction".to_string()));
+    }
+    let open_bids = repository.count_open_bids(asset_id, &mut transaction).await?;
+    if open_bids > 0 {
+        return Err(OrchestrateError::InvalidState(format!("contract can not be updated while the asset has {} open bids", open_bids)));
+    }
+    if repository.find_active_escrow_hold(asset_id, &mut transaction).await?.is_some() {
+        return Err(OrchestrateError::InvalidState("contract can not be updated while the asset is held in escrow".to_string()));
+    }
+
+    // 3. Currencies that were disabled can not be added to contracts
+    if let Some(accepted_currency) = &update.accepted_currency {
+        let registry = repository.find_currency_registry().await?;
+        registry.check_enabled(accepted_currency).map_err(to_orchestrate_error)?;
+    }
+
+    // 4. Write the next revision and keep the previous one
+    let updated = contract.apply_update(update, user_fp).map_err(to_orchestrate_error)?;
+    let saved = repository.update_contract(&contract, &updated, &mut transaction).await?;
+    if !saved {
+        return Err(OrchestrateError::InvalidState("contract was updated concurrently".to_string()));
+    }
+
+    repository.commit(transaction).await?;
+    info!("contract updated :: contract_id={} :: update_count={}", updated.id, updated.update_count);
+    Ok(updated)
+}
+
+fn to_orchestrate_error(e: DomainError) -> OrchestrateError {
+    match e {
+        DomainError::InvalidArgument(msg) | DomainError::ValidationError(msg) => OrchestrateError::InvalidArgument(msg),
+        _ => OrchestrateError::ServerError(e.to_string()),
+    }
+}
+
+/// Upgrades up to `limit` V1 contracts to V2, returns the number of contracts that were upgraded.
+/// Every upgrade is a new revision of the contract, the V1 revision is kept in the contract history.
+pub async fn upgrade_v1_contracts(limit: i64, pg_pool: &PgPool) -> Result<usize, OrchestrateError> {
+    let asset_ids = queries::find_contract_asset_ids_by_version(&ContractVersion::V1, limit, pg_pool).await?;
+    let mut upgraded = 0;
+    for asset_id in asset_ids {
+        let mut transaction = UnitOfWork::begin(pg_pool).await?;
+        let contract = queries::find_contract_by_asset_id_for_update(&asset_id, &mut transaction).await?;
+        if contract.version != ContractVersion::V1 {
+            continue;
+        }
+        let contract_v2 = contract.upgrade_to_v2(CONTRACT_UPGRADE_AUTHOR)
+            .map_err(|e| OrchestrateError::InvalidState(format!("contract {} can not be upgraded: {}", contract.id, e)))?;
+        if !queries::update_contract(&mut transaction, &contract, &contract_v2).await? {
+            return Err(OrchestrateError::InvalidState("contract was updated concurrently".to_string()));
+        }
+        transaction.commit().await?;
+        info!("contract upgraded :: contract_id={} :: version={}", contract_v2.id, contract_v2.version);
+        upgraded += 1;
+    }
+    Ok(upgraded)
+}
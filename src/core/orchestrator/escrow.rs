@@ -0,0 +1,90 @@
+use crate::core::queries::UnitOfWork;
+use crate::core::{queries, BidStatus, DatabaseError, DomainError, EscrowHold, EscrowStatus, OrchestrateError};
+use chrono::Utc;
+use sqlx::PgPool;
+use tracing::{error, info, warn};
+
+/// Maximum number of holds expired by a single run of `expire_escrow_holds`
+const EXPIRE_BATCH_SIZE: i64 = 100;
+
+/// Records that the buyer paid the amount of a pending hold, the seller can then transfer the asset to them.
+pub async fn fund_escrow_hold(hold_id: &str, buyer_fp: &str, pg_pool: &PgPool) -> Result<EscrowHold, OrchestrateError> {
+    info!("funding escrow hold :: hold_id={}", hold_id);
+    let hold = queries::find_escrow_hold_by_id(hold_id, pg_pool)
+        .await
+        .map_err(|e| match e {
+            DatabaseError::NotFound => OrchestrateError::NotFoundError("escrow hold not found".to_string()),
+            _ => OrchestrateError::DatabaseError(e),
+        })?;
+    // do not leak the existence of holds of other users
+    if hold.buyer_fp != buyer_fp {
+        return Err(OrchestrateError::NotFoundError("escrow hold not found".to_string()));
+    }
+    hold.check_fundable(buyer_fp, Utc::now())
+        .map_err(|e| match e {
+            DomainError::ValidationError(msg) => OrchestrateError::InvalidState(msg),
+            _ => OrchestrateError::InvalidArgument(e.to_string()),
+        })?;
+
+    // fails if the sweeper expired the hold in the meantime
+    let funded = queries::update_escrow_hold_status(hold_id, EscrowStatus::Pending, EscrowStatus::Funded, pg_pool).await?;
+    if !funded {
+        return Err(OrchestrateError::InvalidState("escrow hold is no longer pending".to_string()));
+    }
+    let hold = queries::find_escrow_hold_by_id(hold_id, pg_pool).await?;
+    info!("escrow hold funded :: {}", hold);
+    Ok(hold)
+}
+
+/// Expires a hold that passed its deadline, in a single transaction:
+/// 1. A pending hold is cancelled, a funded hold is refunded to the buyer
+/// 2. The accepted bid expires, the asset can be sold again
+///
+/// Returns false if the hold was settled or funded concurrently.
+pub async fn expire_escrow_hold(hold_id: &str, pg_pool: &PgPool) -> Result<bool, OrchestrateError> {
+    let mut transaction = UnitOfWork::begin(pg_pool).await?;
+    let hold = queries::find_escrow_hold_by_id(hold_id, &mut *transaction)
+        .await
+        .map_err(|e| match e {
+            DatabaseError::NotFound => OrchestrateError::NotFoundError("escrow hold not found".to_string()),
+            _ => OrchestrateError::DatabaseError(e),
+        })?;
+    if !hold.is_expired(Utc::now()) {
+        return Err(OrchestrateError::InvalidState("escrow hold has not expired".to_string()));
+    }
+    let Some(expired_status) = hold.status.expired() else {
+        return Ok(false);
+    };
+
+    // 1. Cancel or refund the hold, the status check fails if it changed since it was read
+    let expired = queries::update_escrow_hold_status(hold_id, hold.status, expired_status, &mut *transaction).await?;
+    if !expired {
+        warn!("escrow hold changed before it could expire :: hold_id={}", hold_id);
+        return Ok(false);
+    }
+
+    // 2. Release the asset for new bids
+    let bid_expired = queries::update_bid_status(&hold.bid_id, BidStatus::Accepted, BidStatus::Expired, &mut *transaction).await?;
+    if !bid_expired {
+        return Err(OrchestrateError::InvalidState("escrow hold bid is not accepted".to_string()));
+    }
+
+    transaction.commit().await?;
+    info!("escrow hold expired :: hold_id={} :: status={}", hold_id, expired_status);
+    Ok(true)
+}
+
+/// Expires the holds that passed their deadline, returns the number of holds that were expired.
+/// A failure to expire one hold does not prevent the others from being expired.
+pub async fn expire_escrow_holds(pg_pool: &PgPool) -> Result<usize, OrchestrateError> {
+    let hold_ids = queries::find_expired_escrow_hold_ids(Utc::now(), EXPIRE_BATCH_SIZE, pg_pool).await?;
+    let mut expired = 0;
+    for hold_id in hold_ids {
+        match expire_escrow_hold(&hold_id, pg_pool).await {
+            Ok(true) => expired += 1,
+            Ok(false) => {}
+            Err(e) => error!("failed to expire escrow hold :: hold_id={} :: err={}", hold_id, e),
+        }
+    }
+    Ok(expired)
+}
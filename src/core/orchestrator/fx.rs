@@ -0,0 +1,25 @@
+use crate::core::{queries, Currency, FxPolicy, FxRate, OrchestrateError};
+use chrono::Utc;
+use sqlx::{Executor, Postgres};
+
+/// Latest rate to convert `from` into `to`, `None` when both are the same currency.
+/// A rate stored in the opposite direction is inverted. Fails if there is no rate,
+/// or if the latest rate is older than the policy allows.
+pub async fn find_fx_rate<'a, E>(from: &Currency,
+                                 to: &Currency,
+                                 policy: &FxPolicy,
+                                 executor: E) -> Result<Option<FxRate>, OrchestrateError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    if from == to {
+        return Ok(None);
+    }
+    let rate = queries::find_latest_fx_rate(from, to, executor)
+        .await?
+        .ok_or_else(|| OrchestrateError::InvalidState(format!("no exchange rate from {} to {}", from, to)))?;
+    let rate = if rate.base == *from { rate } else { rate.inverse() };
+    policy.check_fresh(&rate, Utc::now())
+        .map_err(|e| OrchestrateError::InvalidState(e.to_string()))?;
+    Ok(Some(rate))
+}
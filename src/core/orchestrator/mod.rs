@@ -1,3 +1,21 @@
+mod anchor;
 mod asset;
+mod auction;
+mod bid;
+mod collection;
+mod contract;
+mod escrow;
+mod fx;
+mod outbox;
+mod trail;
 
-pub use asset::transfer_asset;
+pub use anchor::{anchor_nfc_certificates, find_inclusion_proof};
+pub use asset::{find_owner_certificate, transfer_asset};
+pub use auction::{close_auction, close_due_auctions};
+pub use bid::{accept_bid, place_bid};
+pub use collection::{create_collection, create_collection_asset, delete_collection, update_collection};
+pub use contract::{update_contract, upgrade_v1_contracts};
+pub use escrow::{expire_escrow_hold, expire_escrow_holds, fund_escrow_hold};
+pub use fx::find_fx_rate;
+pub use outbox::{authorize_event_filter, relay_outbox_events};
+pub use trail::{verify_all_trails, verify_trail_integrity};
@@ -0,0 +1,87 @@
+use anyhow::anyhow;
+use crate::core::{queries, AssetFilter, DatabaseError, EventFilter, EventSink, OrchestrateError};
+use chrono::Utc;
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::time::Duration;
+use tracing::{error, info};
+
+/// Publishes the oldest pending events of the outbox to the sink and marks them dispatched,
+/// returns the number of events that were published.
+/// The events are claimed for `publish_timeout` so that concurrent relays do not publish them too, the claim is
+/// committed before they are published: no transaction stays open while publishing, it would hold back the events
+/// of every later transaction from the subscriptions.
+/// Events the sink failed to receive are released and published again by the next relay,
+/// a publication still running after `publish_timeout` fails so that the events are not claimed for longer.
+/// Events are published at least once, those of a relay that stopped before marking them are published again.
+pub async fn relay_outbox_events<S: EventSink>(sink: &S,
+                                               batch_size: i64,
+                                               publish_timeout: Duration,
+                                               pg_pool: &PgPool) -> Result<usize, OrchestrateError> {
+    // 1. Claim the oldest pending events
+    let claim = chrono::Duration::from_std(publish_timeout)
+        .map_err(|e| OrchestrateError::InvalidArgument(format!("invalid publish timeout: {}", e)))?;
+    let now = Utc::now();
+    let events = queries::claim_pending_outbox_events(batch_size, now, now + claim, pg_pool).await?;
+    if events.is_empty() {
+        return Ok(0);
+    }
+
+    // 2. Publish them in sequence order
+    let first_sequence = events[0].sequence;
+    let last_sequence = events[events.len() - 1].sequence;
+    let sequences: Vec<i64> = events.iter().map(|event| event.sequence).collect();
+    let published = tokio::time::timeout(publish_timeout, sink.publish(&events))
+        .await
+        .unwrap_or_else(|_| Err(anyhow!("publication timed out after {:?}", publish_timeout)));
+    if let Err(e) = published {
+        error!("failed to publish outbox events :: from={} :: to={} :: err={:?}", first_sequence, last_sequence, e);
+        // release the events at once rather than when the claim ends
+        queries::release_outbox_events(&sequences, pg_pool).await?;
+        return Err(OrchestrateError::ServerError(format!("failed to publish outbox events: {}", e)));
+    }
+
+    // 3. Mark them dispatched
+    queries::mark_outbox_events_dispatched(&sequences, Utc::now(), pg_pool).await?;
+    info!("relayed outbox events :: from={} :: to={} :: count={}", first_sequence, last_sequence, events.len());
+    Ok(events.len())
+}
+
+/// Callers only subscribe to their own events, those addressed to them, those of the assets they own
+/// and those of the organizations they are a member of, the organizations of the assets they own.
+/// Admins subscribe to every event.
+pub async fn authorize_event_filter(filter: &EventFilter,
+                                    user_fp: &str,
+                                    admin_fps: &HashSet<String>,
+                                    pg_pool: &PgPool) -> Result<(), OrchestrateError> {
+    if admin_fps.contains(user_fp) {
+        return Ok(());
+    }
+    match filter {
+        EventFilter::OwnerFp(owner_fp) if owner_fp == user_fp => Ok(()),
+        EventFilter::OwnerFp(_) => Err(OrchestrateError::PermissionDenied("only the events of the caller can be subscribed to".to_string())),
+        EventFilter::AssetId(asset_id) => {
+            let asset = queries::find_asset_by_id(asset_id, pg_pool)
+                .await
+                .map_err(|e| match e {
+                    DatabaseError::NotFound => OrchestrateError::NotFoundError(asset_id.to_string()),
+                    _ => OrchestrateError::DatabaseError(e),
+                })?;
+            if asset.owner_fp != user_fp {
+                return Err(OrchestrateError::PermissionDenied("only the owner of the asset can subscribe to its events".to_string()));
+            }
+            Ok(())
+        }
+        EventFilter::Organization(organization) => {
+            let owned = AssetFilter {
+                organization: Some(organization.clone()),
+                owner_fp: Some(user_fp.to_string()),
+                ..AssetFilter::default()
+            };
+            if queries::count_assets(&owned, pg_pool).await? == 0 {
+                return Err(OrchestrateError::PermissionDenied("only the members of the organization can subscribe to its events".to_string()));
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use crate::core::repository::NfcRepository;
+use crate::core::{verify_trail_chain, OrchestrateError, TrailIntegrityReport};
+use tracing::{info, warn};
+
+/// Number of assets whose ids are fetched at once by `verify_all_trails`
+const VERIFY_BATCH_SIZE: i64 = 500;
+
+/// Walks the hash chain of the trail of an asset and reports its first break
+pub async fn verify_trail_integrity<R: NfcRepository>(asset_id: &str, repository: &R) -> Result<TrailIntegrityReport, OrchestrateError> {
+    info!("verifying trail integrity :: asset_id={}", asset_id);
+    let trails = repository.get_nfc_trails_by_asset_id(asset_id).await?;
+    // every asset has at least the trail of its mint
+    if trails.is_empty() {
+        return Err(OrchestrateError::NotFoundError(asset_id.to_string()));
+    }
+
+    let breaks = verify_trail_chain(&trails).into_iter().collect::<Vec<_>>();
+    for trail_break in &breaks {
+        warn!("trail integrity broken :: {}", trail_break);
+    }
+    Ok(TrailIntegrityReport { checked_assets: 1, breaks })
+}
+
+/// Walks the trails of every asset, one asset at a time, and reports the first break of each broken trail
+pub async fn verify_all_trails<R: NfcRepository>(repository: &R) -> Result<TrailIntegrityReport, OrchestrateError> {
+    info!("verifying trail integrity of all assets");
+    let mut report = TrailIntegrityReport::default();
+    let mut after_asset_id = String::new();
+    loop {
+        let asset_ids = repository.find_trail_asset_ids(&after_asset_id, VERIFY_BATCH_SIZE).await?;
+        let Some(last_asset_id) = asset_ids.last().cloned() else {
+            break;
+        };
+        for asset_id in asset_ids {
+            let trails = repository.get_nfc_trails_by_asset_id(&asset_id).await?;
+            if let Some(trail_break) = verify_trail_chain(&trails) {
+                warn!("trail integrity broken :: {}", trail_break);
+                report.breaks.push(trail_break);
+            }
+            report.checked_assets += 1;
+        }
+        after_asset_id = last_asset_id;
+    }
+    info!("verified trail integrity :: checked_assets={} :: breaks={}", report.checked_assets, report.breaks.len());
+    Ok(report)
+}
@@ -0,0 +1,136 @@
+use crate::core::queries::UnitOfWork;
+use crate::core::{DatabaseError, NfcAnchor, NFC};
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres};
+use tracing::info;
+
+/// Serializes anchoring runs until the transaction ends, two runs would otherwise pick the same NFCs
+#[tracing::instrument(skip(transaction))]
+pub async fn lock_nfc_anchoring(transaction: &mut UnitOfWork<'_>) -> Result<(), DatabaseError> {
+    sqlx::query!("SELECT pg_advisory_xact_lock(hashtext('nfc_anchor'))")
+        .execute(&mut **transaction)
+        .await?;
+    Ok(())
+}
+
+/// NFCs created before `before` that are not in any anchor yet, oldest first
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_unanchored_nfcs<'a, E>(before: DateTime<Utc>, limit: i64, pg_pool: E) -> Result<Vec<NFC>, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    info!("finding unanchored nfcs :: before={} :: limit={}", before, limit);
+    let nfcs = sqlx::query_as!(
+        NFC,
+        r#"
+SELECT nfc.id, nfc.asset_id, nfc.cert, nfc.created_at
+FROM nfc
+WHERE nfc.created_at < $1
+  AND NOT EXISTS (SELECT 1 FROM nfc_anchor_leaf leaf WHERE leaf.nfc_id = nfc.id)
+ORDER BY nfc.created_at, nfc.id
+LIMIT $2"#,
+        before,
+        limit,
+    )
+        .fetch_all(pg_pool)
+        .await?;
+    Ok(nfcs)
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_last_nfc_anchor<'a, E>(pg_pool: E) -> Result<Option<NfcAnchor>, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    let anchor = sqlx::query_as!(
+        NfcAnchor,
+        r#"
+SELECT id, window_start, window_end, root, leaf_count, created_at
+FROM nfc_anchor
+ORDER BY window_end DESC, created_at DESC
+LIMIT 1"#
+    )
+        .fetch_optional(pg_pool)
+        .await?;
+    Ok(anchor)
+}
+
+/// Stores the anchor with the `(nfc_id, leaf_hash)` of its leaves, in tree order
+#[tracing::instrument(skip(transaction, anchor, leaves))]
+pub async fn create_nfc_anchor(transaction: &mut UnitOfWork<'_>,
+                               anchor: &NfcAnchor,
+                               leaves: &[(String, String)]) -> Result<bool, DatabaseError> {
+    info!("creating nfc anchor :: {}", anchor);
+    let result = sqlx::query!(
+        r#"
+INSERT INTO nfc_anchor (id, window_start, window_end, root, leaf_count, created_at)
+VALUES ($1, $2, $3, $4, $5, $6)
+"#,
+        anchor.id,
+        anchor.window_start,
+        anchor.window_end,
+        anchor.root,
+        anchor.leaf_count,
+        anchor.created_at,
+    )
+        .execute(&mut **transaction)
+        .await?;
+    if result.rows_affected() != 1 {
+        return Ok(false);
+    }
+
+    let (nfc_ids, leaf_hashes): (Vec<String>, Vec<String>) = leaves.iter().cloned().unzip();
+    let result = sqlx::query!(
+        r#"
+INSERT INTO nfc_anchor_leaf (nfc_id, anchor_id, position, leaf_hash)
+SELECT leaf.nfc_id, $1, (leaf.position - 1)::INTEGER, leaf.leaf_hash
+FROM UNNEST($2::TEXT[], $3::TEXT[]) WITH ORDINALITY AS leaf (nfc_id, leaf_hash, position)
+"#,
+        anchor.id,
+        &nfc_ids,
+        &leaf_hashes,
+    )
+        .execute(&mut **transaction)
+        .await?;
+    Ok(result.rows_affected() == leaves.len() as u64)
+}
+
+/// Anchor the NFC was anchored in, `DatabaseError::NotFound` while it is not anchored
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_nfc_anchor_by_nfc_id<'a, E>(nfc_id: &str, pg_pool: E) -> Result<NfcAnchor, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    info!("getting nfc anchor :: nfcId={}", nfc_id);
+    let anchor = sqlx::query_as!(
+        NfcAnchor,
+        r#"
+SELECT anchor.id, anchor.window_start, anchor.window_end, anchor.root, anchor.leaf_count, anchor.created_at
+FROM nfc_anchor anchor
+         JOIN nfc_anchor_leaf leaf ON leaf.anchor_id = anchor.id
+WHERE leaf.nfc_id = $1"#,
+        nfc_id
+    )
+        .fetch_one(pg_pool)
+        .await?;
+    Ok(anchor)
+}
+
+/// `(nfc_id, leaf_hash)` of the leaves of an anchor, in tree order
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_nfc_anchor_leaves<'a, E>(anchor_id: &str, pg_pool: E) -> Result<Vec<(String, String)>, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    let leaves = sqlx::query!(
+        r#"
+SELECT nfc_id, leaf_hash
+FROM nfc_anchor_leaf
+WHERE anchor_id = $1
+ORDER BY position"#,
+        anchor_id
+    )
+        .fetch_all(pg_pool)
+        .await?;
+    Ok(leaves.into_iter().map(|leaf| (leaf.nfc_id, leaf.leaf_hash)).collect())
+}
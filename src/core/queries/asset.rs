@@ -1,18 +1,28 @@
-use crate::core::queries::{create_nfc, create_nfc_trail, get_nfc_by_asset_id, OrderType};
-use crate::core::{Asset, DatabaseError, NFCTrail, UpdateAssetRequest, NFC};
-use anyhow::anyhow;
-use chrono::Utc;
-use sqlx::{PgPool, QueryBuilder};
+use crate::core::queries::{
+    count_asset_attributes, create_nfc, create_nfc_trail, create_outbox_event, delete_asset_attributes, get_nfc_by_asset_id,
+    replace_asset_tags, update_nfc_cert, upsert_asset_attributes, AssetSort, OrderType, UnitOfWork,
+};
+use crate::core::{
+    Asset, AssetCursor, AssetFilter, AssetSearch, AssetSearchHit, AssetSortField, AttributeValue, CertificateSigner, DatabaseError,
+    NFCTrail, OutboxEvent, UpdateAssetRequest, MAX_ASSET_ATTRIBUTES, NFC,
+};
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, QueryBuilder};
 use tracing::error;
 
-#[tracing::instrument(level = "debug", skip(pg_pool, asset), name = "Create new asset")]
+/// Maximum number of assets in a page of `list_assets`
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+/// Creates the asset with its NFC and the first entry of its trail as part of the caller's unit of work, recording the creation in the outbox.
+/// Nothing is created unless the caller commits, which it should only do when this returns true.
+#[tracing::instrument(level = "debug", skip(transaction, asset, signer), name = "Create new asset")]
 pub async fn create_new_asset(
     asset: &Asset,
     user_fp: String,
-    pg_pool: &PgPool,
-) -> Result<bool, anyhow::Error> {
+    signer: &CertificateSigner,
+    transaction: &mut UnitOfWork<'_>,
+) -> Result<bool, DatabaseError> {
     tracing::debug!("saving new asset to DB :: id={}", &asset.id);
-    let mut transaction = pg_pool.begin().await?;
     let result = sqlx::query!(
         "
         INSERT INTO asset (
@@ -40,25 +50,18 @@ pub async fn create_new_asset(
         asset.updated_by,
         asset.listable,
     )
-        .execute(&mut *transaction)
-        .await
-        .map_err(|e| {
-            error!("Error executing SQL query: {:?}", e);
-            anyhow!("something went wrong")
-        })?;
-    let nf_cert = NFC::new(asset.id.clone()).map_err(|e| anyhow!(e))?;
-
+        .execute(&mut **transaction)
+        .await?;
     if result.rows_affected() == 0 {
         return Ok(false);
     }
 
-    create_nfc(transaction, nf_cert, user_fp)
-        .await
-        .map_err(|e| {
-            error!("Error creating NFC table: {:?}", e);
-            anyhow!("Error creating NFC table")
-        })?;
-
+    let nf_cert = NFC::new(asset.id.clone(), &asset.owner_fp, signer);
+    let event = OutboxEvent::asset_created(asset, &nf_cert.id);
+    if !create_nfc(transaction, nf_cert, user_fp, asset.organization.clone()).await? {
+        return Ok(false);
+    }
+    create_outbox_event(transaction, &event).await?;
     Ok(true)
 }
 
@@ -102,273 +105,319 @@ pub async fn find_asset_by_id_and_org_id(
     Ok(result)
 }
 
-#[tracing::instrument(level = "debug", skip(pg_pool, limit, offset, order_by))]
+/// Page of assets ordered by name then id, starting after the cursor, or at the first asset without one
+#[tracing::instrument(level = "debug", skip(pg_pool, limit, order_by))]
 pub async fn get_all_assets(
     pg_pool: &PgPool,
-    offset: i64,
+    cursor: Option<&AssetCursor>,
     limit: i64,
     order_by: OrderType,
 ) -> Result<Vec<Asset>, DatabaseError> {
-    if limit < 1 || limit > 100 {
-        return Err(DatabaseError::InvalidArgument(
-            "limit must be between 1 and 100".to_string(),
-        ));
-    }
-    tracing::debug!(
-        "fetching assets from DB :: start={} :: limit={}",
-        &offset,
-        &limit
-    );
-    // SQLx often requires i64 for LIMIT & OFFSET to ensure compatibility w/ various DB types and potential large values.
-    let result = match order_by {
-        OrderType::Asc => {
-            sqlx::query_as!(
-                Asset,
-                r#"
-                SELECT
-                    id, name, symbol, description, organization, created_at, updated_at, tradable,
-                    listable, updated_by, owner_fp
-                FROM asset
-                ORDER BY name
-                LIMIT $1 OFFSET $2
-                "#,
-                limit,
-                offset
-            )
-                .fetch_all(pg_pool)
-                .await?
-        }
-        OrderType::Desc => {
-            sqlx::query_as!(
-                Asset,
-                r#"
-                SELECT
-                    id, name, symbol, description, organization, created_at, updated_at, tradable,
-                    listable, updated_by, owner_fp
-                FROM asset
-                ORDER BY name DESC
-                LIMIT $1 OFFSET $2
-                "#,
-                limit,
-                offset
-            )
-                .fetch_all(pg_pool)
-                .await?
-        }
-    };
-    Ok(result)
+    let sort = AssetSort::new(AssetSortField::Name, order_by);
+    list_assets(&AssetFilter::default(), sort, cursor, limit, pg_pool).await
 }
 
-#[tracing::instrument(level = "debug", skip(pg_pool, limit, offset, symbol, order_by))]
-pub async fn find_assets_symbol_like(
-    symbol: &str,
-    limit: i16,
-    offset: i64,
-    order_by: OrderType,
+/// Page of the assets matching the filter in the order of `sort`, starting after the cursor,
+/// or at the first matching asset without one. The cursor must come from a list with the same sort field.
+#[tracing::instrument(level = "debug", skip(pg_pool, limit))]
+pub async fn list_assets(
+    filter: &AssetFilter,
+    sort: AssetSort,
+    cursor: Option<&AssetCursor>,
+    limit: i64,
     pg_pool: &PgPool,
 ) -> Result<Vec<Asset>, DatabaseError> {
-    // TO DO: Look into
-    // 1. Full-text search: Better for complex searches w/ multiple words, phrases, and linguistic considerations
-    // OR
-    // 2. Trigram matching: Efficient for finding similar strings, even with typos or partial matches
-    let search_term = format!("%{}%", sanitize_search_term(symbol).to_uppercase());
-    tracing::debug!("fetching assets from DB :: symbol = {}", &search_term);
-    let result = match order_by {
-        OrderType::Asc => {
-            sqlx::query_as!(
-                Asset,
-                r#"
-                SELECT
-                    id, name, symbol, description, organization, created_at, updated_at, tradable,
-                    listable, updated_by, owner_fp
-                FROM asset
-                WHERE symbol ILIKE $1
-                ORDER BY symbol
-                LIMIT $2
-                OFFSET $3"#,
-                search_term,
-                limit as i64,
-                offset
-            )
-                .fetch_all(pg_pool)
-                .await?
+    if !(1..=MAX_PAGE_SIZE).contains(&limit) {
+        return Err(DatabaseError::InvalidArgument(format!("limit must be between 1 and {}", MAX_PAGE_SIZE)));
+    }
+    tracing::debug!("fetching assets from DB :: filter={} :: sort={} :: after={:?} :: limit={}", filter, sort, cursor, limit);
+    let column = sort.column();
+    let mut query_builder = QueryBuilder::new(
+        "SELECT id, name, symbol, description, organization, created_at, updated_at, tradable, listable, updated_by, owner_fp FROM asset",
+    );
+    push_asset_filter(&mut query_builder, filter);
+
+    // the (column, id) row comparison walks the (column, id) index, rows inserted behind the cursor do not shift the page
+    if let Some(cursor) = cursor {
+        if cursor.field != sort.field {
+            return Err(DatabaseError::InvalidArgument(format!("cursor is not for a list sorted by {}", sort.field)));
         }
-        OrderType::Desc => {
-            sqlx::query_as!(
-                Asset,
-                r#"
-                SELECT
-                    id, name, symbol, description, organization, created_at, updated_at, tradable,
-                    listable, updated_by, owner_fp
-                FROM asset
-                WHERE symbol ILIKE $1
-                ORDER BY symbol DESC
-                LIMIT $2"#,
-                search_term,
-                limit as i64
-            )
-                .fetch_all(pg_pool)
-                .await?
+        let comparison = match sort.order {
+            OrderType::Asc => ">",
+            OrderType::Desc => "<",
+        };
+        query_builder.push(format!(" AND ({}, id) {} (", column, comparison));
+        match sort.field {
+            AssetSortField::Name | AssetSortField::Symbol => {
+                query_builder.push_bind(cursor.key.clone());
+            }
+            AssetSortField::CreatedAt | AssetSortField::UpdatedAt => {
+                let key = cursor.key_timestamp()
+                    .ok_or_else(|| DatabaseError::InvalidArgument("cursor is invalid".to_string()))?;
+                query_builder.push_bind(key);
+            }
         }
-    };
+        query_builder.push(", ").push_bind(cursor.id.clone()).push(")");
+    }
+
+    query_builder.push(format!(" ORDER BY {column} {order}, id {order} LIMIT ", order = sort.order));
+    query_builder.push_bind(limit);
+
+    let result = query_builder.build_query_as::<Asset>()
+        .fetch_all(pg_pool)
+        .await?;
     Ok(result)
 }
 
-#[tracing::instrument(level = "debug", skip(pg_pool, owner_fp, offset, limit, order_by))]
-pub async fn find_assets_by_owner(owner_fp: &str,
-                                  limit: i16,
-                                  offset: i64,
-                                  listable: bool,
-                                  order_by: OrderType, pg_pool: &PgPool) -> Result<Vec<Asset>, DatabaseError> {
-    let search_term = format!("%{}%", sanitize_search_term(owner_fp).to_uppercase());
-    tracing::debug!("fetching assets from DB :: symbol = {}", &search_term);
-    let result = match order_by {
-        OrderType::Asc => {
-            sqlx::query_as!(
-                Asset,
-                r#"
-                SELECT
-                    id, name, symbol, description, organization, created_at, updated_at, tradable, listable, updated_by, owner_fp
-                FROM asset
-                WHERE owner_fp = $1 AND listable = $2
-                ORDER BY symbol
-                LIMIT $3
-                OFFSET $4"#,
-                owner_fp,
-                listable,
-                limit as i64,
-                offset
-            )
-                .fetch_all(pg_pool)
-                .await?
-        }
-        OrderType::Desc => {
-            sqlx::query_as!(
-                Asset,
-                r#"
-                SELECT
-                    id, name, symbol, description, organization, created_at, updated_at, tradable, listable, updated_by, owner_fp
-                FROM asset
-                WHERE owner_fp = $1 AND listable = $2
-                ORDER BY symbol DESC
-                LIMIT $3
-                OFFSET $4"#,
-                search_term,
-                listable,
-                limit as i64,
-                offset as i64,
-            )
-                .fetch_all(pg_pool)
-                .await?
-        }
-    };
+/// Number of assets matching the filter
+#[tracing::instrument(level = "debug", skip(pg_pool))]
+pub async fn count_assets(filter: &AssetFilter, pg_pool: &PgPool) -> Result<i64, DatabaseError> {
+    let mut query_builder = QueryBuilder::new("SELECT COUNT(*) FROM asset");
+    push_asset_filter(&mut query_builder, filter);
+    let count = query_builder.build_query_scalar::<i64>()
+        .fetch_one(pg_pool)
+        .await?;
+    Ok(count)
+}
 
-    Ok(result)
+/// Pushes the WHERE clause of the filter, every value is bound so nothing from the filter ends up in the SQL
+fn push_asset_filter(query_builder: &mut QueryBuilder<'_, Postgres>, filter: &AssetFilter) {
+    query_builder.push(" WHERE TRUE");
+    if let Some(organization) = &filter.organization {
+        query_builder.push(" AND organization = ").push_bind(organization.clone());
+    }
+    if let Some(owner_fp) = &filter.owner_fp {
+        query_builder.push(" AND owner_fp = ").push_bind(owner_fp.clone());
+    }
+    if let Some(tradable) = filter.tradable {
+        query_builder.push(" AND tradable = ").push_bind(tradable);
+    }
+    if let Some(listable) = filter.listable {
+        query_builder.push(" AND listable = ").push_bind(listable);
+    }
+    if let Some(name) = &filter.name_contains {
+        query_builder.push(" AND name ILIKE ").push_bind(format!("%{}%", sanitize_search_term(name)));
+    }
+    if let Some(prefix) = &filter.symbol_prefix {
+        // symbols are stored upper case
+        let pattern = format!("{}%", sanitize_search_term(prefix.trim()).to_uppercase());
+        query_builder.push(" AND symbol LIKE ").push_bind(pattern);
+    }
+    if let Some(from) = filter.created_from {
+        query_builder.push(" AND created_at >= ").push_bind(from);
+    }
+    if let Some(until) = filter.created_until {
+        query_builder.push(" AND created_at < ").push_bind(until);
+    }
+    if let Some(from) = filter.updated_from {
+        query_builder.push(" AND updated_at >= ").push_bind(from);
+    }
+    if let Some(until) = filter.updated_until {
+        query_builder.push(" AND updated_at < ").push_bind(until);
+    }
+    if !filter.tags.is_empty() {
+        // every tag of the filter must be on the asset
+        query_builder.push(" AND (SELECT COUNT(*) FROM asset_tag WHERE asset_tag.asset_id = asset.id AND asset_tag.tag = ANY(")
+            .push_bind(filter.tags.clone())
+            .push(")) = ")
+            .push_bind(filter.tags.len() as i64);
+    }
+    for attribute in &filter.attributes {
+        query_builder.push(" AND EXISTS (SELECT 1 FROM asset_attribute WHERE asset_attribute.asset_id = asset.id AND asset_attribute.key = ")
+            .push_bind(attribute.key.clone());
+        match &attribute.value {
+            AttributeValue::String(value) => query_builder.push(" AND asset_attribute.string_value = ").push_bind(value.clone()),
+            AttributeValue::Number(value) => query_builder.push(" AND asset_attribute.number_value = ").push_bind(*value),
+            AttributeValue::Bool(value) => query_builder.push(" AND asset_attribute.bool_value = ").push_bind(*value),
+            AttributeValue::Date(value) => query_builder.push(" AND asset_attribute.date_value = ").push_bind(*value),
+        };
+        query_builder.push(")");
+    }
+    if let Some(collection_id) = &filter.collection_id {
+        query_builder.push(" AND EXISTS (SELECT 1 FROM collection_asset WHERE collection_asset.asset_id = asset.id AND collection_asset.collection_id = ")
+            .push_bind(collection_id.clone())
+            .push(")");
+    }
 }
 
-#[tracing::instrument(level = "debug", skip(pg_pool, limit, order_by, offset))]
-pub async fn find_assets_name_like(
-    name: &str,
-    offset: i64,
-    limit: usize,
-    order_by: OrderType,
-    pg_pool: &PgPool,
-) -> Result<Vec<Asset>, DatabaseError> {
-    let search_term = format!("%{}%", sanitize_search_term(name));
-    tracing::debug!(
-        "fetching assets from DB :: name={}",
-        sanitize_search_term(name)
-    );
+#[derive(Debug)]
+struct DbAssetSearchHit {
+    pub id: String,
+    pub name: String,
+    pub symbol: String,
+    pub description: String,
+    pub organization: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub tradable: bool,
+    pub listable: bool,
+    pub updated_by: String,
+    pub owner_fp: String,
+    pub score: f32,
+    pub snippet: String,
+}
 
-    let result = match order_by {
-        OrderType::Asc => {
-            sqlx::query_as!(
-                Asset,
-                r#"
-                SELECT
-                    id, name, symbol, description, organization, created_at, updated_at, tradable,
-                    listable, updated_by, owner_fp
-                FROM asset
-                WHERE name ILIKE $1
-                ORDER BY name
-                LIMIT $2
-                OFFSET $3"#,
-                search_term,
-                limit as i64,
-                offset
-            ).fetch_all(pg_pool).await?
-        }
-        OrderType::Desc => {
-            sqlx::query_as!(
-                Asset,
-                r#"
-                SELECT
-                    id, name, symbol, description, organization, created_at, updated_at, tradable, listable, updated_by,
-                    owner_fp
-                FROM asset
-                WHERE name ILIKE $1
-                ORDER BY name DESC
-                LIMIT $2
-                OFFSET $3"#,
-                search_term,
-                limit as i64,
-                offset
-            ).fetch_all(pg_pool).await?
+impl From<DbAssetSearchHit> for AssetSearchHit {
+    fn from(hit: DbAssetSearchHit) -> Self {
+        AssetSearchHit {
+            score: hit.score,
+            snippet: hit.snippet,
+            asset: Asset {
+                id: hit.id,
+                name: hit.name,
+                symbol: hit.symbol,
+                description: hit.description,
+                organization: hit.organization,
+                created_at: hit.created_at,
+                updated_at: hit.updated_at,
+                tradable: hit.tradable,
+                listable: hit.listable,
+                updated_by: hit.updated_by,
+                owner_fp: hit.owner_fp,
+            },
         }
-    };
+    }
+}
 
-    Ok(result)
+/// Assets whose name, symbol or description match the words of the term, or whose name or symbol is similar to it.
+/// Hits are ordered by score: the full-text rank plus the best trigram similarity of the name or symbol.
+#[tracing::instrument(level = "debug", skip(pg_pool))]
+pub async fn search_assets(search: &AssetSearch, pg_pool: &PgPool) -> Result<Vec<AssetSearchHit>, DatabaseError> {
+    tracing::debug!("searching assets :: {}", search);
+    let hits = sqlx::query_as!(
+        DbAssetSearchHit,
+        r#"
+        WITH query AS (SELECT WEBSEARCH_TO_TSQUERY('english', $1) AS ts),
+             hit AS (SELECT asset.*,
+                            TS_RANK_CD(asset.search_vector, query.ts)
+                                + GREATEST(SIMILARITY(asset.name, $1), SIMILARITY(asset.symbol, $1)) AS score
+                     FROM asset, query
+                     WHERE (asset.search_vector @@ query.ts OR asset.name % $1 OR asset.symbol % $1)
+                       AND ($2::TEXT IS NULL OR asset.organization = $2)
+                       AND ($3::BOOLEAN IS NULL OR asset.tradable = $3)
+                       AND ($4::BOOLEAN IS NULL OR asset.listable = $4)
+                     ORDER BY score DESC, asset.id
+                     LIMIT $5 OFFSET $6)
+        SELECT hit.id,
+               hit.name,
+               hit.symbol,
+               hit.description,
+               hit.organization,
+               hit.created_at,
+               hit.updated_at,
+               hit.tradable,
+               hit.listable,
+               hit.updated_by,
+               hit.owner_fp,
+               hit.score as "score!",
+               TS_HEADLINE('english', hit.name || ' - ' || hit.description, query.ts,
+                           'StartSel=<b>, StopSel=</b>, MaxWords=24, MinWords=8') as "snippet!"
+        FROM hit, query
+        ORDER BY hit.score DESC, hit.id"#,
+        search.term,
+        search.organization,
+        search.tradable,
+        search.listable,
+        search.limit,
+        search.offset,
+    )
+        .fetch_all(pg_pool)
+        .await?;
+    Ok(hits.into_iter().map(AssetSearchHit::from).collect())
 }
 
+/// Deletes the asset with its NFC, trail and contract, recording the deletion in the outbox
 #[tracing::instrument(level = "debug", skip(pg_pool))]
 pub async fn delete_asset_by_id(asset_id: &str, pg_pool: &PgPool) -> Result<bool, DatabaseError> {
     tracing::debug!("deleting asset :: id = {}", asset_id);
-    let result = sqlx::query!("DELETE FROM asset WHERE id = $1", asset_id)
-        .execute(pg_pool)
+    let mut transaction = UnitOfWork::begin(pg_pool).await?;
+    let deleted = sqlx::query!("DELETE FROM asset WHERE id = $1 RETURNING organization, owner_fp", asset_id)
+        .fetch_optional(&mut *transaction)
         .await?;
-    Ok(result.rows_affected() == 1)
+    let Some(deleted) = deleted else {
+        return Ok(false);
+    };
+    create_outbox_event(&mut transaction, &OutboxEvent::asset_deleted(asset_id, &deleted.organization, &deleted.owner_fp)).await?;
+    transaction.commit().await?;
+    Ok(true)
 }
 
-#[tracing::instrument(level = "debug", skip(pg_pool, asset_id, new_owner_fp))]
+/// Moves the asset to the new owner, appends the NFC trail and certifies the new owner as part of the caller's unit of work,
+/// recording the transfer in the outbox.
+/// `sale_id` is the sale the asset was transferred for, it must already be recorded in the unit of work.
+#[tracing::instrument(level = "debug", skip(transaction, asset_id, new_owner_fp, signer))]
 pub async fn transfer_asset_query(new_org: &str,
                                   asset_id: &str,
                                   new_owner_fp: &str,
-                                  pg_pool: &PgPool)
+                                  sale_id: Option<&str>,
+                                  signer: &CertificateSigner,
+                                  transaction: &mut UnitOfWork<'_>)
                                   -> Result<NFC, DatabaseError> {
-    let nfc = get_nfc_by_asset_id(asset_id, pg_pool)
+    let mut nfc = get_nfc_by_asset_id(asset_id, &mut **transaction)
         .await
         .map_err(|e| match e {
             DatabaseError::NotFound => DatabaseError::InvalidRecordState("Invalid asset without nfc".to_string()),
             _ => DatabaseError::Unknown("something went wrong".to_string())
         })?;
+    // owner and organization the asset is transferred from
+    let previous = find_asset_by_id_for_update(asset_id, transaction).await?;
 
-    let mut transaction = pg_pool.begin().await?;
     let result = sqlx::query!(r#"
     UPDATE asset
     SET organization = $1, updated_by = $2, owner_fp = $2 WHERE id = $3
 "#,
         new_org, new_owner_fp, asset_id,
     )
-        .execute(&mut *transaction)
+        .execute(&mut **transaction)
         .await?;
 
     if result.rows_affected() != 1 {
-        transaction.rollback().await?;
-        return Err(DatabaseError::TransactionStepError("Failed to transfer asset and rolled back".to_string()));
+        return Err(DatabaseError::TransactionStepError("Failed to transfer asset".to_string()));
     }
 
-    let nfc_trail = NFCTrail::new(nfc.id.clone(), new_owner_fp.to_string(), asset_id.to_string());
-    let trail_created = create_nfc_trail(&mut transaction, &nfc_trail).await?;
+    let mut nfc_trail = NFCTrail::transfer(nfc.id.clone(),
+                                       &previous,
+                                       new_owner_fp.to_string(),
+                                       new_org.to_string(),
+                                       sale_id.map(str::to_string));
+    let trail_created = create_nfc_trail(transaction, &mut nfc_trail).await?;
 
     if !trail_created {
-        transaction.rollback().await?;
-        return Err(DatabaseError::TransactionStepError("failed to create NFC trail and rolled back".to_string()));
+        return Err(DatabaseError::TransactionStepError("failed to create NFC trail".to_string()));
     }
 
-    transaction.commit().await?;
+    // the certificate of the previous owner no longer verifies
+    nfc.sign_for(new_owner_fp, signer);
+    let cert_updated = update_nfc_cert(&nfc.id, &nfc.cert, &mut **transaction).await?;
+    if !cert_updated {
+        return Err(DatabaseError::TransactionStepError("failed to certify new owner".to_string()));
+    }
+
+    let event = OutboxEvent::asset_transferred(&previous, &nfc.id, new_org, new_owner_fp, sale_id);
+    create_outbox_event(transaction, &event).await?;
     Ok(nfc)
 }
 
+/// Fetches an asset and locks its row until the transaction ends, serializing concurrent changes of ownership.
+#[tracing::instrument(level = "debug", skip(transaction))]
+pub async fn find_asset_by_id_for_update(asset_id: &str,
+                                         transaction: &mut UnitOfWork<'_>) -> Result<Asset, DatabaseError> {
+    let result = sqlx::query_as!(
+        Asset,
+        r#"
+        SELECT
+            id, name, symbol, description, organization, created_at, updated_at, tradable, listable,
+            updated_by, owner_fp
+        FROM asset
+        WHERE id = $1
+        FOR UPDATE"#,
+        asset_id
+    )
+        .fetch_one(&mut **transaction)
+        .await?;
+    Ok(result)
+}
+
 #[tracing::instrument(level = "debug", skip(pg_pool, asset))]
 pub async fn update_asset(
     asset_id: &str,
@@ -388,6 +437,7 @@ pub async fn update_asset(
         && asset.tradable.is_none()
         && asset.description.is_none()
         && asset.organization.is_none()
+        && !asset.updates_metadata()
     {
         return Ok(true);
     }
@@ -429,26 +479,47 @@ pub async fn update_asset(
     }
 
     // SET the necessary fields
-    query_builder.push("updated_at = ").push_bind(Utc::now());
+    let updated_at = Utc::now();
+    query_builder.push("updated_at = ").push_bind(updated_at);
     query_builder
         .push(", updated_by = ")
         .push_bind(updated_by);
 
     // SET WHERE clause
     query_builder.push(" WHERE id = ").push_bind(asset_id);
+    // the event is addressed to the owner of the asset once it is updated
+    query_builder.push(" RETURNING organization, owner_fp");
 
-    if pg_pool.is_closed() {
-        error!("Database connection pool is closed");
-        return Err(DatabaseError::PoolClosed);
-    }
-
-    match query_builder.build().execute(pg_pool).await {
-        Ok(res) => Ok(res.rows_affected() > 0),
+    // the asset row, its tags and attributes and the event of the update change together
+    let mut transaction = UnitOfWork::begin(pg_pool).await?;
+    let updated = match query_builder.build_query_as::<(String, String)>().fetch_optional(&mut *transaction).await {
+        Ok(res) => res,
         Err(e) => {
             error!("Error executing SQL query: {:?}", e);
-            Err(DatabaseError::from(e))
+            return Err(DatabaseError::from(e));
+        }
+    };
+    let Some((organization, owner_fp)) = updated else {
+        return Ok(false);
+    };
+
+    if let Some(tags) = &asset.tags {
+        replace_asset_tags(asset_id, tags, &mut transaction).await?;
+    }
+    if !asset.remove_attributes.is_empty() {
+        delete_asset_attributes(asset_id, &asset.remove_attributes, &mut transaction).await?;
+    }
+    if !asset.set_attributes.is_empty() {
+        upsert_asset_attributes(asset_id, &asset.set_attributes, &mut transaction).await?;
+        if count_asset_attributes(asset_id, &mut *transaction).await? > MAX_ASSET_ATTRIBUTES as i64 {
+            return Err(DatabaseError::InvalidArgument(format!("an asset can have at most {} attributes", MAX_ASSET_ATTRIBUTES)));
         }
     }
+
+    let event = OutboxEvent::asset_updated(asset_id, &organization, &owner_fp, updated_by, asset, updated_at);
+    create_outbox_event(&mut transaction, &event).await?;
+    transaction.commit().await?;
+    Ok(true)
 }
 
 fn sanitize_search_term(search_term: &str) -> String {
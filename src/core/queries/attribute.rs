@@ -0,0 +1,159 @@
+use crate::core::queries::UnitOfWork;
+use crate::core::{AssetAttribute, AssetMetadata, AttributeKind, AttributeValue, DatabaseError};
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, PgPool, Postgres};
+use std::collections::HashMap;
+use tracing::info;
+
+#[derive(Debug)]
+struct DbAssetAttribute {
+    pub asset_id: String,
+    pub key: String,
+    pub kind: AttributeKind,
+    pub string_value: Option<String>,
+    pub number_value: Option<f64>,
+    pub bool_value: Option<bool>,
+    pub date_value: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<DbAssetAttribute> for AssetAttribute {
+    type Error = DatabaseError;
+
+    fn try_from(db_attribute: DbAssetAttribute) -> Result<Self, Self::Error> {
+        let value = match db_attribute.kind {
+            AttributeKind::String => db_attribute.string_value.map(AttributeValue::String),
+            AttributeKind::Number => db_attribute.number_value.map(AttributeValue::Number),
+            AttributeKind::Bool => db_attribute.bool_value.map(AttributeValue::Bool),
+            AttributeKind::Date => db_attribute.date_value.map(AttributeValue::Date),
+        };
+        let value = value.ok_or_else(|| {
+            DatabaseError::InvalidRecordState(format!("attribute {} has no {} value", db_attribute.key, db_attribute.kind))
+        })?;
+        Ok(AssetAttribute { key: db_attribute.key, value })
+    }
+}
+
+/// `(string_value, number_value, bool_value, date_value)` columns of an attribute
+type AttributeColumns<'a> = (Option<&'a str>, Option<f64>, Option<bool>, Option<DateTime<Utc>>);
+
+/// Value columns of an attribute, only the column of its kind is set
+fn attribute_columns(value: &AttributeValue) -> AttributeColumns<'_> {
+    match value {
+        AttributeValue::String(value) => (Some(value.as_str()), None, None, None),
+        AttributeValue::Number(value) => (None, Some(*value), None, None),
+        AttributeValue::Bool(value) => (None, None, Some(*value), None),
+        AttributeValue::Date(value) => (None, None, None, Some(*value)),
+    }
+}
+
+/// Replaces every tag of the asset with `tags`
+#[tracing::instrument(skip(transaction, tags))]
+pub async fn replace_asset_tags(asset_id: &str, tags: &[String], transaction: &mut UnitOfWork<'_>) -> Result<(), DatabaseError> {
+    info!("replacing asset tags :: assetId={} :: tags={:?}", asset_id, tags);
+    sqlx::query!("DELETE FROM asset_tag WHERE asset_id = $1", asset_id)
+        .execute(&mut **transaction)
+        .await?;
+    sqlx::query!(
+        r#"
+INSERT INTO asset_tag (asset_id, tag)
+SELECT $1, tag
+FROM UNNEST($2::TEXT[]) AS tag
+ON CONFLICT DO NOTHING"#,
+        asset_id,
+        tags,
+    )
+        .execute(&mut **transaction)
+        .await?;
+    Ok(())
+}
+
+/// Adds the attributes, replacing the value and kind of the attributes of the asset with the same key
+#[tracing::instrument(skip(transaction, attributes))]
+pub async fn upsert_asset_attributes(asset_id: &str,
+                                     attributes: &[AssetAttribute],
+                                     transaction: &mut UnitOfWork<'_>) -> Result<(), DatabaseError> {
+    for attribute in attributes {
+        info!("setting asset attribute :: assetId={} :: {}", asset_id, attribute);
+        let (string_value, number_value, bool_value, date_value) = attribute_columns(&attribute.value);
+        sqlx::query!(
+            r#"
+INSERT INTO asset_attribute (asset_id, key, kind, string_value, number_value, bool_value, date_value)
+VALUES ($1, $2, $3, $4, $5, $6, $7)
+ON CONFLICT (asset_id, key) DO UPDATE
+    SET kind         = EXCLUDED.kind,
+        string_value = EXCLUDED.string_value,
+        number_value = EXCLUDED.number_value,
+        bool_value   = EXCLUDED.bool_value,
+        date_value   = EXCLUDED.date_value"#,
+            asset_id,
+            attribute.key,
+            attribute.value.kind() as AttributeKind,
+            string_value,
+            number_value,
+            bool_value,
+            date_value,
+        )
+            .execute(&mut **transaction)
+            .await?;
+    }
+    Ok(())
+}
+
+#[tracing::instrument(skip(transaction))]
+pub async fn delete_asset_attributes(asset_id: &str, keys: &[String], transaction: &mut UnitOfWork<'_>) -> Result<u64, DatabaseError> {
+    info!("removing asset attributes :: assetId={} :: keys={:?}", asset_id, keys);
+    let result = sqlx::query!("DELETE FROM asset_attribute WHERE asset_id = $1 AND key = ANY($2)", asset_id, keys)
+        .execute(&mut **transaction)
+        .await?;
+    Ok(result.rows_affected())
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn count_asset_attributes<'a, E>(asset_id: &str, pg_pool: E) -> Result<i64, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    let count = sqlx::query_scalar!(r#"SELECT COUNT(*) as "count!" FROM asset_attribute WHERE asset_id = $1"#, asset_id)
+        .fetch_one(pg_pool)
+        .await?;
+    Ok(count)
+}
+
+/// Tags and attributes of the assets by asset id, assets without any have no entry
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_asset_metadata(asset_ids: &[String], pg_pool: &PgPool) -> Result<HashMap<String, AssetMetadata>, DatabaseError> {
+    let mut metadata: HashMap<String, AssetMetadata> = HashMap::new();
+    if asset_ids.is_empty() {
+        return Ok(metadata);
+    }
+    let tags = sqlx::query!(
+        r#"
+SELECT asset_id, tag
+FROM asset_tag
+WHERE asset_id = ANY($1)
+ORDER BY asset_id, tag"#,
+        asset_ids
+    )
+        .fetch_all(pg_pool)
+        .await?;
+    for row in tags {
+        metadata.entry(row.asset_id).or_default().tags.push(row.tag);
+    }
+
+    let attributes = sqlx::query_as!(
+        DbAssetAttribute,
+        r#"
+SELECT asset_id, key, kind as "kind: AttributeKind", string_value, number_value, bool_value, date_value
+FROM asset_attribute
+WHERE asset_id = ANY($1)
+ORDER BY asset_id, key"#,
+        asset_ids
+    )
+        .fetch_all(pg_pool)
+        .await?;
+    for db_attribute in attributes {
+        let asset_id = db_attribute.asset_id.clone();
+        metadata.entry(asset_id).or_default().attributes.push(db_attribute.try_into()?);
+    }
+    Ok(metadata)
+}
@@ -0,0 +1,152 @@
+use crate::core::queries::{decode_money, UnitOfWork};
+use crate::core::{Auction, AuctionType, Currency, DatabaseError, DutchDecay, Money};
+use chrono::{DateTime, Utc};
+use sqlx::types::BigDecimal;
+use sqlx::{Executor, PgPool, Postgres};
+use tracing::info;
+
+#[derive(Debug)]
+struct DbAuction {
+    pub auction_type: AuctionType,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub currency: Currency,
+    pub reserve_price: BigDecimal,
+    pub bid_increment: BigDecimal,
+    pub dutch_start_price: Option<BigDecimal>,
+    pub dutch_decay_amount: Option<BigDecimal>,
+    pub dutch_decay_interval_secs: Option<i64>,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub winning_bid_id: Option<String>,
+}
+
+impl TryFrom<DbAuction> for Auction {
+    type Error = DatabaseError;
+
+    fn try_from(db_auction: DbAuction) -> Result<Self, Self::Error> {
+        let currency = db_auction.currency;
+        let dutch_decay = match (&db_auction.dutch_start_price, &db_auction.dutch_decay_amount, db_auction.dutch_decay_interval_secs) {
+            (Some(start_price), Some(decay_amount), Some(decay_interval_secs)) => Some(DutchDecay {
+                decay_interval_secs,
+                start_price: decode_money(start_price, currency.clone())?,
+                decay_amount: decode_money(decay_amount, currency.clone())?,
+            }),
+            _ => None,
+        };
+        Ok(Auction {
+            dutch_decay,
+            end_time: db_auction.end_time,
+            closed_at: db_auction.closed_at,
+            start_time: db_auction.start_time,
+            auction_type: db_auction.auction_type,
+            winning_bid_id: db_auction.winning_bid_id,
+            bid_increment: decode_money(&db_auction.bid_increment, currency.clone())?,
+            reserve_price: decode_money(&db_auction.reserve_price, currency)?,
+        })
+    }
+}
+
+#[tracing::instrument(skip(transaction, auction))]
+pub async fn create_contract_auction(contract_id: &str,
+                                     asset_id: &str,
+                                     auction: &Auction,
+                                     transaction: &mut UnitOfWork<'_>) -> Result<bool, DatabaseError> {
+    info!("creating contract auction :: contractId={} :: auction={}", contract_id, auction);
+    let decay = auction.dutch_decay.as_ref();
+    let result = sqlx::query!(
+        r#"
+INSERT INTO contract_auction (contract_id,
+                              asset_id,
+                              auction_type,
+                              start_time,
+                              end_time,
+                              currency,
+                              reserve_price,
+                              bid_increment,
+                              dutch_start_price,
+                              dutch_decay_amount,
+                              dutch_decay_interval_secs)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)"#,
+        contract_id,
+        asset_id,
+        auction.auction_type as AuctionType,
+        auction.start_time,
+        auction.end_time,
+        auction.currency() as &Currency,
+        &auction.reserve_price as &Money,
+        &auction.bid_increment as &Money,
+        decay.map(|d| d.start_price.to_decimal()),
+        decay.map(|d| d.decay_amount.to_decimal()),
+        decay.map(|d| d.decay_interval_secs),
+    )
+        .execute(&mut **transaction)
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_auction_by_contract_id<'a, E>(contract_id: &str, pg_pool: E) -> Result<Option<Auction>, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    let auction = sqlx::query_as!(
+        DbAuction,
+        r#"
+SELECT auction_type as "auction_type: AuctionType",
+       start_time,
+       end_time,
+       currency as "currency: Currency",
+       reserve_price,
+       bid_increment,
+       dutch_start_price,
+       dutch_decay_amount,
+       dutch_decay_interval_secs,
+       closed_at,
+       winning_bid_id
+FROM contract_auction
+WHERE contract_id = $1"#,
+        contract_id
+    )
+        .fetch_optional(pg_pool)
+        .await?;
+    auction.map(Auction::try_from).transpose()
+}
+
+/// Asset ids of the auctions that ended before `now` and have not been closed yet, oldest first
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_due_auction_asset_ids(now: DateTime<Utc>, limit: i64, pg_pool: &PgPool) -> Result<Vec<String>, DatabaseError> {
+    let result = sqlx::query!(
+        r#"
+SELECT asset_id
+FROM contract_auction
+WHERE closed_at IS NULL AND end_time <= $1
+ORDER BY end_time
+LIMIT $2"#,
+        now,
+        limit
+    )
+        .fetch_all(pg_pool)
+        .await?;
+    Ok(result.into_iter().map(|r| r.asset_id).collect())
+}
+
+/// Marks the auction as closed, returns false if it was already closed
+#[tracing::instrument(skip(transaction))]
+pub async fn close_contract_auction(contract_id: &str,
+                                    winning_bid_id: Option<&str>,
+                                    closed_at: DateTime<Utc>,
+                                    transaction: &mut UnitOfWork<'_>) -> Result<bool, DatabaseError> {
+    info!("closing contract auction :: contractId={} :: winningBidId={:?}", contract_id, winning_bid_id);
+    let result = sqlx::query!(
+        r#"
+UPDATE contract_auction
+SET closed_at = $1, winning_bid_id = $2
+WHERE contract_id = $3 AND closed_at IS NULL"#,
+        closed_at,
+        winning_bid_id,
+        contract_id
+    )
+        .execute(&mut **transaction)
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
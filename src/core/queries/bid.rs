@@ -0,0 +1,344 @@
+use crate::core::queries::decode_money;
+use crate::core::{Bid, BidStatus, Currency, DatabaseError, Money};
+use chrono::{DateTime, Utc};
+use sqlx::types::BigDecimal;
+use sqlx::{Executor, PgPool, Postgres};
+use tracing::info;
+
+#[derive(Debug)]
+struct DbBid {
+    pub id: String,
+    pub asset_id: String,
+    pub bidder_fp: String,
+    pub bidder_org: String,
+    pub amount: BigDecimal,
+    pub currency: Currency,
+    pub anonymous: bool,
+    pub status: BidStatus,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<DbBid> for Bid {
+    type Error = DatabaseError;
+
+    fn try_from(db_bid: DbBid) -> Result<Self, Self::Error> {
+        Ok(Bid {
+            amount: decode_money(&db_bid.amount, db_bid.currency)?,
+            id: db_bid.id,
+            status: db_bid.status,
+            asset_id: db_bid.asset_id,
+            anonymous: db_bid.anonymous,
+            bidder_fp: db_bid.bidder_fp,
+            bidder_org: db_bid.bidder_org,
+            expires_at: db_bid.expires_at,
+            created_at: db_bid.created_at,
+            updated_at: db_bid.updated_at,
+        })
+    }
+}
+
+#[tracing::instrument(skip(pg_pool, bid))]
+pub async fn create_bid<'a, E>(pg_pool: E, bid: &Bid) -> Result<bool, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    info!("creating bid :: bidId={} :: assetId={}", bid.id, bid.asset_id);
+    let result = sqlx::query!(
+        r#"
+INSERT INTO bid (
+                 id,
+                 asset_id,
+                 bidder_fp,
+                 bidder_org,
+                 amount,
+                 currency,
+                 anonymous,
+                 status,
+                 expires_at,
+                 created_at,
+                 updated_at
+        )
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+"#,
+        bid.id,
+        bid.asset_id,
+        bid.bidder_fp,
+        bid.bidder_org,
+        &bid.amount as &Money,
+        bid.amount.currency() as &Currency,
+        bid.anonymous,
+        &bid.status as &BidStatus,
+        bid.expires_at,
+        bid.created_at,
+        bid.updated_at,
+    )
+        .execute(pg_pool)
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_bid_by_id<'a, E>(bid_id: &str, pg_pool: E) -> Result<Bid, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    info!("getting bid by id={}", bid_id);
+    let bid = sqlx::query_as!(
+        DbBid,
+        r#"
+SELECT id,
+       asset_id,
+       bidder_fp,
+       bidder_org,
+       amount,
+       currency as "currency: Currency",
+       anonymous,
+       status as "status: BidStatus",
+       expires_at,
+       created_at,
+       updated_at
+FROM bid
+WHERE id = $1"#,
+        bid_id
+    )
+        .fetch_one(pg_pool)
+        .await?;
+    bid.try_into()
+}
+
+/// Bids on the asset, highest first. With a `bidder_fp` only the bids of that bidder are returned
+#[tracing::instrument(skip(pg_pool, bidder_fp, limit, offset))]
+pub async fn find_bids_by_asset_id(asset_id: &str,
+                                   bidder_fp: Option<&str>,
+                                   limit: i64,
+                                   offset: i64,
+                                   pg_pool: &PgPool) -> Result<Vec<Bid>, DatabaseError> {
+    info!("getting bids for asset :: assetId={}", asset_id);
+    let bids = sqlx::query_as!(
+        DbBid,
+        r#"
+SELECT id,
+       asset_id,
+       bidder_fp,
+       bidder_org,
+       amount,
+       currency as "currency: Currency",
+       anonymous,
+       status as "status: BidStatus",
+       expires_at,
+       created_at,
+       updated_at
+FROM bid
+WHERE asset_id = $1 AND ($2::TEXT IS NULL OR bidder_fp = $2)
+ORDER BY amount DESC, created_at
+LIMIT $3 OFFSET $4"#,
+        asset_id,
+        bidder_fp,
+        limit,
+        offset
+    )
+        .fetch_all(pg_pool)
+        .await?;
+    bids.into_iter().map(Bid::try_from).collect()
+}
+
+/// Number of bids on the asset whatever their status, the total of `find_bids_by_asset_id` with the same `bidder_fp`
+#[tracing::instrument(skip(pg_pool, bidder_fp))]
+pub async fn count_bids_by_asset_id<'a, E>(asset_id: &str, bidder_fp: Option<&str>, pg_pool: E) -> Result<i64, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM bid WHERE asset_id = $1 AND ($2::TEXT IS NULL OR bidder_fp = $2)"#,
+        asset_id,
+        bidder_fp
+    )
+        .fetch_one(pg_pool)
+        .await?;
+    Ok(count)
+}
+
+/// All open bids on the asset regardless of their expiry, bids on an auction expire when the auction ends
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_open_bids_for_asset<'a, E>(asset_id: &str, pg_pool: E) -> Result<Vec<Bid>, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    let bids = sqlx::query_as!(
+        DbBid,
+        r#"
+SELECT id,
+       asset_id,
+       bidder_fp,
+       bidder_org,
+       amount,
+       currency as "currency: Currency",
+       anonymous,
+       status as "status: BidStatus",
+       expires_at,
+       created_at,
+       updated_at
+FROM bid
+WHERE asset_id = $1 AND status = $2
+ORDER BY created_at"#,
+        asset_id,
+        BidStatus::Open as BidStatus,
+    )
+        .fetch_all(pg_pool)
+        .await?;
+    bids.into_iter().map(Bid::try_from).collect()
+}
+
+/// Number of bids on the asset that are open and have not expired
+#[tracing::instrument(skip(pg_pool))]
+pub async fn count_open_bids_for_asset<'a, E>(asset_id: &str, pg_pool: E) -> Result<i64, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    let result = sqlx::query!(
+        r#"SELECT COUNT(*) AS "count!" FROM bid WHERE asset_id = $1 AND status = $2 AND expires_at > $3"#,
+        asset_id,
+        BidStatus::Open as BidStatus,
+        Utc::now(),
+    )
+        .fetch_one(pg_pool)
+        .await?;
+    Ok(result.count)
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_highest_open_bid<'a, E>(asset_id: &str, pg_pool: E) -> Result<Option<Bid>, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    let bid = sqlx::query_as!(
+        DbBid,
+        r#"
+SELECT id,
+       asset_id,
+       bidder_fp,
+       bidder_org,
+       amount,
+       currency as "currency: Currency",
+       anonymous,
+       status as "status: BidStatus",
+       expires_at,
+       created_at,
+       updated_at
+FROM bid
+WHERE asset_id = $1 AND status = $2 AND expires_at > $3
+ORDER BY amount DESC, created_at
+LIMIT 1"#,
+        asset_id,
+        BidStatus::Open as BidStatus,
+        Utc::now(),
+    )
+        .fetch_optional(pg_pool)
+        .await?;
+    bid.map(Bid::try_from).transpose()
+}
+
+#[tracing::instrument(skip(pg_pool, bidder_fp, limit, offset))]
+pub async fn find_bids_by_bidder(bidder_fp: &str,
+                                 limit: i64,
+                                 offset: i64,
+                                 pg_pool: &PgPool) -> Result<Vec<Bid>, DatabaseError> {
+    let bids = sqlx::query_as!(
+        DbBid,
+        r#"
+SELECT id,
+       asset_id,
+       bidder_fp,
+       bidder_org,
+       amount,
+       currency as "currency: Currency",
+       anonymous,
+       status as "status: BidStatus",
+       expires_at,
+       created_at,
+       updated_at
+FROM bid
+WHERE bidder_fp = $1
+ORDER BY created_at DESC
+LIMIT $2 OFFSET $3"#,
+        bidder_fp,
+        limit,
+        offset
+    )
+        .fetch_all(pg_pool)
+        .await?;
+    bids.into_iter().map(Bid::try_from).collect()
+}
+
+/// Number of bids of the bidder whatever their status, the total of `find_bids_by_bidder`
+#[tracing::instrument(skip(pg_pool, bidder_fp))]
+pub async fn count_bids_by_bidder<'a, E>(bidder_fp: &str, pg_pool: E) -> Result<i64, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    let count = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM bid WHERE bidder_fp = $1"#, bidder_fp)
+        .fetch_one(pg_pool)
+        .await?;
+    Ok(count)
+}
+
+/// Moves a bid from `from_status` to `to_status`. Returns `false` if the bid is not in `from_status`
+#[tracing::instrument(skip(pg_pool))]
+pub async fn update_bid_status<'a, E>(bid_id: &str,
+                                      from_status: BidStatus,
+                                      to_status: BidStatus,
+                                      pg_pool: E) -> Result<bool, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    info!("updating bid status :: bidId={} :: {} -> {}", bid_id, from_status, to_status);
+    let result = sqlx::query!(
+        r#"
+UPDATE bid
+SET status = $1, updated_at = $2
+WHERE id = $3 AND status = $4"#,
+        to_status as BidStatus,
+        Utc::now(),
+        bid_id,
+        from_status as BidStatus,
+    )
+        .execute(pg_pool)
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+/// Rejects every open bid on an asset except `accepted_bid_id`. Returns the number of rejected bids
+#[tracing::instrument(skip(pg_pool))]
+pub async fn reject_open_bids_for_asset<'a, E>(asset_id: &str,
+                                               accepted_bid_id: &str,
+                                               pg_pool: E) -> Result<u64, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    info!("rejecting competing bids :: assetId={} :: acceptedBidId={}", asset_id, accepted_bid_id);
+    let result = sqlx::query!(
+        r#"
+UPDATE bid
+SET status = $1, updated_at = $2
+WHERE asset_id = $3 AND id <> $4 AND status = $5"#,
+        BidStatus::Rejected as BidStatus,
+        Utc::now(),
+        asset_id,
+        accepted_bid_id,
+        BidStatus::Open as BidStatus,
+    )
+        .execute(pg_pool)
+        .await?;
+    Ok(result.rows_affected())
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn delete_bid_by_id(bid_id: &str, pg_pool: &PgPool) -> Result<bool, DatabaseError> {
+    info!("deleting bid :: id={}", bid_id);
+    let result = sqlx::query!("DELETE FROM bid WHERE id = $1", bid_id)
+        .execute(pg_pool)
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
@@ -0,0 +1,297 @@
+use crate::core::queries::{decode_money, UnitOfWork};
+use crate::core::{
+    Collection, ContractTerms, Currency, CurrencyList, DatabaseError, DefaultContract, ResaleRestriction, RoyaltySplit,
+};
+use chrono::{DateTime, Utc};
+use sqlx::types::BigDecimal;
+use sqlx::{Executor, PgPool, Postgres};
+use tracing::info;
+
+#[derive(Debug)]
+struct DbCollection {
+    pub id: String,
+    pub organization: String,
+    pub name: String,
+    pub symbol_prefix: String,
+    pub description: String,
+    pub supply_cap: Option<i32>,
+    pub created_by: String,
+    pub updated_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl DbCollection {
+    fn into_collection(self, default_contract: Option<DefaultContract>) -> Collection {
+        Collection {
+            default_contract,
+            id: self.id,
+            organization: self.organization,
+            name: self.name,
+            symbol_prefix: self.symbol_prefix,
+            description: self.description,
+            supply_cap: self.supply_cap,
+            created_by: self.created_by,
+            updated_by: self.updated_by,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct DbDefaultContract {
+    pub content: String,
+    pub summary: String,
+    pub min_price: BigDecimal,
+    pub min_price_currency: Currency,
+    pub accepted_currency: CurrencyList,
+    pub anonymous_buyer_only: bool,
+    pub resale_allowed: bool,
+    pub resale_min_holding_days: i32,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub receivers: Vec<String>,
+    pub basis_points: Vec<i32>,
+}
+
+impl TryFrom<DbDefaultContract> for DefaultContract {
+    type Error = DatabaseError;
+
+    fn try_from(db_contract: DbDefaultContract) -> Result<Self, Self::Error> {
+        Ok(DefaultContract {
+            min_price: decode_money(&db_contract.min_price, db_contract.min_price_currency)?,
+            details: db_contract.content,
+            summary: db_contract.summary,
+            anonymous_buyer_only: db_contract.anonymous_buyer_only,
+            accepted_currency: db_contract.accepted_currency.0.into_iter().collect(),
+            royalty_splits: db_contract.receivers.into_iter()
+                .zip(db_contract.basis_points)
+                .map(|(receiver_fp, basis_points)| RoyaltySplit { receiver_fp, basis_points: basis_points as u32 })
+                .collect(),
+            terms: ContractTerms {
+                expires_at: db_contract.expires_at,
+                resale: ResaleRestriction {
+                    allowed: db_contract.resale_allowed,
+                    min_holding_days: db_contract.resale_min_holding_days as u32,
+                },
+            },
+        })
+    }
+}
+
+/// Creates the collection with its default contract
+#[tracing::instrument(skip(transaction, collection))]
+pub async fn create_collection(collection: &Collection, transaction: &mut UnitOfWork<'_>) -> Result<bool, DatabaseError> {
+    info!("creating collection :: {}", collection);
+    let result = sqlx::query!(
+        r#"
+INSERT INTO collection (id, organization, name, symbol_prefix, description, supply_cap, created_by, updated_by, created_at, updated_at)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"#,
+        collection.id,
+        collection.organization,
+        collection.name,
+        collection.symbol_prefix,
+        collection.description,
+        collection.supply_cap,
+        collection.created_by,
+        collection.updated_by,
+        collection.created_at,
+        collection.updated_at,
+    )
+        .execute(&mut **transaction)
+        .await?;
+    if result.rows_affected() != 1 {
+        return Ok(false);
+    }
+    if let Some(default_contract) = &collection.default_contract {
+        create_default_contract(&collection.id, default_contract, transaction).await?;
+    }
+    Ok(true)
+}
+
+/// Writes the fields and default contract of the collection, the symbol prefix never changes
+#[tracing::instrument(skip(transaction, collection))]
+pub async fn update_collection(collection: &Collection, transaction: &mut UnitOfWork<'_>) -> Result<bool, DatabaseError> {
+    info!("updating collection :: {}", collection);
+    let result = sqlx::query!(
+        r#"
+UPDATE collection
+SET name        = $2,
+    description = $3,
+    supply_cap  = $4,
+    updated_by  = $5,
+    updated_at  = $6
+WHERE id = $1"#,
+        collection.id,
+        collection.name,
+        collection.description,
+        collection.supply_cap,
+        collection.updated_by,
+        collection.updated_at,
+    )
+        .execute(&mut **transaction)
+        .await?;
+    if result.rows_affected() != 1 {
+        return Ok(false);
+    }
+    // the royalty splits of the previous default contract are removed with it
+    sqlx::query!("DELETE FROM collection_default_contract WHERE collection_id = $1", collection.id)
+        .execute(&mut **transaction)
+        .await?;
+    if let Some(default_contract) = &collection.default_contract {
+        create_default_contract(&collection.id, default_contract, transaction).await?;
+    }
+    Ok(true)
+}
+
+async fn create_default_contract(collection_id: &str,
+                                 default_contract: &DefaultContract,
+                                 transaction: &mut UnitOfWork<'_>) -> Result<(), DatabaseError> {
+    let accepted_currency = CurrencyList(default_contract.accepted_currency.iter().cloned().collect());
+    sqlx::query!(
+        r#"
+INSERT INTO collection_default_contract (collection_id,
+                                         content,
+                                         summary,
+                                         min_price,
+                                         min_price_currency,
+                                         accepted_currency,
+                                         anonymous_buyer_only,
+                                         resale_allowed,
+                                         resale_min_holding_days,
+                                         expires_at)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"#,
+        collection_id,
+        default_contract.details,
+        default_contract.summary,
+        default_contract.min_price.to_decimal(),
+        default_contract.min_price.currency() as &Currency,
+        &accepted_currency as &CurrencyList,
+        default_contract.anonymous_buyer_only,
+        default_contract.terms.resale.allowed,
+        default_contract.terms.resale.min_holding_days as i32,
+        default_contract.terms.expires_at,
+    )
+        .execute(&mut **transaction)
+        .await?;
+    for split in &default_contract.royalty_splits {
+        sqlx::query!(
+            r#"
+INSERT INTO collection_royalty_split (collection_id, receiver_fp, basis_points)
+VALUES ($1, $2, $3)"#,
+            collection_id,
+            split.receiver_fp,
+            split.basis_points as i32,
+        )
+            .execute(&mut **transaction)
+            .await?;
+    }
+    Ok(())
+}
+
+async fn find_default_contract<'a, E>(collection_id: &str, pg_pool: E) -> Result<Option<DefaultContract>, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    let result = sqlx::query_as!(
+        DbDefaultContract,
+        r#"
+SELECT contract.content,
+       contract.summary,
+       contract.min_price,
+       contract.min_price_currency as "min_price_currency: Currency",
+       contract.accepted_currency as "accepted_currency: CurrencyList",
+       contract.anonymous_buyer_only,
+       contract.resale_allowed,
+       contract.resale_min_holding_days,
+       contract.expires_at,
+       COALESCE(ARRAY_AGG(split.receiver_fp ORDER BY split.basis_points DESC, split.receiver_fp)
+                FILTER (WHERE split.receiver_fp IS NOT NULL), '{}') as "receivers!",
+       COALESCE(ARRAY_AGG(split.basis_points ORDER BY split.basis_points DESC, split.receiver_fp)
+                FILTER (WHERE split.receiver_fp IS NOT NULL), '{}') as "basis_points!"
+FROM collection_default_contract contract
+         LEFT JOIN collection_royalty_split split ON split.collection_id = contract.collection_id
+WHERE contract.collection_id = $1
+GROUP BY contract.collection_id"#,
+        collection_id
+    )
+        .fetch_optional(pg_pool)
+        .await?;
+    result.map(DefaultContract::try_from).transpose()
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_collection_by_id(collection_id: &str, pg_pool: &PgPool) -> Result<Collection, DatabaseError> {
+    info!("getting collection :: id={}", collection_id);
+    let db_collection = sqlx::query_as!(
+        DbCollection,
+        r#"
+SELECT id, organization, name, symbol_prefix, description, supply_cap, created_by, updated_by, created_at, updated_at
+FROM collection
+WHERE id = $1"#,
+        collection_id
+    )
+        .fetch_one(pg_pool)
+        .await?;
+    let default_contract = find_default_contract(collection_id, pg_pool).await?;
+    Ok(db_collection.into_collection(default_contract))
+}
+
+/// Locks the collection until the transaction ends, assets join a collection one at a time
+#[tracing::instrument(skip(transaction))]
+pub async fn find_collection_for_update(collection_id: &str,
+                                        transaction: &mut UnitOfWork<'_>) -> Result<Collection, DatabaseError> {
+    let db_collection = sqlx::query_as!(
+        DbCollection,
+        r#"
+SELECT id, organization, name, symbol_prefix, description, supply_cap, created_by, updated_by, created_at, updated_at
+FROM collection
+WHERE id = $1
+FOR UPDATE"#,
+        collection_id
+    )
+        .fetch_one(&mut **transaction)
+        .await?;
+    let default_contract = find_default_contract(collection_id, &mut **transaction).await?;
+    Ok(db_collection.into_collection(default_contract))
+}
+
+/// Deletes a collection without assets, `DatabaseError::ForeignKeyViolation` while it still has some
+#[tracing::instrument(skip(pg_pool))]
+pub async fn delete_collection(collection_id: &str, organization: &str, pg_pool: &PgPool) -> Result<bool, DatabaseError> {
+    info!("deleting collection :: id={} :: org={}", collection_id, organization);
+    let result = sqlx::query!("DELETE FROM collection WHERE id = $1 AND organization = $2", collection_id, organization)
+        .execute(pg_pool)
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+#[tracing::instrument(skip(transaction))]
+pub async fn add_asset_to_collection(collection_id: &str,
+                                     asset_id: &str,
+                                     joined_at: DateTime<Utc>,
+                                     transaction: &mut UnitOfWork<'_>) -> Result<bool, DatabaseError> {
+    let result = sqlx::query!(
+        "INSERT INTO collection_asset (asset_id, collection_id, joined_at) VALUES ($1, $2, $3)",
+        asset_id,
+        collection_id,
+        joined_at,
+    )
+        .execute(&mut **transaction)
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn count_collection_assets<'a, E>(collection_id: &str, pg_pool: E) -> Result<i64, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) as "count!" FROM collection_asset WHERE collection_id = $1"#,
+        collection_id
+    )
+        .fetch_one(pg_pool)
+        .await?;
+    Ok(count)
+}
@@ -1,32 +1,41 @@
-use crate::core::{Contract, ContractVersion, CurrencyList, DatabaseError};
+use crate::core::queries::auction::{create_contract_auction, find_auction_by_contract_id};
+use crate::core::queries::{create_outbox_event, decode_money, UnitOfWork};
+use crate::core::{Contract, ContractTerms, ContractVersion, Currency, CurrencyList, DatabaseError, OutboxEvent, ResaleRestriction,
+                  RoyaltySplit};
 use chrono::{DateTime, Utc};
-use sqlx::PgPool;
+use sqlx::types::BigDecimal;
+use sqlx::{Executor, PgPool, Postgres};
+use std::collections::HashMap;
 use std::fmt::Display;
+use std::str::FromStr;
 use tracing::info;
 
 #[derive(Debug)]
 struct DbContract {
     pub id: String,
     pub content: String,
-    pub min_price: f64,
+    pub min_price: BigDecimal,
+    pub min_price_currency: Currency,
     pub summary: String,
     pub version: String,
     pub asset_id: String,
     pub update_count: i32,
     pub updated_by: String,
-    pub royalty_percentage: f64,
-    pub royalty_receiver: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub anonymous_buyer_only: bool,
     pub accepted_currency: CurrencyList, // Change to Vec<String> for database compatibility
+    pub resale_allowed: bool,
+    pub resale_min_holding_days: i32,
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug)]
 struct DbContractResponse {
     pub id: String,
     pub content: String,
-    pub min_price: f64,
+    pub min_price: BigDecimal,
+    pub min_price_currency: Currency,
     pub version: String,
     pub asset_id: String,
     pub update_count: i32,
@@ -35,9 +44,10 @@ struct DbContractResponse {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub anonymous_buyer_only: bool,
-    pub royalty_percentage: Option<f64>,
-    pub royalty_receiver: Option<String>,
     pub accepted_currency: CurrencyList, // Change to Vec<String> for database compatibility
+    pub resale_allowed: bool,
+    pub resale_min_holding_days: i32,
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 impl From<Contract> for DbContract {
@@ -47,29 +57,35 @@ impl From<Contract> for DbContract {
             content: contract.details,
             summary: contract.summary,
             asset_id: contract.asset_id,
-            min_price: contract.min_price,
+            min_price: contract.min_price.to_decimal(),
+            min_price_currency: contract.min_price.currency().clone(),
             updated_by: contract.updated_by,
             created_at: contract.created_at,
             updated_at: contract.updated_at,
             update_count: contract.update_count,
             version: contract.version.to_string(),
-            royalty_receiver: contract.royalty_receiver_id,
             anonymous_buyer_only: contract.anonymous_buyer_only,
-            royalty_percentage: contract.royalty_percentage as f64,
             accepted_currency: CurrencyList(contract.accepted_currency.into_iter().map(|c| c).collect()), // Convert to Vec<String>
+            resale_allowed: contract.terms.resale.allowed,
+            resale_min_holding_days: contract.terms.resale.min_holding_days as i32,
+            expires_at: contract.terms.expires_at,
         }
     }
 }
 
-impl From<DbContractResponse> for Contract {
-    fn from(db_contract: DbContractResponse) -> Self {
-        let version = ContractVersion::from(db_contract.version);
-        Contract {
+/// Rows with a version this build does not know are refused instead of being read with the wrong format
+impl TryFrom<DbContractResponse> for Contract {
+    type Error = DatabaseError;
+
+    fn try_from(db_contract: DbContractResponse) -> Result<Self, Self::Error> {
+        let version = ContractVersion::from_str(&db_contract.version)
+            .map_err(|e| DatabaseError::Decode(format!("contract {} :: {}", db_contract.id, e)))?;
+        Ok(Contract {
             version,
             id: db_contract.id,
             details: db_contract.content,
             asset_id: db_contract.asset_id,
-            min_price: db_contract.min_price,
+            min_price: decode_money(&db_contract.min_price, db_contract.min_price_currency)?,
             created_at: db_contract.created_at,
             updated_at: db_contract.updated_at,
             updated_by: db_contract.updated_by,
@@ -77,9 +93,16 @@ impl From<DbContractResponse> for Contract {
             anonymous_buyer_only: db_contract.anonymous_buyer_only,
             summary: db_contract.summary.unwrap_or_else(|| "".to_string()),
             accepted_currency: db_contract.accepted_currency.0.into_iter().collect(),
-            royalty_percentage: db_contract.royalty_percentage.unwrap_or_else(|| 0.0) as f32,
-            royalty_receiver_id: db_contract.royalty_receiver.unwrap_or_else(|| "".to_string()),
-        }
+            auction: None,
+            royalty_splits: vec![],
+            terms: ContractTerms {
+                expires_at: db_contract.expires_at,
+                resale: ResaleRestriction {
+                    allowed: db_contract.resale_allowed,
+                    min_holding_days: db_contract.resale_min_holding_days as u32,
+                },
+            },
+        })
     }
 }
 
@@ -109,7 +132,22 @@ pub async fn create_contract(pg_pool: &PgPool, contract: Contract) -> Result<boo
         return Err(DatabaseError::RecordExists("contract for given asset id exists".to_string()));
     }
 
-    let db_contract: DbContract = DbContract::from(contract);
+    // the contract and its auction are created together
+    let mut transaction = UnitOfWork::begin(pg_pool).await?;
+    let created = create_contract_in_transaction(contract, &mut transaction).await?;
+    if created {
+        transaction.commit().await?;
+    }
+    Ok(created)
+}
+
+/// Creates the contract with its royalty splits and auction as part of the caller's transaction, recording the creation in the outbox.
+/// The caller is responsible for committing, any error leaves the transaction to be rolled back.
+#[tracing::instrument(skip(transaction, contract))]
+pub async fn create_contract_in_transaction(contract: Contract, transaction: &mut UnitOfWork<'_>) -> Result<bool, DatabaseError> {
+    let auction = contract.auction.clone();
+    let royalty_splits = contract.royalty_splits.clone();
+    let db_contract: DbContract = DbContract::from(contract.clone());
     info!("creating contract :: currencyList={}", db_contract.accepted_currency);
     let result = sqlx::query!(
         r#"
@@ -120,16 +158,18 @@ INSERT INTO contract (
                       version,
                       asset_id,
                       min_price,
+                      min_price_currency,
                       created_at,
                       updated_by,
                       updated_at,
                       update_count,
-                      royalty_receiver,
                       accepted_currency,
-                      royalty_percentage,
-                      anonymous_buyer_only
+                      anonymous_buyer_only,
+                      resale_allowed,
+                      resale_min_holding_days,
+                      expires_at
         )
-VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
 "#,
         db_contract.id,
         db_contract.content,
@@ -137,18 +177,37 @@ VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
         db_contract.version,
         db_contract.asset_id,
         db_contract.min_price,
+        &db_contract.min_price_currency as &Currency,
         db_contract.created_at,
         db_contract.updated_by,
         db_contract.updated_at,
         db_contract.update_count,
-        db_contract.royalty_receiver,
         &db_contract.accepted_currency as &CurrencyList,
-        db_contract.royalty_percentage,
         db_contract.anonymous_buyer_only,
+        db_contract.resale_allowed,
+        db_contract.resale_min_holding_days,
+        db_contract.expires_at,
     )
-        .execute(pg_pool)
+        .execute(&mut **transaction)
         .await?;
-    Ok(result.rows_affected() == 1)
+    if result.rows_affected() != 1 {
+        return Ok(false);
+    }
+    create_royalty_splits(&db_contract.id, db_contract.update_count, &royalty_splits, transaction).await?;
+
+    if let Some(auction) = auction {
+        let auction_created = create_contract_auction(&db_contract.id, &db_contract.asset_id, &auction, transaction).await?;
+        if !auction_created {
+            return Err(DatabaseError::TransactionStepError("failed to create contract auction".to_string()));
+        }
+    }
+
+    // the asset exists, the contract would not have passed its foreign key otherwise
+    let owner = sqlx::query!("SELECT organization, owner_fp FROM asset WHERE id = $1", contract.asset_id)
+        .fetch_one(&mut **transaction)
+        .await?;
+    create_outbox_event(transaction, &OutboxEvent::contract_created(&contract, &owner.organization, &owner.owner_fp)).await?;
+    Ok(true)
 }
 
 #[tracing::instrument(skip(pg_pool))]
@@ -181,22 +240,320 @@ pub async fn find_contract_by_asset_id(asset_id: &str, pg_pool: &PgPool) -> Resu
 SELECT id,
        content,
        min_price,
+       min_price_currency as "min_price_currency: Currency",
        summary,
        version,
        asset_id,
        update_count,
        updated_by,
-       royalty_percentage,
        created_at,
        anonymous_buyer_only,
        updated_at,
-       royalty_receiver,
-       accepted_currency as "accepted_currency: CurrencyList"
+       accepted_currency as "accepted_currency: CurrencyList",
+       resale_allowed,
+       resale_min_holding_days,
+       expires_at
 FROM contract
 WHERE asset_id=$1"#,
         asset_id
     )
         .fetch_one(pg_pool)
         .await?;
-    Ok(result.into())
+    let mut contract: Contract = result.try_into()?;
+    contract.auction = find_auction_by_contract_id(&contract.id, pg_pool).await?;
+    contract.royalty_splits = find_royalty_splits(&contract.id, Some(contract.update_count), pg_pool)
+        .await?
+        .remove(&contract.update_count)
+        .unwrap_or_default();
+    Ok(contract)
+}
+
+/// Same as `find_contract_by_asset_id` but locks the contract row until the transaction ends
+#[tracing::instrument(skip(transaction))]
+pub async fn find_contract_by_asset_id_for_update(asset_id: &str, transaction: &mut UnitOfWork<'_>) -> Result<Contract, DatabaseError> {
+    let result = sqlx::query_as!(
+        DbContractResponse,
+        r#"
+SELECT id,
+       content,
+       min_price,
+       min_price_currency as "min_price_currency: Currency",
+       summary,
+       version,
+       asset_id,
+       update_count,
+       updated_by,
+       created_at,
+       anonymous_buyer_only,
+       updated_at,
+       accepted_currency as "accepted_currency: CurrencyList",
+       resale_allowed,
+       resale_min_holding_days,
+       expires_at
+FROM contract
+WHERE asset_id=$1
+FOR UPDATE"#,
+        asset_id
+    )
+        .fetch_one(&mut **transaction)
+        .await?;
+    let mut contract: Contract = result.try_into()?;
+    contract.auction = find_auction_by_contract_id(&contract.id, &mut **transaction).await?;
+    contract.royalty_splits = find_royalty_splits(&contract.id, Some(contract.update_count), &mut **transaction)
+        .await?
+        .remove(&contract.update_count)
+        .unwrap_or_default();
+    Ok(contract)
+}
+
+/// Replaces `previous` with `updated` and records `previous` in the contract history.
+/// Returns false if the contract was changed since `previous` was read.
+#[tracing::instrument(skip(transaction, previous, updated))]
+pub async fn update_contract(transaction: &mut UnitOfWork<'_>,
+                             previous: &Contract,
+                             updated: &Contract) -> Result<bool, DatabaseError> {
+    info!("updating contract :: contractId={} :: updateCount={}", updated.id, updated.update_count);
+    let previous = DbContract::from(previous.clone());
+    sqlx::query!(
+        r#"
+INSERT INTO contract_history (contract_id,
+                              asset_id,
+                              update_count,
+                              content,
+                              summary,
+                              version,
+                              min_price,
+                              min_price_currency,
+                              updated_by,
+                              accepted_currency,
+                              anonymous_buyer_only,
+                              created_at,
+                              updated_at,
+                              resale_allowed,
+                              resale_min_holding_days,
+                              expires_at)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)"#,
+        previous.id,
+        previous.asset_id,
+        previous.update_count,
+        previous.content,
+        previous.summary,
+        previous.version,
+        previous.min_price,
+        &previous.min_price_currency as &Currency,
+        previous.updated_by,
+        &previous.accepted_currency as &CurrencyList,
+        previous.anonymous_buyer_only,
+        previous.created_at,
+        previous.updated_at,
+        previous.resale_allowed,
+        previous.resale_min_holding_days,
+        previous.expires_at,
+    )
+        .execute(&mut **transaction)
+        .await?;
+
+    let royalty_splits = updated.royalty_splits.clone();
+    let updated = DbContract::from(updated.clone());
+    let result = sqlx::query!(
+        r#"
+UPDATE contract
+SET content                 = $1,
+    summary                 = $2,
+    min_price               = $3,
+    updated_by              = $4,
+    updated_at              = $5,
+    update_count            = $6,
+    accepted_currency       = $7,
+    anonymous_buyer_only    = $8,
+    version                 = $9,
+    resale_allowed          = $10,
+    resale_min_holding_days = $11,
+    expires_at              = $12,
+    min_price_currency      = $13
+WHERE id = $14 AND update_count = $15"#,
+        updated.content,
+        updated.summary,
+        updated.min_price,
+        updated.updated_by,
+        updated.updated_at,
+        updated.update_count,
+        &updated.accepted_currency as &CurrencyList,
+        updated.anonymous_buyer_only,
+        updated.version,
+        updated.resale_allowed,
+        updated.resale_min_holding_days,
+        updated.expires_at,
+        &updated.min_price_currency as &Currency,
+        updated.id,
+        previous.update_count,
+    )
+        .execute(&mut **transaction)
+        .await?;
+    if result.rows_affected() != 1 {
+        return Ok(false);
+    }
+    create_royalty_splits(&updated.id, updated.update_count, &royalty_splits, transaction).await?;
+    Ok(true)
+}
+
+/// Previous revisions of the asset's contract, newest first. The current revision is not included.
+#[tracing::instrument(skip(pg_pool, limit, offset))]
+pub async fn find_contract_history(asset_id: &str,
+                                   limit: i64,
+                                   offset: i64,
+                                   pg_pool: &PgPool) -> Result<Vec<Contract>, DatabaseError> {
+    info!("getting contract history :: assetId={}", asset_id);
+    let result = sqlx::query_as!(
+        DbContractResponse,
+        r#"
+SELECT contract_id as id,
+       content,
+       min_price,
+       min_price_currency as "min_price_currency: Currency",
+       summary,
+       version,
+       asset_id,
+       update_count,
+       updated_by,
+       created_at,
+       anonymous_buyer_only,
+       updated_at,
+       accepted_currency as "accepted_currency: CurrencyList",
+       resale_allowed,
+       resale_min_holding_days,
+       expires_at
+FROM contract_history
+WHERE asset_id = $1
+ORDER BY update_count DESC
+LIMIT $2 OFFSET $3"#,
+        asset_id,
+        limit,
+        offset
+    )
+        .fetch_all(pg_pool)
+        .await?;
+    let Some(contract_id) = result.first().map(|c| c.id.clone()) else {
+        return Ok(vec![]);
+    };
+
+    let mut royalty_splits = find_royalty_splits(&contract_id, None, pg_pool).await?;
+    let mut contracts = Vec::with_capacity(result.len());
+    for db_contract in result {
+        let mut contract = Contract::try_from(db_contract)?;
+        contract.royalty_splits = royalty_splits.remove(&contract.update_count).unwrap_or_default();
+        contracts.push(contract);
+    }
+    Ok(contracts)
+}
+
+/// The asset's contract as it was after `update_count` updates, the current contract has the highest update count
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_contract_at_version(asset_id: &str, update_count: i32, pg_pool: &PgPool) -> Result<Contract, DatabaseError> {
+    let contract = find_contract_by_asset_id(asset_id, pg_pool).await?;
+    if contract.update_count == update_count {
+        return Ok(contract);
+    }
+    let result = sqlx::query_as!(
+        DbContractResponse,
+        r#"
+SELECT contract_id as id,
+       content,
+       min_price,
+       min_price_currency as "min_price_currency: Currency",
+       summary,
+       version,
+       asset_id,
+       update_count,
+       updated_by,
+       created_at,
+       anonymous_buyer_only,
+       updated_at,
+       accepted_currency as "accepted_currency: CurrencyList",
+       resale_allowed,
+       resale_min_holding_days,
+       expires_at
+FROM contract_history
+WHERE contract_id = $1 AND update_count = $2"#,
+        contract.id,
+        update_count
+    )
+        .fetch_one(pg_pool)
+        .await?;
+    let mut revision = Contract::try_from(result)?;
+    revision.royalty_splits = find_royalty_splits(&revision.id, Some(update_count), pg_pool)
+        .await?
+        .remove(&update_count)
+        .unwrap_or_default();
+    Ok(revision)
+}
+
+/// Asset ids of the contracts still written in the given version, used to upgrade contracts in batches
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_contract_asset_ids_by_version(version: &ContractVersion,
+                                                limit: i64,
+                                                pg_pool: &PgPool) -> Result<Vec<String>, DatabaseError> {
+    // the version is matched case-insensitively, rows written before versions were parsed strictly may be in upper case
+    let result = sqlx::query!(
+        r#"
+SELECT asset_id
+FROM contract
+WHERE lower(version) = $1
+ORDER BY created_at
+LIMIT $2"#,
+        version.to_string(),
+        limit
+    )
+        .fetch_all(pg_pool)
+        .await?;
+    Ok(result.into_iter().map(|r| r.asset_id).collect())
+}
+
+async fn create_royalty_splits(contract_id: &str,
+                               update_count: i32,
+                               royalty_splits: &[RoyaltySplit],
+                               transaction: &mut UnitOfWork<'_>) -> Result<(), DatabaseError> {
+    for split in royalty_splits {
+        sqlx::query!(
+            r#"
+INSERT INTO contract_royalty_split (contract_id, update_count, receiver_fp, basis_points)
+VALUES ($1, $2, $3, $4)"#,
+            contract_id,
+            update_count,
+            split.receiver_fp,
+            split.basis_points as i32,
+        )
+            .execute(&mut **transaction)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Royalty splits of the contract grouped by revision, all revisions when `update_count` is not set
+async fn find_royalty_splits<'a, E>(contract_id: &str,
+                                    update_count: Option<i32>,
+                                    pg_pool: E) -> Result<HashMap<i32, Vec<RoyaltySplit>>, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    let result = sqlx::query!(
+        r#"
+SELECT update_count, receiver_fp, basis_points
+FROM contract_royalty_split
+WHERE contract_id = $1 AND ($2::INTEGER IS NULL OR update_count = $2)
+ORDER BY update_count, basis_points DESC, receiver_fp"#,
+        contract_id,
+        update_count
+    )
+        .fetch_all(pg_pool)
+        .await?;
+
+    let mut royalty_splits: HashMap<i32, Vec<RoyaltySplit>> = HashMap::new();
+    for row in result {
+        royalty_splits.entry(row.update_count).or_default().push(RoyaltySplit {
+            receiver_fp: row.receiver_fp,
+            basis_points: row.basis_points as u32,
+        });
+    }
+    Ok(royalty_splits)
 }
@@ -0,0 +1,79 @@
+use crate::core::{Currency, CurrencyInfo, CurrencyRegistry, DatabaseError};
+use sqlx::{Executor, Postgres};
+use tracing::info;
+
+#[derive(Debug)]
+struct DbCurrency {
+    pub code: Currency,
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub decimals: i32,
+    pub crypto: bool,
+    pub enabled: bool,
+}
+
+impl TryFrom<DbCurrency> for CurrencyInfo {
+    type Error = DatabaseError;
+
+    fn try_from(db_currency: DbCurrency) -> Result<Self, Self::Error> {
+        let decimals = u32::try_from(db_currency.decimals)
+            .map_err(|_| DatabaseError::Decode(format!("invalid decimals for {} :: {}", db_currency.code, db_currency.decimals)))?;
+        Ok(CurrencyInfo {
+            code: db_currency.code,
+            name: db_currency.name,
+            aliases: db_currency.aliases,
+            decimals,
+            crypto: db_currency.crypto,
+            enabled: db_currency.enabled,
+        })
+    }
+}
+
+/// Loads the registry from the `currency` table, it is read on every call so that changes apply without a restart
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_currency_registry<'a, E>(pg_pool: E) -> Result<CurrencyRegistry, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    info!("finding currency registry");
+    let currencies = sqlx::query_as!(
+        DbCurrency,
+        r#"
+SELECT code as "code: Currency",
+       name,
+       aliases,
+       decimals,
+       crypto,
+       enabled
+FROM currency
+"#,
+    )
+        .fetch_all(pg_pool)
+        .await?
+        .into_iter()
+        .map(CurrencyInfo::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    CurrencyRegistry::new(currencies).map_err(|e| DatabaseError::Decode(e.to_string()))
+}
+
+/// Enables or disables a currency, returns false if the currency is not in the registry
+#[tracing::instrument(skip(pg_pool))]
+pub async fn set_currency_enabled<'a, E>(currency: &Currency, enabled: bool, pg_pool: E) -> Result<bool, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    info!("setting currency enabled :: currency={} :: enabled={}", currency, enabled);
+    let result = sqlx::query!(
+        r#"
+UPDATE currency
+SET enabled    = $1,
+    updated_at = now()
+WHERE code = $2
+"#,
+        enabled,
+        currency as &Currency,
+    )
+        .execute(pg_pool)
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
@@ -0,0 +1,241 @@
+use crate::core::queries::{decode_money, UnitOfWork};
+use crate::core::{Currency, DatabaseError, EscrowHold, EscrowStatus, Money};
+use chrono::{DateTime, Utc};
+use sqlx::types::BigDecimal;
+use sqlx::{Executor, PgPool, Postgres};
+use tracing::info;
+
+#[derive(Debug)]
+struct DbEscrowHold {
+    pub id: String,
+    pub bid_id: String,
+    pub asset_id: String,
+    pub nfc_id: String,
+    pub buyer_fp: String,
+    pub buyer_org: String,
+    pub seller_fp: String,
+    pub amount: BigDecimal,
+    pub currency: Currency,
+    pub status: EscrowStatus,
+    pub deadline: DateTime<Utc>,
+    pub funded_at: Option<DateTime<Utc>>,
+    pub settled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<DbEscrowHold> for EscrowHold {
+    type Error = DatabaseError;
+
+    fn try_from(db_hold: DbEscrowHold) -> Result<Self, Self::Error> {
+        Ok(EscrowHold {
+            amount: decode_money(&db_hold.amount, db_hold.currency)?,
+            id: db_hold.id,
+            bid_id: db_hold.bid_id,
+            asset_id: db_hold.asset_id,
+            nfc_id: db_hold.nfc_id,
+            buyer_fp: db_hold.buyer_fp,
+            buyer_org: db_hold.buyer_org,
+            seller_fp: db_hold.seller_fp,
+            status: db_hold.status,
+            deadline: db_hold.deadline,
+            funded_at: db_hold.funded_at,
+            settled_at: db_hold.settled_at,
+            created_at: db_hold.created_at,
+            updated_at: db_hold.updated_at,
+        })
+    }
+}
+
+#[tracing::instrument(skip(transaction, hold))]
+pub async fn create_escrow_hold(transaction: &mut UnitOfWork<'_>, hold: &EscrowHold) -> Result<bool, DatabaseError> {
+    info!("creating escrow hold :: {}", hold);
+    let result = sqlx::query!(
+        r#"
+INSERT INTO escrow (
+                    id,
+                    bid_id,
+                    asset_id,
+                    nfc_id,
+                    buyer_fp,
+                    buyer_org,
+                    seller_fp,
+                    amount,
+                    currency,
+                    status,
+                    deadline,
+                    created_at,
+                    updated_at
+        )
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+"#,
+        hold.id,
+        hold.bid_id,
+        hold.asset_id,
+        hold.nfc_id,
+        hold.buyer_fp,
+        hold.buyer_org,
+        hold.seller_fp,
+        &hold.amount as &Money,
+        hold.amount.currency() as &Currency,
+        hold.status as EscrowStatus,
+        hold.deadline,
+        hold.created_at,
+        hold.updated_at,
+    )
+        .execute(&mut **transaction)
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_escrow_hold_by_id<'a, E>(hold_id: &str, pg_pool: E) -> Result<EscrowHold, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    info!("getting escrow hold :: holdId={}", hold_id);
+    let hold = sqlx::query_as!(
+        DbEscrowHold,
+        r#"
+SELECT id,
+       bid_id,
+       asset_id,
+       nfc_id,
+       buyer_fp,
+       buyer_org,
+       seller_fp,
+       amount,
+       currency as "currency: Currency",
+       status as "status: EscrowStatus",
+       deadline,
+       funded_at,
+       settled_at,
+       created_at,
+       updated_at
+FROM escrow
+WHERE id = $1"#,
+        hold_id
+    )
+        .fetch_one(pg_pool)
+        .await?;
+    hold.try_into()
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_escrow_hold_by_bid_id<'a, E>(bid_id: &str, pg_pool: E) -> Result<EscrowHold, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    info!("getting escrow hold by bid :: bidId={}", bid_id);
+    let hold = sqlx::query_as!(
+        DbEscrowHold,
+        r#"
+SELECT id,
+       bid_id,
+       asset_id,
+       nfc_id,
+       buyer_fp,
+       buyer_org,
+       seller_fp,
+       amount,
+       currency as "currency: Currency",
+       status as "status: EscrowStatus",
+       deadline,
+       funded_at,
+       settled_at,
+       created_at,
+       updated_at
+FROM escrow
+WHERE bid_id = $1"#,
+        bid_id
+    )
+        .fetch_one(pg_pool)
+        .await?;
+    hold.try_into()
+}
+
+/// Finds the pending or funded hold that reserves the asset, there is at most one
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_active_escrow_hold_for_asset<'a, E>(asset_id: &str, pg_pool: E) -> Result<Option<EscrowHold>, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    info!("getting active escrow hold :: assetId={}", asset_id);
+    let hold = sqlx::query_as!(
+        DbEscrowHold,
+        r#"
+SELECT id,
+       bid_id,
+       asset_id,
+       nfc_id,
+       buyer_fp,
+       buyer_org,
+       seller_fp,
+       amount,
+       currency as "currency: Currency",
+       status as "status: EscrowStatus",
+       deadline,
+       funded_at,
+       settled_at,
+       created_at,
+       updated_at
+FROM escrow
+WHERE asset_id = $1 AND status IN ('pending', 'funded')"#,
+        asset_id
+    )
+        .fetch_optional(pg_pool)
+        .await?;
+    hold.map(EscrowHold::try_from).transpose()
+}
+
+/// Moves a hold from `from_status` to `to_status`, stamping when it was funded or settled.
+/// Returns `false` if the hold is not in `from_status`
+#[tracing::instrument(skip(pg_pool))]
+pub async fn update_escrow_hold_status<'a, E>(hold_id: &str,
+                                              from_status: EscrowStatus,
+                                              to_status: EscrowStatus,
+                                              pg_pool: E) -> Result<bool, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    info!("updating escrow hold status :: holdId={} :: {} -> {}", hold_id, from_status, to_status);
+    let now = Utc::now();
+    let funded_at = (to_status == EscrowStatus::Funded).then_some(now);
+    let settled_at = (!to_status.is_active()).then_some(now);
+    let result = sqlx::query!(
+        r#"
+UPDATE escrow
+SET status     = $1,
+    funded_at  = COALESCE($2, funded_at),
+    settled_at = COALESCE($3, settled_at),
+    updated_at = $4
+WHERE id = $5 AND status = $6"#,
+        to_status as EscrowStatus,
+        funded_at,
+        settled_at,
+        now,
+        hold_id,
+        from_status as EscrowStatus,
+    )
+        .execute(pg_pool)
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+/// Ids of the pending or funded holds whose deadline passed, oldest deadline first
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_expired_escrow_hold_ids(now: DateTime<Utc>, limit: i64, pg_pool: &PgPool) -> Result<Vec<String>, DatabaseError> {
+    let result = sqlx::query!(
+        r#"
+SELECT id
+FROM escrow
+WHERE status IN ('pending', 'funded') AND deadline <= $1
+ORDER BY deadline
+LIMIT $2"#,
+        now,
+        limit
+    )
+        .fetch_all(pg_pool)
+        .await?;
+    Ok(result.into_iter().map(|r| r.id).collect())
+}
@@ -0,0 +1,74 @@
+use crate::core::queries::UnitOfWork;
+use crate::core::{Currency, DatabaseError, FxRate};
+use chrono::{DateTime, Utc};
+use sqlx::types::BigDecimal;
+use sqlx::{Executor, Postgres};
+use tracing::info;
+
+#[derive(Debug)]
+struct DbFxRate {
+    pub base: Currency,
+    pub quote: Currency,
+    pub rate: BigDecimal,
+    pub as_of: DateTime<Utc>,
+}
+
+impl From<DbFxRate> for FxRate {
+    fn from(db_rate: DbFxRate) -> Self {
+        FxRate {
+            base: db_rate.base,
+            quote: db_rate.quote,
+            rate: db_rate.rate,
+            as_of: db_rate.as_of,
+        }
+    }
+}
+
+/// Saves a rate, a rate of the same pair at the same time is replaced
+#[tracing::instrument(skip(transaction))]
+pub async fn upsert_fx_rate(transaction: &mut UnitOfWork<'_>, rate: &FxRate) -> Result<bool, DatabaseError> {
+    info!("upserting fx rate :: rate={}", rate);
+    let result = sqlx::query!(
+        r#"
+INSERT INTO fx_rate (base, quote, rate, as_of)
+VALUES ($1, $2, $3, $4)
+ON CONFLICT (base, quote, as_of) DO UPDATE SET rate       = EXCLUDED.rate,
+                                               created_at = now()
+"#,
+        &rate.base as &Currency,
+        &rate.quote as &Currency,
+        rate.rate,
+        rate.as_of,
+    )
+        .execute(&mut **transaction)
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+/// Latest rate between the two currencies in either direction, the returned rate may go from `to` to `from`
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_latest_fx_rate<'a, E>(from: &Currency, to: &Currency, pg_pool: E) -> Result<Option<FxRate>, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    info!("finding latest fx rate :: from={} :: to={}", from, to);
+    let rate = sqlx::query_as!(
+        DbFxRate,
+        r#"
+SELECT base as "base: Currency",
+       quote as "quote: Currency",
+       rate,
+       as_of
+FROM fx_rate
+WHERE (base = $1 AND quote = $2)
+   OR (base = $2 AND quote = $1)
+ORDER BY as_of DESC
+LIMIT 1
+"#,
+        from as &Currency,
+        to as &Currency,
+    )
+        .fetch_optional(pg_pool)
+        .await?;
+    Ok(rate.map(FxRate::from))
+}
@@ -0,0 +1,181 @@
+use crate::core::queries::{decode_money, UnitOfWork};
+use crate::core::{Currency, DatabaseError, JournalEntry, LedgerAccount, Money, Posting, PostingKind};
+use chrono::{DateTime, Utc};
+use sqlx::types::BigDecimal;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use tracing::info;
+
+#[derive(Debug)]
+struct DbJournalEntry {
+    pub id: String,
+    pub reference_id: String,
+    pub description: String,
+    pub currency: Currency,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+struct DbPosting {
+    pub entry_id: String,
+    pub amount: BigDecimal,
+    pub currency: Currency,
+    pub kind: PostingKind,
+    pub owner_fp: String,
+}
+
+impl TryFrom<DbPosting> for Posting {
+    type Error = DatabaseError;
+
+    fn try_from(db_posting: DbPosting) -> Result<Self, Self::Error> {
+        Ok(Posting {
+            kind: db_posting.kind,
+            amount: decode_money(&db_posting.amount, db_posting.currency)?,
+            account_owner_fp: db_posting.owner_fp,
+        })
+    }
+}
+
+/// Returns the account of the user in the given currency, creating it if it does not exist yet
+#[tracing::instrument(skip(transaction))]
+pub async fn find_or_create_ledger_account(owner_fp: &str,
+                                           currency: &Currency,
+                                           transaction: &mut UnitOfWork<'_>) -> Result<LedgerAccount, DatabaseError> {
+    let account = LedgerAccount::new(owner_fp.to_string(), currency.clone());
+    // DO UPDATE (instead of DO NOTHING) so that RETURNING yields the existing row on conflict
+    let account = sqlx::query_as!(
+        LedgerAccount,
+        r#"
+INSERT INTO ledger_account (id, owner_fp, currency, created_at)
+VALUES ($1, $2, $3, $4)
+ON CONFLICT (owner_fp, currency) DO UPDATE SET owner_fp = EXCLUDED.owner_fp
+RETURNING id, owner_fp, currency as "currency: Currency", created_at"#,
+        account.id,
+        account.owner_fp,
+        &account.currency as &Currency,
+        account.created_at,
+    )
+        .fetch_one(&mut **transaction)
+        .await?;
+    Ok(account)
+}
+
+/// Writes a journal entry and its postings. The database refuses to commit entries that do not sum to zero.
+#[tracing::instrument(skip(transaction, entry))]
+pub async fn create_journal_entry(transaction: &mut UnitOfWork<'_>, entry: &JournalEntry) -> Result<bool, DatabaseError> {
+    info!("creating journal entry :: entryId={} :: referenceId={}", entry.id, entry.reference_id);
+    if !entry.is_balanced() {
+        return Err(DatabaseError::InvalidArgument(format!("journal entry {} is not balanced", entry.id)));
+    }
+    let result = sqlx::query!(
+        r#"
+INSERT INTO journal_entry (id, reference_id, description, currency, created_at)
+VALUES ($1, $2, $3, $4, $5)"#,
+        entry.id,
+        entry.reference_id,
+        entry.description,
+        &entry.currency as &Currency,
+        entry.created_at,
+    )
+        .execute(&mut **transaction)
+        .await?;
+    if result.rows_affected() != 1 {
+        return Ok(false);
+    }
+
+    for posting in &entry.postings {
+        let account = find_or_create_ledger_account(&posting.account_owner_fp, &entry.currency, transaction).await?;
+        let result = sqlx::query!(
+            r#"
+INSERT INTO journal_posting (entry_id, account_id, amount, kind)
+VALUES ($1, $2, $3, $4)"#,
+            entry.id,
+            account.id,
+            &posting.amount as &Money,
+            posting.kind as PostingKind,
+        )
+            .execute(&mut **transaction)
+            .await?;
+        if result.rows_affected() != 1 {
+            return Err(DatabaseError::TransactionStepError("failed to create journal posting".to_string()));
+        }
+    }
+    Ok(true)
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn get_account_balance(owner_fp: &str, currency: &Currency, pg_pool: &PgPool) -> Result<Money, DatabaseError> {
+    let result = sqlx::query!(
+        r#"
+SELECT ROUND(COALESCE(SUM(p.amount), 0), (SELECT decimals FROM currency WHERE code = $2)) AS "balance!"
+FROM journal_posting p
+         JOIN ledger_account a ON a.id = p.account_id
+WHERE a.owner_fp = $1 AND a.currency = $2"#,
+        owner_fp,
+        currency as &Currency,
+    )
+        .fetch_one(pg_pool)
+        .await?;
+    decode_money(&result.balance, currency.clone())
+}
+
+/// Journal entries with at least one posting on the user's accounts, newest first.
+/// Every entry is returned with all of its postings, not only the ones of the user.
+#[tracing::instrument(skip(pg_pool, limit, offset))]
+pub async fn find_journal_entries_by_owner(owner_fp: &str,
+                                           currency: Option<Currency>,
+                                           limit: i64,
+                                           offset: i64,
+                                           pg_pool: &PgPool) -> Result<Vec<JournalEntry>, DatabaseError> {
+    info!("getting journal entries :: currency={:?}", currency);
+    let db_entries = sqlx::query_as!(
+        DbJournalEntry,
+        r#"
+SELECT e.id, e.reference_id, e.description, e.currency as "currency: Currency", e.created_at
+FROM journal_entry e
+WHERE EXISTS (SELECT 1
+              FROM journal_posting p
+                       JOIN ledger_account a ON a.id = p.account_id
+              WHERE p.entry_id = e.id AND a.owner_fp = $1)
+  AND ($2::currency_enum IS NULL OR e.currency = $2)
+ORDER BY e.created_at DESC, e.id
+LIMIT $3 OFFSET $4"#,
+        owner_fp,
+        currency as Option<Currency>,
+        limit,
+        offset,
+    )
+        .fetch_all(pg_pool)
+        .await?;
+
+    let entry_ids: Vec<String> = db_entries.iter().map(|e| e.id.clone()).collect();
+    let db_postings = sqlx::query_as!(
+        DbPosting,
+        r#"
+SELECT p.entry_id, p.amount, a.currency as "currency: Currency", p.kind as "kind: PostingKind", a.owner_fp
+FROM journal_posting p
+         JOIN ledger_account a ON a.id = p.account_id
+WHERE p.entry_id = ANY($1)
+ORDER BY p.id"#,
+        &entry_ids,
+    )
+        .fetch_all(pg_pool)
+        .await?;
+
+    let mut postings_by_entry: HashMap<String, Vec<Posting>> = HashMap::new();
+    for db_posting in db_postings {
+        postings_by_entry.entry(db_posting.entry_id.clone()).or_default().push(db_posting.try_into()?);
+    }
+
+    let entries = db_entries.into_iter()
+        .map(|e| JournalEntry {
+            postings: postings_by_entry.remove(&e.id).unwrap_or_default(),
+            id: e.id,
+            currency: e.currency,
+            created_at: e.created_at,
+            description: e.description,
+            reference_id: e.reference_id,
+        })
+        .collect();
+    Ok(entries)
+}
@@ -0,0 +1,42 @@
+use crate::core::DatabaseError;
+use sqlx::migrate::Migrator;
+use sqlx::PgPool;
+use std::collections::HashSet;
+use tracing::info;
+
+/// Migrations of the `migrations` directory, embedded in the binary at compile time
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Applies the embedded migrations that were not applied yet, in version order
+#[tracing::instrument(skip(pg_pool))]
+pub async fn run_migrations(pg_pool: &PgPool) -> Result<(), DatabaseError> {
+    info!("applying database migrations :: latest={:?}", MIGRATOR.iter().map(|m| m.version).max());
+    MIGRATOR.run(pg_pool)
+        .await
+        .map_err(|e| DatabaseError::Migration(e.to_string()))
+}
+
+/// Versions of the embedded migrations the database is missing, oldest first.
+/// Migrations that failed part way are missing as well, a database that was never migrated is missing all of them.
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_pending_migrations(pg_pool: &PgPool) -> Result<Vec<i64>, DatabaseError> {
+    // the migrations table is created by the first migration run, it is not part of the schema checked at compile time
+    let migrated: bool = sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+        .fetch_one(pg_pool)
+        .await?;
+    let applied: HashSet<i64> = if migrated {
+        sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success")
+            .fetch_all(pg_pool)
+            .await?
+            .into_iter()
+            .collect()
+    } else {
+        HashSet::new()
+    };
+    let mut pending: Vec<i64> = MIGRATOR.iter()
+        .filter(|migration| migration.migration_type.is_up_migration() && !applied.contains(&migration.version))
+        .map(|migration| migration.version)
+        .collect();
+    pending.sort();
+    Ok(pending)
+}
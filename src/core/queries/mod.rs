@@ -1,18 +1,76 @@
+mod anchor;
 mod asset;
+mod attribute;
+mod auction;
+mod bid;
+mod collection;
 mod contract;
+mod currency;
+mod escrow;
+mod fx;
+mod ledger;
+mod migration;
 mod nfc;
 mod ordering;
+mod outbox;
+mod sale;
+mod unit_of_work;
 
+pub use anchor::{
+    create_nfc_anchor, find_last_nfc_anchor, find_nfc_anchor_by_nfc_id, find_nfc_anchor_leaves, find_unanchored_nfcs, lock_nfc_anchoring,
+};
 pub use asset::{
-    create_new_asset, delete_asset_by_id, find_asset_by_id, find_asset_by_id_and_org_id, find_assets_by_owner, find_assets_name_like,
-    find_assets_symbol_like, get_all_assets, transfer_asset_query, update_asset,
+    count_assets, create_new_asset, delete_asset_by_id, find_asset_by_id, find_asset_by_id_and_org_id, find_asset_by_id_for_update,
+    get_all_assets, list_assets, search_assets, transfer_asset_query, update_asset, MAX_PAGE_SIZE,
+};
+pub use attribute::{
+    count_asset_attributes, delete_asset_attributes, find_asset_metadata, replace_asset_tags, upsert_asset_attributes,
+};
+pub use auction::{close_contract_auction, create_contract_auction, find_auction_by_contract_id, find_due_auction_asset_ids};
+pub use bid::{
+    count_bids_by_asset_id, count_bids_by_bidder, count_open_bids_for_asset, create_bid, delete_bid_by_id, find_bid_by_id, find_bids_by_asset_id, find_bids_by_bidder, find_highest_open_bid,
+    find_open_bids_for_asset, reject_open_bids_for_asset, update_bid_status,
+};
+pub use collection::{
+    add_asset_to_collection, count_collection_assets, create_collection, delete_collection, find_collection_by_id,
+    find_collection_for_update, update_collection,
+};
+pub use contract::{
+    create_contract, create_contract_in_transaction, find_contract_asset_ids_by_version, find_contract_at_version, find_contract_by_asset_id,
+    find_contract_by_asset_id_for_update, find_contract_history,
+    update_contract,
 };
-pub use contract::{create_contract, find_contract_by_asset_id};
-pub use nfc::{create_nfc, create_nfc_trail, get_nfc_by_asset_id, get_nfc_by_id, get_nfc_trails_by_nfc_id};
-pub use ordering::OrderType;
+pub use currency::{find_currency_registry, set_currency_enabled};
+pub use escrow::{
+    create_escrow_hold, find_active_escrow_hold_for_asset, find_escrow_hold_by_bid_id, find_escrow_hold_by_id, find_expired_escrow_hold_ids,
+    update_escrow_hold_status,
+};
+pub use fx::{find_latest_fx_rate, upsert_fx_rate};
+pub use ledger::{
+    create_journal_entry, find_journal_entries_by_owner, find_or_create_ledger_account, get_account_balance,
+};
+pub use migration::{find_pending_migrations, run_migrations, MIGRATOR};
+pub use nfc::{
+    create_nfc, create_nfc_trail, find_asset_provenance, find_trail_asset_ids, get_nfc_by_asset_id, get_nfc_by_id,
+    get_nfc_trails_by_asset_id, get_nfc_trails_by_nfc_id, update_nfc_cert,
+};
+pub use ordering::{AssetSort, OrderType};
+pub use outbox::{
+    claim_pending_outbox_events, create_outbox_event, find_last_outbox_sequence, find_outbox_events_after, find_outbox_events_by_asset_id,
+    has_outbox_events_held_back, mark_outbox_events_dispatched, release_outbox_events, OUTBOX_CHANNEL,
+};
+pub use sale::{create_sale, find_last_sale_time_by_asset_id, find_sale_by_bid_id, find_sales_by_asset_id};
+pub use unit_of_work::UnitOfWork;
+use crate::core::{Currency, DatabaseError, Money};
+use sqlx::types::BigDecimal;
 use sqlx::{Postgres, Transaction};
 
 // PgTransaction type alias for Transaction <'a, Postgres> represents a database transaction.
 // It's a "handle" to a series of operations that must happen (either all succeed or are rolled back).
 // The 'a is a lifetime param, ensuring the transaction doesn't outlive the db connection it's tied to.
 pub type PgTransaction<'a> = Transaction<'a, Postgres>;
+
+/// Amounts are stored as NUMERIC, with the decimals of the currency they are in, next to that currency
+fn decode_money(amount: &BigDecimal, currency: Currency) -> Result<Money, DatabaseError> {
+    Money::from_scaled(amount, currency).map_err(|e| DatabaseError::Decode(e.to_string()))
+}
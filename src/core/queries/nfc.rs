@@ -1,5 +1,7 @@
-use crate::core::queries::PgTransaction;
-use crate::core::{DatabaseError, NFCTrail, NFC};
+use crate::core::queries::{decode_money, UnitOfWork};
+use crate::core::{Currency, DatabaseError, NFCTrail, ProvenanceEntry, TransferReason, NFC};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::types::BigDecimal;
 use sqlx::{Executor, PgPool, Postgres};
 use tracing::{debug, info};
 
@@ -19,21 +21,21 @@ pub async fn get_nfc_by_id(nfc_id: &str, pool: &PgPool) -> Result<NFC, DatabaseE
     Ok(row)
 }
 
-#[tracing::instrument(skip(transaction, nf_cert, user_fp))]
+#[tracing::instrument(skip(transaction, nf_cert, user_fp, organization))]
 pub async fn create_nfc(
-    mut transaction: PgTransaction<'_>,
+    transaction: &mut UnitOfWork<'_>,
     nf_cert: NFC,
     user_fp: String,
+    organization: String,
 ) -> Result<bool, DatabaseError> {
     info!("Creating nfc :: id={}", &nf_cert.id);
-    let nfc = get_nfc_by_asset_id(&nf_cert.asset_id, &mut *transaction)
+    let nfc = get_nfc_by_asset_id(&nf_cert.asset_id, &mut **transaction)
         .await
         .map_err(|e| match e {
             DatabaseError::NotFound => debug!("Can not find nfc by id"),
             _ => debug!("ignoring error: {:?}", e),
         });
     if nfc.is_ok() {
-        transaction.rollback().await?;
         return Err(DatabaseError::TransactionStepError("Asset already has an nfc. rolling back".to_string()));
     }
 
@@ -47,25 +49,17 @@ pub async fn create_nfc(
         nf_cert.asset_id,
         nf_cert.created_at,
     )
-    .execute(&mut *transaction)
+    .execute(&mut **transaction)
     .await?;
 
     let nfc_created = result.rows_affected() == 1;
     if !nfc_created {
-        // No need to rollback here, as nothing has been committed yet.
         return Ok(false);
     }
 
     // create a new nfc trail history if nfc is created
-    let trail = NFCTrail::new(nf_cert.id, user_fp, nf_cert.asset_id);
-    let nfc_trail_created = create_nfc_trail(&mut transaction, &trail).await?;
-    if !nfc_trail_created {
-        transaction.rollback().await?;
-        return Ok(false);
-    }
-
-    transaction.commit().await?;
-    Ok(true) // Return true; we know both operations succeeded at this point.
+    let mut trail = NFCTrail::mint(&nf_cert, user_fp, organization);
+    create_nfc_trail(transaction, &mut trail).await
 }
 
 #[tracing::instrument(skip(pg_pool, asset_id))]
@@ -89,25 +83,88 @@ where
     Ok(row)
 }
 
+/// Replaces the certificate of an NFC, when the asset changes owner or the certificate has to be signed again
+#[tracing::instrument(skip(pg_pool, cert))]
+pub async fn update_nfc_cert<'a, E>(nfc_id: &str, cert: &str, pg_pool: E) -> Result<bool, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    info!("Updating nfc certificate :: nfc_id={}", nfc_id);
+    let result = sqlx::query!(
+        r#"
+        UPDATE nfc SET cert = $1 WHERE id = $2
+        "#,
+        cert,
+        nfc_id,
+    )
+        .execute(pg_pool)
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+/// Appends the trail to the hash chain of its NFC, setting its previous hash and its hash.
+/// Concurrent appends to the same chain are rejected by the unique index on the previous hash.
 #[tracing::instrument(skip(transaction, trail))]
 pub async fn create_nfc_trail(
-    transaction: &mut PgTransaction<'_>,
-    trail: &NFCTrail,
+    transaction: &mut UnitOfWork<'_>,
+    trail: &mut NFCTrail,
 ) -> Result<bool, DatabaseError> {
     info!(
         "Creating nfc trail :: nfc_id={} :: created_on={}",
         &trail.nfc_id, trail.transferred_on
     );
-    dbg!(&trail);
+    let last = sqlx::query!(
+        r#"
+        SELECT hash, transferred_on
+        FROM nfc_asset_trail
+        WHERE nfc_id = $1
+        ORDER BY transferred_on DESC
+        LIMIT 1
+        "#,
+        trail.nfc_id,
+    )
+    .fetch_optional(&mut **transaction)
+    .await?;
+    let previous_hash = match last {
+        Some(last) => {
+            // the chain is walked in time order, a row can not be dated before the row it follows
+            if trail.transferred_on <= last.transferred_on {
+                trail.transferred_on = last.transferred_on + Duration::microseconds(1);
+            }
+            Some(last.hash)
+        }
+        None => None,
+    };
+    trail.chain_to(previous_hash);
+
     let result = sqlx::query!(
         r#"
-        INSERT INTO nfc_asset_trail (nfc_id, user_fp, asset_id, transferred_on)
-        VALUES ($1, $2, $3, $4)
+        INSERT INTO nfc_asset_trail (
+            nfc_id,
+            user_fp,
+            asset_id,
+            transferred_on,
+            organization,
+            previous_owner_fp,
+            previous_org,
+            reason,
+            sale_id,
+            previous_hash,
+            hash
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
         "#,
         trail.nfc_id,
         trail.user_fp,
         trail.asset_id,
         trail.transferred_on,
+        trail.organization,
+        trail.previous_owner_fp,
+        trail.previous_org,
+        trail.reason as TransferReason,
+        trail.sale_id,
+        trail.previous_hash,
+        trail.hash,
     )
     .execute(&mut **transaction)
     .await?;
@@ -122,10 +179,161 @@ pub async fn get_nfc_trails_by_nfc_id(
     info!("Getting nfc trail history by nfc_id={}", nfc_id);
     let rows = sqlx::query_as!(
         NFCTrail,
-        "SELECT * FROM nfc_asset_trail WHERE nfc_id = $1",
+        r#"
+        SELECT nfc_id,
+               user_fp,
+               asset_id,
+               transferred_on,
+               organization,
+               previous_owner_fp,
+               previous_org,
+               reason as "reason: TransferReason",
+               sale_id,
+               previous_hash,
+               hash
+        FROM nfc_asset_trail
+        WHERE nfc_id = $1
+        ORDER BY transferred_on
+        "#,
         nfc_id
     )
     .fetch_all(pool)
     .await?;
     Ok(rows)
 }
+
+/// Trail of the NFC of an asset, ordered from its mint
+#[tracing::instrument(skip(pg_pool))]
+pub async fn get_nfc_trails_by_asset_id<'a, E>(asset_id: &str, pg_pool: E) -> Result<Vec<NFCTrail>, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    info!("Getting nfc trail history by asset_id={}", asset_id);
+    let rows = sqlx::query_as!(
+        NFCTrail,
+        r#"
+        SELECT nfc_id,
+               user_fp,
+               asset_id,
+               transferred_on,
+               organization,
+               previous_owner_fp,
+               previous_org,
+               reason as "reason: TransferReason",
+               sale_id,
+               previous_hash,
+               hash
+        FROM nfc_asset_trail
+        WHERE asset_id = $1
+        ORDER BY transferred_on
+        "#,
+        asset_id
+    )
+        .fetch_all(pg_pool)
+        .await?;
+    Ok(rows)
+}
+
+/// Ids of the assets with a trail, in id order after `after_asset_id`
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_trail_asset_ids<'a, E>(after_asset_id: &str, limit: i64, pg_pool: E) -> Result<Vec<String>, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    info!("finding trail asset ids :: after={} :: limit={}", after_asset_id, limit);
+    let ids = sqlx::query_scalar!(
+        r#"
+        SELECT DISTINCT asset_id
+        FROM nfc_asset_trail
+        WHERE asset_id > $1
+        ORDER BY asset_id
+        LIMIT $2
+        "#,
+        after_asset_id,
+        limit,
+    )
+        .fetch_all(pg_pool)
+        .await?;
+    Ok(ids)
+}
+
+#[derive(Debug)]
+struct DbProvenanceEntry {
+    pub nfc_id: String,
+    pub user_fp: String,
+    pub asset_id: String,
+    pub transferred_on: DateTime<Utc>,
+    pub organization: Option<String>,
+    pub previous_owner_fp: Option<String>,
+    pub previous_org: Option<String>,
+    pub reason: TransferReason,
+    pub sale_id: Option<String>,
+    pub previous_hash: Option<String>,
+    pub hash: String,
+    pub price: Option<BigDecimal>,
+    pub currency: Option<Currency>,
+}
+
+impl TryFrom<DbProvenanceEntry> for ProvenanceEntry {
+    type Error = DatabaseError;
+
+    fn try_from(db_entry: DbProvenanceEntry) -> Result<Self, Self::Error> {
+        let price = match (db_entry.price, db_entry.currency) {
+            (Some(price), Some(currency)) => Some(decode_money(&price, currency)?),
+            _ => None,
+        };
+        Ok(ProvenanceEntry {
+            price,
+            trail: NFCTrail {
+                nfc_id: db_entry.nfc_id,
+                user_fp: db_entry.user_fp,
+                asset_id: db_entry.asset_id,
+                transferred_on: db_entry.transferred_on,
+                organization: db_entry.organization,
+                previous_owner_fp: db_entry.previous_owner_fp,
+                previous_org: db_entry.previous_org,
+                reason: db_entry.reason,
+                sale_id: db_entry.sale_id,
+                previous_hash: db_entry.previous_hash,
+                hash: db_entry.hash,
+            },
+        })
+    }
+}
+
+/// Ownership chain of an asset from its mint to its current owner, with the price of every sale
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_asset_provenance<'a, E>(asset_id: &str, pg_pool: E) -> Result<Vec<ProvenanceEntry>, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    info!("finding asset provenance :: asset_id={}", asset_id);
+    sqlx::query_as!(
+        DbProvenanceEntry,
+        r#"
+        SELECT trail.nfc_id,
+               trail.user_fp,
+               trail.asset_id,
+               trail.transferred_on,
+               trail.organization,
+               trail.previous_owner_fp,
+               trail.previous_org,
+               trail.reason as "reason: TransferReason",
+               trail.sale_id,
+               trail.previous_hash,
+               trail.hash,
+               sale.price as "price?",
+               sale.currency as "currency?: Currency"
+        FROM nfc_asset_trail trail
+        LEFT JOIN sale ON sale.id = trail.sale_id
+        WHERE trail.asset_id = $1
+        ORDER BY trail.transferred_on
+        "#,
+        asset_id
+    )
+        .fetch_all(pg_pool)
+        .await?
+        .into_iter()
+        .map(ProvenanceEntry::try_from)
+        .collect()
+}
@@ -1,3 +1,4 @@
+use crate::core::AssetSortField;
 use anyhow::anyhow;
 use std::fmt::Display;
 use std::str::FromStr;
@@ -45,6 +46,35 @@ impl From<String> for OrderType {
     }
 }
 
+/// Order of a list of assets, ties on the field are broken by id in the same direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AssetSort {
+    pub field: AssetSortField,
+    pub order: OrderType,
+}
+
+impl AssetSort {
+    pub fn new(field: AssetSortField, order: OrderType) -> Self {
+        Self { field, order }
+    }
+
+    /// Column of the sort field, only ever one of a fixed set of names so it is safe to push into a query
+    pub(crate) fn column(&self) -> &'static str {
+        match self.field {
+            AssetSortField::Name => "name",
+            AssetSortField::Symbol => "symbol",
+            AssetSortField::CreatedAt => "created_at",
+            AssetSortField::UpdatedAt => "updated_at",
+        }
+    }
+}
+
+impl Display for AssetSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.field, self.order)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
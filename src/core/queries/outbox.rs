@@ -0,0 +1,235 @@
+use crate::core::queries::UnitOfWork;
+use crate::core::{DatabaseError, EventFilter, EventType, OutboxEvent};
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres};
+use tracing::{debug, info};
+
+/// Channel of the notifications sent when events are recorded, the payload is the sequence of the event
+pub const OUTBOX_CHANNEL: &str = "outbox_events";
+
+/// Records the event as part of the change it is about, returns the sequence it was given.
+/// The event is stored with the id of the unit of work, subscribers read the events in the order of their transaction.
+#[tracing::instrument(skip(transaction, event))]
+pub async fn create_outbox_event(transaction: &mut UnitOfWork<'_>, event: &OutboxEvent) -> Result<i64, DatabaseError> {
+    debug!("recording outbox event :: {}", event);
+    let sequence = sqlx::query_scalar!(
+        r#"
+INSERT INTO outbox (id, event_type, asset_id, organization, owner_fp, payload, created_at)
+VALUES ($1, $2, $3, $4, $5, $6, $7)
+RETURNING sequence"#,
+        event.id,
+        event.event_type as EventType,
+        event.asset_id,
+        event.organization,
+        event.owner_fp,
+        event.payload,
+        event.created_at,
+    )
+        .fetch_one(&mut **transaction)
+        .await?;
+    Ok(sequence)
+}
+
+/// Claims the oldest events that were neither dispatched nor claimed by another relay, in sequence order.
+/// The events stay claimed until `claimed_until` and are not locked once the claim is committed,
+/// the claim of a relay that stopped ends at `claimed_until` and the events are claimed again after `now` passes it.
+#[tracing::instrument(skip(pg_pool))]
+pub async fn claim_pending_outbox_events<'a, E>(limit: i64,
+                                                now: DateTime<Utc>,
+                                                claimed_until: DateTime<Utc>,
+                                                pg_pool: E) -> Result<Vec<OutboxEvent>, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    let mut events = sqlx::query_as!(
+        OutboxEvent,
+        r#"
+UPDATE outbox
+SET claimed_until = $3
+WHERE sequence IN (SELECT sequence
+                   FROM outbox
+                   WHERE dispatched_at IS NULL
+                     AND (claimed_until IS NULL OR claimed_until <= $2)
+                   ORDER BY sequence
+                   LIMIT $1
+                   FOR UPDATE SKIP LOCKED)
+RETURNING sequence,
+          id,
+          event_type as "event_type: EventType",
+          asset_id,
+          organization,
+          owner_fp,
+          payload,
+          created_at,
+          dispatched_at"#,
+        limit,
+        now,
+        claimed_until
+    )
+        .fetch_all(pg_pool)
+        .await?;
+    // the updated rows are not returned in any order
+    events.sort_by_key(|event| event.sequence);
+    Ok(events)
+}
+
+/// Ends the claim of the events that were not dispatched, returns the number of events that were released
+#[tracing::instrument(skip(pg_pool, sequences))]
+pub async fn release_outbox_events<'a, E>(sequences: &[i64], pg_pool: E) -> Result<u64, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    info!("releasing outbox events :: count={}", sequences.len());
+    let result = sqlx::query!(
+        "UPDATE outbox SET claimed_until = NULL WHERE sequence = ANY($1) AND dispatched_at IS NULL",
+        sequences
+    )
+        .execute(pg_pool)
+        .await?;
+    Ok(result.rows_affected())
+}
+
+/// Returns the number of events that were marked, events that were already dispatched are left as they are
+#[tracing::instrument(skip(pg_pool, sequences))]
+pub async fn mark_outbox_events_dispatched<'a, E>(sequences: &[i64],
+                                                  dispatched_at: DateTime<Utc>,
+                                                  pg_pool: E) -> Result<u64, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    info!("marking outbox events dispatched :: count={}", sequences.len());
+    let result = sqlx::query!(
+        "UPDATE outbox SET dispatched_at = $1 WHERE sequence = ANY($2) AND dispatched_at IS NULL",
+        dispatched_at,
+        sequences
+    )
+        .execute(pg_pool)
+        .await?;
+    Ok(result.rows_affected())
+}
+
+/// Events of the asset, dispatched or not, in sequence order
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_outbox_events_by_asset_id<'a, E>(asset_id: &str, pg_pool: E) -> Result<Vec<OutboxEvent>, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    let events = sqlx::query_as!(
+        OutboxEvent,
+        r#"
+SELECT sequence,
+       id,
+       event_type as "event_type: EventType",
+       asset_id,
+       organization,
+       owner_fp,
+       payload,
+       created_at,
+       dispatched_at
+FROM outbox
+WHERE asset_id = $1
+ORDER BY sequence"#,
+        asset_id
+    )
+        .fetch_all(pg_pool)
+        .await?;
+    Ok(events)
+}
+
+/// Events of the filter that follow the event of `after_sequence`, 0 to read from the first event.
+/// Events are read in the order of the transaction that recorded them then of their sequence, and only those of the
+/// transactions older than every transaction still running, so that the events of a transaction that commits later follow them.
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_outbox_events_after<'a, E>(filter: &EventFilter,
+                                             after_sequence: i64,
+                                             limit: i64,
+                                             pg_pool: E) -> Result<Vec<OutboxEvent>, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    let (asset_id, organization, owner_fp) = filter_columns(filter);
+    let events = sqlx::query_as!(
+        OutboxEvent,
+        r#"
+SELECT sequence,
+       id,
+       event_type as "event_type: EventType",
+       asset_id,
+       organization,
+       owner_fp,
+       payload,
+       created_at,
+       dispatched_at
+FROM outbox
+WHERE (transaction_id, sequence) > (COALESCE((SELECT transaction_id FROM outbox WHERE sequence = $1), 0), $1)
+  AND transaction_id < pg_snapshot_xmin(pg_current_snapshot())::TEXT::BIGINT
+  AND ($2::TEXT IS NULL OR asset_id = $2)
+  AND ($3::TEXT IS NULL OR organization = $3)
+  AND ($4::TEXT IS NULL OR owner_fp = $4)
+ORDER BY transaction_id, sequence
+LIMIT $5"#,
+        after_sequence,
+        asset_id,
+        organization,
+        owner_fp,
+        limit
+    )
+        .fetch_all(pg_pool)
+        .await?;
+    Ok(events)
+}
+
+/// Whether events of the filter that follow the event of `after_sequence` were committed but are not read yet,
+/// because a transaction older than theirs is still running
+#[tracing::instrument(skip(pg_pool))]
+pub async fn has_outbox_events_held_back<'a, E>(filter: &EventFilter, after_sequence: i64, pg_pool: E) -> Result<bool, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    let (asset_id, organization, owner_fp) = filter_columns(filter);
+    let held_back = sqlx::query_scalar!(
+        r#"
+SELECT EXISTS(SELECT 1
+              FROM outbox
+              WHERE (transaction_id, sequence) > (COALESCE((SELECT transaction_id FROM outbox WHERE sequence = $1), 0), $1)
+                AND transaction_id >= pg_snapshot_xmin(pg_current_snapshot())::TEXT::BIGINT
+                AND ($2::TEXT IS NULL OR asset_id = $2)
+                AND ($3::TEXT IS NULL OR organization = $3)
+                AND ($4::TEXT IS NULL OR owner_fp = $4)) as "held_back!""#,
+        after_sequence,
+        asset_id,
+        organization,
+        owner_fp
+    )
+        .fetch_one(pg_pool)
+        .await?;
+    Ok(held_back)
+}
+
+/// Sequence of the last event read in the order of `find_outbox_events_after`, 0 when there is none
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_last_outbox_sequence<'a, E>(pg_pool: E) -> Result<i64, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    let sequence = sqlx::query_scalar!(
+        r#"
+SELECT COALESCE((SELECT sequence
+                 FROM outbox
+                 WHERE transaction_id < pg_snapshot_xmin(pg_current_snapshot())::TEXT::BIGINT
+                 ORDER BY transaction_id DESC, sequence DESC
+                 LIMIT 1), 0) as "sequence!""#
+    )
+        .fetch_one(pg_pool)
+        .await?;
+    Ok(sequence)
+}
+
+// asset id, organization and owner of the events of the filter, only one is set
+fn filter_columns(filter: &EventFilter) -> (Option<&String>, Option<&String>, Option<&String>) {
+    match filter {
+        EventFilter::AssetId(asset_id) => (Some(asset_id), None, None),
+        EventFilter::Organization(organization) => (None, Some(organization), None),
+        EventFilter::OwnerFp(owner_fp) => (None, None, Some(owner_fp)),
+    }
+}
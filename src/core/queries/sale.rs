@@ -0,0 +1,148 @@
+use crate::core::queries::{decode_money, UnitOfWork};
+use crate::core::{Currency, DatabaseError, Money, Sale};
+use chrono::{DateTime, Utc};
+use sqlx::types::BigDecimal;
+use sqlx::{Executor, PgPool, Postgres};
+use tracing::info;
+
+#[derive(Debug)]
+struct DbSale {
+    pub id: String,
+    pub bid_id: String,
+    pub asset_id: String,
+    pub nfc_id: String,
+    pub seller_fp: String,
+    pub seller_org: String,
+    pub buyer_fp: String,
+    pub buyer_org: String,
+    pub price: BigDecimal,
+    pub currency: Currency,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<DbSale> for Sale {
+    type Error = DatabaseError;
+
+    fn try_from(db_sale: DbSale) -> Result<Self, Self::Error> {
+        Ok(Sale {
+            price: decode_money(&db_sale.price, db_sale.currency)?,
+            id: db_sale.id,
+            bid_id: db_sale.bid_id,
+            nfc_id: db_sale.nfc_id,
+            asset_id: db_sale.asset_id,
+            buyer_fp: db_sale.buyer_fp,
+            buyer_org: db_sale.buyer_org,
+            seller_fp: db_sale.seller_fp,
+            seller_org: db_sale.seller_org,
+            created_at: db_sale.created_at,
+        })
+    }
+}
+
+#[tracing::instrument(skip(transaction, sale))]
+pub async fn create_sale(transaction: &mut UnitOfWork<'_>, sale: &Sale) -> Result<bool, DatabaseError> {
+    info!("creating sale :: saleId={} :: bidId={}", sale.id, sale.bid_id);
+    let result = sqlx::query!(
+        r#"
+INSERT INTO sale (
+                  id,
+                  bid_id,
+                  asset_id,
+                  nfc_id,
+                  seller_fp,
+                  seller_org,
+                  buyer_fp,
+                  buyer_org,
+                  price,
+                  currency,
+                  created_at
+        )
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+"#,
+        sale.id,
+        sale.bid_id,
+        sale.asset_id,
+        sale.nfc_id,
+        sale.seller_fp,
+        sale.seller_org,
+        sale.buyer_fp,
+        sale.buyer_org,
+        &sale.price as &Money,
+        sale.price.currency() as &Currency,
+        sale.created_at,
+    )
+        .execute(&mut **transaction)
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_sale_by_bid_id<'a, E>(bid_id: &str, pg_pool: E) -> Result<Sale, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    info!("getting sale by bid id={}", bid_id);
+    let sale = sqlx::query_as!(
+        DbSale,
+        r#"
+SELECT id,
+       bid_id,
+       asset_id,
+       nfc_id,
+       seller_fp,
+       seller_org,
+       buyer_fp,
+       buyer_org,
+       price,
+       currency as "currency: Currency",
+       created_at
+FROM sale
+WHERE bid_id = $1"#,
+        bid_id
+    )
+        .fetch_one(pg_pool)
+        .await?;
+    sale.try_into()
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_sales_by_asset_id(asset_id: &str, pg_pool: &PgPool) -> Result<Vec<Sale>, DatabaseError> {
+    info!("getting sales for asset :: assetId={}", asset_id);
+    let sales = sqlx::query_as!(
+        DbSale,
+        r#"
+SELECT id,
+       bid_id,
+       asset_id,
+       nfc_id,
+       seller_fp,
+       seller_org,
+       buyer_fp,
+       buyer_org,
+       price,
+       currency as "currency: Currency",
+       created_at
+FROM sale
+WHERE asset_id = $1
+ORDER BY created_at"#,
+        asset_id
+    )
+        .fetch_all(pg_pool)
+        .await?;
+    sales.into_iter().map(Sale::try_from).collect()
+}
+
+/// Time of the most recent sale of the asset, `None` if it was never sold
+#[tracing::instrument(skip(pg_pool))]
+pub async fn find_last_sale_time_by_asset_id<'a, E>(asset_id: &str, pg_pool: E) -> Result<Option<DateTime<Utc>>, DatabaseError>
+where
+    E: Executor<'a, Database=Postgres>,
+{
+    let result = sqlx::query!(
+        r#"SELECT MAX(created_at) AS last_sale_at FROM sale WHERE asset_id = $1"#,
+        asset_id
+    )
+        .fetch_one(pg_pool)
+        .await?;
+    Ok(result.last_sale_at)
+}
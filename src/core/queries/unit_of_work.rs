@@ -0,0 +1,53 @@
+use crate::core::queries::PgTransaction;
+use crate::core::DatabaseError;
+use sqlx::{PgConnection, PgPool};
+use std::ops::{Deref, DerefMut};
+use tracing::debug;
+
+/// A change spanning several query functions, all of its steps are committed together or not at all.
+/// Orchestrators open one, pass it to the query functions of each step and commit it once the change is complete.
+/// Dropping it without committing, e.g. when a step fails, rolls back every step.
+///
+/// Query functions taking a unit of work use it as the executor of their statements through `&mut **transaction`.
+#[derive(Debug)]
+pub struct UnitOfWork<'a> {
+    transaction: PgTransaction<'a>,
+}
+
+impl UnitOfWork<'static> {
+    pub async fn begin(pg_pool: &PgPool) -> Result<Self, DatabaseError> {
+        if pg_pool.is_closed() {
+            return Err(DatabaseError::PoolClosed);
+        }
+        let transaction = pg_pool.begin().await?;
+        Ok(UnitOfWork { transaction })
+    }
+}
+
+impl UnitOfWork<'_> {
+    pub async fn commit(self) -> Result<(), DatabaseError> {
+        self.transaction.commit().await?;
+        debug!("unit of work committed");
+        Ok(())
+    }
+
+    /// Rolls back every step, same as dropping the unit of work but waiting for the rollback to complete
+    pub async fn rollback(self) -> Result<(), DatabaseError> {
+        self.transaction.rollback().await?;
+        Ok(())
+    }
+}
+
+impl Deref for UnitOfWork<'_> {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &Self::Target {
+        &self.transaction
+    }
+}
+
+impl DerefMut for UnitOfWork<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.transaction
+    }
+}
@@ -0,0 +1,662 @@
+use crate::core::queries::{AssetSort, OrderType, MAX_PAGE_SIZE};
+use crate::core::repository::{AssetRepository, ContractRepository, NfcRepository, Transactional};
+use crate::core::{Asset, AssetAttribute, AssetCursor, AssetFilter, AssetMetadata, AssetSearch, AssetSearchHit, AssetSortField, AttributeValue,
+                  Bid, BidStatus, CertificateSigner, Collection, Contract, CurrencyRegistry, DatabaseError, EscrowHold, EscrowStatus, JournalEntry,
+                  NFCTrail, NfcAnchor, ProvenanceEntry, Sale, UpdateAssetRequest, MAX_ASSET_ATTRIBUTES, NFC};
+use chrono::{DateTime, Duration, SubsecRound, Utc};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+use tokio::sync::{Mutex, MutexGuard, OwnedMutexGuard};
+
+/// Rows of the in-memory repository, keyed like the Postgres tables
+#[derive(Debug, Clone, Default)]
+struct Tables {
+    /// by asset id
+    assets: HashMap<String, Asset>,
+    /// tags of an asset by asset id
+    tags: HashMap<String, BTreeSet<String>>,
+    /// attributes of an asset by asset id then key
+    attributes: HashMap<String, BTreeMap<String, AttributeValue>>,
+    /// by NFC id
+    nfcs: HashMap<String, NFC>,
+    /// in the order they were appended
+    trails: Vec<NFCTrail>,
+    /// current revision of the contract of an asset by asset id
+    contracts: HashMap<String, Contract>,
+    /// previous revisions of every contract
+    contract_history: Vec<Contract>,
+    /// by bid id
+    bids: HashMap<String, Bid>,
+    /// by escrow hold id
+    escrow_holds: HashMap<String, EscrowHold>,
+    /// in the order they were recorded
+    sales: Vec<Sale>,
+    /// in the order they were posted
+    journal_entries: Vec<JournalEntry>,
+}
+
+/// Repositories kept in memory, for tests that do not need Postgres.
+/// The keys, unique and foreign key constraints of the Postgres tables are checked the same way and fail with the same errors.
+/// Every change is applied to a copy of the rows that only replaces them once every step of the change succeeded,
+/// changes run one at a time as if every transaction locked every row.
+/// A unit of work holds the rows until it ends, other changes and reads wait for it.
+/// Collections and anchors are only stored in Postgres, there is no collection to create an asset in, filtering by collection matches
+/// no asset and no NFC is anchored. Searches are not ranked, see `search_assets`.
+/// Bids and escrow holds are placed and accepted in Postgres only, tests of the steps that settle them insert them.
+#[derive(Debug, Default)]
+pub struct InMemoryRepository {
+    tables: Arc<Mutex<Tables>>,
+    currencies: CurrencyRegistry,
+}
+
+/// Copy of the rows the steps of a unit of work change, it replaces the rows when the unit of work commits
+#[derive(Debug)]
+pub struct InMemoryUnitOfWork {
+    tables: OwnedMutexGuard<Tables>,
+    staged: Tables,
+}
+
+impl InMemoryRepository {
+    /// Contracts can only accept the currencies enabled in the registry, none are without one
+    pub fn new(currencies: CurrencyRegistry) -> Self {
+        InMemoryRepository { tables: Arc::default(), currencies }
+    }
+
+    pub async fn insert_bid(&self, bid: Bid) {
+        self.tables().await.bids.insert(bid.id.clone(), bid);
+    }
+
+    pub async fn insert_escrow_hold(&self, hold: EscrowHold) {
+        self.tables().await.escrow_holds.insert(hold.id.clone(), hold);
+    }
+
+    async fn tables(&self) -> MutexGuard<'_, Tables> {
+        self.tables.lock().await
+    }
+
+    /// Runs the steps on a copy of the rows, the copy replaces the rows only when every step succeeded
+    async fn transaction<T>(&self, steps: impl FnOnce(&mut Tables) -> Result<T, DatabaseError>) -> Result<T, DatabaseError> {
+        let mut tables = self.tables().await;
+        let mut staged = tables.clone();
+        let result = steps(&mut staged)?;
+        *tables = staged;
+        Ok(result)
+    }
+}
+
+impl Tables {
+    fn asset(&self, asset_id: &str) -> Result<&Asset, DatabaseError> {
+        self.assets.get(asset_id).ok_or(DatabaseError::NotFound)
+    }
+
+    fn nfc_of_asset(&self, asset_id: &str) -> Option<&NFC> {
+        self.nfcs.values().find(|nfc| nfc.asset_id == asset_id)
+    }
+
+    /// Appends the trail to the hash chain of its NFC, same as `queries::create_nfc_trail`
+    fn append_trail(&mut self, mut trail: NFCTrail) {
+        let last = self.trails.iter()
+            .filter(|last| last.nfc_id == trail.nfc_id)
+            .max_by_key(|last| last.transferred_on);
+        let previous_hash = match last {
+            Some(last) => {
+                // the chain is walked in time order, a row can not be dated before the row it follows
+                if trail.transferred_on <= last.transferred_on {
+                    trail.transferred_on = last.transferred_on + Duration::microseconds(1);
+                }
+                Some(last.hash.clone())
+            }
+            None => None,
+        };
+        trail.chain_to(previous_hash);
+        self.trails.push(trail);
+    }
+
+    /// Creates the asset with its NFC and mint trail, same as `queries::create_new_asset`
+    fn create_asset(&mut self, asset: &Asset, user_fp: &str, signer: &CertificateSigner) -> Result<bool, DatabaseError> {
+        if self.assets.contains_key(&asset.id) {
+            return Err(DatabaseError::UniqueViolation);
+        }
+        // new assets are not tradable until updated
+        let asset = Asset {
+            tradable: false,
+            created_at: stored_time(asset.created_at),
+            updated_at: stored_time(asset.updated_at),
+            ..asset.clone()
+        };
+        let nfc = NFC::new(asset.id.clone(), &asset.owner_fp, signer);
+        self.append_trail(NFCTrail::mint(&nfc, user_fp.to_string(), asset.organization.clone()));
+        self.nfcs.insert(nfc.id.clone(), nfc);
+        self.assets.insert(asset.id.clone(), asset);
+        Ok(true)
+    }
+
+    /// Same constraints as the contract tables
+    fn create_contract(&mut self, contract: Contract) -> Result<bool, DatabaseError> {
+        if self.contracts.contains_key(&contract.asset_id) {
+            return Err(DatabaseError::RecordExists("contract for given asset id exists".to_string()));
+        }
+        if self.contracts.values().any(|existing| existing.id == contract.id) {
+            return Err(DatabaseError::UniqueViolation);
+        }
+        if !self.assets.contains_key(&contract.asset_id) {
+            return Err(DatabaseError::ForeignKeyViolation);
+        }
+        self.contracts.insert(contract.asset_id.clone(), stored_contract(contract));
+        Ok(true)
+    }
+
+    /// Moves the asset to the new owner, same as `queries::transfer_asset_query`
+    fn transfer(&mut self,
+                asset_id: &str,
+                new_org: &str,
+                new_owner_fp: &str,
+                sale_id: Option<&str>,
+                signer: &CertificateSigner) -> Result<NFC, DatabaseError> {
+        let mut nfc = self.nfc_of_asset(asset_id)
+            .cloned()
+            .ok_or_else(|| DatabaseError::InvalidRecordState("Invalid asset without nfc".to_string()))?;
+        let asset = self.assets.get_mut(asset_id).ok_or(DatabaseError::NotFound)?;
+        // owner and organization the asset is transferred from
+        let previous = asset.clone();
+        asset.organization = new_org.to_string();
+        asset.updated_by = new_owner_fp.to_string();
+        asset.owner_fp = new_owner_fp.to_string();
+
+        let trail = NFCTrail::transfer(nfc.id.clone(), &previous, new_owner_fp.to_string(), new_org.to_string(), sale_id.map(str::to_string));
+        self.append_trail(trail);
+        // the certificate of the previous owner no longer verifies
+        nfc.sign_for(new_owner_fp, signer);
+        self.nfcs.insert(nfc.id.clone(), nfc.clone());
+        Ok(nfc)
+    }
+
+    /// Same conditions as the WHERE clause of `queries::list_assets`
+    fn matches(&self, asset: &Asset, filter: &AssetFilter) -> bool {
+        let tags = self.tags.get(&asset.id);
+        let attributes = self.attributes.get(&asset.id);
+        filter.organization.as_ref().is_none_or(|organization| &asset.organization == organization)
+            && filter.owner_fp.as_ref().is_none_or(|owner_fp| &asset.owner_fp == owner_fp)
+            && filter.tradable.is_none_or(|tradable| asset.tradable == tradable)
+            && filter.listable.is_none_or(|listable| asset.listable == listable)
+            && filter.name_contains.as_ref().is_none_or(|name| name_like(asset, name))
+            // symbols are stored upper case
+            && filter.symbol_prefix.as_ref().is_none_or(|prefix| asset.symbol.starts_with(&prefix.trim().to_uppercase()))
+            && filter.created_from.is_none_or(|from| asset.created_at >= stored_time(from))
+            && filter.created_until.is_none_or(|until| asset.created_at < stored_time(until))
+            && filter.updated_from.is_none_or(|from| asset.updated_at >= stored_time(from))
+            && filter.updated_until.is_none_or(|until| asset.updated_at < stored_time(until))
+            // every tag of the filter must be on the asset
+            && tags.into_iter().flatten().filter(|tag| filter.tags.contains(tag)).count() == filter.tags.len()
+            && filter.attributes.iter().all(|attribute| {
+                attributes.and_then(|attributes| attributes.get(&attribute.key)) == Some(&attribute.value)
+            })
+            && filter.collection_id.is_none()
+    }
+}
+
+/// Timestamps are stored with microseconds
+fn stored_time(time: DateTime<Utc>) -> DateTime<Utc> {
+    time.trunc_subsecs(6)
+}
+
+/// The contract as it reads back from the contract tables
+fn stored_contract(mut contract: Contract) -> Contract {
+    contract.created_at = stored_time(contract.created_at);
+    contract.updated_at = stored_time(contract.updated_at);
+    contract.terms.expires_at = contract.terms.expires_at.map(stored_time);
+    if let Some(auction) = contract.auction.as_mut() {
+        auction.start_time = stored_time(auction.start_time);
+        auction.end_time = stored_time(auction.end_time);
+        auction.closed_at = auction.closed_at.map(stored_time);
+    }
+    contract.royalty_splits.sort_by(|a, b| {
+        Reverse(a.basis_points).cmp(&Reverse(b.basis_points)).then_with(|| a.receiver_fp.cmp(&b.receiver_fp))
+    });
+    contract
+}
+
+/// Order of the sort field then id, ascending
+fn compare_assets(a: &Asset, b: &Asset, field: AssetSortField) -> Ordering {
+    match field {
+        AssetSortField::Name => a.name.cmp(&b.name),
+        AssetSortField::Symbol => a.symbol.cmp(&b.symbol),
+        AssetSortField::CreatedAt => a.created_at.cmp(&b.created_at),
+        AssetSortField::UpdatedAt => a.updated_at.cmp(&b.updated_at),
+    }
+        .then_with(|| a.id.cmp(&b.id))
+}
+
+/// Order of the asset and the asset the cursor was taken after, ascending
+fn compare_to_cursor(asset: &Asset, cursor: &AssetCursor) -> Result<Ordering, DatabaseError> {
+    let key = match cursor.field {
+        AssetSortField::Name => asset.name.as_str().cmp(cursor.key.as_str()),
+        AssetSortField::Symbol => asset.symbol.as_str().cmp(cursor.key.as_str()),
+        AssetSortField::CreatedAt | AssetSortField::UpdatedAt => {
+            let key = cursor.key_timestamp()
+                .ok_or_else(|| DatabaseError::InvalidArgument("cursor is invalid".to_string()))?;
+            let time = if cursor.field == AssetSortField::CreatedAt { asset.created_at } else { asset.updated_at };
+            time.cmp(&key)
+        }
+    };
+    Ok(key.then_with(|| asset.id.as_str().cmp(cursor.id.as_str())))
+}
+
+/// Assets in the order of `field`, starting after the cursor
+fn page(mut assets: Vec<Asset>,
+        field: AssetSortField,
+        order: OrderType,
+        cursor: Option<&AssetCursor>,
+        limit: usize) -> Result<Vec<Asset>, DatabaseError> {
+    assets.sort_by(|a, b| match order {
+        OrderType::Asc => compare_assets(a, b, field),
+        OrderType::Desc => compare_assets(b, a, field),
+    });
+    let after = match order {
+        OrderType::Asc => Ordering::Greater,
+        OrderType::Desc => Ordering::Less,
+    };
+    let mut page = Vec::with_capacity(limit.min(assets.len()));
+    for asset in assets {
+        if page.len() == limit {
+            break;
+        }
+        if cursor.map(|cursor| compare_to_cursor(&asset, cursor)).transpose()?.is_none_or(|ordering| ordering == after) {
+            page.push(asset);
+        }
+    }
+    Ok(page)
+}
+
+fn name_like(asset: &Asset, name: &str) -> bool {
+    asset.name.to_lowercase().contains(&name.to_lowercase())
+}
+
+impl Transactional for InMemoryRepository {
+    type UnitOfWork = InMemoryUnitOfWork;
+
+    async fn begin(&self) -> Result<InMemoryUnitOfWork, DatabaseError> {
+        let tables = self.tables.clone().lock_owned().await;
+        let staged = tables.clone();
+        Ok(InMemoryUnitOfWork { tables, staged })
+    }
+
+    async fn commit(&self, mut transaction: InMemoryUnitOfWork) -> Result<(), DatabaseError> {
+        *transaction.tables = transaction.staged;
+        Ok(())
+    }
+}
+
+impl AssetRepository for InMemoryRepository {
+    async fn create_asset(&self, asset: &Asset, user_fp: &str, signer: &CertificateSigner) -> Result<bool, DatabaseError> {
+        self.transaction(|tables| tables.create_asset(asset, user_fp, signer)).await
+    }
+
+    async fn find_asset_by_id(&self, asset_id: &str) -> Result<Asset, DatabaseError> {
+        self.tables().await.asset(asset_id).cloned()
+    }
+
+    async fn find_asset_by_id_and_org_id(&self, asset_id: &str, org_id: &str) -> Result<Asset, DatabaseError> {
+        self.tables().await.assets.get(asset_id)
+            .filter(|asset| asset.organization == org_id)
+            .cloned()
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    async fn list_assets(&self, filter: &AssetFilter, sort: AssetSort, cursor: Option<&AssetCursor>, limit: i64)
+                         -> Result<Vec<Asset>, DatabaseError> {
+        if !(1..=MAX_PAGE_SIZE).contains(&limit) {
+            return Err(DatabaseError::InvalidArgument(format!("limit must be between 1 and {}", MAX_PAGE_SIZE)));
+        }
+        if cursor.is_some_and(|cursor| cursor.field != sort.field) {
+            return Err(DatabaseError::InvalidArgument(format!("cursor is not for a list sorted by {}", sort.field)));
+        }
+        let tables = self.tables().await;
+        let assets = tables.assets.values()
+            .filter(|asset| tables.matches(asset, filter))
+            .cloned()
+            .collect();
+        page(assets, sort.field, sort.order, cursor, limit as usize)
+    }
+
+    async fn count_assets(&self, filter: &AssetFilter) -> Result<i64, DatabaseError> {
+        let tables = self.tables().await;
+        Ok(tables.assets.values().filter(|asset| tables.matches(asset, filter)).count() as i64)
+    }
+
+    /// Every asset with the term in its name, symbol or description is a hit with the same score, in the order of their names.
+    /// The snippet is the name of the asset.
+    async fn search_assets(&self, search: &AssetSearch) -> Result<Vec<AssetSearchHit>, DatabaseError> {
+        let term = search.term.to_lowercase();
+        let tables = self.tables().await;
+        let mut hits: Vec<AssetSearchHit> = tables.assets.values()
+            .filter(|asset| {
+                search.organization.as_ref().is_none_or(|organization| &asset.organization == organization)
+                    && search.tradable.is_none_or(|tradable| asset.tradable == tradable)
+                    && search.listable.is_none_or(|listable| asset.listable == listable)
+                    && [&asset.name, &asset.symbol, &asset.description].iter().any(|text| text.to_lowercase().contains(&term))
+            })
+            .map(|asset| AssetSearchHit { asset: asset.clone(), score: 1.0, snippet: asset.name.clone() })
+            .collect();
+        hits.sort_by(|a, b| compare_assets(&a.asset, &b.asset, AssetSortField::Name));
+        Ok(hits.into_iter().skip(search.offset.max(0) as usize).take(search.limit.max(0) as usize).collect())
+    }
+
+    async fn find_asset_metadata(&self, asset_ids: &[String]) -> Result<HashMap<String, AssetMetadata>, DatabaseError> {
+        let tables = self.tables().await;
+        let mut metadata: HashMap<String, AssetMetadata> = HashMap::new();
+        for asset_id in asset_ids {
+            let tags: Vec<String> = tables.tags.get(asset_id).into_iter().flatten().cloned().collect();
+            let attributes: Vec<AssetAttribute> = tables.attributes.get(asset_id)
+                .into_iter()
+                .flatten()
+                .map(|(key, value)| AssetAttribute { key: key.clone(), value: value.clone() })
+                .collect();
+            if !tags.is_empty() || !attributes.is_empty() {
+                metadata.insert(asset_id.clone(), AssetMetadata { tags, attributes });
+            }
+        }
+        Ok(metadata)
+    }
+
+    async fn update_asset(&self, asset_id: &str, updated_by: &str, update: &UpdateAssetRequest) -> Result<bool, DatabaseError> {
+        if updated_by.is_empty() || updated_by.len() < 50 {
+            return Err(DatabaseError::InvalidArgument("updated_by is required".to_string()));
+        }
+        // no field is there to be updated, return early
+        if update.name.is_none()
+            && update.symbol.is_none()
+            && update.listable.is_none()
+            && update.tradable.is_none()
+            && update.description.is_none()
+            && update.organization.is_none()
+            && !update.updates_metadata()
+        {
+            return Ok(true);
+        }
+        self.transaction(|tables| {
+            let Some(asset) = tables.assets.get_mut(asset_id) else {
+                return Ok(false);
+            };
+            if let Some(name) = &update.name {
+                asset.name = name.clone();
+            }
+            if let Some(symbol) = &update.symbol {
+                asset.symbol = symbol.clone();
+            }
+            if let Some(description) = &update.description {
+                asset.description = description.clone();
+            }
+            if let Some(organization) = &update.organization {
+                asset.organization = organization.clone();
+            }
+            if let Some(listable) = update.listable {
+                asset.listable = listable;
+            }
+            if let Some(tradable) = update.tradable {
+                asset.tradable = tradable;
+            }
+            asset.updated_at = stored_time(Utc::now());
+            asset.updated_by = updated_by.to_string();
+
+            if let Some(tags) = &update.tags {
+                tables.tags.insert(asset_id.to_string(), tags.iter().cloned().collect());
+            }
+            let attributes = tables.attributes.entry(asset_id.to_string()).or_default();
+            for key in &update.remove_attributes {
+                attributes.remove(key);
+            }
+            for attribute in &update.set_attributes {
+                attributes.insert(attribute.key.clone(), attribute.value.clone());
+            }
+            if attributes.len() > MAX_ASSET_ATTRIBUTES {
+                return Err(DatabaseError::InvalidArgument(format!("an asset can have at most {} attributes", MAX_ASSET_ATTRIBUTES)));
+            }
+            Ok(true)
+        }).await
+    }
+
+    async fn delete_asset_by_id(&self, asset_id: &str) -> Result<bool, DatabaseError> {
+        self.transaction(|tables| {
+            if tables.assets.remove(asset_id).is_none() {
+                return Ok(false);
+            }
+            // rows of the asset are deleted with it
+            tables.tags.remove(asset_id);
+            tables.attributes.remove(asset_id);
+            tables.nfcs.retain(|_, nfc| nfc.asset_id != asset_id);
+            let nfcs = &tables.nfcs;
+            tables.trails.retain(|trail| nfcs.contains_key(&trail.nfc_id));
+            if let Some(contract) = tables.contracts.remove(asset_id) {
+                tables.contract_history.retain(|revision| revision.id != contract.id);
+            }
+            Ok(true)
+        }).await
+    }
+
+    async fn lock_asset(&self, asset_id: &str, transaction: &mut InMemoryUnitOfWork) -> Result<Asset, DatabaseError> {
+        transaction.staged.asset(asset_id).cloned()
+    }
+
+    async fn transfer_asset(&self,
+                            asset_id: &str,
+                            new_org: &str,
+                            new_owner_fp: &str,
+                            signer: &CertificateSigner,
+                            transaction: &mut InMemoryUnitOfWork) -> Result<NFC, DatabaseError> {
+        transaction.staged.transfer(asset_id, new_org, new_owner_fp, None, signer)
+    }
+
+    async fn create_asset_in_transaction(&self,
+                                         asset: &Asset,
+                                         user_fp: &str,
+                                         signer: &CertificateSigner,
+                                         transaction: &mut InMemoryUnitOfWork) -> Result<bool, DatabaseError> {
+        transaction.staged.create_asset(asset, user_fp, signer)
+    }
+
+    async fn lock_collection(&self, _collection_id: &str, _transaction: &mut InMemoryUnitOfWork) -> Result<Collection, DatabaseError> {
+        Err(DatabaseError::NotFound)
+    }
+
+    async fn count_collection_assets(&self, _collection_id: &str, _transaction: &mut InMemoryUnitOfWork) -> Result<i64, DatabaseError> {
+        Ok(0)
+    }
+
+    async fn add_asset_to_collection(&self,
+                                     _collection_id: &str,
+                                     _asset_id: &str,
+                                     _joined_at: DateTime<Utc>,
+                                     _transaction: &mut InMemoryUnitOfWork) -> Result<bool, DatabaseError> {
+        Ok(false)
+    }
+}
+
+impl ContractRepository for InMemoryRepository {
+    async fn create_contract(&self, contract: Contract) -> Result<bool, DatabaseError> {
+        self.transaction(|tables| tables.create_contract(contract)).await
+    }
+
+    async fn create_contract_in_transaction(&self, contract: Contract, transaction: &mut InMemoryUnitOfWork) -> Result<bool, DatabaseError> {
+        transaction.staged.create_contract(contract)
+    }
+
+    async fn find_contract_by_asset_id(&self, asset_id: &str) -> Result<Contract, DatabaseError> {
+        self.tables().await.contracts.get(asset_id).cloned().ok_or(DatabaseError::NotFound)
+    }
+
+    async fn update_contract(&self, previous: &Contract, updated: &Contract, transaction: &mut InMemoryUnitOfWork)
+                             -> Result<bool, DatabaseError> {
+        let tables = &mut transaction.staged;
+        if !tables.contracts.values().any(|contract| contract.id == previous.id) {
+            return Err(DatabaseError::ForeignKeyViolation);
+        }
+        let recorded = tables.contract_history.iter()
+            .any(|revision| revision.id == previous.id && revision.update_count == previous.update_count);
+        if recorded {
+            return Err(DatabaseError::UniqueViolation);
+        }
+        let Some(current) = tables.contracts.values_mut()
+            .find(|contract| contract.id == updated.id && contract.update_count == previous.update_count) else {
+            return Ok(false);
+        };
+        // the id, asset, creation time and auction of a contract do not change with its revisions
+        *current = Contract {
+            id: current.id.clone(),
+            asset_id: current.asset_id.clone(),
+            created_at: current.created_at,
+            auction: current.auction.clone(),
+            ..stored_contract(updated.clone())
+        };
+        // revisions in the history have no auction
+        tables.contract_history.push(Contract { auction: None, ..stored_contract(previous.clone()) });
+        Ok(true)
+    }
+
+    async fn find_contract_history(&self, asset_id: &str, limit: i64, offset: i64) -> Result<Vec<Contract>, DatabaseError> {
+        let (Ok(limit), Ok(offset)) = (usize::try_from(limit), usize::try_from(offset)) else {
+            return Err(DatabaseError::InvalidArgument("limit and offset must not be negative".to_string()));
+        };
+        let mut revisions: Vec<Contract> = self.tables().await.contract_history.iter()
+            .filter(|revision| revision.asset_id == asset_id)
+            .cloned()
+            .collect();
+        revisions.sort_by_key(|revision| Reverse(revision.update_count));
+        Ok(revisions.into_iter().skip(offset).take(limit).collect())
+    }
+
+    async fn find_contract_at_version(&self, asset_id: &str, update_count: i32) -> Result<Contract, DatabaseError> {
+        let tables = self.tables().await;
+        let contract = tables.contracts.get(asset_id).ok_or(DatabaseError::NotFound)?;
+        if contract.update_count == update_count {
+            return Ok(contract.clone());
+        }
+        tables.contract_history.iter()
+            .find(|revision| revision.id == contract.id && revision.update_count == update_count)
+            .cloned()
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    async fn find_currency_registry(&self) -> Result<CurrencyRegistry, DatabaseError> {
+        Ok(self.currencies.clone())
+    }
+
+    async fn lock_contract(&self, asset_id: &str, transaction: &mut InMemoryUnitOfWork) -> Result<Contract, DatabaseError> {
+        transaction.staged.contracts.get(asset_id).cloned().ok_or(DatabaseError::NotFound)
+    }
+
+    async fn count_open_bids(&self, asset_id: &str, transaction: &mut InMemoryUnitOfWork) -> Result<i64, DatabaseError> {
+        let now = Utc::now();
+        Ok(transaction.staged.bids.values()
+            .filter(|bid| bid.asset_id == asset_id && bid.status == BidStatus::Open && bid.expires_at > now)
+            .count() as i64)
+    }
+
+    async fn find_active_escrow_hold(&self, asset_id: &str, transaction: &mut InMemoryUnitOfWork)
+                                     -> Result<Option<EscrowHold>, DatabaseError> {
+        Ok(transaction.staged.escrow_holds.values()
+            .find(|hold| hold.asset_id == asset_id && hold.status.is_active())
+            .cloned())
+    }
+
+    async fn sell_to_bidder(&self,
+                            bid_id: &str,
+                            asset: &Asset,
+                            contract: &Contract,
+                            signer: &CertificateSigner,
+                            transaction: &mut InMemoryUnitOfWork) -> Result<(NFC, Sale), DatabaseError> {
+        let tables = &mut transaction.staged;
+        let bid = tables.bids.get(bid_id).cloned().ok_or(DatabaseError::NotFound)?;
+        let nfc_id = tables.nfc_of_asset(&asset.id).map(|nfc| nfc.id.clone()).ok_or(DatabaseError::NotFound)?;
+        let sale = Sale::from_bid(&bid, asset, nfc_id);
+        let nfc = tables.transfer(&asset.id, &bid.bidder_org, &bid.bidder_fp, Some(&sale.id), signer)?;
+        let entry = JournalEntry::for_sale(&sale, contract)
+            .map_err(|e| DatabaseError::TransactionStepError(format!("failed to create journal entry: {}", e)))?;
+        tables.sales.push(sale.clone());
+        tables.journal_entries.push(entry);
+        Ok((nfc, sale))
+    }
+
+    async fn release_escrow_hold(&self, hold_id: &str, transaction: &mut InMemoryUnitOfWork) -> Result<bool, DatabaseError> {
+        let Some(hold) = transaction.staged.escrow_holds.get_mut(hold_id).filter(|hold| hold.status == EscrowStatus::Funded) else {
+            return Ok(false);
+        };
+        let now = Utc::now();
+        hold.status = EscrowStatus::Released;
+        hold.settled_at = Some(now);
+        hold.updated_at = now;
+        Ok(true)
+    }
+}
+
+impl NfcRepository for InMemoryRepository {
+    async fn get_nfc_by_id(&self, nfc_id: &str) -> Result<NFC, DatabaseError> {
+        self.tables().await.nfcs.get(nfc_id).cloned().ok_or(DatabaseError::NotFound)
+    }
+
+    async fn get_nfc_by_asset_id(&self, asset_id: &str) -> Result<NFC, DatabaseError> {
+        self.tables().await.nfc_of_asset(asset_id).cloned().ok_or(DatabaseError::NotFound)
+    }
+
+    async fn get_nfc_trails_by_nfc_id(&self, nfc_id: &str) -> Result<Vec<NFCTrail>, DatabaseError> {
+        let mut trails: Vec<NFCTrail> = self.tables().await.trails.iter()
+            .filter(|trail| trail.nfc_id == nfc_id)
+            .cloned()
+            .collect();
+        trails.sort_by_key(|trail| trail.transferred_on);
+        Ok(trails)
+    }
+
+    async fn get_nfc_trails_by_asset_id(&self, asset_id: &str) -> Result<Vec<NFCTrail>, DatabaseError> {
+        let mut trails: Vec<NFCTrail> = self.tables().await.trails.iter()
+            .filter(|trail| trail.asset_id == asset_id)
+            .cloned()
+            .collect();
+        trails.sort_by_key(|trail| trail.transferred_on);
+        Ok(trails)
+    }
+
+    async fn get_nfc_by_asset_id_in_transaction(&self, asset_id: &str, transaction: &mut InMemoryUnitOfWork) -> Result<NFC, DatabaseError> {
+        transaction.staged.nfc_of_asset(asset_id).cloned().ok_or(DatabaseError::NotFound)
+    }
+
+    async fn update_nfc_cert(&self, nfc_id: &str, cert: &str, transaction: &mut InMemoryUnitOfWork) -> Result<bool, DatabaseError> {
+        match transaction.staged.nfcs.get_mut(nfc_id) {
+            Some(nfc) => {
+                nfc.cert = cert.to_string();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn find_asset_provenance(&self, asset_id: &str) -> Result<Vec<ProvenanceEntry>, DatabaseError> {
+        let tables = self.tables().await;
+        let mut trails: Vec<&NFCTrail> = tables.trails.iter().filter(|trail| trail.asset_id == asset_id).collect();
+        trails.sort_by_key(|trail| trail.transferred_on);
+        Ok(trails.into_iter()
+            .map(|trail| ProvenanceEntry {
+                trail: trail.clone(),
+                price: tables.sales.iter().find(|sale| trail.sale_id.as_ref() == Some(&sale.id)).map(|sale| sale.price.clone()),
+            })
+            .collect())
+    }
+
+    async fn find_trail_asset_ids(&self, after_asset_id: &str, limit: i64) -> Result<Vec<String>, DatabaseError> {
+        let tables = self.tables().await;
+        let asset_ids: BTreeSet<&String> = tables.trails.iter()
+            .map(|trail| &trail.asset_id)
+            .filter(|asset_id| asset_id.as_str() > after_asset_id)
+            .collect();
+        Ok(asset_ids.into_iter().take(limit.max(0) as usize).cloned().collect())
+    }
+
+    async fn find_nfc_anchor_by_nfc_id(&self, _nfc_id: &str) -> Result<NfcAnchor, DatabaseError> {
+        Err(DatabaseError::NotFound)
+    }
+
+    async fn find_nfc_anchor_leaves(&self, _anchor_id: &str) -> Result<Vec<(String, String)>, DatabaseError> {
+        Ok(vec![])
+    }
+}
@@ -0,0 +1,182 @@
+//! Storage of assets, contracts and NFCs behind traits, services and orchestrators generic over them run against
+//! Postgres in the server and against memory in tests.
+//! Bids, escrow holds, collections and anchors are read and settled through the repositories,
+//! they are created in Postgres only, see `queries`.
+mod memory;
+mod postgres;
+
+pub use memory::{InMemoryRepository, InMemoryUnitOfWork};
+pub use postgres::PgRepository;
+
+use crate::core::queries::AssetSort;
+use crate::core::{Asset, AssetCursor, AssetFilter, AssetMetadata, AssetSearch, AssetSearchHit, CertificateSigner, Collection, Contract,
+                  CurrencyRegistry, DatabaseError, EscrowHold, NFCTrail, NfcAnchor, ProvenanceEntry, Sale, UpdateAssetRequest, NFC};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::future::Future;
+
+/// Changes spanning several steps, orchestrators begin a unit of work, pass it to the steps and commit it
+pub trait Transactional: Send + Sync {
+    /// The steps run in a unit of work are committed together or not at all, dropping it without committing rolls
+    /// them back. Rows locked in a unit of work stay locked until it ends.
+    type UnitOfWork: Send;
+
+    fn begin(&self) -> impl Future<Output=Result<Self::UnitOfWork, DatabaseError>> + Send;
+
+    fn commit(&self, transaction: Self::UnitOfWork) -> impl Future<Output=Result<(), DatabaseError>> + Send;
+}
+
+pub trait AssetRepository: Transactional {
+    /// Creates the asset with its NFC and the first entry of its trail, nothing is created when a step fails.
+    /// Fails with `UniqueViolation` when an asset with the same id exists.
+    fn create_asset(&self, asset: &Asset, user_fp: &str, signer: &CertificateSigner)
+                    -> impl Future<Output=Result<bool, DatabaseError>> + Send;
+
+    /// Fails with `NotFound` when there is no asset with the id
+    fn find_asset_by_id(&self, asset_id: &str) -> impl Future<Output=Result<Asset, DatabaseError>> + Send;
+
+    /// Fails with `NotFound` when there is no asset with the id in the organization
+    fn find_asset_by_id_and_org_id(&self, asset_id: &str, org_id: &str) -> impl Future<Output=Result<Asset, DatabaseError>> + Send;
+
+    /// Page of the assets matching the filter in the order of `sort`, starting after the cursor,
+    /// or at the first matching asset without one. The cursor must come from a list with the same sort field.
+    fn list_assets(&self, filter: &AssetFilter, sort: AssetSort, cursor: Option<&AssetCursor>, limit: i64)
+                   -> impl Future<Output=Result<Vec<Asset>, DatabaseError>> + Send;
+
+    /// Number of assets matching the filter
+    fn count_assets(&self, filter: &AssetFilter) -> impl Future<Output=Result<i64, DatabaseError>> + Send;
+
+    /// Assets matching the search, best matches first
+    fn search_assets(&self, search: &AssetSearch) -> impl Future<Output=Result<Vec<AssetSearchHit>, DatabaseError>> + Send;
+
+    /// Tags and attributes of the assets by asset id, assets without any have no entry
+    fn find_asset_metadata(&self, asset_ids: &[String])
+                           -> impl Future<Output=Result<HashMap<String, AssetMetadata>, DatabaseError>> + Send;
+
+    /// Applies the update with its tags and attributes at once, returns false when there is no asset with the id
+    fn update_asset(&self, asset_id: &str, updated_by: &str, update: &UpdateAssetRequest)
+                    -> impl Future<Output=Result<bool, DatabaseError>> + Send;
+
+    /// Deletes the asset with its NFC, trail and contract, returns false when there is no asset with the id
+    fn delete_asset_by_id(&self, asset_id: &str) -> impl Future<Output=Result<bool, DatabaseError>> + Send;
+
+    /// Locks the asset until the unit of work ends, every change of ownership goes through this lock.
+    /// Fails with `NotFound` when there is no asset with the id
+    fn lock_asset(&self, asset_id: &str, transaction: &mut Self::UnitOfWork)
+                  -> impl Future<Output=Result<Asset, DatabaseError>> + Send;
+
+    /// Moves the asset to the new owner, appends the NFC trail and certifies the new owner.
+    /// A transfer the asset does not allow in its current state fails with `InvalidRecordState`.
+    /// Escrow holds are not checked, transfers that may complete a sale go through `orchestrator::transfer_asset`.
+    fn transfer_asset(&self,
+                      asset_id: &str,
+                      new_org: &str,
+                      new_owner_fp: &str,
+                      signer: &CertificateSigner,
+                      transaction: &mut Self::UnitOfWork) -> impl Future<Output=Result<NFC, DatabaseError>> + Send;
+
+    /// Same as `create_asset`, as a step of the unit of work
+    fn create_asset_in_transaction(&self, asset: &Asset, user_fp: &str, signer: &CertificateSigner, transaction: &mut Self::UnitOfWork)
+                                   -> impl Future<Output=Result<bool, DatabaseError>> + Send;
+
+    /// Locks the collection until the unit of work ends, assets join a collection one at a time.
+    /// Fails with `NotFound` when there is no collection with the id
+    fn lock_collection(&self, collection_id: &str, transaction: &mut Self::UnitOfWork)
+                       -> impl Future<Output=Result<Collection, DatabaseError>> + Send;
+
+    fn count_collection_assets(&self, collection_id: &str, transaction: &mut Self::UnitOfWork)
+                               -> impl Future<Output=Result<i64, DatabaseError>> + Send;
+
+    fn add_asset_to_collection(&self, collection_id: &str, asset_id: &str, joined_at: DateTime<Utc>, transaction: &mut Self::UnitOfWork)
+                               -> impl Future<Output=Result<bool, DatabaseError>> + Send;
+}
+
+pub trait ContractRepository: Transactional {
+    /// Creates the contract with its royalty splits and auction. Fails with `RecordExists` when the asset has a contract
+    /// and with `ForeignKeyViolation` when there is no asset with its asset id.
+    fn create_contract(&self, contract: Contract) -> impl Future<Output=Result<bool, DatabaseError>> + Send;
+
+    /// Fails with `NotFound` when the asset has no contract
+    fn find_contract_by_asset_id(&self, asset_id: &str) -> impl Future<Output=Result<Contract, DatabaseError>> + Send;
+
+    /// Same as `create_contract`, as a step of the unit of work
+    fn create_contract_in_transaction(&self, contract: Contract, transaction: &mut Self::UnitOfWork)
+                                      -> impl Future<Output=Result<bool, DatabaseError>> + Send;
+
+    /// Replaces `previous` with `updated` and records `previous` in the contract history.
+    /// Returns false, changing nothing, if the contract was changed since `previous` was read.
+    fn update_contract(&self, previous: &Contract, updated: &Contract, transaction: &mut Self::UnitOfWork)
+                       -> impl Future<Output=Result<bool, DatabaseError>> + Send;
+
+    /// Previous revisions of the asset's contract, newest first. The current revision is not included.
+    fn find_contract_history(&self, asset_id: &str, limit: i64, offset: i64)
+                             -> impl Future<Output=Result<Vec<Contract>, DatabaseError>> + Send;
+
+    /// The asset's contract as it was after `update_count` updates, the current contract has the highest update count
+    fn find_contract_at_version(&self, asset_id: &str, update_count: i32)
+                                -> impl Future<Output=Result<Contract, DatabaseError>> + Send;
+
+    /// Currencies contracts can be written in
+    fn find_currency_registry(&self) -> impl Future<Output=Result<CurrencyRegistry, DatabaseError>> + Send;
+
+    /// Locks the asset's contract until the unit of work ends. Fails with `NotFound` when the asset has no contract
+    fn lock_contract(&self, asset_id: &str, transaction: &mut Self::UnitOfWork)
+                     -> impl Future<Output=Result<Contract, DatabaseError>> + Send;
+
+    /// Bids on the asset that are open and not expired
+    fn count_open_bids(&self, asset_id: &str, transaction: &mut Self::UnitOfWork)
+                       -> impl Future<Output=Result<i64, DatabaseError>> + Send;
+
+    /// The pending or funded escrow hold of the asset, an asset has at most one
+    fn find_active_escrow_hold(&self, asset_id: &str, transaction: &mut Self::UnitOfWork)
+                               -> impl Future<Output=Result<Option<EscrowHold>, DatabaseError>> + Send;
+
+    /// Completes the sale of the asset to the bidder of the bid under the contract:
+    /// 1. A sale is recorded with the price and currency of the bid
+    /// 2. Ownership of the asset moves to the bidder and the NFC trail is appended, pointing to the sale
+    /// 3. The sale is posted to the ledger: the buyer is debited, the seller and royalty receivers are credited
+    ///
+    /// `asset` is the asset as it was before the sale. Fails with `NotFound` when there is no bid with the id.
+    fn sell_to_bidder(&self,
+                      bid_id: &str,
+                      asset: &Asset,
+                      contract: &Contract,
+                      signer: &CertificateSigner,
+                      transaction: &mut Self::UnitOfWork) -> impl Future<Output=Result<(NFC, Sale), DatabaseError>> + Send;
+
+    /// Releases the funds of a funded escrow hold to the seller, returns false when the hold is not funded
+    fn release_escrow_hold(&self, hold_id: &str, transaction: &mut Self::UnitOfWork)
+                           -> impl Future<Output=Result<bool, DatabaseError>> + Send;
+}
+
+pub trait NfcRepository: Transactional {
+    fn get_nfc_by_id(&self, nfc_id: &str) -> impl Future<Output=Result<NFC, DatabaseError>> + Send;
+
+    fn get_nfc_by_asset_id(&self, asset_id: &str) -> impl Future<Output=Result<NFC, DatabaseError>> + Send;
+
+    /// Same as `get_nfc_by_asset_id`, as a step of the unit of work
+    fn get_nfc_by_asset_id_in_transaction(&self, asset_id: &str, transaction: &mut Self::UnitOfWork)
+                                          -> impl Future<Output=Result<NFC, DatabaseError>> + Send;
+
+    /// Trail of the NFC, ordered from its mint
+    fn get_nfc_trails_by_nfc_id(&self, nfc_id: &str) -> impl Future<Output=Result<Vec<NFCTrail>, DatabaseError>> + Send;
+
+    /// Trail of the NFC of an asset, ordered from its mint
+    fn get_nfc_trails_by_asset_id(&self, asset_id: &str) -> impl Future<Output=Result<Vec<NFCTrail>, DatabaseError>> + Send;
+
+    /// Replaces the certificate of an NFC, returns false when there is no NFC with the id
+    fn update_nfc_cert(&self, nfc_id: &str, cert: &str, transaction: &mut Self::UnitOfWork)
+                       -> impl Future<Output=Result<bool, DatabaseError>> + Send;
+
+    /// Trail of the asset with the price of every sale, ordered from its mint
+    fn find_asset_provenance(&self, asset_id: &str) -> impl Future<Output=Result<Vec<ProvenanceEntry>, DatabaseError>> + Send;
+
+    /// Ids of up to `limit` assets with a trail, in order, starting after `after_asset_id`
+    fn find_trail_asset_ids(&self, after_asset_id: &str, limit: i64) -> impl Future<Output=Result<Vec<String>, DatabaseError>> + Send;
+
+    /// Fails with `NotFound` while the NFC is not anchored
+    fn find_nfc_anchor_by_nfc_id(&self, nfc_id: &str) -> impl Future<Output=Result<NfcAnchor, DatabaseError>> + Send;
+
+    /// NFC ids and leaf hashes of the anchor, in the order of the leaves of its tree
+    fn find_nfc_anchor_leaves(&self, anchor_id: &str) -> impl Future<Output=Result<Vec<(String, String)>, DatabaseError>> + Send;
+}
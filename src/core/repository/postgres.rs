@@ -0,0 +1,231 @@
+use crate::core::queries::{AssetSort, UnitOfWork};
+use crate::core::repository::{AssetRepository, ContractRepository, NfcRepository, Transactional};
+use crate::core::{queries, Asset, AssetCursor, AssetFilter, AssetMetadata, AssetSearch, AssetSearchHit, CertificateSigner, Collection,
+                  Contract, CurrencyRegistry, DatabaseError, EscrowHold, EscrowStatus, JournalEntry, NFCTrail, NfcAnchor, ProvenanceEntry, Sale,
+                  UpdateAssetRequest, NFC};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Repositories over the Postgres tables, every multi-step change runs in its own unit of work
+#[derive(Debug, Clone)]
+pub struct PgRepository {
+    pg_pool: Arc<PgPool>,
+}
+
+impl PgRepository {
+    pub fn new(pg_pool: Arc<PgPool>) -> Self {
+        PgRepository { pg_pool }
+    }
+}
+
+impl Transactional for PgRepository {
+    type UnitOfWork = UnitOfWork<'static>;
+
+    async fn begin(&self) -> Result<UnitOfWork<'static>, DatabaseError> {
+        UnitOfWork::begin(&self.pg_pool).await
+    }
+
+    async fn commit(&self, transaction: UnitOfWork<'static>) -> Result<(), DatabaseError> {
+        transaction.commit().await
+    }
+}
+
+impl AssetRepository for PgRepository {
+    async fn create_asset(&self, asset: &Asset, user_fp: &str, signer: &CertificateSigner) -> Result<bool, DatabaseError> {
+        let mut transaction = UnitOfWork::begin(&self.pg_pool).await?;
+        let created = queries::create_new_asset(asset, user_fp.to_string(), signer, &mut transaction).await?;
+        if created {
+            transaction.commit().await?;
+        }
+        Ok(created)
+    }
+
+    async fn find_asset_by_id(&self, asset_id: &str) -> Result<Asset, DatabaseError> {
+        queries::find_asset_by_id(asset_id, &self.pg_pool).await
+    }
+
+    async fn find_asset_by_id_and_org_id(&self, asset_id: &str, org_id: &str) -> Result<Asset, DatabaseError> {
+        queries::find_asset_by_id_and_org_id(asset_id, org_id, &self.pg_pool).await
+    }
+
+    async fn list_assets(&self, filter: &AssetFilter, sort: AssetSort, cursor: Option<&AssetCursor>, limit: i64)
+                         -> Result<Vec<Asset>, DatabaseError> {
+        queries::list_assets(filter, sort, cursor, limit, &self.pg_pool).await
+    }
+
+    async fn count_assets(&self, filter: &AssetFilter) -> Result<i64, DatabaseError> {
+        queries::count_assets(filter, &self.pg_pool).await
+    }
+
+    async fn search_assets(&self, search: &AssetSearch) -> Result<Vec<AssetSearchHit>, DatabaseError> {
+        queries::search_assets(search, &self.pg_pool).await
+    }
+
+    async fn find_asset_metadata(&self, asset_ids: &[String]) -> Result<HashMap<String, AssetMetadata>, DatabaseError> {
+        queries::find_asset_metadata(asset_ids, &self.pg_pool).await
+    }
+
+    async fn update_asset(&self, asset_id: &str, updated_by: &str, update: &UpdateAssetRequest) -> Result<bool, DatabaseError> {
+        queries::update_asset(asset_id, updated_by, update, &self.pg_pool).await
+    }
+
+    async fn delete_asset_by_id(&self, asset_id: &str) -> Result<bool, DatabaseError> {
+        queries::delete_asset_by_id(asset_id, &self.pg_pool).await
+    }
+
+    async fn lock_asset(&self, asset_id: &str, transaction: &mut UnitOfWork<'static>) -> Result<Asset, DatabaseError> {
+        queries::find_asset_by_id_for_update(asset_id, transaction).await
+    }
+
+    async fn transfer_asset(&self,
+                            asset_id: &str,
+                            new_org: &str,
+                            new_owner_fp: &str,
+                            signer: &CertificateSigner,
+                            transaction: &mut UnitOfWork<'static>) -> Result<NFC, DatabaseError> {
+        queries::transfer_asset_query(new_org, asset_id, new_owner_fp, None, signer, transaction).await
+    }
+
+    async fn create_asset_in_transaction(&self,
+                                         asset: &Asset,
+                                         user_fp: &str,
+                                         signer: &CertificateSigner,
+                                         transaction: &mut UnitOfWork<'static>) -> Result<bool, DatabaseError> {
+        queries::create_new_asset(asset, user_fp.to_string(), signer, transaction).await
+    }
+
+    async fn lock_collection(&self, collection_id: &str, transaction: &mut UnitOfWork<'static>) -> Result<Collection, DatabaseError> {
+        queries::find_collection_for_update(collection_id, transaction).await
+    }
+
+    async fn count_collection_assets(&self, collection_id: &str, transaction: &mut UnitOfWork<'static>) -> Result<i64, DatabaseError> {
+        queries::count_collection_assets(collection_id, &mut **transaction).await
+    }
+
+    async fn add_asset_to_collection(&self,
+                                     collection_id: &str,
+                                     asset_id: &str,
+                                     joined_at: DateTime<Utc>,
+                                     transaction: &mut UnitOfWork<'static>) -> Result<bool, DatabaseError> {
+        queries::add_asset_to_collection(collection_id, asset_id, joined_at, transaction).await
+    }
+}
+
+impl ContractRepository for PgRepository {
+    async fn create_contract(&self, contract: Contract) -> Result<bool, DatabaseError> {
+        queries::create_contract(&self.pg_pool, contract).await
+    }
+
+    async fn find_contract_by_asset_id(&self, asset_id: &str) -> Result<Contract, DatabaseError> {
+        queries::find_contract_by_asset_id(asset_id, &self.pg_pool).await
+    }
+
+    async fn create_contract_in_transaction(&self, contract: Contract, transaction: &mut UnitOfWork<'static>) -> Result<bool, DatabaseError> {
+        queries::create_contract_in_transaction(contract, transaction).await
+    }
+
+    async fn update_contract(&self, previous: &Contract, updated: &Contract, transaction: &mut UnitOfWork<'static>)
+                             -> Result<bool, DatabaseError> {
+        queries::update_contract(transaction, previous, updated).await
+    }
+
+    async fn find_contract_history(&self, asset_id: &str, limit: i64, offset: i64) -> Result<Vec<Contract>, DatabaseError> {
+        queries::find_contract_history(asset_id, limit, offset, &self.pg_pool).await
+    }
+
+    async fn find_contract_at_version(&self, asset_id: &str, update_count: i32) -> Result<Contract, DatabaseError> {
+        queries::find_contract_at_version(asset_id, update_count, &self.pg_pool).await
+    }
+
+    async fn find_currency_registry(&self) -> Result<CurrencyRegistry, DatabaseError> {
+        queries::find_currency_registry(self.pg_pool.as_ref()).await
+    }
+
+    async fn lock_contract(&self, asset_id: &str, transaction: &mut UnitOfWork<'static>) -> Result<Contract, DatabaseError> {
+        queries::find_contract_by_asset_id_for_update(asset_id, transaction).await
+    }
+
+    async fn count_open_bids(&self, asset_id: &str, transaction: &mut UnitOfWork<'static>) -> Result<i64, DatabaseError> {
+        queries::count_open_bids_for_asset(asset_id, &mut **transaction).await
+    }
+
+    async fn find_active_escrow_hold(&self, asset_id: &str, transaction: &mut UnitOfWork<'static>)
+                                     -> Result<Option<EscrowHold>, DatabaseError> {
+        queries::find_active_escrow_hold_for_asset(asset_id, &mut **transaction).await
+    }
+
+    async fn sell_to_bidder(&self,
+                            bid_id: &str,
+                            asset: &Asset,
+                            contract: &Contract,
+                            signer: &CertificateSigner,
+                            transaction: &mut UnitOfWork<'static>) -> Result<(NFC, Sale), DatabaseError> {
+        // 1. Record the sale
+        let bid = queries::find_bid_by_id(bid_id, &mut **transaction).await?;
+        let nfc = queries::get_nfc_by_asset_id(&asset.id, &mut **transaction).await?;
+        let sale = Sale::from_bid(&bid, asset, nfc.id.clone());
+        if !queries::create_sale(transaction, &sale).await? {
+            return Err(DatabaseError::TransactionStepError("failed to record sale".to_string()));
+        }
+
+        // 2. Move ownership to the bidder, the trail points to the sale
+        let nfc = queries::transfer_asset_query(&bid.bidder_org, &asset.id, &bid.bidder_fp, Some(&sale.id), signer, transaction)
+            .await?;
+
+        // 3. Post the sale to the ledger
+        let entry = JournalEntry::for_sale(&sale, contract)
+            .map_err(|e| DatabaseError::TransactionStepError(format!("failed to create journal entry: {}", e)))?;
+        if !queries::create_journal_entry(transaction, &entry).await? {
+            return Err(DatabaseError::TransactionStepError("failed to post sale to ledger".to_string()));
+        }
+        Ok((nfc, sale))
+    }
+
+    async fn release_escrow_hold(&self, hold_id: &str, transaction: &mut UnitOfWork<'static>) -> Result<bool, DatabaseError> {
+        queries::update_escrow_hold_status(hold_id, EscrowStatus::Funded, EscrowStatus::Released, &mut **transaction).await
+    }
+}
+
+impl NfcRepository for PgRepository {
+    async fn get_nfc_by_id(&self, nfc_id: &str) -> Result<NFC, DatabaseError> {
+        queries::get_nfc_by_id(nfc_id, &self.pg_pool).await
+    }
+
+    async fn get_nfc_by_asset_id(&self, asset_id: &str) -> Result<NFC, DatabaseError> {
+        queries::get_nfc_by_asset_id(asset_id, self.pg_pool.as_ref()).await
+    }
+
+    async fn get_nfc_by_asset_id_in_transaction(&self, asset_id: &str, transaction: &mut UnitOfWork<'static>) -> Result<NFC, DatabaseError> {
+        queries::get_nfc_by_asset_id(asset_id, &mut **transaction).await
+    }
+
+    async fn get_nfc_trails_by_nfc_id(&self, nfc_id: &str) -> Result<Vec<NFCTrail>, DatabaseError> {
+        queries::get_nfc_trails_by_nfc_id(nfc_id, &self.pg_pool).await
+    }
+
+    async fn get_nfc_trails_by_asset_id(&self, asset_id: &str) -> Result<Vec<NFCTrail>, DatabaseError> {
+        queries::get_nfc_trails_by_asset_id(asset_id, self.pg_pool.as_ref()).await
+    }
+
+    async fn update_nfc_cert(&self, nfc_id: &str, cert: &str, transaction: &mut UnitOfWork<'static>) -> Result<bool, DatabaseError> {
+        queries::update_nfc_cert(nfc_id, cert, &mut **transaction).await
+    }
+
+    async fn find_asset_provenance(&self, asset_id: &str) -> Result<Vec<ProvenanceEntry>, DatabaseError> {
+        queries::find_asset_provenance(asset_id, self.pg_pool.as_ref()).await
+    }
+
+    async fn find_trail_asset_ids(&self, after_asset_id: &str, limit: i64) -> Result<Vec<String>, DatabaseError> {
+        queries::find_trail_asset_ids(after_asset_id, limit, self.pg_pool.as_ref()).await
+    }
+
+    async fn find_nfc_anchor_by_nfc_id(&self, nfc_id: &str) -> Result<NfcAnchor, DatabaseError> {
+        queries::find_nfc_anchor_by_nfc_id(nfc_id, self.pg_pool.as_ref()).await
+    }
+
+    async fn find_nfc_anchor_leaves(&self, anchor_id: &str) -> Result<Vec<(String, String)>, DatabaseError> {
+        queries::find_nfc_anchor_leaves(anchor_id, self.pg_pool.as_ref()).await
+    }
+}
@@ -3,6 +3,7 @@ pub mod core;
 pub mod server;
 pub mod startup;
 pub mod telemetry;
+pub mod workers;
 pub mod common;
 mod context;
 pub mod constant;
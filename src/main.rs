@@ -29,6 +29,11 @@ async fn main() -> anyhow::Result<()> {
     // these tasks are currently running concurrently (read NOTE)
     let api_server_task = tokio::spawn(app.http_server.run_until_stopped());
     let grpc_server_task = tokio::spawn(app.grpc_server.run_until_stopped());
+    let auction_worker_task = tokio::spawn(app.auction_worker.run_until_stopped());
+    let escrow_worker_task = tokio::spawn(app.escrow_worker.run_until_stopped());
+    let anchor_worker_task = tokio::spawn(app.anchor_worker.run_until_stopped());
+    let outbox_relay_task = tokio::spawn(app.outbox_relay.run_until_stopped());
+    let outbox_notifier_task = tokio::spawn(app.outbox_notifier.run_until_stopped());
 
     // tokio::select! returns as soon as one of the two tasks completes or errors out
     // There's a pitfall to be mindful of when using tokio::select! - all selected Futures are
@@ -44,6 +49,11 @@ async fn main() -> anyhow::Result<()> {
     tokio::select! {
         outcome = api_server_task => report_exit("api-worker", outcome),
         outcome = grpc_server_task =>  report_exit("gRPC-worker", outcome),
+        outcome = auction_worker_task => report_exit("auction-worker", outcome),
+        outcome = escrow_worker_task => report_exit("escrow-worker", outcome),
+        outcome = anchor_worker_task => report_exit("anchor-worker", outcome),
+        outcome = outbox_relay_task => report_exit("outbox-relay", outcome),
+        outcome = outbox_notifier_task => report_exit("outbox-notifier", outcome),
     }
 
     Ok(())
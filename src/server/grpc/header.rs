@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use tonic::metadata::{MetadataKey, MetadataMap};
 use tonic::Status;
 use tracing::error;
@@ -43,6 +44,15 @@ pub fn get_xrf_user_auth_header(metadata_map: &MetadataMap, header_name: &str) -
     }
 }
 
+/// Fingerprint of the caller, fails unless the caller is one of the configured admins
+pub fn get_xrf_admin_auth_header(metadata_map: &MetadataMap, admin_fps: &HashSet<String>) -> Result<String, Status> {
+    let user_fp = get_xrf_user_auth_header(metadata_map, XRF_USER_FINGERPRINT)?;
+    if !admin_fps.contains(&user_fp) {
+        return Err(Status::permission_denied("only admins can call this method"));
+    }
+    Ok(user_fp)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
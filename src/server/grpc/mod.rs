@@ -2,11 +2,19 @@ mod server;
 mod interceptors;
 mod services;
 mod header;
+mod money;
 
-pub use header::{get_xrf_user_auth_header, XRF_USER_FINGERPRINT};
+pub use header::{get_xrf_admin_auth_header, get_xrf_user_auth_header, XRF_USER_FINGERPRINT};
 pub use server::GrpcServer;
 
 pub mod asset {
     tonic::include_proto!("asset_rpc");
+    tonic::include_proto!("proto.money.v1");
     tonic::include_proto!("proto.contract.v1");
+    tonic::include_proto!("proto.bid.v1");
+    tonic::include_proto!("proto.ledger.v1");
+    tonic::include_proto!("proto.currency.v1");
+    tonic::include_proto!("proto.fx.v1");
+    tonic::include_proto!("proto.escrow.v1");
+    tonic::include_proto!("proto.collection.v1");
 }
@@ -0,0 +1,43 @@
+use crate::core::{queries, Currency, CurrencyRegistry, Money};
+use crate::server::grpc::asset::Money as GrpcMoney;
+use sqlx::PgPool;
+use tonic::Status;
+use tracing::error;
+
+impl From<Money> for GrpcMoney {
+    fn from(money: Money) -> Self {
+        GrpcMoney {
+            amount: money.to_decimal().to_string(),
+            currency: money.currency().to_string(),
+        }
+    }
+}
+
+/// Loads the registry that the currencies and amounts of a request are read with
+pub async fn find_currency_registry(pg_pool: &PgPool) -> Result<CurrencyRegistry, Status> {
+    queries::find_currency_registry(pg_pool)
+        .await
+        .map_err(|e| {
+            error!("failed to load currency registry :: err={:?}", e);
+            Status::internal("server error")
+        })
+}
+
+/// Reads a currency by its code or one of its aliases in the registry
+pub fn process_currency(currency: &str, registry: &CurrencyRegistry) -> Result<Currency, Status> {
+    registry.resolve(currency)
+        .map(|info| info.code.clone())
+        .ok_or_else(|| Status::invalid_argument(format!("invalid currency '{}'", currency)))
+}
+
+/// Reads an amount, exact to the decimals of its currency in the registry
+pub fn to_money(money: GrpcMoney, registry: &CurrencyRegistry) -> Result<Money, Status> {
+    let currency = registry.resolve(&money.currency)
+        .ok_or_else(|| Status::invalid_argument(format!("invalid currency '{}'", money.currency)))?;
+    Money::parse(&money.amount, currency).map_err(|e| Status::invalid_argument(e.to_string()))
+}
+
+/// Reads a money field that the request must set
+pub fn process_money(money: Option<GrpcMoney>, field: &str, registry: &CurrencyRegistry) -> Result<Money, Status> {
+    to_money(money.ok_or_else(|| Status::invalid_argument(format!("{} is required", field)))?, registry)
+}
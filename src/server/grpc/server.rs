@@ -1,18 +1,30 @@
 use crate::common::generate_request_id;
-use crate::configs::GrpcServerConfig;
+use crate::configs::{AdminConfig, EscrowConfig, FxConfig, GrpcServerConfig};
 use crate::constant::{CERT_PEM_PATH, KEY_PEM_PATH, REQUEST_ID_KEY};
 use crate::context::AppContext;
+use crate::core::repository::PgRepository;
+use crate::core::event_proto::event_service_server::EventServiceServer;
+use crate::core::{queries, CertificateSigner, FxPolicy};
 use crate::server::grpc::asset::asset_service_server::AssetServiceServer;
+use crate::server::grpc::asset::bid_service_server::BidServiceServer;
+use crate::server::grpc::asset::collection_service_server::CollectionServiceServer;
 use crate::server::grpc::asset::contract_service_server::ContractServiceServer;
-use crate::server::grpc::services::{AssetServiceManager, ContractServiceManager};
+use crate::server::grpc::asset::currency_service_server::CurrencyServiceServer;
+use crate::server::grpc::asset::escrow_service_server::EscrowServiceServer;
+use crate::server::grpc::asset::fx_service_server::FxServiceServer;
+use crate::server::grpc::asset::ledger_service_server::LedgerServiceServer;
+use crate::server::grpc::services::{AssetServiceManager, BidServiceManager, CollectionServiceManager, ContractServiceManager, CurrencyServiceManager,
+                                    EscrowServiceManager, EventServiceManager, FxServiceManager, LedgerServiceManager};
 use anyhow::Context;
 use bytes::Bytes;
 use sqlx::PgPool;
+use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::broadcast;
 use tonic::metadata::{KeyAndValueRef, MetadataKey, MetadataValue};
 use tonic::transport::{Identity, Server, ServerTlsConfig};
 use tonic::{Request, Status};
@@ -22,15 +34,29 @@ use tracing::{debug, info, info_span, warn};
 pub struct GrpcServer {
     timeout: Duration,
     addr: core::net::SocketAddr,
-    asset_service: AssetServiceManager,
-    contract_service: ContractServiceManager,
+    pg_pool: Arc<PgPool>,
+    asset_service: AssetServiceManager<PgRepository>,
+    bid_service: BidServiceManager,
+    collection_service: CollectionServiceManager,
+    contract_service: ContractServiceManager<PgRepository>,
+    currency_service: CurrencyServiceManager,
+    escrow_service: EscrowServiceManager,
+    event_service: EventServiceManager,
+    fx_service: FxServiceManager,
+    ledger_service: LedgerServiceManager,
 }
 
 const SSL_PEM_SERVE_KEY_PATH: &str = "./local/ssl/server.key";
 const SSL_PEM_SERVE_CERT_PATH: &str = "./local/ssl/server.crt";
 
 impl GrpcServer {
-    pub fn new(pg_pool: PgPool, config: GrpcServerConfig) -> Result<Self, anyhow::Error> {
+    pub fn new(pg_pool: PgPool,
+               config: GrpcServerConfig,
+               fx_config: &FxConfig,
+               escrow_config: &EscrowConfig,
+               admin_config: &AdminConfig,
+               signer: CertificateSigner,
+               event_notifications: broadcast::Sender<i64>) -> Result<Self, anyhow::Error> {
         let addr = format!("[::]:{}", config.port)
             .parse()
             .context("Failed to parse grpc server address")?;
@@ -38,22 +64,44 @@ impl GrpcServer {
         // Create the PgArc, so we only have one strong reference initially
         let pg_pool_arc = Arc::new(pg_pool);
 
+        let fx_policy = FxPolicy::new(chrono::Duration::seconds(fx_config.max_rate_age_secs as i64),
+                                      chrono::Duration::seconds(fx_config.max_crypto_rate_age_secs as i64));
+        let admin_fps: HashSet<String> = admin_config.fingerprints.iter().cloned().collect();
+
+        let repository = Arc::new(PgRepository::new(pg_pool_arc.clone()));
+
         // create the services
-        let asset_service = AssetServiceManager::new(pg_pool_arc.clone());
-        let contract_service = ContractServiceManager::new(pg_pool_arc.clone());
+        let asset_service = AssetServiceManager::new(repository.clone(), signer, admin_fps.clone());
+        let bid_service = BidServiceManager::new(pg_pool_arc.clone(), fx_policy.clone(), escrow_config.hold_ttl());
+        let collection_service = CollectionServiceManager::new(pg_pool_arc.clone());
+        let contract_service = ContractServiceManager::new(repository);
+        let currency_service = CurrencyServiceManager::new(pg_pool_arc.clone());
+        let escrow_service = EscrowServiceManager::new(pg_pool_arc.clone());
+        let event_service = EventServiceManager::new(pg_pool_arc.clone(), event_notifications, admin_fps.clone());
+        let fx_service = FxServiceManager::new(pg_pool_arc.clone(), fx_policy, admin_fps);
+        let ledger_service = LedgerServiceManager::new(pg_pool_arc.clone());
 
         let config_timeout = config.timeout;
 
         Ok(Self {
             addr,
+            pg_pool: pg_pool_arc,
             asset_service,
+            bid_service,
+            collection_service,
             contract_service,
+            currency_service,
+            escrow_service,
+            event_service,
+            fx_service,
+            ledger_service,
             timeout: Duration::from_millis(config_timeout as u64),
         })
     }
 
     pub async fn run_until_stopped(self) -> anyhow::Result<()> {
         info!("starting gRPC server :: port {}", &self.addr.port());
+        self.check_schema().await?;
         let key_path = &get_path_from_env_or(KEY_PEM_PATH, SSL_PEM_SERVE_KEY_PATH)?;
         let cert_path = &get_path_from_env_or(CERT_PEM_PATH, SSL_PEM_SERVE_CERT_PATH)?;
         // Load the PEM-encoded data directly. Pem (Privacy-Enhanced Mail)
@@ -78,12 +126,30 @@ impl GrpcServer {
             .layer(tower_layers)
             .max_connection_age(self.timeout)
             .add_service(AssetServiceServer::new(self.asset_service))
+            .add_service(BidServiceServer::new(self.bid_service))
+            .add_service(CollectionServiceServer::new(self.collection_service))
             .add_service(ContractServiceServer::new(self.contract_service))
+            .add_service(CurrencyServiceServer::new(self.currency_service))
+            .add_service(EscrowServiceServer::new(self.escrow_service))
+            .add_service(EventServiceServer::new(self.event_service))
+            .add_service(FxServiceServer::new(self.fx_service))
+            .add_service(LedgerServiceServer::new(self.ledger_service))
             .serve(self.addr)
             .await
             .context("gRPC server failed")
     }
 
+    /// Refuses to serve a database whose schema is behind the migrations embedded in the binary
+    async fn check_schema(&self) -> anyhow::Result<()> {
+        let pending = queries::find_pending_migrations(&self.pg_pool)
+            .await
+            .context("Failed to check the database schema")?;
+        if !pending.is_empty() {
+            return Err(anyhow::anyhow!("database schema is behind the binary :: pending migrations={:?}", pending));
+        }
+        Ok(())
+    }
+
     fn request_id_interceptor(mut req: Request<()>) -> Result<Request<()>, Status> {
         let req_id = generate_request_id();
         let span = info_span!("gRPC", request_id = req_id);
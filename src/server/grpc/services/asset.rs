@@ -1,15 +1,24 @@
 use crate::constant::REQUEST_ID_KEY;
-use crate::core::{orchestrator, queries, Asset, DatabaseError, DomainError, OrchestrateError, UpdateAssetRequest};
+use crate::core::repository::{AssetRepository, ContractRepository, NfcRepository};
+use crate::core::{normalize_attribute_key, normalize_tag, normalize_tags, orchestrator, queries, Asset, AssetAttribute, AssetCursor, AssetFilter, AssetSearch, AssetMetadata, AssetSearchHit, AssetSortField, AttributeValue, CertificateSigner, DatabaseError, DomainError, InclusionProof, NfcAnchor, OrchestrateError,
+                  OwnershipClaim, ProofSide, ProofStep, ProvenanceEntry, TrailBreak, TrailIntegrityReport, UpdateAssetRequest, NFC};
 use crate::server::grpc::asset::asset_service_server::AssetService;
-use crate::server::grpc::asset::{Asset as GrpcAsset, CreateRequest, CreateResponse,
-                                 DeleteAssetRequest, DeleteAssetResponse,
+use crate::server::grpc::asset::{Asset as GrpcAsset, Certificate, CreateRequest, CreateResponse,
+                                 DeleteAssetRequest, DeleteAssetResponse, GetAssetProvenanceRequest, GetAssetProvenanceResponse,
+                                 GetCertificateRequest, GetCertificateResponse, GetInclusionProofRequest, GetInclusionProofResponse,
+                                 NfcAnchor as GrpcNfcAnchor, ProofStep as GrpcProofStep, AssetSearchResult, SearchAssetsRequest,
+                                 SearchAssetsResponse, AssetFilter as GrpcAssetFilter, ListAssetsRequest, ListAssetsResponse,
+                                 AssetAttribute as GrpcAssetAttribute, asset_attribute::Value as AttributeValueOneOf,
                                  GetAssetByIdRequest, GetAssetByIdResponse, GetAssetsNameLikeRequest, GetAssetsNameLikeResponse,
                                  GetPaginatedAssetsRequest, GetPaginatedAssetsResponse, GetStreamedAssetsRequest,
-                                 GetStreamedAssetsResponse, TransferAssetRequest, TransferAssetResponse, UpdateAssetRequest as GrpcUpdateAsset, UpdateAssetResponse};
+                                 GetStreamedAssetsResponse, TransferAssetRequest, TransferAssetResponse, UpdateAssetRequest as GrpcUpdateAsset, UpdateAssetResponse,
+                                 ProvenanceEntry as GrpcProvenanceEntry, TrailBreak as GrpcTrailBreak, VerifyCertificateRequest,
+                                 VerifyCertificateResponse, VerifyTrailIntegrityRequest, VerifyTrailIntegrityResponse};
 use crate::server::grpc::interceptors::trace_request;
-use crate::server::grpc::{get_xrf_user_auth_header, XRF_USER_FINGERPRINT};
+use crate::server::grpc::{get_xrf_admin_auth_header, get_xrf_user_auth_header, XRF_USER_FINGERPRINT};
+use chrono::DateTime;
 use prost_types::Timestamp;
-use sqlx::PgPool;
+use std::collections::HashSet;
 use std::pin::Pin;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -17,8 +26,6 @@ use tonic::codegen::tokio_stream::Stream;
 use tonic::{Request, Response, Status};
 use tracing::{debug, error, info, info_span, log};
 
-const MAX_DB_LIMIT: usize = 1000;
-
 const MAX_LIMIT: i16 = 100;
 
 impl From<Asset> for GrpcAsset {
@@ -40,20 +47,68 @@ impl From<Asset> for GrpcAsset {
             }),
             listable: asset.listable,
             tradable: asset.tradable,
+            tags: vec![],
+            attributes: vec![],
         }
     }
 }
 
-impl From<GrpcUpdateAsset> for UpdateAssetRequest {
-    fn from(value: GrpcUpdateAsset) -> Self {
-        UpdateAssetRequest {
+impl TryFrom<GrpcUpdateAsset> for UpdateAssetRequest {
+    type Error = Status;
+
+    fn try_from(value: GrpcUpdateAsset) -> Result<Self, Self::Error> {
+        let invalid_argument = |e: DomainError| Status::invalid_argument(e.to_string());
+        let tags = value.tags
+            .map(|tags| normalize_tags(&tags.tags))
+            .transpose()
+            .map_err(invalid_argument)?;
+        let remove_attributes = value.remove_attributes.iter()
+            .map(|key| normalize_attribute_key(key))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(invalid_argument)?;
+        Ok(UpdateAssetRequest {
+            tags,
+            remove_attributes,
+            set_attributes: to_attributes(value.set_attributes)?,
             name: value.name,
             symbol: value.symbol,
             tradable: value.tradable,
             listable: value.listable,
             description: value.description,
             organization: Option::from(value.org_id),
-        }
+        })
+    }
+}
+
+impl From<AssetAttribute> for GrpcAssetAttribute {
+    fn from(attribute: AssetAttribute) -> Self {
+        let value = match attribute.value {
+            AttributeValue::String(value) => AttributeValueOneOf::String(value),
+            AttributeValue::Number(value) => AttributeValueOneOf::Number(value),
+            AttributeValue::Bool(value) => AttributeValueOneOf::Bool(value),
+            AttributeValue::Date(value) => AttributeValueOneOf::Date(Timestamp {
+                seconds: value.timestamp(),
+                nanos: value.timestamp_subsec_nanos() as i32,
+            }),
+        };
+        GrpcAssetAttribute { key: attribute.key, value: Some(value) }
+    }
+}
+
+impl TryFrom<GrpcAssetAttribute> for AssetAttribute {
+    type Error = Status;
+
+    fn try_from(attribute: GrpcAssetAttribute) -> Result<Self, Self::Error> {
+        let value = match attribute.value {
+            Some(AttributeValueOneOf::String(value)) => AttributeValue::String(value),
+            Some(AttributeValueOneOf::Number(value)) => AttributeValue::Number(value),
+            Some(AttributeValueOneOf::Bool(value)) => AttributeValue::Bool(value),
+            Some(AttributeValueOneOf::Date(ts)) => DateTime::from_timestamp(ts.seconds, ts.nanos as u32)
+                .map(AttributeValue::Date)
+                .ok_or_else(|| Status::invalid_argument(format!("attribute {} has an invalid date", attribute.key)))?,
+            None => return Err(Status::invalid_argument(format!("attribute {} has no value", attribute.key))),
+        };
+        AssetAttribute::new(&attribute.key, value).map_err(|e| Status::invalid_argument(e.to_string()))
     }
 }
 
@@ -76,23 +131,197 @@ impl From<&Asset> for GrpcAsset {
             }),
             listable: asset.listable,
             tradable: asset.tradable,
+            tags: vec![],
+            attributes: vec![],
         }
     }
 }
 
 #[derive(Debug)]
-pub struct AssetServiceManager {
-    pg_pool: Arc<PgPool>,
+pub struct AssetServiceManager<R> {
+    repository: Arc<R>,
+    signer: CertificateSigner,
+    admin_fps: HashSet<String>,
 }
 
-impl AssetServiceManager {
-    pub fn new(pg_pool: Arc<PgPool>) -> Self {
-        AssetServiceManager { pg_pool }
+impl<R> AssetServiceManager<R> {
+    pub fn new(repository: Arc<R>, signer: CertificateSigner, admin_fps: HashSet<String>) -> Self {
+        AssetServiceManager { repository, signer, admin_fps }
+    }
+}
+
+impl Certificate {
+    fn new(nfc: NFC, owner_fp: String) -> Self {
+        Certificate {
+            asset_id: nfc.asset_id,
+            nfc_id: nfc.id,
+            created_at: Some(Timestamp {
+                seconds: nfc.created_at.timestamp(),
+                nanos: nfc.created_at.timestamp_subsec_nanos() as i32,
+            }),
+            owner_fp,
+            certificate: nfc.cert,
+        }
+    }
+}
+
+impl From<ProvenanceEntry> for GrpcProvenanceEntry {
+    fn from(entry: ProvenanceEntry) -> Self {
+        let trail = entry.trail;
+        GrpcProvenanceEntry {
+            nfc_id: trail.nfc_id,
+            owner_fp: trail.user_fp,
+            organization: trail.organization.unwrap_or_default(),
+            previous_owner_fp: trail.previous_owner_fp.unwrap_or_default(),
+            previous_org: trail.previous_org.unwrap_or_default(),
+            reason: trail.reason.to_string(),
+            transferred_on: Some(Timestamp {
+                seconds: trail.transferred_on.timestamp(),
+                nanos: trail.transferred_on.timestamp_subsec_nanos() as i32,
+            }),
+            sale_id: trail.sale_id,
+            price: entry.price.map(Into::into),
+        }
+    }
+}
+
+impl From<TrailBreak> for GrpcTrailBreak {
+    fn from(trail_break: TrailBreak) -> Self {
+        GrpcTrailBreak {
+            asset_id: trail_break.asset_id,
+            nfc_id: trail_break.nfc_id,
+            position: trail_break.position as u32,
+            transferred_on: Some(Timestamp {
+                seconds: trail_break.transferred_on.timestamp(),
+                nanos: trail_break.transferred_on.timestamp_subsec_nanos() as i32,
+            }),
+            kind: trail_break.kind.to_string(),
+        }
+    }
+}
+
+impl From<TrailIntegrityReport> for VerifyTrailIntegrityResponse {
+    fn from(report: TrailIntegrityReport) -> Self {
+        VerifyTrailIntegrityResponse {
+            intact: report.is_intact(),
+            checked_assets: report.checked_assets as u32,
+            breaks: report.breaks.into_iter().map(GrpcTrailBreak::from).collect(),
+        }
+    }
+}
+
+impl From<AssetSearchHit> for AssetSearchResult {
+    fn from(hit: AssetSearchHit) -> Self {
+        AssetSearchResult {
+            asset: Some(hit.asset.into()),
+            score: hit.score,
+            snippet: hit.snippet,
+        }
+    }
+}
+
+impl From<NfcAnchor> for GrpcNfcAnchor {
+    fn from(anchor: NfcAnchor) -> Self {
+        GrpcNfcAnchor {
+            id: anchor.id,
+            root: anchor.root,
+            leaf_count: anchor.leaf_count as u32,
+            window_start: Some(Timestamp {
+                seconds: anchor.window_start.timestamp(),
+                nanos: anchor.window_start.timestamp_subsec_nanos() as i32,
+            }),
+            window_end: Some(Timestamp {
+                seconds: anchor.window_end.timestamp(),
+                nanos: anchor.window_end.timestamp_subsec_nanos() as i32,
+            }),
+            anchored_at: Some(Timestamp {
+                seconds: anchor.created_at.timestamp(),
+                nanos: anchor.created_at.timestamp_subsec_nanos() as i32,
+            }),
+        }
+    }
+}
+
+impl From<ProofStep> for GrpcProofStep {
+    fn from(step: ProofStep) -> Self {
+        GrpcProofStep {
+            sibling: step.sibling,
+            left: step.side == ProofSide::Left,
+        }
+    }
+}
+
+impl From<InclusionProof> for GetInclusionProofResponse {
+    fn from(proof: InclusionProof) -> Self {
+        GetInclusionProofResponse {
+            anchor: Some(proof.anchor.into()),
+            nfc_id: proof.nfc.id,
+            asset_id: proof.nfc.asset_id,
+            created_at: Some(Timestamp {
+                seconds: proof.nfc.created_at.timestamp(),
+                nanos: proof.nfc.created_at.timestamp_subsec_nanos() as i32,
+            }),
+            leaf_hash: proof.leaf_hash,
+            leaf_index: proof.leaf_index as u32,
+            steps: proof.steps.into_iter().map(GrpcProofStep::from).collect(),
+        }
+    }
+}
+
+impl TryFrom<Certificate> for OwnershipClaim {
+    type Error = Status;
+
+    fn try_from(certificate: Certificate) -> Result<Self, Self::Error> {
+        let created_at = certificate.created_at
+            .and_then(|ts| DateTime::from_timestamp(ts.seconds, ts.nanos as u32))
+            .ok_or_else(|| Status::invalid_argument("created_at is required"))?;
+        Ok(OwnershipClaim {
+            asset_id: certificate.asset_id,
+            nfc_id: certificate.nfc_id,
+            created_at,
+            owner_fp: certificate.owner_fp,
+        })
+    }
+}
+
+impl TryFrom<GrpcAssetFilter> for AssetFilter {
+    type Error = Status;
+
+    fn try_from(filter: GrpcAssetFilter) -> Result<Self, Self::Error> {
+        let to_date_time = |ts: Option<Timestamp>, field: &str| match ts {
+            None => Ok(None),
+            Some(ts) => DateTime::from_timestamp(ts.seconds, ts.nanos as u32)
+                .map(Some)
+                .ok_or_else(|| Status::invalid_argument(format!("invalid {}", field))),
+        };
+        let filter = AssetFilter {
+            organization: filter.organization,
+            owner_fp: filter.owner_fp,
+            tradable: filter.tradable,
+            listable: filter.listable,
+            name_contains: None,
+            symbol_prefix: filter.symbol_prefix,
+            created_from: to_date_time(filter.created_from, "created_from")?,
+            created_until: to_date_time(filter.created_until, "created_until")?,
+            updated_from: to_date_time(filter.updated_from, "updated_from")?,
+            updated_until: to_date_time(filter.updated_until, "updated_until")?,
+            tags: filter.tags.iter()
+                .map(|tag| normalize_tag(tag))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| Status::invalid_argument(e.to_string()))?,
+            attributes: to_attributes(filter.attributes)?,
+            collection_id: filter.collection_id,
+        };
+        filter.validate().map_err(|e| Status::invalid_argument(e.to_string()))?;
+        Ok(filter)
     }
 }
 
 #[tonic::async_trait]
-impl AssetService for AssetServiceManager {
+impl<R> AssetService for AssetServiceManager<R>
+where
+    R: AssetRepository + ContractRepository + NfcRepository + 'static,
+{
     async fn create(&self, request: Request<CreateRequest>) -> Result<Response<CreateResponse>, Status> {
         trace_request!(request, "create_asset");
         let user_fp = get_xrf_user_auth_header(&request.metadata(), XRF_USER_FINGERPRINT)?;
@@ -107,9 +336,26 @@ impl AssetService for AssetServiceManager {
                 DomainError::InvalidArgument(err) => Status::invalid_argument(err.to_string()),
                 DomainError::ValidationError(err) => Status::invalid_argument(err.to_string()),
             })?;
-        let asset_create_resp = queries::create_new_asset(&asset, user_fp, &self.pg_pool).await;
+        if let Some(collection_id) = req.collection_id {
+            orchestrator::create_collection_asset(&asset, &collection_id, &user_fp, &self.signer, self.repository.as_ref())
+                .await
+                .map_err(|e| match e {
+                    OrchestrateError::NotFoundError(err) => Status::not_found(err),
+                    OrchestrateError::InvalidArgument(err) => Status::invalid_argument(err),
+                    OrchestrateError::InvalidState(err) => Status::failed_precondition(err),
+                    OrchestrateError::PermissionDenied(err) => Status::permission_denied(err),
+                    OrchestrateError::ServerError(err) => Status::internal(err),
+                    OrchestrateError::DatabaseError(err) => {
+                        error!("failed to create collection asset :: err={:?}", err);
+                        Status::internal("server error")
+                    }
+                })?;
+            return Ok(Response::new(CreateResponse { asset_id: asset.id }));
+        }
+        let asset_create_resp = self.repository.create_asset(&asset, &user_fp, &self.signer).await;
         if let Err(err) = asset_create_resp {
-            return Err(Status::internal(err.to_string()));
+            error!("Error creating asset: {:?}", err);
+            return Err(Status::internal("something went wrong"));
         }
         let response = CreateResponse { asset_id: asset.id };
         Ok(Response::new(response))
@@ -127,17 +373,18 @@ impl AssetService for AssetServiceManager {
             return Err(Status::invalid_argument("please provide a valid asset id and organization id"));
         }
 
-        let updated_asset_req: UpdateAssetRequest = req.into();
+        let updated_asset_req = UpdateAssetRequest::try_from(req)?;
 
         if updated_asset_req.name.is_none()
             && updated_asset_req.symbol.is_none()
             && updated_asset_req.listable.is_none()
             && updated_asset_req.tradable.is_none()
-            && updated_asset_req.description.is_none() {
+            && updated_asset_req.description.is_none()
+            && !updated_asset_req.updates_metadata() {
             return Err(Status::invalid_argument("At least one updatable field is required"));
         }
 
-        let response = match queries::update_asset(&asset_id, &user_fp, &updated_asset_req, &self.pg_pool).await {
+        let response = match self.repository.update_asset(&asset_id, &user_fp, &updated_asset_req).await {
             Ok(updated) => updated,
             Err(e) => {
                 return match e {
@@ -165,14 +412,14 @@ impl AssetService for AssetServiceManager {
         let org_id = req.org_id;
         let asset_id = req.asset_id;
 
-        let asset = queries::find_asset_by_id_and_org_id(&asset_id, &org_id, &self.pg_pool)
+        let asset = self.repository.find_asset_by_id_and_org_id(&asset_id, &org_id)
             .await
             .map_err(|e| match e {
                 DatabaseError::NotFound => Status::not_found("invalid org id or asset id"),
                 _ => Status::unknown("server error"),
             })?;
 
-        let asset_deleted = queries::delete_asset_by_id(&asset.id, &self.pg_pool)
+        let asset_deleted = self.repository.delete_asset_by_id(&asset.id)
             .await
             .map_err(|e| match e {
                 DatabaseError::NotFound => Status::not_found("invalid org id or asset id"),
@@ -190,14 +437,14 @@ impl AssetService for AssetServiceManager {
         let req = request.into_inner();
         info!("get asset by id :: id={}", &req.asset_id);
         let asset_id = req.asset_id;
-        let asset = queries::find_asset_by_id(&asset_id, &self.pg_pool)
+        let asset = self.repository.find_asset_by_id(&asset_id)
             .await
             .map_err(|e| match e {
                 DatabaseError::NotFound => Status::not_found("asset not found"),
                 _ => Status::unknown("server error"),
             })?;
         let response = GetAssetByIdResponse {
-            asset: Some(asset.into()),
+            asset: with_metadata(vec![asset], self.repository.as_ref()).await?.pop(),
         };
 
         Ok(Response::new(response))
@@ -213,32 +460,166 @@ impl AssetService for AssetServiceManager {
         let new_owner_id = req.new_owner_fp;
         let new_org_owner = req.new_owner_org_id;
         let nfc = orchestrator::transfer_asset(&org_id, &asset_id, &new_org_owner,
-                                               &new_owner_id, &self.pg_pool)
+                                               &new_owner_id, &self.signer, self.repository.as_ref())
             .await
             .map_err(|e| match e {
                 OrchestrateError::NotFoundError(err) => Status::not_found(err),
                 OrchestrateError::ServerError(err) => Status::internal(err.to_string()),
                 OrchestrateError::InvalidArgument(msg) => Status::invalid_argument(msg),
+                OrchestrateError::InvalidState(msg) => Status::failed_precondition(msg),
+                OrchestrateError::PermissionDenied(msg) => Status::permission_denied(msg),
                 OrchestrateError::DatabaseError(err) => Status::internal(err.to_string()),
             })?;
 
         Ok(Response::new(TransferAssetResponse { certificate_id: nfc.id }))
     }
 
+    async fn get_certificate(&self, request: Request<GetCertificateRequest>)
+                             -> Result<Response<GetCertificateResponse>, Status> {
+        trace_request!(request, "get_certificate");
+        get_xrf_user_auth_header(request.metadata(), XRF_USER_FINGERPRINT)?;
+        let req = request.into_inner();
+        info!("getting certificate :: assetId={}", &req.asset_id);
+
+        let (nfc, owner_fp) = orchestrator::find_owner_certificate(&req.asset_id, &self.signer, self.repository.as_ref())
+            .await
+            .map_err(|e| match e {
+                OrchestrateError::NotFoundError(err) => Status::not_found(err),
+                OrchestrateError::InvalidState(msg) => Status::failed_precondition(msg),
+                _ => {
+                    error!("failed to get certificate :: err={:?}", e);
+                    Status::internal("server error")
+                }
+            })?;
+
+        Ok(Response::new(GetCertificateResponse { certificate: Some(Certificate::new(nfc, owner_fp)) }))
+    }
+
+    async fn verify_certificate(&self, request: Request<VerifyCertificateRequest>)
+                                -> Result<Response<VerifyCertificateResponse>, Status> {
+        trace_request!(request, "verify_certificate");
+        let req = request.into_inner();
+        let certificate = req.certificate.ok_or_else(|| Status::invalid_argument("certificate is required"))?;
+        let signature = certificate.certificate.clone();
+        let claim = OwnershipClaim::try_from(certificate)?;
+        info!("verifying certificate :: assetId={} :: nfcId={}", &claim.asset_id, &claim.nfc_id);
+
+        let valid = self.signer.verify(&claim, &signature);
+        if !valid {
+            return Ok(Response::new(VerifyCertificateResponse { valid, current: false }));
+        }
+
+        // a valid certificate is current while the asset, its NFC and its owner are unchanged
+        let asset = match self.repository.find_asset_by_id(&claim.asset_id).await {
+            Ok(asset) => Some(asset),
+            Err(DatabaseError::NotFound) => None,
+            Err(e) => {
+                error!("failed to fetch asset of certificate :: err={:?}", e);
+                return Err(Status::internal("server error"));
+            }
+        };
+        let nfc = match self.repository.get_nfc_by_asset_id(&claim.asset_id).await {
+            Ok(nfc) => Some(nfc),
+            Err(DatabaseError::NotFound) => None,
+            Err(e) => {
+                error!("failed to fetch nfc of certificate :: err={:?}", e);
+                return Err(Status::internal("server error"));
+            }
+        };
+        let current = asset.is_some_and(|asset| asset.owner_fp == claim.owner_fp)
+            && nfc.is_some_and(|nfc| nfc.id == claim.nfc_id && nfc.created_at == claim.created_at);
+
+        Ok(Response::new(VerifyCertificateResponse { valid, current }))
+    }
+
+    async fn get_asset_provenance(&self, request: Request<GetAssetProvenanceRequest>)
+                                  -> Result<Response<GetAssetProvenanceResponse>, Status> {
+        trace_request!(request, "get_asset_provenance");
+        get_xrf_user_auth_header(request.metadata(), XRF_USER_FINGERPRINT)?;
+        let req = request.into_inner();
+        info!("getting asset provenance :: assetId={}", &req.asset_id);
+
+        let entries = self.repository.find_asset_provenance(&req.asset_id)
+            .await
+            .map_err(|e| {
+                error!("failed to get asset provenance :: err={:?}", e);
+                Status::internal("server error")
+            })?;
+        // every asset has at least the trail of its mint
+        if entries.is_empty() {
+            return Err(Status::not_found(req.asset_id));
+        }
+
+        let entries = entries.into_iter().map(GrpcProvenanceEntry::from).collect();
+        Ok(Response::new(GetAssetProvenanceResponse { entries }))
+    }
+
+    async fn verify_trail_integrity(&self, request: Request<VerifyTrailIntegrityRequest>)
+                                    -> Result<Response<VerifyTrailIntegrityResponse>, Status> {
+        trace_request!(request, "verify_trail_integrity");
+        let admin_fp = get_xrf_admin_auth_header(request.metadata(), &self.admin_fps)?;
+        let req = request.into_inner();
+        info!("verifying trail integrity :: adminFp={} :: assetId={:?}", admin_fp, &req.asset_id);
+
+        let report = match &req.asset_id {
+            Some(asset_id) => orchestrator::verify_trail_integrity(asset_id, self.repository.as_ref()).await,
+            None => orchestrator::verify_all_trails(self.repository.as_ref()).await,
+        }
+            .map_err(|e| match e {
+                OrchestrateError::NotFoundError(err) => Status::not_found(err),
+                _ => {
+                    error!("failed to verify trail integrity :: err={:?}", e);
+                    Status::internal("server error")
+                }
+            })?;
+
+        Ok(Response::new(report.into()))
+    }
+
+    async fn get_inclusion_proof(&self, request: Request<GetInclusionProofRequest>)
+                                 -> Result<Response<GetInclusionProofResponse>, Status> {
+        trace_request!(request, "get_inclusion_proof");
+        let req = request.into_inner();
+        info!("getting inclusion proof :: nfcId={}", &req.nfc_id);
+
+        let proof = orchestrator::find_inclusion_proof(&req.nfc_id, self.repository.as_ref())
+            .await
+            .map_err(|e| match e {
+                OrchestrateError::NotFoundError(err) => Status::not_found(err),
+                OrchestrateError::InvalidState(msg) => Status::failed_precondition(msg),
+                _ => {
+                    error!("failed to get inclusion proof :: err={:?}", e);
+                    Status::internal("server error")
+                }
+            })?;
+
+        Ok(Response::new(proof.into()))
+    }
+
     async fn get_assets_name_like(&self, request: Request<GetAssetsNameLikeRequest>) -> Result<Response<GetAssetsNameLikeResponse>, Status> {
         trace_request!(request, "get_assets_name_like");
         let req = request.into_inner();
-        info!("get assets name-like :: name={}", &req.name);
-        let assets = queries::find_assets_name_like(&req.name, req.offset as i64, req.limit as usize, queries::OrderType::Asc, &self.pg_pool)
+        validate_limit(req.limit)?;
+        let cursor = decode_cursor(req.cursor.as_deref())?;
+        info!("get assets name-like :: name={} :: after={:?}", &req.name, &cursor);
+        let filter = AssetFilter { name_contains: Some(req.name), ..Default::default() };
+        let sort = queries::AssetSort::new(AssetSortField::Name, queries::OrderType::from(req.sort_order));
+        let assets = self.repository.list_assets(&filter, sort, cursor.as_ref(), req.limit as i64)
             .await
             .map_err(|e| match e {
                 DatabaseError::NotFound => Status::not_found("No assets found"),
                 DatabaseError::InvalidArgument(err) => Status::invalid_argument(err.to_string()),
                 _ => Status::unknown("server error"),
             })?;
+        let total = self.repository.count_assets(&filter)
+            .await
+            .map_err(|e| {
+                error!("failed to count assets :: err={:?}", e);
+                Status::internal("server error")
+            })?;
         let response = GetAssetsNameLikeResponse {
-            offset: req.offset,
-            total: assets.len() as i32,
+            total,
+            next_cursor: next_cursor(&assets, req.limit as usize, AssetSortField::Name),
             assets: assets.into_iter()
                 .map(|a| a.into())
                 .collect(),
@@ -246,25 +627,79 @@ impl AssetService for AssetServiceManager {
         Ok(Response::new(response))
     }
 
+    async fn search_assets(&self, request: Request<SearchAssetsRequest>) -> Result<Response<SearchAssetsResponse>, Status> {
+        trace_request!(request, "search_assets");
+        let req = request.into_inner();
+        let search = AssetSearch::new(&req.query,
+                                      req.organization,
+                                      req.tradable,
+                                      req.listable,
+                                      req.limit as i64,
+                                      req.offset as i64)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        info!("searching assets :: {}", &search);
+
+        let hits = self.repository.search_assets(&search)
+            .await
+            .map_err(|e| {
+                error!("failed to search assets :: err={:?}", e);
+                Status::internal("server error")
+            })?;
+
+        Ok(Response::new(SearchAssetsResponse {
+            offset: req.offset,
+            results: hits.into_iter().map(AssetSearchResult::from).collect(),
+        }))
+    }
+
+    async fn list_assets(&self, request: Request<ListAssetsRequest>) -> Result<Response<ListAssetsResponse>, Status> {
+        trace_request!(request, "list_assets");
+        let req = request.into_inner();
+        validate_limit(req.limit)?;
+        let filter = AssetFilter::try_from(req.filter.unwrap_or_default())?;
+        let sort_field = match req.sort_by.trim() {
+            "" => AssetSortField::default(),
+            sort_by => AssetSortField::from_str(sort_by)
+                .map_err(|_| Status::invalid_argument("sort_by is invalid"))?,
+        };
+        let sort_order = queries::OrderType::from_str(&req.sort_order)
+            .map_err(|_| Status::invalid_argument("sort_order is invalid"))?;
+        let sort = queries::AssetSort::new(sort_field, sort_order);
+        let cursor = decode_cursor(req.cursor.as_deref())?;
+        info!("listing assets :: filter={} :: sort={} :: after={:?} :: limit={}", &filter, &sort, &cursor, req.limit);
+
+        let assets = self.repository.list_assets(&filter, sort, cursor.as_ref(), req.limit as i64)
+            .await
+            .map_err(|e| match e {
+                DatabaseError::InvalidArgument(err) => Status::invalid_argument(err),
+                e => {
+                    error!("failed to list assets :: err={:?}", e);
+                    Status::internal("server error")
+                }
+            })?;
+        let total = count_assets(&filter, self.repository.as_ref()).await?;
+
+        Ok(Response::new(ListAssetsResponse {
+            total,
+            next_cursor: next_cursor(&assets, req.limit as usize, sort_field),
+            assets: with_metadata(assets, self.repository.as_ref()).await?,
+        }))
+    }
+
     async fn get_paginated_assets(&self, request: Request<GetPaginatedAssetsRequest>) -> Result<Response<GetPaginatedAssetsResponse>, Status> {
         trace_request!(request, "get_paginated_assets");
 
         let req = request.into_inner();
-        if req.offset < 0 || req.limit < 0 || (req.offset == 0 && req.limit == 0) {
-            return Err(Status::invalid_argument("start and limit must be positive"));
-        }
-        if req.limit < 1 || req.limit > 3000 {
-            return Err(Status::invalid_argument("limit must be between 1 and 3000"));
-        }
-        let limit = req.limit as i16;
-        let offset = req.offset as i64;
+        validate_limit(req.limit)?;
+        let cursor = decode_cursor(req.cursor.as_deref())?;
 
-        info!("fetching paginated assets :: offset={} limit={}", req.offset, &limit);
-        let assets = fetch_assets(&self.pg_pool, offset, limit, &req.sort_order).await?;
+        info!("fetching paginated assets :: after={:?} limit={}", &cursor, req.limit);
+        let assets = fetch_assets(self.repository.as_ref(), cursor.as_ref(), req.limit as i64, &req.sort_order).await?;
+        let total = count_assets(&AssetFilter::default(), self.repository.as_ref()).await?;
 
         let response = GetPaginatedAssetsResponse {
-            offset: req.offset,
-            total: assets.len() as i32,
+            total,
+            next_cursor: next_cursor(&assets, req.limit as usize, AssetSortField::Name),
             assets: assets.into_iter()
                 .map(|a| a.into())
                 .collect(),
@@ -279,28 +714,24 @@ impl AssetService for AssetServiceManager {
                                  -> Result<Response<Self::GetStreamedAssetsStream>, Status> {
         trace_request!(request, "get_streamed_assets");
         let req = request.into_inner();
-        validate_request_parameters(req.offset, req.limit)?;
-
-        let limit: usize = req.limit
-            .try_into()
-            .map_err(|_| Status::invalid_argument("limit is invalid"))?; // Use usize for consistency and indexing
-        let mut offset = req.offset
-            .try_into()
-            .map_err(|_| Status::invalid_argument("offset is invalid"))?;
-        debug!("streaming assets :: startingAt={} limit={}", offset, limit);
+        validate_limit(req.limit)?;
+        let mut cursor = decode_cursor(req.cursor.as_deref())?;
+        // the sort order is checked before the stream starts
+        queries::OrderType::from_str(&req.sort_order)
+            .map_err(|_| Status::invalid_argument("sort_order is invalid"))?;
 
-        let pool = self.pg_pool.clone();
+        let limit = req.limit as usize;
+        debug!("streaming assets :: after={:?} limit={}", &cursor, limit);
+        let total = count_assets(&AssetFilter::default(), self.repository.as_ref()).await?;
 
-        let max_offset = 9999999; // only usage is to avoid infinite loops
+        let repository = self.repository.clone();
         let stream = async_stream::stream! {
-            let batch_size = (limit * 10).min(MAX_DB_LIMIT); // Fetch 10 times the requested limit for efficiency
+            // Fetch 10 times the requested limit for efficiency
+            let batch_size = (limit * 10).min(queries::MAX_PAGE_SIZE as usize);
 
             loop {
-                if offset >= max_offset {
-                    break;
-                }
-                // 1. Fetch a larger batch of assets
-                let mut batch_assets = match fetch_assets(&pool, offset, batch_size as i16, &req.sort_order).await {
+                // 1. Fetch a larger batch of assets after the last one sent
+                let batch_assets = match fetch_assets(repository.as_ref(), cursor.as_ref(), batch_size as i64, &req.sort_order).await {
                     Ok(assets) => assets,
                     Err(e) => {
                         error!("Failed to fetch assets from database: {:?}", e);
@@ -310,34 +741,29 @@ impl AssetService for AssetServiceManager {
                 };
 
                 // 2. Break the loop if there are no more assets
-                if batch_assets.is_empty() {
+                let Some(last) = batch_assets.last() else {
                     break; // End of data
+                };
+                let last_batch = batch_assets.len() < batch_size;
+                cursor = Some(AssetCursor::after(last, AssetSortField::Name));
+
+                // 3. Send the batch in messages of the user's limit
+                let messages = batch_assets.len().div_ceil(limit);
+                for (i, assets_to_send) in batch_assets.chunks(limit).enumerate() {
+                    // the last message of the stream has no next page
+                    let next_cursor = if last_batch && i + 1 == messages {
+                        None
+                    } else {
+                        assets_to_send.last().map(|asset| AssetCursor::after(asset, AssetSortField::Name).encode())
+                    };
+                    yield Ok(GetStreamedAssetsResponse {
+                        total,
+                        next_cursor,
+                        assets: assets_to_send.iter().map(GrpcAsset::from).collect(),
+                    });
                 }
-
-                while !batch_assets.is_empty() {
-                    // 2. Take the user's limit from the fetched batch
-                    let assets_to_send: Vec<_> = batch_assets.drain(..limit.min(batch_assets.len())).collect();
-                    let total_assets = assets_to_send.len() as i32;
-
-                    // Create a new Vec for the response to avoid consuming assets_to_send
-                    let assets_response: Result<Vec<_>, Status> = assets_to_send.iter()
-                        .map(|a| a.try_into().map_err(|_| Status::internal("Failed to convert asset")))
-                        .collect();
-                    // 3. Yield the response
-                    match assets_response {
-                        Ok(assets) => yield Ok(GetStreamedAssetsResponse {
-                            offset: offset as i32,
-                            total: total_assets,
-                            assets,
-                        }),
-                        Err(e) => {
-                            error!("Failed to serialize assets to be streamed: {:?}", e);
-                            yield Err(e);
-                            break;
-                        }
-                    }
-
-                    offset += total_assets as i64; // Update start based on the actual sent assets
+                if last_batch {
+                    break;
                 }
             }
         };
@@ -347,20 +773,65 @@ impl AssetService for AssetServiceManager {
 }
 
 ///// Helper methods
-fn validate_request_parameters(start: i32, limit: i32) -> Result<(), Status> {
-    if start < 0 || limit < 0 || (start == 0 && limit == 0) {
-        return Err(Status::invalid_argument("start and limit must be positive"));
-    }
+pub(super) fn validate_limit(limit: i32) -> Result<(), Status> {
     if limit < 1 || limit > MAX_LIMIT.into() {
         return Err(Status::invalid_argument("limit must be between 1 and 100"));
     }
     Ok(())
 }
 
-async fn fetch_assets(pg_pool: &PgPool, start: i64, limit: i16, sort_order: &str) -> Result<Vec<Asset>, Status> {
+pub(super) fn decode_cursor(token: Option<&str>) -> Result<Option<AssetCursor>, Status> {
+    AssetCursor::from_token(token).map_err(|e| Status::invalid_argument(e.to_string()))
+}
+
+/// Token of the page after `assets` in a list sorted by `field`, None when the page is not full and there is nothing after it
+pub(super) fn next_cursor(assets: &[Asset], limit: usize, field: AssetSortField) -> Option<String> {
+    if assets.len() < limit {
+        return None;
+    }
+    assets.last().map(|asset| AssetCursor::after(asset, field).encode())
+}
+
+fn to_attributes(attributes: Vec<GrpcAssetAttribute>) -> Result<Vec<AssetAttribute>, Status> {
+    attributes.into_iter().map(AssetAttribute::try_from).collect()
+}
+
+/// Assets with their tags and attributes, loaded for the whole page at once
+pub(super) async fn with_metadata<R: AssetRepository>(assets: Vec<Asset>, repository: &R) -> Result<Vec<GrpcAsset>, Status> {
+    let asset_ids: Vec<String> = assets.iter().map(|asset| asset.id.clone()).collect();
+    let mut metadata = repository.find_asset_metadata(&asset_ids)
+        .await
+        .map_err(|e| {
+            error!("failed to fetch asset metadata :: err={:?}", e);
+            Status::internal("server error")
+        })?;
+    Ok(assets.into_iter()
+        .map(|asset| {
+            let AssetMetadata { tags, attributes } = metadata.remove(&asset.id).unwrap_or_default();
+            GrpcAsset {
+                tags,
+                attributes: attributes.into_iter().map(GrpcAssetAttribute::from).collect(),
+                ..asset.into()
+            }
+        })
+        .collect())
+}
+
+pub(super) async fn count_assets<R: AssetRepository>(filter: &AssetFilter, repository: &R) -> Result<i64, Status> {
+    repository.count_assets(filter)
+        .await
+        .map_err(|e| {
+            error!("failed to count assets :: err={:?}", e);
+            Status::internal("server error")
+        })
+}
+
+/// Page of assets ordered by name then id, same as `queries::get_all_assets`
+async fn fetch_assets<R: AssetRepository>(repository: &R, cursor: Option<&AssetCursor>, limit: i64, sort_order: &str) -> Result<Vec<Asset>, Status> {
     let order_type = queries::OrderType::from_str(sort_order)
         .map_err(|_| Status::invalid_argument("sort_order is invalid"))?;
-    queries::get_all_assets(pg_pool, start, limit as i64, order_type)
+    let sort = queries::AssetSort::new(AssetSortField::Name, order_type);
+    repository.list_assets(&AssetFilter::default(), sort, cursor, limit)
         .await
         .map_err(|e| {
             match e {
@@ -373,3 +844,84 @@ async fn fetch_assets(pg_pool: &PgPool, start: i64, limit: i16, sort_order: &str
             }
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::repository::InMemoryRepository;
+    use crate::core::{test_currency, Contract, Currency, Money};
+    use tonic::Code;
+    use uuid::Uuid;
+
+    fn user_fp() -> String {
+        format!("{}{}", Uuid::new_v4(), Uuid::new_v4())
+    }
+
+    fn request<T>(message: T, user_fp: &str) -> Request<T> {
+        let mut request = Request::new(message);
+        request.metadata_mut().insert(XRF_USER_FINGERPRINT, user_fp.parse().unwrap());
+        request
+    }
+
+    #[tokio::test]
+    async fn test_asset_service_on_in_memory_repository() -> Result<(), Box<dyn std::error::Error>> {
+        let owner = user_fp();
+        let new_owner = user_fp();
+        let admin = user_fp();
+        let service = AssetServiceManager::new(Arc::new(InMemoryRepository::default()),
+                                               CertificateSigner::generate()?,
+                                               HashSet::from([admin.clone()]));
+
+        let org_id = Uuid::new_v4().to_string();
+        let create = CreateRequest {
+            name: "memory asset".to_string(),
+            symbol: "XRF-MEM".to_string(),
+            description: "asset of the in memory repository".to_string(),
+            organization: org_id.clone(),
+            collection_id: None,
+        };
+        let asset_id = service.create(request(create.clone(), &owner)).await?.into_inner().asset_id;
+
+        let new_org_id = Uuid::new_v4().to_string();
+        let transfer = TransferAssetRequest {
+            org_id,
+            asset_id: asset_id.clone(),
+            new_owner_fp: new_owner.clone(),
+            new_owner_org_id: new_org_id,
+        };
+        // transfers lock the contract of the asset
+        let min_price = Money::parse("20.00", &test_currency(Currency::USD))?;
+        let contract = Contract::new(asset_id.clone(), "details".to_string(), "summary".to_string(), owner.clone(),
+                                     min_price, false, vec![], HashSet::from([Currency::USD]))?;
+        service.repository.create_contract(contract).await?;
+
+        let nfc_id = service.transfer_asset(request(transfer, &owner)).await?.into_inner().certificate_id;
+
+        let certificate = service.get_certificate(request(GetCertificateRequest { asset_id: asset_id.clone() }, &owner))
+            .await?
+            .into_inner()
+            .certificate
+            .expect("certificate is set");
+        assert_eq!(certificate.nfc_id, nfc_id);
+        assert_eq!(certificate.owner_fp, new_owner);
+
+        let provenance = service.get_asset_provenance(request(GetAssetProvenanceRequest { asset_id: asset_id.clone() }, &owner))
+            .await?
+            .into_inner();
+        assert_eq!(provenance.entries.len(), 2);
+
+        let verification = service.verify_trail_integrity(request(VerifyTrailIntegrityRequest { asset_id: None }, &admin))
+            .await?
+            .into_inner();
+        assert!(verification.intact);
+        assert_eq!(verification.checked_assets, 1);
+
+        let proof = service.get_inclusion_proof(request(GetInclusionProofRequest { nfc_id }, &owner)).await;
+        assert_eq!(proof.unwrap_err().code(), Code::FailedPrecondition);
+
+        let in_collection = CreateRequest { collection_id: Some(Uuid::new_v4().to_string()), ..create };
+        let created = service.create(request(in_collection, &owner)).await;
+        assert_eq!(created.unwrap_err().code(), Code::NotFound);
+        Ok(())
+    }
+}
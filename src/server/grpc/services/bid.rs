@@ -0,0 +1,257 @@
+use crate::constant::REQUEST_ID_KEY;
+use crate::core::{orchestrator, queries, AuctionType, Bid, BidStatus, DatabaseError, FxPolicy, OrchestrateError};
+use crate::server::grpc::asset::bid_service_server::BidService;
+use crate::server::grpc::asset::{AcceptBidRequest, AcceptBidResponse, Bid as GrpcBid, ListBidsByBidderRequest, ListBidsByBidderResponse,
+                                 ListBidsForAssetRequest, ListBidsForAssetResponse, PlaceBidRequest,
+                                 PlaceBidResponse, WithdrawBidRequest, WithdrawBidResponse};
+use crate::server::grpc::interceptors::trace_request;
+use crate::server::grpc::money::{find_currency_registry, process_money};
+use crate::server::grpc::{get_xrf_user_auth_header, XRF_USER_FINGERPRINT};
+use chrono::{DateTime, Duration};
+use prost_types::Timestamp;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use tracing::{error, info, info_span, warn};
+
+const MAX_LIMIT: i32 = 100;
+
+pub struct BidServiceManager {
+    pg_pool: Arc<PgPool>,
+    fx_policy: FxPolicy,
+    hold_ttl: Duration,
+}
+
+impl BidServiceManager {
+    pub fn new(pg_pool: Arc<PgPool>, fx_policy: FxPolicy, hold_ttl: Duration) -> Self {
+        BidServiceManager { pg_pool, fx_policy, hold_ttl }
+    }
+}
+
+impl From<Bid> for GrpcBid {
+    fn from(bid: Bid) -> Self {
+        GrpcBid {
+            id: bid.id,
+            asset_id: bid.asset_id,
+            bidder_fp: bid.bidder_fp,
+            bidder_org: bid.bidder_org,
+            amount: Some(bid.amount.into()),
+            anonymous: bid.anonymous,
+            status: bid.status.to_string(),
+            expires_at: Some(Timestamp {
+                seconds: bid.expires_at.timestamp(),
+                nanos: bid.expires_at.timestamp_subsec_nanos() as i32,
+            }),
+            created_at: Some(Timestamp {
+                seconds: bid.created_at.timestamp(),
+                nanos: bid.created_at.timestamp_subsec_nanos() as i32,
+            }),
+            updated_at: Some(Timestamp {
+                seconds: bid.updated_at.timestamp(),
+                nanos: bid.updated_at.timestamp_subsec_nanos() as i32,
+            }),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl BidService for BidServiceManager {
+    async fn place_bid(&self, request: Request<PlaceBidRequest>) -> Result<Response<PlaceBidResponse>, Status> {
+        trace_request!(request, "place_bid");
+        let user_fp = get_xrf_user_auth_header(request.metadata(), XRF_USER_FINGERPRINT)?;
+        let req = request.into_inner();
+        info!("placing bid :: assetId={}", &req.asset_id);
+
+        let registry = find_currency_registry(&self.pg_pool).await?;
+        let amount = process_money(req.amount, "amount", &registry)?;
+        let expires_at = match req.expires_at {
+            None => None,
+            Some(ts) => Some(DateTime::from_timestamp(ts.seconds, ts.nanos as u32)
+                .ok_or_else(|| Status::invalid_argument("invalid expires_at"))?),
+        };
+
+        let (bid, contract) = orchestrator::place_bid(&req.asset_id,
+                                                      user_fp,
+                                                      req.org_id,
+                                                      amount,
+                                                      req.anonymous,
+                                                      expires_at,
+                                                      &self.fx_policy,
+                                                      &self.pg_pool)
+            .await
+            .map_err(|e| match e {
+                OrchestrateError::NotFoundError(err) => Status::not_found(err),
+                OrchestrateError::ServerError(err) => Status::internal(err),
+                OrchestrateError::InvalidArgument(msg) => Status::invalid_argument(msg),
+                OrchestrateError::InvalidState(msg) => Status::failed_precondition(msg),
+                OrchestrateError::PermissionDenied(msg) => Status::permission_denied(msg),
+                OrchestrateError::DatabaseError(err) => {
+                    error!("failed to place bid :: err={:?}", err);
+                    Status::internal("server error")
+                }
+            })?;
+
+        // the first bid meeting the price of a dutch auction wins it
+        let is_dutch_auction = contract.auction.as_ref().is_some_and(|a| a.auction_type == AuctionType::Dutch);
+        if is_dutch_auction {
+            if let Err(e) = orchestrator::close_auction(&bid.asset_id, self.hold_ttl, &self.pg_pool).await {
+                warn!("failed to close dutch auction, it will be closed when it ends :: err={}", e);
+            }
+        }
+
+        Ok(Response::new(PlaceBidResponse { bid_id: bid.id }))
+    }
+
+    async fn accept_bid(&self, request: Request<AcceptBidRequest>) -> Result<Response<AcceptBidResponse>, Status> {
+        trace_request!(request, "accept_bid");
+        let user_fp = get_xrf_user_auth_header(request.metadata(), XRF_USER_FINGERPRINT)?;
+        let req = request.into_inner();
+        info!("accepting bid :: bidId={}", &req.bid_id);
+
+        let hold = orchestrator::accept_bid(&req.bid_id, &user_fp, &self.fx_policy, self.hold_ttl, &self.pg_pool)
+            .await
+            .map_err(|e| match e {
+                OrchestrateError::NotFoundError(err) => Status::not_found(err),
+                OrchestrateError::ServerError(err) => Status::internal(err),
+                OrchestrateError::InvalidArgument(msg) => Status::invalid_argument(msg),
+                OrchestrateError::InvalidState(msg) => Status::failed_precondition(msg),
+                OrchestrateError::PermissionDenied(msg) => Status::permission_denied(msg),
+                OrchestrateError::DatabaseError(err) => {
+                    error!("failed to accept bid :: err={:?}", err);
+                    Status::internal("server error")
+                }
+            })?;
+
+        let response = AcceptBidResponse {
+            escrow_hold_id: hold.id,
+            funding_deadline: Some(Timestamp {
+                seconds: hold.deadline.timestamp(),
+                nanos: hold.deadline.timestamp_subsec_nanos() as i32,
+            }),
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn withdraw_bid(&self, request: Request<WithdrawBidRequest>)
+                          -> Result<Response<WithdrawBidResponse>, Status> {
+        trace_request!(request, "withdraw_bid");
+        let user_fp = get_xrf_user_auth_header(request.metadata(), XRF_USER_FINGERPRINT)?;
+        let req = request.into_inner();
+        info!("withdrawing bid :: bidId={}", &req.bid_id);
+
+        let bid = queries::find_bid_by_id(&req.bid_id, self.pg_pool.as_ref())
+            .await
+            .map_err(|e| match e {
+                DatabaseError::NotFound => Status::not_found("bid not found"),
+                _ => Status::internal("server error"),
+            })?;
+        // do not leak the existence of bids placed by other users
+        if bid.bidder_fp != user_fp {
+            return Err(Status::not_found("bid not found"));
+        }
+        if !bid.is_open() {
+            return Err(Status::failed_precondition(format!("bid is not open :: status={}", bid.status)));
+        }
+
+        let withdrawn = queries::update_bid_status(&bid.id, BidStatus::Open, BidStatus::Withdrawn, self.pg_pool.as_ref())
+            .await
+            .map_err(|e| {
+                error!("failed to withdraw bid :: err={:?}", e);
+                Status::internal("server error")
+            })?;
+
+        Ok(Response::new(WithdrawBidResponse { withdrawn }))
+    }
+
+    async fn list_bids_for_asset(&self, request: Request<ListBidsForAssetRequest>)
+                                 -> Result<Response<ListBidsForAssetResponse>, Status> {
+        trace_request!(request, "list_bids_for_asset");
+        let user_fp = get_xrf_user_auth_header(request.metadata(), XRF_USER_FINGERPRINT)?;
+        let req = request.into_inner();
+        validate_request_parameters(req.offset, req.limit)?;
+        info!("listing bids for asset :: assetId={}", &req.asset_id);
+
+        // bids of a sealed-bid auction are only visible by their bidder until the auction closes
+        let sealed = match queries::find_contract_by_asset_id(&req.asset_id, &self.pg_pool).await {
+            Ok(contract) => contract.auction.is_some_and(|auction| auction.is_sealed()),
+            Err(DatabaseError::NotFound) => false,
+            Err(e) => {
+                error!("failed to fetch contract for bids :: err={:?}", e);
+                return Err(Status::internal("server error"));
+            }
+        };
+        let bidder_fp = sealed.then_some(user_fp.as_str());
+
+        let bids = queries::find_bids_by_asset_id(&req.asset_id, bidder_fp, req.limit as i64, req.offset as i64, &self.pg_pool)
+            .await
+            .map_err(|e| {
+                error!("failed to list bids for asset :: err={:?}", e);
+                Status::internal("server error")
+            })?;
+        let total = queries::count_bids_by_asset_id(&req.asset_id, bidder_fp, self.pg_pool.as_ref())
+            .await
+            .map_err(|e| {
+                error!("failed to count bids for asset :: err={:?}", e);
+                Status::internal("server error")
+            })?;
+
+        let response = ListBidsForAssetResponse {
+            offset: req.offset,
+            total: total as i32,
+            bids: bids.into_iter()
+                .map(|bid| mask_anonymous_bidder(bid, &user_fp).into())
+                .collect(),
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn list_bids_by_bidder(&self, request: Request<ListBidsByBidderRequest>)
+                                 -> Result<Response<ListBidsByBidderResponse>, Status> {
+        trace_request!(request, "list_bids_by_bidder");
+        let user_fp = get_xrf_user_auth_header(request.metadata(), XRF_USER_FINGERPRINT)?;
+        let req = request.into_inner();
+        validate_request_parameters(req.offset, req.limit)?;
+
+        let bids = queries::find_bids_by_bidder(&user_fp, req.limit as i64, req.offset as i64, &self.pg_pool)
+            .await
+            .map_err(|e| {
+                error!("failed to list bids by bidder :: err={:?}", e);
+                Status::internal("server error")
+            })?;
+        let total = queries::count_bids_by_bidder(&user_fp, self.pg_pool.as_ref())
+            .await
+            .map_err(|e| {
+                error!("failed to count bids by bidder :: err={:?}", e);
+                Status::internal("server error")
+            })?;
+
+        let response = ListBidsByBidderResponse {
+            offset: req.offset,
+            total: total as i32,
+            bids: bids.into_iter()
+                .map(|bid| bid.into())
+                .collect(),
+        };
+        Ok(Response::new(response))
+    }
+}
+
+///// Helper methods
+fn validate_request_parameters(offset: i32, limit: i32) -> Result<(), Status> {
+    if offset < 0 {
+        return Err(Status::invalid_argument("offset must be positive"));
+    }
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(Status::invalid_argument(format!("limit must be between 1 and {}", MAX_LIMIT)));
+    }
+    Ok(())
+}
+
+// anonymous bidders are only visible to themselves
+fn mask_anonymous_bidder(mut bid: Bid, caller_fp: &str) -> Bid {
+    if bid.anonymous && bid.bidder_fp != caller_fp {
+        bid.bidder_fp = "".to_string();
+        bid.bidder_org = "".to_string();
+    }
+    bid
+}
@@ -0,0 +1,247 @@
+use crate::constant::REQUEST_ID_KEY;
+use crate::core::repository::{AssetRepository, PgRepository};
+use crate::core::{orchestrator, queries, AssetFilter, AssetSortField, Collection, ContractTerms, CurrencyRegistry, DatabaseError,
+                  DefaultContract, OrchestrateError, UpdateCollectionRequest as CollectionUpdate};
+use crate::server::grpc::asset::collection_service_server::CollectionService;
+use crate::server::grpc::asset::{Collection as GrpcCollection, CreateCollectionRequest, CreateCollectionResponse,
+                                 DefaultContract as GrpcDefaultContract, DeleteCollectionRequest, DeleteCollectionResponse,
+                                 GetCollectionRequest, GetCollectionResponse, ListCollectionAssetsRequest, ListCollectionAssetsResponse,
+                                 RoyaltySplit as GrpcRoyaltySplit, UpdateCollectionRequest, UpdateCollectionResponse};
+use crate::server::grpc::interceptors::trace_request;
+use crate::server::grpc::money::{find_currency_registry, process_money};
+use crate::server::grpc::services::asset::{count_assets, decode_cursor, next_cursor, validate_limit, with_metadata};
+use crate::server::grpc::services::contract::{process_accepted_currencies, process_contract_terms, process_royalty_splits};
+use crate::server::grpc::{get_xrf_user_auth_header, XRF_USER_FINGERPRINT};
+use prost_types::Timestamp;
+use sqlx::PgPool;
+use std::str::FromStr;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use tracing::{error, info, info_span};
+
+pub struct CollectionServiceManager {
+    /// assets of the collections are listed through the asset repository
+    repository: PgRepository,
+    pg_pool: Arc<PgPool>,
+}
+
+impl CollectionServiceManager {
+    pub fn new(pg_pool: Arc<PgPool>) -> Self {
+        CollectionServiceManager { repository: PgRepository::new(pg_pool.clone()), pg_pool }
+    }
+}
+
+impl From<DefaultContract> for GrpcDefaultContract {
+    fn from(default_contract: DefaultContract) -> Self {
+        GrpcDefaultContract {
+            summary: default_contract.summary,
+            details: default_contract.details,
+            min_price: Some(default_contract.min_price.into()),
+            anonymous_buyers: default_contract.anonymous_buyer_only,
+            accepted_currencies: default_contract.accepted_currency.into_iter().map(|c| c.to_string()).collect(),
+            royalty_splits: default_contract.royalty_splits.into_iter().map(GrpcRoyaltySplit::from).collect(),
+            terms: Some(default_contract.terms.into()),
+        }
+    }
+}
+
+fn process_default_contract(default_contract: GrpcDefaultContract, registry: &CurrencyRegistry) -> Result<DefaultContract, Status> {
+    let terms = match default_contract.terms {
+        None => ContractTerms::default(),
+        Some(terms) => process_contract_terms(terms)?,
+    };
+    let accepted_currency = process_accepted_currencies(default_contract.accepted_currencies, registry)
+        .map_err(Status::invalid_argument)?;
+    DefaultContract::new(default_contract.details,
+                         default_contract.summary,
+                         process_money(default_contract.min_price, "default contract min_price", registry)?,
+                         default_contract.anonymous_buyers,
+                         process_royalty_splits(default_contract.royalty_splits),
+                         terms,
+                         accepted_currency)
+        .map_err(|e| Status::invalid_argument(e.to_string()))
+}
+
+fn to_grpc_collection(collection: Collection, asset_count: i64) -> GrpcCollection {
+    GrpcCollection {
+        asset_count,
+        id: collection.id,
+        organization: collection.organization,
+        name: collection.name,
+        symbol_prefix: collection.symbol_prefix,
+        description: collection.description,
+        supply_cap: collection.supply_cap,
+        default_contract: collection.default_contract.map(GrpcDefaultContract::from),
+        created_by: collection.created_by,
+        updated_by: collection.updated_by,
+        created_at: Some(Timestamp {
+            seconds: collection.created_at.timestamp(),
+            nanos: collection.created_at.timestamp_subsec_nanos() as i32,
+        }),
+        updated_at: Some(Timestamp {
+            seconds: collection.updated_at.timestamp(),
+            nanos: collection.updated_at.timestamp_subsec_nanos() as i32,
+        }),
+    }
+}
+
+fn process_collection_update(req: UpdateCollectionRequest, registry: &CurrencyRegistry) -> Result<CollectionUpdate, Status> {
+    let supply_cap = match (req.supply_cap, req.remove_supply_cap) {
+        (Some(_), true) => return Err(Status::invalid_argument("supply_cap can not be set and removed at once")),
+        (Some(supply_cap), false) => Some(Some(supply_cap)),
+        (None, true) => Some(None),
+        (None, false) => None,
+    };
+    let default_contract = match (req.default_contract, req.remove_default_contract) {
+        (Some(_), true) => return Err(Status::invalid_argument("default_contract can not be set and removed at once")),
+        (Some(default_contract), false) => Some(Some(process_default_contract(default_contract, registry)?)),
+        (None, true) => Some(None),
+        (None, false) => None,
+    };
+    Ok(CollectionUpdate { name: req.name, description: req.description, supply_cap, default_contract })
+}
+
+#[tonic::async_trait]
+impl CollectionService for CollectionServiceManager {
+    async fn create_collection(&self, request: Request<CreateCollectionRequest>)
+                               -> Result<Response<CreateCollectionResponse>, Status> {
+        trace_request!(request, "create_collection");
+        let user_fp = get_xrf_user_auth_header(request.metadata(), XRF_USER_FINGERPRINT)?;
+        let req = request.into_inner();
+        info!("creating collection :: (name={} -> symbolPrefix={})", &req.name, &req.symbol_prefix);
+        let registry = find_currency_registry(&self.pg_pool).await?;
+        let default_contract = req.default_contract
+            .map(|default_contract| process_default_contract(default_contract, &registry))
+            .transpose()?;
+        let collection = Collection::new(req.organization,
+                                         req.name,
+                                         req.symbol_prefix,
+                                         req.description,
+                                         req.supply_cap,
+                                         default_contract,
+                                         user_fp)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        orchestrator::create_collection(&collection, &self.pg_pool)
+            .await
+            .map_err(|e| to_status(e, "create collection"))?;
+        Ok(Response::new(CreateCollectionResponse { collection_id: collection.id }))
+    }
+
+    async fn get_collection(&self, request: Request<GetCollectionRequest>)
+                            -> Result<Response<GetCollectionResponse>, Status> {
+        trace_request!(request, "get_collection");
+        let req = request.into_inner();
+        info!("getting collection :: id={}", &req.collection_id);
+        let collection = queries::find_collection_by_id(&req.collection_id, &self.pg_pool)
+            .await
+            .map_err(|e| match e {
+                DatabaseError::NotFound => Status::not_found("collection not found"),
+                _ => {
+                    error!("failed to get collection :: err={:?}", e);
+                    Status::internal("server error")
+                }
+            })?;
+        let asset_count = count_collection_assets(&collection.id, &self.pg_pool).await?;
+        Ok(Response::new(GetCollectionResponse { collection: Some(to_grpc_collection(collection, asset_count)) }))
+    }
+
+    async fn update_collection(&self, request: Request<UpdateCollectionRequest>)
+                               -> Result<Response<UpdateCollectionResponse>, Status> {
+        trace_request!(request, "update_collection");
+        let user_fp = get_xrf_user_auth_header(request.metadata(), XRF_USER_FINGERPRINT)?;
+        let req = request.into_inner();
+        info!("updating collection :: id={}", &req.collection_id);
+        let collection_id = req.collection_id.clone();
+        let org_id = req.org_id.clone();
+        if collection_id.is_empty() || org_id.is_empty() {
+            return Err(Status::invalid_argument("please provide a valid collection id and organization id"));
+        }
+        let update = process_collection_update(req, &find_currency_registry(&self.pg_pool).await?)?;
+
+        let collection = orchestrator::update_collection(&collection_id, &org_id, &user_fp, &update, &self.pg_pool)
+            .await
+            .map_err(|e| to_status(e, "update collection"))?;
+        let asset_count = count_collection_assets(&collection.id, &self.pg_pool).await?;
+        Ok(Response::new(UpdateCollectionResponse { collection: Some(to_grpc_collection(collection, asset_count)) }))
+    }
+
+    async fn delete_collection(&self, request: Request<DeleteCollectionRequest>)
+                               -> Result<Response<DeleteCollectionResponse>, Status> {
+        trace_request!(request, "delete_collection");
+        let req = request.into_inner();
+        info!("deleting collection :: id={}", &req.collection_id);
+        orchestrator::delete_collection(&req.collection_id, &req.org_id, &self.pg_pool)
+            .await
+            .map_err(|e| to_status(e, "delete collection"))?;
+        Ok(Response::new(DeleteCollectionResponse { deleted: true }))
+    }
+
+    async fn list_collection_assets(&self, request: Request<ListCollectionAssetsRequest>)
+                                    -> Result<Response<ListCollectionAssetsResponse>, Status> {
+        trace_request!(request, "list_collection_assets");
+        let req = request.into_inner();
+        validate_limit(req.limit)?;
+        let sort_field = match req.sort_by.trim() {
+            "" => AssetSortField::default(),
+            sort_by => AssetSortField::from_str(sort_by)
+                .map_err(|_| Status::invalid_argument("sort_by is invalid"))?,
+        };
+        let sort_order = queries::OrderType::from_str(&req.sort_order)
+            .map_err(|_| Status::invalid_argument("sort_order is invalid"))?;
+        let sort = queries::AssetSort::new(sort_field, sort_order);
+        let cursor = decode_cursor(req.cursor.as_deref())?;
+        info!("listing collection assets :: id={} :: sort={} :: after={:?} :: limit={}", &req.collection_id, &sort, &cursor, req.limit);
+
+        // an unknown collection is reported as such rather than as an empty collection
+        queries::find_collection_by_id(&req.collection_id, &self.pg_pool)
+            .await
+            .map_err(|e| match e {
+                DatabaseError::NotFound => Status::not_found("collection not found"),
+                _ => {
+                    error!("failed to get collection :: err={:?}", e);
+                    Status::internal("server error")
+                }
+            })?;
+        let filter = AssetFilter { collection_id: Some(req.collection_id), ..Default::default() };
+        let assets = self.repository.list_assets(&filter, sort, cursor.as_ref(), req.limit as i64)
+            .await
+            .map_err(|e| match e {
+                DatabaseError::InvalidArgument(err) => Status::invalid_argument(err),
+                e => {
+                    error!("failed to list collection assets :: err={:?}", e);
+                    Status::internal("server error")
+                }
+            })?;
+        let total = count_assets(&filter, &self.repository).await?;
+
+        Ok(Response::new(ListCollectionAssetsResponse {
+            total,
+            next_cursor: next_cursor(&assets, req.limit as usize, sort_field),
+            assets: with_metadata(assets, &self.repository).await?,
+        }))
+    }
+}
+
+async fn count_collection_assets(collection_id: &str, pg_pool: &PgPool) -> Result<i64, Status> {
+    queries::count_collection_assets(collection_id, pg_pool)
+        .await
+        .map_err(|e| {
+            error!("failed to count collection assets :: err={:?}", e);
+            Status::internal("server error")
+        })
+}
+
+fn to_status(e: OrchestrateError, action: &str) -> Status {
+    match e {
+        OrchestrateError::NotFoundError(msg) => Status::not_found(msg),
+        OrchestrateError::InvalidArgument(msg) => Status::invalid_argument(msg),
+        OrchestrateError::InvalidState(msg) => Status::failed_precondition(msg),
+        OrchestrateError::PermissionDenied(msg) => Status::permission_denied(msg),
+        OrchestrateError::ServerError(err) => Status::internal(err),
+        OrchestrateError::DatabaseError(err) => {
+            error!("failed to {} :: err={:?}", action, err);
+            Status::internal("server error")
+        }
+    }
+}
@@ -1,40 +1,109 @@
 use crate::constant::REQUEST_ID_KEY;
-use crate::core::{queries, Contract, Currency, DatabaseError};
+use crate::core::repository::{AssetRepository, ContractRepository};
+use crate::core::{orchestrator, Auction, AuctionType, Contract, ContractTerms, ContractVersion, Currency, CurrencyRegistry, DatabaseError,
+                  DutchDecay, OrchestrateError, ResaleRestriction, RoyaltySplit, UpdateContractRequest as ContractUpdate};
 use crate::server::grpc::asset::contract_service_server::ContractService;
-use crate::server::grpc::asset::{ContractResponse, CreateContractRequest, CreateContractResponse, FindContractRequest, FindContractResponse};
+use crate::server::grpc::asset::{AuctionConfig, AuctionState, ContractResponse, CreateContractRequest, CreateContractResponse,
+                                 DutchDecay as GrpcDutchDecay, FindContractRequest, FindContractResponse, GetContractAtVersionRequest,
+                                 GetContractAtVersionResponse, GetContractHistoryRequest, GetContractHistoryResponse,
+                                 ContractTerms as GrpcContractTerms, ResaleRestriction as GrpcResaleRestriction,
+                                 RoyaltySplit as GrpcRoyaltySplit, UpdateContractRequest, UpdateContractResponse};
 use crate::server::grpc::interceptors::trace_request;
+use crate::server::grpc::money::{process_money, to_money};
+use crate::server::grpc::{get_xrf_user_auth_header, XRF_USER_FINGERPRINT};
+use chrono::{DateTime, Utc};
 use prost_types::Timestamp;
 use rayon::prelude::*;
-use sqlx::PgPool;
 use std::collections::HashSet;
 use std::str::FromStr;
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
 use tracing::{error, info, info_span};
 
-pub struct ContractServiceManager {
-    pg_pool: Arc<PgPool>,
+const MAX_LIMIT: i32 = 100;
+
+pub struct ContractServiceManager<R> {
+    repository: Arc<R>,
+}
+
+impl<R> ContractServiceManager<R> {
+    pub fn new(repository: Arc<R>) -> Self {
+        ContractServiceManager { repository }
+    }
+}
+
+impl From<Auction> for AuctionState {
+    fn from(auction: Auction) -> Self {
+        AuctionState {
+            closed: auction.is_closed(),
+            current_price: if auction.is_closed() { None } else { auction.price_at(Utc::now()).map(Into::into) },
+            closed_at: auction.closed_at.map(|closed_at| Timestamp {
+                seconds: closed_at.timestamp(),
+                nanos: closed_at.timestamp_subsec_nanos() as i32,
+            }),
+            winning_bid_id: auction.winning_bid_id,
+            config: Some(AuctionConfig {
+                auction_type: auction.auction_type.to_string(),
+                reserve_price: Some(auction.reserve_price.into()),
+                bid_increment: Some(auction.bid_increment.into()),
+                dutch_decay: auction.dutch_decay.map(|decay| GrpcDutchDecay {
+                    start_price: Some(decay.start_price.into()),
+                    decay_amount: Some(decay.decay_amount.into()),
+                    decay_interval_seconds: decay.decay_interval_secs,
+                }),
+                start_time: Some(Timestamp {
+                    seconds: auction.start_time.timestamp(),
+                    nanos: auction.start_time.timestamp_subsec_nanos() as i32,
+                }),
+                end_time: Some(Timestamp {
+                    seconds: auction.end_time.timestamp(),
+                    nanos: auction.end_time.timestamp_subsec_nanos() as i32,
+                }),
+            }),
+        }
+    }
 }
 
-impl ContractServiceManager {
-    pub fn new(pg_pool: Arc<PgPool>) -> Self {
-        ContractServiceManager { pg_pool }
+impl From<RoyaltySplit> for GrpcRoyaltySplit {
+    fn from(split: RoyaltySplit) -> Self {
+        GrpcRoyaltySplit { receiver_fp: split.receiver_fp, basis_points: split.basis_points }
+    }
+}
+
+impl From<ContractTerms> for GrpcContractTerms {
+    fn from(terms: ContractTerms) -> Self {
+        GrpcContractTerms {
+            resale: Some(GrpcResaleRestriction {
+                allowed: terms.resale.allowed,
+                min_holding_days: terms.resale.min_holding_days,
+            }),
+            expires_at: terms.expires_at.map(|expires_at| Timestamp {
+                seconds: expires_at.timestamp(),
+                nanos: expires_at.timestamp_subsec_nanos() as i32,
+            }),
+        }
     }
 }
 
 impl From<Contract> for ContractResponse {
     fn from(contract: Contract) -> Self {
+        // v1 contracts have no terms
+        let terms = match contract.version {
+            ContractVersion::V1 => None,
+            ContractVersion::V2 => Some(contract.terms.into()),
+        };
         ContractResponse {
+            terms,
+            royalty_splits: contract.royalty_splits.into_iter().map(GrpcRoyaltySplit::from).collect(),
+            auction: contract.auction.map(AuctionState::from),
             version: contract.version.to_string(),
             asset_id: contract.asset_id.to_string(),
             details: contract.details,
             summary: contract.summary,
-            min_price: contract.min_price as f32,
+            min_price: Some(contract.min_price.into()),
             update_count: contract.update_count as u32,
             anonymous_buyers: contract.anonymous_buyer_only,
             last_updated_by: contract.updated_by.to_string(),
-            royalty_receiver: contract.royalty_receiver_id.to_string(),
-            royalty_percentage: contract.royalty_percentage,
             accepted_currency: contract.accepted_currency.into_iter()
                 .map(|x| x.to_string())
                 .collect(),
@@ -51,14 +120,17 @@ impl From<Contract> for ContractResponse {
 }
 
 #[tonic::async_trait]
-impl ContractService for ContractServiceManager {
+impl<R> ContractService for ContractServiceManager<R>
+where
+    R: AssetRepository + ContractRepository + 'static,
+{
     async fn find_contract(&self, request: Request<FindContractRequest>)
                            -> Result<Response<FindContractResponse>, Status> {
         trace_request!(request, "find_contract");
         let req = request.into_inner();
         info!("Finding contract by asset id :: (id={})", &req.asset_id);
         let asset_id = req.asset_id;
-        let contract = queries::find_contract_by_asset_id(&asset_id, &self.pg_pool)
+        let contract = self.repository.find_contract_by_asset_id(&asset_id)
             .await
             .map_err(|e| {
                 match e {
@@ -80,7 +152,7 @@ impl ContractService for ContractServiceManager {
         let req = request.into_inner();
         info!("creating new contract :: (assetId={})", &req.asset_id);
 
-        let saved_asset = queries::find_asset_by_id(&req.asset_id, &self.pg_pool).await
+        let saved_asset = self.repository.find_asset_by_id(&req.asset_id).await
             .map_err(|err| match err {
                 DatabaseError::NotFound => {
                     error!(?req.asset_id, " asset not found");
@@ -92,39 +164,52 @@ impl ContractService for ContractServiceManager {
                 },
             })?;
 
+        let registry = self.repository.find_currency_registry().await
+            .map_err(|err| {
+                error!("failed to load currency registry :: err={:?}", err);
+                Status::internal("server error")
+            })?;
         let details = req.details;
         let asset_id = saved_asset.id;
-        let min_price = req.min_price as f64;
+        let min_price = process_money(req.min_price, "min_price", &registry)?;
         let user_fp = req.user_finger_print;
         let anonymous_buyers_only = req.anonymous_buyers;
-        let royalty_percentage = req.royalty_percentage.unwrap_or(0.0);
-        let royalty_receiver = match req.royalty_receiver {
-            None => {
-                // if royalty_receiver is not set and royalty_percentage is > 0.0, then set
-                // receiver to user_fp that's creating the contract
-                if royalty_percentage > 0.0 {
-                    user_fp.clone().to_string()
-                } else {
-                    "".to_string()
-                }
-            }
-            Some(receiver_user_id) => { receiver_user_id }
-        };
-        let accepted_currencies = process_accepted_currencies(req.accepted_currencies)
+        let accepted_currencies = process_accepted_currencies(req.accepted_currencies, &registry)
             .map_err(|er| Status::invalid_argument(er.to_string()))?;
-        let contract = Contract::new(asset_id,
-                                     details,
-                                     req.summary,
-                                     user_fp.clone(),
-                                     min_price,
-                                     anonymous_buyers_only,
-                                     royalty_percentage,
-                                     royalty_receiver,
-                                     accepted_currencies)
+        let royalty_splits = process_royalty_splits(req.royalty_splits);
+        let contract = match req.terms {
+            None => Contract::new(asset_id,
+                                  details,
+                                  req.summary,
+                                  user_fp.clone(),
+                                  min_price,
+                                  anonymous_buyers_only,
+                                  royalty_splits,
+                                  accepted_currencies),
+            Some(terms) => Contract::new_v2(asset_id,
+                                            details,
+                                            req.summary,
+                                            user_fp.clone(),
+                                            min_price,
+                                            anonymous_buyers_only,
+                                            royalty_splits,
+                                            process_contract_terms(terms)?,
+                                            accepted_currencies),
+        }
+            .map_err(|err| Status::invalid_argument(err.to_string()))?;
+        registry.check_enabled(&contract.accepted_currency)
             .map_err(|err| Status::invalid_argument(err.to_string()))?;
+        let contract = match req.auction {
+            None => contract,
+            Some(config) => {
+                let auction = process_auction_config(config, &registry)?;
+                contract.with_auction(auction)
+                    .map_err(|err| Status::invalid_argument(err.to_string()))?
+            }
+        };
         let contract_id = contract.id.clone();
 
-        let contract_created = queries::create_contract(&self.pg_pool, contract).await.map_err(|err| match err {
+        let contract_created = self.repository.create_contract(contract).await.map_err(|err| match err {
             DatabaseError::InvalidArgument(err) => {
                 Status::invalid_argument(err.to_string())
             },
@@ -143,15 +228,162 @@ impl ContractService for ContractServiceManager {
         }
         Ok(Response::new(CreateContractResponse { contract_id }))
     }
+
+    async fn update_contract(&self, request: Request<UpdateContractRequest>)
+                             -> Result<Response<UpdateContractResponse>, Status> {
+        trace_request!(request, "update_contract");
+        let user_fp = get_xrf_user_auth_header(request.metadata(), XRF_USER_FINGERPRINT)?;
+        let req = request.into_inner();
+        info!("updating contract :: (assetId={})", &req.asset_id);
+
+        let registry = self.repository.find_currency_registry().await
+            .map_err(|err| {
+                error!("failed to load currency registry :: err={:?}", err);
+                Status::internal("server error")
+            })?;
+        let accepted_currency = if req.accepted_currencies.is_empty() {
+            None
+        } else {
+            Some(process_accepted_currencies(req.accepted_currencies, &registry)
+                .map_err(Status::invalid_argument)?)
+        };
+        let royalty_splits = if req.royalty_splits.is_empty() {
+            None
+        } else {
+            Some(process_royalty_splits(req.royalty_splits))
+        };
+        let terms = req.terms.map(process_contract_terms).transpose()?;
+        let update = ContractUpdate::new(req.details,
+                                         req.summary,
+                                         req.min_price.map(|min_price| to_money(min_price, &registry)).transpose()?,
+                                         req.anonymous_buyers,
+                                         accepted_currency,
+                                         royalty_splits,
+                                         terms);
+
+        let contract = orchestrator::update_contract(&req.asset_id, &user_fp, &update, self.repository.as_ref())
+            .await
+            .map_err(|e| match e {
+                OrchestrateError::NotFoundError(err) => Status::not_found(err),
+                OrchestrateError::ServerError(err) => Status::internal(err),
+                OrchestrateError::InvalidArgument(msg) => Status::invalid_argument(msg),
+                OrchestrateError::InvalidState(msg) => Status::failed_precondition(msg),
+                OrchestrateError::PermissionDenied(msg) => Status::permission_denied(msg),
+                OrchestrateError::DatabaseError(err) => {
+                    error!("failed to update contract :: err={:?}", err);
+                    Status::internal("server error")
+                }
+            })?;
+
+        Ok(Response::new(UpdateContractResponse { contract: Some(contract.into()) }))
+    }
+
+    async fn get_contract_history(&self, request: Request<GetContractHistoryRequest>)
+                                  -> Result<Response<GetContractHistoryResponse>, Status> {
+        trace_request!(request, "get_contract_history");
+        let req = request.into_inner();
+        if req.offset < 0 {
+            return Err(Status::invalid_argument("offset must be positive"));
+        }
+        if !(1..=MAX_LIMIT).contains(&req.limit) {
+            return Err(Status::invalid_argument(format!("limit must be between 1 and {}", MAX_LIMIT)));
+        }
+        info!("getting contract history :: (assetId={})", &req.asset_id);
+
+        let contracts = self.repository.find_contract_history(&req.asset_id, req.limit as i64, req.offset as i64)
+            .await
+            .map_err(|e| {
+                error!("failed to get contract history :: err={:?}", e);
+                Status::internal("server error")
+            })?;
+
+        let response = GetContractHistoryResponse {
+            offset: req.offset,
+            total: contracts.len() as i32,
+            contracts: contracts.into_iter().map(ContractResponse::from).collect(),
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn get_contract_at_version(&self, request: Request<GetContractAtVersionRequest>)
+                                     -> Result<Response<GetContractAtVersionResponse>, Status> {
+        trace_request!(request, "get_contract_at_version");
+        let req = request.into_inner();
+        info!("getting contract at version :: (assetId={}) :: (updateCount={})", &req.asset_id, req.update_count);
+        let update_count = i32::try_from(req.update_count)
+            .map_err(|_| Status::invalid_argument("invalid update_count"))?;
+
+        let contract = self.repository.find_contract_at_version(&req.asset_id, update_count)
+            .await
+            .map_err(|e| match e {
+                DatabaseError::NotFound => Status::not_found("contract version not found"),
+                _ => {
+                    error!("failed to get contract at version :: err={:?}", e);
+                    Status::internal("server error")
+                }
+            })?;
+
+        Ok(Response::new(GetContractAtVersionResponse { contract: Some(contract.into()) }))
+    }
+}
+
+fn process_auction_config(config: AuctionConfig, registry: &CurrencyRegistry) -> Result<Auction, Status> {
+    let auction_type = AuctionType::from_str(&config.auction_type)
+        .map_err(|_| Status::invalid_argument("invalid auction type"))?;
+    let start_time = match config.start_time {
+        None => Utc::now(),
+        Some(ts) => to_date_time(ts).ok_or_else(|| Status::invalid_argument("invalid auction start_time"))?,
+    };
+    let end_time = config.end_time
+        .and_then(to_date_time)
+        .ok_or_else(|| Status::invalid_argument("invalid auction end_time"))?;
+    let reserve_price = process_money(config.reserve_price, "auction reserve_price", registry)?;
+    // auctions without a bid increment accept any bid above the reserve price
+    let bid_increment = match config.bid_increment {
+        None => reserve_price.with_minor_units(0),
+        Some(bid_increment) => to_money(bid_increment, registry)?,
+    };
+    let dutch_decay = match config.dutch_decay {
+        None => None,
+        Some(decay) => Some(DutchDecay {
+            start_price: process_money(decay.start_price, "dutch auction start_price", registry)?,
+            decay_amount: process_money(decay.decay_amount, "dutch auction decay_amount", registry)?,
+            decay_interval_secs: decay.decay_interval_seconds,
+        }),
+    };
+
+    Auction::new(auction_type, start_time, end_time, reserve_price, bid_increment, dutch_decay)
+        .map_err(|err| Status::invalid_argument(err.to_string()))
 }
 
-fn process_accepted_currencies(accepted_currencies: Vec<String>) -> Result<HashSet<Currency>, String> {
+pub(super) fn process_royalty_splits(royalty_splits: Vec<GrpcRoyaltySplit>) -> Vec<RoyaltySplit> {
+    royalty_splits.into_iter()
+        .map(|split| RoyaltySplit { receiver_fp: split.receiver_fp, basis_points: split.basis_points })
+        .collect()
+}
+
+pub(super) fn process_contract_terms(terms: GrpcContractTerms) -> Result<ContractTerms, Status> {
+    let expires_at = match terms.expires_at {
+        None => None,
+        Some(ts) => Some(to_date_time(ts).ok_or_else(|| Status::invalid_argument("invalid contract expires_at"))?),
+    };
+    let resale = terms.resale
+        .map(|resale| ResaleRestriction { allowed: resale.allowed, min_holding_days: resale.min_holding_days })
+        .unwrap_or_default();
+    Ok(ContractTerms { resale, expires_at })
+}
+
+fn to_date_time(ts: Timestamp) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(ts.seconds, ts.nanos as u32)
+}
+
+pub(super) fn process_accepted_currencies(accepted_currencies: Vec<String>, registry: &CurrencyRegistry) -> Result<HashSet<Currency>, String> {
     // Use Rayon's parallel iterators to ensure thread safety
     let (valid_currencies, invalid_currencies): (Vec<_>, Vec<_>) = accepted_currencies
         .par_iter()
-        .map(|c| match Currency::from_str(c) {
-            Ok(currency) => (Some(currency), None),
-            Err(_) => (None, Some(c.clone())),
+        .map(|c| match registry.resolve(c) {
+            Some(info) => (Some(info.code.clone()), None),
+            None => (None, Some(c.clone())),
         })
         .unzip();
 
@@ -176,6 +408,19 @@ fn process_accepted_currencies(accepted_currencies: Vec<String>) -> Result<HashS
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::CurrencyInfo;
+
+    fn registry() -> CurrencyRegistry {
+        let info = |code: Currency, aliases: &[&str]| CurrencyInfo {
+            name: code.to_string(),
+            aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+            decimals: 2,
+            crypto: false,
+            enabled: true,
+            code,
+        };
+        CurrencyRegistry::new(vec![info(Currency::USD, &[]), info(Currency::EUR, &["Euro"]), info(Currency::GBP, &[])]).unwrap()
+    }
 
     #[test]
     fn test_process_accepted_currencies() {
@@ -186,8 +431,8 @@ mod tests {
             "Euro".to_string(),
         ];
 
-        let result = process_accepted_currencies(valid_currencies);
-        assert!(result.is_ok());
+        let result = process_accepted_currencies(valid_currencies, &registry());
+        assert_eq!(result.unwrap(), HashSet::from([Currency::USD, Currency::EUR, Currency::GBP]));
     }
 
     #[test]
@@ -197,7 +442,7 @@ mod tests {
             "EUR ".to_string(),
             " GBP".to_string(),
         ];
-        let result = process_accepted_currencies(invalid_currencies);
+        let result = process_accepted_currencies(invalid_currencies, &registry());
         assert!(result.is_err());
     }
 }
@@ -0,0 +1,54 @@
+use crate::constant::REQUEST_ID_KEY;
+use crate::core::{queries, CurrencyInfo};
+use crate::server::grpc::asset::currency_service_server::CurrencyService;
+use crate::server::grpc::asset::{CurrencyInfo as GrpcCurrencyInfo, ListCurrenciesRequest, ListCurrenciesResponse};
+use crate::server::grpc::interceptors::trace_request;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use tracing::{error, info, info_span};
+
+pub struct CurrencyServiceManager {
+    pg_pool: Arc<PgPool>,
+}
+
+impl CurrencyServiceManager {
+    pub fn new(pg_pool: Arc<PgPool>) -> Self {
+        CurrencyServiceManager { pg_pool }
+    }
+}
+
+impl From<&CurrencyInfo> for GrpcCurrencyInfo {
+    fn from(info: &CurrencyInfo) -> Self {
+        GrpcCurrencyInfo {
+            code: info.code.to_string(),
+            name: info.name.clone(),
+            aliases: info.aliases.clone(),
+            decimals: info.decimals,
+            crypto: info.crypto,
+            enabled: info.enabled,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl CurrencyService for CurrencyServiceManager {
+    async fn list_currencies(&self, request: Request<ListCurrenciesRequest>) -> Result<Response<ListCurrenciesResponse>, Status> {
+        trace_request!(request, "list_currencies");
+        let req = request.into_inner();
+        info!("listing currencies :: include_disabled={}", req.include_disabled);
+
+        let registry = queries::find_currency_registry(&*self.pg_pool)
+            .await
+            .map_err(|e| {
+                error!("failed to load currency registry :: err={:?}", e);
+                Status::internal("server error")
+            })?;
+
+        let currencies = registry.list(req.include_disabled)
+            .into_iter()
+            .map(GrpcCurrencyInfo::from)
+            .collect();
+        Ok(Response::new(ListCurrenciesResponse { currencies }))
+    }
+}
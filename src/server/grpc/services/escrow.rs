@@ -0,0 +1,104 @@
+use crate::constant::REQUEST_ID_KEY;
+use crate::core::{orchestrator, queries, DatabaseError, EscrowHold, OrchestrateError};
+use crate::server::grpc::asset::escrow_service_server::EscrowService;
+use crate::server::grpc::asset::get_escrow_hold_request::Key;
+use crate::server::grpc::asset::{EscrowHold as GrpcEscrowHold, FundEscrowHoldRequest, FundEscrowHoldResponse, GetEscrowHoldRequest,
+                                 GetEscrowHoldResponse};
+use crate::server::grpc::interceptors::trace_request;
+use crate::server::grpc::{get_xrf_user_auth_header, XRF_USER_FINGERPRINT};
+use chrono::{DateTime, Utc};
+use prost_types::Timestamp;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use tracing::{error, info, info_span};
+
+pub struct EscrowServiceManager {
+    pg_pool: Arc<PgPool>,
+}
+
+impl EscrowServiceManager {
+    pub fn new(pg_pool: Arc<PgPool>) -> Self {
+        EscrowServiceManager { pg_pool }
+    }
+}
+
+fn to_timestamp(time: DateTime<Utc>) -> Timestamp {
+    Timestamp {
+        seconds: time.timestamp(),
+        nanos: time.timestamp_subsec_nanos() as i32,
+    }
+}
+
+impl From<EscrowHold> for GrpcEscrowHold {
+    fn from(hold: EscrowHold) -> Self {
+        GrpcEscrowHold {
+            id: hold.id,
+            bid_id: hold.bid_id,
+            asset_id: hold.asset_id,
+            nfc_id: hold.nfc_id,
+            buyer_fp: hold.buyer_fp,
+            buyer_org: hold.buyer_org,
+            seller_fp: hold.seller_fp,
+            amount: Some(hold.amount.into()),
+            status: hold.status.to_string(),
+            deadline: Some(to_timestamp(hold.deadline)),
+            funded_at: hold.funded_at.map(to_timestamp),
+            settled_at: hold.settled_at.map(to_timestamp),
+            created_at: Some(to_timestamp(hold.created_at)),
+            updated_at: Some(to_timestamp(hold.updated_at)),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl EscrowService for EscrowServiceManager {
+    async fn get_escrow_hold(&self, request: Request<GetEscrowHoldRequest>) -> Result<Response<GetEscrowHoldResponse>, Status> {
+        trace_request!(request, "get_escrow_hold");
+        let user_fp = get_xrf_user_auth_header(request.metadata(), XRF_USER_FINGERPRINT)?;
+        let req = request.into_inner();
+
+        let hold = match req.key {
+            Some(Key::HoldId(hold_id)) => queries::find_escrow_hold_by_id(&hold_id, self.pg_pool.as_ref()).await,
+            Some(Key::BidId(bid_id)) => queries::find_escrow_hold_by_bid_id(&bid_id, self.pg_pool.as_ref()).await,
+            None => return Err(Status::invalid_argument("hold_id or bid_id is required")),
+        }
+            .map_err(|e| match e {
+                DatabaseError::NotFound => Status::not_found("escrow hold not found"),
+                _ => {
+                    error!("failed to fetch escrow hold :: err={:?}", e);
+                    Status::internal("server error")
+                }
+            })?;
+        // do not leak the existence of holds of other users
+        if hold.buyer_fp != user_fp && hold.seller_fp != user_fp {
+            return Err(Status::not_found("escrow hold not found"));
+        }
+        info!("found escrow hold :: {}", hold);
+
+        Ok(Response::new(GetEscrowHoldResponse { hold: Some(hold.into()) }))
+    }
+
+    async fn fund_escrow_hold(&self, request: Request<FundEscrowHoldRequest>) -> Result<Response<FundEscrowHoldResponse>, Status> {
+        trace_request!(request, "fund_escrow_hold");
+        let user_fp = get_xrf_user_auth_header(request.metadata(), XRF_USER_FINGERPRINT)?;
+        let req = request.into_inner();
+        info!("funding escrow hold :: holdId={}", &req.hold_id);
+
+        let hold = orchestrator::fund_escrow_hold(&req.hold_id, &user_fp, &self.pg_pool)
+            .await
+            .map_err(|e| match e {
+                OrchestrateError::NotFoundError(err) => Status::not_found(err),
+                OrchestrateError::ServerError(err) => Status::internal(err),
+                OrchestrateError::InvalidArgument(msg) => Status::invalid_argument(msg),
+                OrchestrateError::InvalidState(msg) => Status::failed_precondition(msg),
+                OrchestrateError::PermissionDenied(msg) => Status::permission_denied(msg),
+                OrchestrateError::DatabaseError(err) => {
+                    error!("failed to fund escrow hold :: err={:?}", err);
+                    Status::internal("server error")
+                }
+            })?;
+
+        Ok(Response::new(FundEscrowHoldResponse { hold: Some(hold.into()) }))
+    }
+}
@@ -0,0 +1,181 @@
+use crate::constant::REQUEST_ID_KEY;
+use crate::core::event_proto::event_service_server::EventService;
+use crate::core::event_proto::subscribe_events_request::Filter;
+use crate::core::event_proto::{Event, SubscribeEventsRequest};
+use crate::core::{orchestrator, queries, EventFilter, EventType, OrchestrateError};
+use crate::server::grpc::interceptors::trace_request;
+use crate::server::grpc::{get_xrf_user_auth_header, XRF_USER_FINGERPRINT};
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+use tonic::codegen::tokio_stream::Stream;
+use tonic::{Request, Response, Status};
+use tracing::{debug, error, info, info_span};
+
+/// Events read from the outbox at a time
+const BATCH_SIZE: i64 = 100;
+/// Subscriptions read the outbox at least this often, notifications are lost while the notifier reconnects
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// Subscriptions read the outbox this often while committed events wait for an older transaction to end,
+/// the transaction may record no event and end without a notification.
+/// The events wait as long as the transaction runs, the outbox relay does not keep one open while it publishes.
+const HELD_BACK_INTERVAL: Duration = Duration::from_millis(500);
+
+pub struct EventServiceManager {
+    pg_pool: Arc<PgPool>,
+    notifications: broadcast::Sender<i64>,
+    admin_fps: HashSet<String>,
+}
+
+impl EventServiceManager {
+    /// `notifications` broadcasts the sequence of the events recorded in the outbox
+    pub fn new(pg_pool: Arc<PgPool>, notifications: broadcast::Sender<i64>, admin_fps: HashSet<String>) -> Self {
+        EventServiceManager { pg_pool, notifications, admin_fps }
+    }
+}
+
+fn to_event_filter(filter: Option<Filter>) -> Result<EventFilter, Status> {
+    match filter {
+        Some(Filter::AssetId(asset_id)) if !asset_id.is_empty() => Ok(EventFilter::AssetId(asset_id)),
+        Some(Filter::Organization(organization)) if !organization.is_empty() => Ok(EventFilter::Organization(organization)),
+        Some(Filter::OwnerFp(owner_fp)) if !owner_fp.is_empty() => Ok(EventFilter::OwnerFp(owner_fp)),
+        _ => Err(Status::invalid_argument("an asset id, organization or owner fingerprint is required")),
+    }
+}
+
+fn authorization_status(e: OrchestrateError) -> Status {
+    match e {
+        OrchestrateError::NotFoundError(err) => Status::not_found(err),
+        OrchestrateError::ServerError(err) => Status::internal(err),
+        OrchestrateError::InvalidArgument(msg) => Status::invalid_argument(msg),
+        OrchestrateError::InvalidState(msg) => Status::failed_precondition(msg),
+        OrchestrateError::PermissionDenied(msg) => Status::permission_denied(msg),
+        OrchestrateError::DatabaseError(err) => {
+            error!("failed to authorize event subscription :: err={:?}", err);
+            Status::internal("server error")
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl EventService for EventServiceManager {
+    type SubscribeEventsStream = Pin<Box<dyn Stream<Item=Result<Event, Status>> + Send + 'static>>;
+
+    async fn subscribe_events(&self, request: Request<SubscribeEventsRequest>)
+                              -> Result<Response<Self::SubscribeEventsStream>, Status> {
+        trace_request!(request, "subscribe_events");
+        let user_fp = get_xrf_user_auth_header(request.metadata(), XRF_USER_FINGERPRINT)?;
+        let req = request.into_inner();
+        let filter = to_event_filter(req.filter)?;
+        if req.after_sequence.is_some_and(|sequence| sequence < 0) {
+            return Err(Status::invalid_argument("after_sequence must be positive"));
+        }
+        orchestrator::authorize_event_filter(&filter, &user_fp, &self.admin_fps, self.pg_pool.as_ref())
+            .await
+            .map_err(authorization_status)?;
+
+        // subscribe before reading the outbox so that the events recorded meanwhile are notified
+        let mut notifications = self.notifications.subscribe();
+        let mut last_sequence = match req.after_sequence {
+            Some(sequence) => sequence,
+            None => queries::find_last_outbox_sequence(self.pg_pool.as_ref())
+                .await
+                .map_err(|e| {
+                    error!("failed to find last outbox sequence :: err={:?}", e);
+                    Status::internal("server error")
+                })?,
+        };
+        info!("subscribing to events :: {} :: after={}", &filter, last_sequence);
+
+        let pg_pool = self.pg_pool.clone();
+        let admin_fps = self.admin_fps.clone();
+        let stream = async_stream::stream! {
+            'subscription: loop {
+                // 1. Send the events that follow the last one sent
+                let events = match queries::find_outbox_events_after(&filter, last_sequence, BATCH_SIZE, pg_pool.as_ref()).await {
+                    Ok(events) => events,
+                    Err(e) => {
+                        error!("Failed to fetch events from database: {:?}", e);
+                        yield Err(Status::internal("server error"));
+                        break;
+                    }
+                };
+                let caught_up = (events.len() as i64) < BATCH_SIZE;
+                for event in &events {
+                    // the caller may no longer own the asset or be a member of the organization once it is transferred
+                    if event.event_type == EventType::AssetTransferred {
+                        if let Err(e) = orchestrator::authorize_event_filter(&filter, &user_fp, &admin_fps, pg_pool.as_ref()).await {
+                            info!("ending event subscription :: {} :: sequence={} :: err={:?}", &filter, event.sequence, e);
+                            yield Err(authorization_status(e));
+                            break 'subscription;
+                        }
+                    }
+                    match Event::try_from(event) {
+                        Ok(message) => {
+                            last_sequence = event.sequence;
+                            yield Ok(message);
+                        }
+                        Err(e) => {
+                            error!("Failed to decode event {}: {:?}", event, e);
+                            yield Err(Status::internal("server error"));
+                            break 'subscription;
+                        }
+                    }
+                }
+                if !caught_up {
+                    continue;
+                }
+                let wait = match queries::has_outbox_events_held_back(&filter, last_sequence, pg_pool.as_ref()).await {
+                    Ok(true) => HELD_BACK_INTERVAL,
+                    Ok(false) => POLL_INTERVAL,
+                    Err(e) => {
+                        error!("Failed to fetch events from database: {:?}", e);
+                        yield Err(Status::internal("server error"));
+                        break;
+                    }
+                };
+
+                // 2. Wait for the next events, notifications are only a signal to read the outbox again
+                match tokio::time::timeout(wait, notifications.recv()).await {
+                    Ok(Ok(_)) | Err(_) => {}
+                    Ok(Err(RecvError::Lagged(missed))) => debug!("subscription lagged behind :: missed={}", missed),
+                    Ok(Err(RecvError::Closed)) => {
+                        yield Err(Status::unavailable("event notifications stopped"));
+                        break;
+                    }
+                }
+                // the events of the notifications received meanwhile are read at once
+                while !matches!(notifications.try_recv(), Err(TryRecvError::Empty | TryRecvError::Closed)) {}
+            }
+        };
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tonic::Code;
+
+    #[test]
+    fn test_event_filter_of_request() {
+        let filter = to_event_filter(Some(Filter::AssetId("asset".to_string())));
+        assert_eq!(filter.unwrap(), EventFilter::AssetId("asset".to_string()));
+        let filter = to_event_filter(Some(Filter::OwnerFp("user".to_string())));
+        assert_eq!(filter.unwrap(), EventFilter::OwnerFp("user".to_string()));
+    }
+
+    #[test]
+    fn test_invalid_event_filter() {
+        assert_eq!(to_event_filter(None).unwrap_err().code(), Code::InvalidArgument);
+        let filter = to_event_filter(Some(Filter::Organization("".to_string())));
+        assert_eq!(filter.unwrap_err().code(), Code::InvalidArgument);
+        let filter = to_event_filter(Some(Filter::OwnerFp("".to_string())));
+        assert_eq!(filter.unwrap_err().code(), Code::InvalidArgument);
+    }
+}
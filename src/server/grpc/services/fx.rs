@@ -0,0 +1,129 @@
+use crate::constant::REQUEST_ID_KEY;
+use crate::core::queries::UnitOfWork;
+use crate::core::{orchestrator, queries, CurrencyRegistry, FxPolicy, FxRate, OrchestrateError};
+use crate::server::grpc::asset::fx_service_server::FxService;
+use crate::server::grpc::asset::{ExchangeRate, QuoteRequest, QuoteResponse, UpsertRatesRequest, UpsertRatesResponse};
+use crate::server::grpc::interceptors::trace_request;
+use crate::server::grpc::money::{find_currency_registry, process_currency, process_money};
+use crate::server::grpc::get_xrf_admin_auth_header;
+use bigdecimal::{BigDecimal, RoundingMode};
+use chrono::DateTime;
+use prost_types::Timestamp;
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use tracing::{error, info, info_span};
+
+const MAX_RATES_PER_REQUEST: usize = 500;
+
+pub struct FxServiceManager {
+    pg_pool: Arc<PgPool>,
+    fx_policy: FxPolicy,
+    admin_fps: HashSet<String>,
+}
+
+impl FxServiceManager {
+    pub fn new(pg_pool: Arc<PgPool>, fx_policy: FxPolicy, admin_fps: HashSet<String>) -> Self {
+        FxServiceManager { pg_pool, fx_policy, admin_fps }
+    }
+}
+
+impl From<FxRate> for ExchangeRate {
+    fn from(rate: FxRate) -> Self {
+        ExchangeRate {
+            base: rate.base.to_string(),
+            quote: rate.quote.to_string(),
+            rate: rate.rate.normalized().to_string(),
+            as_of: Some(Timestamp {
+                seconds: rate.as_of.timestamp(),
+                nanos: rate.as_of.timestamp_subsec_nanos() as i32,
+            }),
+        }
+    }
+}
+
+fn process_rate(rate: ExchangeRate, registry: &CurrencyRegistry) -> Result<FxRate, Status> {
+    let base = process_currency(&rate.base, registry)?;
+    let quote = process_currency(&rate.quote, registry)?;
+    let value = BigDecimal::from_str(rate.rate.trim())
+        .map_err(|_| Status::invalid_argument(format!("invalid rate '{}'", rate.rate)))?;
+    let as_of = rate.as_of
+        .and_then(|ts| DateTime::from_timestamp(ts.seconds, ts.nanos as u32))
+        .ok_or_else(|| Status::invalid_argument("as_of is required"))?;
+    FxRate::new(base, quote, value, as_of).map_err(|e| Status::invalid_argument(e.to_string()))
+}
+
+#[tonic::async_trait]
+impl FxService for FxServiceManager {
+    async fn upsert_rates(&self, request: Request<UpsertRatesRequest>) -> Result<Response<UpsertRatesResponse>, Status> {
+        trace_request!(request, "upsert_rates");
+        let admin_fp = get_xrf_admin_auth_header(request.metadata(), &self.admin_fps)?;
+        let req = request.into_inner();
+        info!("upserting fx rates :: count={} :: admin_fp={}", req.rates.len(), admin_fp);
+        if req.rates.is_empty() || req.rates.len() > MAX_RATES_PER_REQUEST {
+            return Err(Status::invalid_argument(format!("between 1 and {} rates can be upserted at once", MAX_RATES_PER_REQUEST)));
+        }
+        let registry = find_currency_registry(&self.pg_pool).await?;
+        let rates = req.rates.into_iter()
+            .map(|rate| process_rate(rate, &registry))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut transaction = UnitOfWork::begin(&self.pg_pool).await
+            .map_err(|e| {
+                error!("failed to begin unit of work :: err={:?}", e);
+                Status::internal("server error")
+            })?;
+        let mut upserted = 0;
+        for rate in &rates {
+            let saved = queries::upsert_fx_rate(&mut transaction, rate)
+                .await
+                .map_err(|e| {
+                    error!("failed to upsert fx rate :: err={:?}", e);
+                    Status::internal("server error")
+                })?;
+            if saved {
+                upserted += 1;
+            }
+        }
+        transaction.commit().await
+            .map_err(|e| {
+                error!("failed to commit fx rates :: err={:?}", e);
+                Status::internal("server error")
+            })?;
+
+        Ok(Response::new(UpsertRatesResponse { upserted }))
+    }
+
+    async fn quote(&self, request: Request<QuoteRequest>) -> Result<Response<QuoteResponse>, Status> {
+        trace_request!(request, "quote");
+        let req = request.into_inner();
+        let registry = find_currency_registry(&self.pg_pool).await?;
+        let amount = process_money(req.amount, "amount", &registry)?;
+        let target = registry.resolve(&req.target_currency)
+            .ok_or_else(|| Status::invalid_argument(format!("invalid currency '{}'", req.target_currency)))?;
+        let target_currency = target.code.clone();
+        info!("quoting conversion :: amount={} :: target_currency={}", amount, target_currency);
+
+        let rate = orchestrator::find_fx_rate(amount.currency(), &target_currency, &self.fx_policy, self.pg_pool.as_ref())
+            .await
+            .map_err(|e| match e {
+                OrchestrateError::InvalidState(msg) => Status::failed_precondition(msg),
+                _ => {
+                    error!("failed to find fx rate :: err={:?}", e);
+                    Status::internal("server error")
+                }
+            })?;
+        let converted = match &rate {
+            None => amount,
+            Some(rate) => rate.convert(&amount, target.decimals, RoundingMode::HalfEven)
+                .map_err(|e| Status::invalid_argument(e.to_string()))?,
+        };
+
+        Ok(Response::new(QuoteResponse {
+            converted: Some(converted.into()),
+            rate: rate.map(ExchangeRate::from),
+        }))
+    }
+}
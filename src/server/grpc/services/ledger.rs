@@ -0,0 +1,99 @@
+use crate::constant::REQUEST_ID_KEY;
+use crate::core::{queries, JournalEntry};
+use crate::server::grpc::asset::ledger_service_server::LedgerService;
+use crate::server::grpc::asset::{GetBalanceRequest, GetBalanceResponse, JournalEntry as GrpcJournalEntry,
+                                 ListEntriesRequest, ListEntriesResponse, Posting as GrpcPosting};
+use crate::server::grpc::interceptors::trace_request;
+use crate::server::grpc::money::{find_currency_registry, process_currency};
+use crate::server::grpc::{get_xrf_user_auth_header, XRF_USER_FINGERPRINT};
+use prost_types::Timestamp;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use tracing::{error, info, info_span};
+
+const MAX_LIMIT: i32 = 100;
+
+pub struct LedgerServiceManager {
+    pg_pool: Arc<PgPool>,
+}
+
+impl LedgerServiceManager {
+    pub fn new(pg_pool: Arc<PgPool>) -> Self {
+        LedgerServiceManager { pg_pool }
+    }
+}
+
+// counterparties of an entry are only visible by the kind of their posting
+fn to_grpc_entry(entry: JournalEntry, caller_fp: &str) -> GrpcJournalEntry {
+    GrpcJournalEntry {
+        id: entry.id,
+        reference_id: entry.reference_id,
+        description: entry.description,
+        currency: entry.currency.to_string(),
+        postings: entry.postings.into_iter()
+            .map(|p| GrpcPosting {
+                amount: Some(p.amount.into()),
+                kind: p.kind.to_string(),
+                account_owner_fp: if p.account_owner_fp == caller_fp { p.account_owner_fp } else { "".to_string() },
+            })
+            .collect(),
+        created_at: Some(Timestamp {
+            seconds: entry.created_at.timestamp(),
+            nanos: entry.created_at.timestamp_subsec_nanos() as i32,
+        }),
+    }
+}
+
+#[tonic::async_trait]
+impl LedgerService for LedgerServiceManager {
+    async fn get_balance(&self, request: Request<GetBalanceRequest>) -> Result<Response<GetBalanceResponse>, Status> {
+        trace_request!(request, "get_balance");
+        let user_fp = get_xrf_user_auth_header(request.metadata(), XRF_USER_FINGERPRINT)?;
+        let req = request.into_inner();
+        info!("getting ledger balance :: currency={}", &req.currency);
+
+        let registry = find_currency_registry(&self.pg_pool).await?;
+        let currency = process_currency(&req.currency, &registry)?;
+        let balance = queries::get_account_balance(&user_fp, &currency, &self.pg_pool)
+            .await
+            .map_err(|e| {
+                error!("failed to get ledger balance :: err={:?}", e);
+                Status::internal("server error")
+            })?;
+
+        Ok(Response::new(GetBalanceResponse { balance: Some(balance.into()) }))
+    }
+
+    async fn list_entries(&self, request: Request<ListEntriesRequest>) -> Result<Response<ListEntriesResponse>, Status> {
+        trace_request!(request, "list_entries");
+        let user_fp = get_xrf_user_auth_header(request.metadata(), XRF_USER_FINGERPRINT)?;
+        let req = request.into_inner();
+        if req.offset < 0 {
+            return Err(Status::invalid_argument("offset must be positive"));
+        }
+        if !(1..=MAX_LIMIT).contains(&req.limit) {
+            return Err(Status::invalid_argument(format!("limit must be between 1 and {}", MAX_LIMIT)));
+        }
+        let currency = match req.currency {
+            None => None,
+            Some(currency) => Some(process_currency(&currency, &find_currency_registry(&self.pg_pool).await?)?),
+        };
+
+        let entries = queries::find_journal_entries_by_owner(&user_fp, currency, req.limit as i64, req.offset as i64, &self.pg_pool)
+            .await
+            .map_err(|e| {
+                error!("failed to list journal entries :: err={:?}", e);
+                Status::internal("server error")
+            })?;
+
+        let response = ListEntriesResponse {
+            offset: req.offset,
+            total: entries.len() as i32,
+            entries: entries.into_iter()
+                .map(|e| to_grpc_entry(e, &user_fp))
+                .collect(),
+        };
+        Ok(Response::new(response))
+    }
+}
@@ -1,5 +1,19 @@
 mod asset;
+mod bid;
+mod collection;
 mod contract;
+mod currency;
+mod escrow;
+mod event;
+mod fx;
+mod ledger;
 
 pub use asset::AssetServiceManager;
+pub use bid::BidServiceManager;
+pub use collection::CollectionServiceManager;
 pub use contract::ContractServiceManager;
+pub use currency::CurrencyServiceManager;
+pub use escrow::EscrowServiceManager;
+pub use event::EventServiceManager;
+pub use fx::FxServiceManager;
+pub use ledger::LedgerServiceManager;
@@ -1,3 +1,3 @@
 mod routes;
 pub mod server;
-pub use routes::get_app_health;
+pub use routes::{get_app_health, get_certificate_public_key};
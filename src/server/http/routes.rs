@@ -1,4 +1,6 @@
-use actix_web::HttpResponse;
+use crate::core::{CertificateSigner, CERTIFICATE_FORMAT};
+use actix_web::{web, HttpResponse};
+use serde::Serialize;
 use tracing::instrument;
 
 #[instrument]
@@ -8,3 +10,23 @@ pub async fn get_app_health() -> HttpResponse {
         .content_type("application/json")
         .body("healthy")
 }
+
+/// Key third parties need to verify NFC certificates offline
+#[derive(Serialize)]
+struct PublicKeyResponse {
+    algorithm: &'static str,
+    /// URL-safe base64 without padding, same as the certificates
+    public_key: String,
+    /// layout of the signed message, see `OwnershipClaim::message`
+    format: &'static str,
+}
+
+#[instrument(skip(signer))]
+pub async fn get_certificate_public_key(signer: web::Data<CertificateSigner>) -> HttpResponse {
+    tracing::info!("GET /certificate/public-key");
+    HttpResponse::Ok().json(PublicKeyResponse {
+        algorithm: "Ed25519",
+        public_key: signer.public_key_base64(),
+        format: CERTIFICATE_FORMAT,
+    })
+}
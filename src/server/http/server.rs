@@ -1,12 +1,19 @@
 use crate::configs::HttpServerConfig;
-use crate::server::http::get_app_health;
+use crate::core::CertificateSigner;
+use crate::server::http::{get_app_health, get_certificate_public_key};
 use actix_web::dev::Server;
 use actix_web::{web, App, HttpServer};
 
-pub async fn create_http_server(http_config: &HttpServerConfig) -> Result<Server, std::io::Error> {
+pub async fn create_http_server(http_config: &HttpServerConfig, signer: CertificateSigner) -> Result<Server, std::io::Error> {
     let address = format!("{}:{}", &http_config.host, &http_config.port);
 
-    let server = HttpServer::new(|| App::new().route("/health", web::get().to(get_app_health)))
+    let signer = web::Data::new(signer);
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(signer.clone())
+            .route("/health", web::get().to(get_app_health))
+            .route("/certificate/public-key", web::get().to(get_certificate_public_key))
+    })
         .bind(address)?
         .run();
 
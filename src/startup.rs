@@ -1,19 +1,27 @@
 use crate::configs::{Configurations, DatabaseConfig, HttpServerConfig};
+use crate::constant::NFC_SIGNING_KEY_PATH;
+use crate::context::AppContext;
+use crate::core::{queries, CertificateSigner};
 use crate::server::http::server::create_http_server;
 use crate::server::GrpcServer;
+use crate::workers::{AnchorWorker, AuctionWorker, ConfiguredSink, EscrowWorker, OutboxNotifier, OutboxRelay};
 use actix_web::dev::Server;
+use anyhow::Context;
+use base64::engine::general_purpose;
+use base64::Engine;
 use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
-use tracing::info;
+use std::path::Path;
+use tracing::{info, warn};
 
 pub struct HttpServer {
     pub server: Server,
 }
 
 impl HttpServer {
-    pub async fn new(config: &HttpServerConfig) -> Result<Self, std::io::Error> {
+    pub async fn new(config: &HttpServerConfig, signer: CertificateSigner) -> Result<Self, std::io::Error> {
         info!("starting HTTP server :: port {}", config.port);
-        let http_server = create_http_server(&config).await?;
+        let http_server = create_http_server(config, signer).await?;
         Ok(HttpServer { server: http_server })
     }
 
@@ -24,18 +32,34 @@ impl HttpServer {
 
 pub struct Application {
     pub http_server: HttpServer,
-    pub grpc_server: GrpcServer
+    pub grpc_server: GrpcServer,
+    pub auction_worker: AuctionWorker,
+    pub escrow_worker: EscrowWorker,
+    pub anchor_worker: AnchorWorker,
+    pub outbox_relay: OutboxRelay<ConfiguredSink>,
+    pub outbox_notifier: OutboxNotifier,
 }
 
 impl Application {
     pub async fn build(config: Configurations) -> Result<Self, anyhow::Error> {
-        let http_server = HttpServer::new(&config.server.http).await?;
+        let signer = load_certificate_signer()?;
+        let http_server = HttpServer::new(&config.server.http, signer.clone()).await?;
 
         let connection_pool = get_connection_pool(&config.database);
         info!("connected to database successfully :: {}", &config.database.postgres.name);
-        let grpc_server = GrpcServer::new(connection_pool, config.server.grpc)?;
+        if config.database.migrate_on_startup {
+            queries::run_migrations(&connection_pool).await.context("Failed to migrate the database")?;
+        }
+        let auction_worker = AuctionWorker::new(connection_pool.clone(), &config.workers.auction, config.escrow.hold_ttl());
+        let escrow_worker = EscrowWorker::new(connection_pool.clone(), &config.workers.escrow);
+        let anchor_worker = AnchorWorker::new(connection_pool.clone(), &config.workers.anchor);
+        let event_sink = ConfiguredSink::from_config(&config.workers.outbox.sink, config.workers.outbox.publish_timeout())?;
+        let outbox_relay = OutboxRelay::new(connection_pool.clone(), event_sink, &config.workers.outbox);
+        let outbox_notifier = OutboxNotifier::new(connection_pool.clone());
+        let grpc_server = GrpcServer::new(connection_pool, config.server.grpc, &config.fx, &config.escrow, &config.admin, signer,
+                                          outbox_notifier.sender())?;
 
-        Ok(Self { http_server, grpc_server })
+        Ok(Self { http_server, grpc_server, auction_worker, escrow_worker, anchor_worker, outbox_relay, outbox_notifier })
     }
 }
 
@@ -43,3 +67,35 @@ pub fn get_connection_pool(configuration: &DatabaseConfig) -> PgPool {
     PgPoolOptions::new()
         .connect_lazy_with(configuration.postgres.connect_to_database(&configuration.postgres.name))
 }
+
+const LOCAL_NFC_SIGNING_KEY_PATH: &str = "./local/keys/nfc_signing.pem";
+
+/// Loads the Ed25519 key that signs NFC certificates, a PKCS#8 key in PEM or DER format.
+/// Local environments without a key get a new key on every start, their certificates do not survive a restart.
+fn load_certificate_signer() -> anyhow::Result<CertificateSigner> {
+    let path = match std::env::var(NFC_SIGNING_KEY_PATH) {
+        Ok(path) => path,
+        Err(_) => {
+            let app_env = AppContext::environment();
+            if app_env.is_none() || app_env.clone().unwrap().is_not_local() {
+                return Err(anyhow::anyhow!("{} environment variable is missing", NFC_SIGNING_KEY_PATH));
+            }
+            if !Path::new(LOCAL_NFC_SIGNING_KEY_PATH).exists() {
+                warn!("no NFC signing key found, generating a key for this run :: path={}", LOCAL_NFC_SIGNING_KEY_PATH);
+                return Ok(CertificateSigner::generate()?);
+            }
+            LOCAL_NFC_SIGNING_KEY_PATH.to_string()
+        }
+    };
+    let key = std::fs::read(&path).with_context(|| format!("Failed to read NFC signing key from {}", path))?;
+    let pkcs8 = match std::str::from_utf8(&key) {
+        Ok(pem) if pem.trim_start().starts_with("-----BEGIN") => {
+            let body: String = pem.lines().filter(|line| !line.starts_with("-----")).collect();
+            general_purpose::STANDARD.decode(body.trim()).context("Failed to decode PEM NFC signing key")?
+        }
+        _ => key,
+    };
+    let signer = CertificateSigner::from_pkcs8(&pkcs8)?;
+    info!("loaded NFC signing key :: public_key={}", signer.public_key_base64());
+    Ok(signer)
+}
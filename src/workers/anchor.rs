@@ -0,0 +1,34 @@
+use crate::configs::AnchorWorkerConfig;
+use crate::core::orchestrator;
+use crate::workers::ticker;
+use sqlx::PgPool;
+use std::time::Duration;
+use tracing::{error, info};
+
+/// Periodically anchors the NFC certificates issued since the last run under a new Merkle root
+pub struct AnchorWorker {
+    pg_pool: PgPool,
+    interval: Duration,
+}
+
+impl AnchorWorker {
+    pub fn new(pg_pool: PgPool, config: &AnchorWorkerConfig) -> Self {
+        AnchorWorker {
+            pg_pool,
+            interval: Duration::from_secs(config.interval_secs),
+        }
+    }
+
+    pub async fn run_until_stopped(self) -> anyhow::Result<()> {
+        info!("starting anchor worker :: interval={}s", self.interval.as_secs());
+        let mut ticker = ticker(self.interval);
+        loop {
+            ticker.tick().await;
+            match orchestrator::anchor_nfc_certificates(&self.pg_pool).await {
+                Ok(None) => {}
+                Ok(Some(anchor)) => info!("anchored nfc certificates :: anchorId={} :: leafCount={}", anchor.id, anchor.leaf_count),
+                Err(e) => error!("failed to anchor nfc certificates :: err={}", e),
+            }
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use crate::configs::AuctionWorkerConfig;
+use crate::core::orchestrator;
+use crate::workers::ticker;
+use sqlx::PgPool;
+use std::time::Duration;
+use tracing::{error, info};
+
+/// Periodically closes the auctions that ended and reserves each asset for its winning bid
+pub struct AuctionWorker {
+    pg_pool: PgPool,
+    interval: Duration,
+    hold_ttl: chrono::Duration,
+}
+
+impl AuctionWorker {
+    pub fn new(pg_pool: PgPool, config: &AuctionWorkerConfig, hold_ttl: chrono::Duration) -> Self {
+        AuctionWorker {
+            pg_pool,
+            interval: Duration::from_secs(config.interval_secs),
+            hold_ttl,
+        }
+    }
+
+    pub async fn run_until_stopped(self) -> anyhow::Result<()> {
+        info!("starting auction worker :: interval={}s", self.interval.as_secs());
+        let mut ticker = ticker(self.interval);
+        loop {
+            ticker.tick().await;
+            match orchestrator::close_due_auctions(self.hold_ttl, &self.pg_pool).await {
+                Ok(0) => {}
+                Ok(closed) => info!("closed due auctions :: count={}", closed),
+                Err(e) => error!("failed to close due auctions :: err={}", e),
+            }
+        }
+    }
+}
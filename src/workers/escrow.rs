@@ -0,0 +1,34 @@
+use crate::configs::EscrowWorkerConfig;
+use crate::core::orchestrator;
+use crate::workers::ticker;
+use sqlx::PgPool;
+use std::time::Duration;
+use tracing::{error, info};
+
+/// Periodically cancels the escrow holds that were not funded in time and refunds the ones that were not released in time
+pub struct EscrowWorker {
+    pg_pool: PgPool,
+    interval: Duration,
+}
+
+impl EscrowWorker {
+    pub fn new(pg_pool: PgPool, config: &EscrowWorkerConfig) -> Self {
+        EscrowWorker {
+            pg_pool,
+            interval: Duration::from_secs(config.interval_secs),
+        }
+    }
+
+    pub async fn run_until_stopped(self) -> anyhow::Result<()> {
+        info!("starting escrow worker :: interval={}s", self.interval.as_secs());
+        let mut ticker = ticker(self.interval);
+        loop {
+            ticker.tick().await;
+            match orchestrator::expire_escrow_holds(&self.pg_pool).await {
+                Ok(0) => {}
+                Ok(expired) => info!("expired escrow holds :: count={}", expired),
+                Err(e) => error!("failed to expire escrow holds :: err={}", e),
+            }
+        }
+    }
+}
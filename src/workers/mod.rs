@@ -0,0 +1,24 @@
+mod anchor;
+mod auction;
+mod escrow;
+mod notifier;
+mod outbox;
+mod sink;
+
+pub use anchor::AnchorWorker;
+pub use auction::AuctionWorker;
+pub use escrow::EscrowWorker;
+pub use notifier::OutboxNotifier;
+pub use outbox::OutboxRelay;
+pub use sink::{ConfiguredSink, GrpcStreamSink, WriterSink};
+
+use std::time::Duration;
+use tokio::time::{Interval, MissedTickBehavior};
+
+/// Ticks every `period`, the first tick completes immediately.
+/// A slow run delays the next tick instead of triggering a burst of runs to catch up.
+fn ticker(period: Duration) -> Interval {
+    let mut ticker = tokio::time::interval(period);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    ticker
+}
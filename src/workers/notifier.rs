@@ -0,0 +1,58 @@
+use crate::core::queries::OUTBOX_CHANNEL;
+use anyhow::Context;
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::{error, info, warn};
+
+/// Notifications a subscription has not received yet, a subscription further behind misses the oldest of them
+const NOTIFICATION_CAPACITY: usize = 1024;
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Listens to the notifications of the events recorded in the outbox and broadcasts their sequence to the
+/// event subscriptions, so that every subscription is served by a single database connection.
+pub struct OutboxNotifier {
+    pg_pool: PgPool,
+    sender: broadcast::Sender<i64>,
+}
+
+impl OutboxNotifier {
+    pub fn new(pg_pool: PgPool) -> Self {
+        let (sender, _) = broadcast::channel(NOTIFICATION_CAPACITY);
+        OutboxNotifier { pg_pool, sender }
+    }
+
+    /// Subscriptions subscribe to the sender to receive the sequence of every event recorded from then on
+    pub fn sender(&self) -> broadcast::Sender<i64> {
+        self.sender.clone()
+    }
+
+    pub async fn run_until_stopped(self) -> anyhow::Result<()> {
+        let mut listener = PgListener::connect_with(&self.pg_pool)
+            .await
+            .context("Failed to connect the outbox listener")?;
+        listener.listen(OUTBOX_CHANNEL)
+            .await
+            .with_context(|| format!("Failed to listen to {}", OUTBOX_CHANNEL))?;
+        info!("starting outbox notifier :: channel={}", OUTBOX_CHANNEL);
+        loop {
+            // the listener connects again on the next call after losing its connection,
+            // notifications sent meanwhile are lost and subscriptions catch up on their next read
+            let notification = match listener.recv().await {
+                Ok(notification) => notification,
+                Err(e) => {
+                    error!("failed to receive outbox notification :: err={}", e);
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                    continue;
+                }
+            };
+            let Ok(sequence) = notification.payload().parse::<i64>() else {
+                warn!("ignoring outbox notification :: payload={}", notification.payload());
+                continue;
+            };
+            // sending only fails when no subscription is open
+            let _ = self.sender.send(sequence);
+        }
+    }
+}
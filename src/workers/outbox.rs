@@ -0,0 +1,45 @@
+use crate::configs::OutboxWorkerConfig;
+use crate::core::{orchestrator, EventSink};
+use crate::workers::ticker;
+use sqlx::PgPool;
+use std::time::Duration;
+use tracing::{error, info};
+
+/// Periodically publishes the pending events of the outbox to the sink, a batch at a time until none is left
+pub struct OutboxRelay<S> {
+    pg_pool: PgPool,
+    sink: S,
+    interval: Duration,
+    batch_size: i64,
+    publish_timeout: Duration,
+}
+
+impl<S: EventSink> OutboxRelay<S> {
+    pub fn new(pg_pool: PgPool, sink: S, config: &OutboxWorkerConfig) -> Self {
+        OutboxRelay {
+            pg_pool,
+            sink,
+            interval: Duration::from_secs(config.interval_secs),
+            batch_size: config.batch_size,
+            publish_timeout: config.publish_timeout(),
+        }
+    }
+
+    pub async fn run_until_stopped(self) -> anyhow::Result<()> {
+        info!("starting outbox relay :: interval={}s :: batch_size={}", self.interval.as_secs(), self.batch_size);
+        let mut ticker = ticker(self.interval);
+        loop {
+            ticker.tick().await;
+            loop {
+                match orchestrator::relay_outbox_events(&self.sink, self.batch_size, self.publish_timeout, &self.pg_pool).await {
+                    Ok(relayed) if relayed as i64 == self.batch_size => {}
+                    Ok(_) => break,
+                    Err(e) => {
+                        error!("failed to relay outbox events :: err={}", e);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::configs::EventSinkConfig;
+use crate::core::event_proto::event_sink_service_client::EventSinkServiceClient;
+use crate::core::event_proto::Event;
+use crate::core::{EventSink, OutboxEvent};
+use anyhow::{anyhow, Context};
+use base64::engine::general_purpose;
+use base64::Engine;
+use prost::Message;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tonic::transport::{Channel, Endpoint};
+
+/// Writes a line per event: its sequence, type and asset id, then the base64 encoded `Event`, separated by tabs.
+/// The writes block, they run on the blocking threads of the runtime.
+pub struct WriterSink {
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+}
+
+impl WriterSink {
+    pub fn new(writer: Box<dyn Write + Send>) -> Self {
+        WriterSink { writer: Arc::new(Mutex::new(writer)) }
+    }
+
+    pub fn stdout() -> Self {
+        WriterSink::new(Box::new(std::io::stdout()))
+    }
+
+    /// Appends to the file, it is created if it does not exist
+    pub fn file(path: &str) -> anyhow::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open event sink file {}", path))?;
+        Ok(WriterSink::new(Box::new(file)))
+    }
+}
+
+impl EventSink for WriterSink {
+    async fn publish(&self, events: &[OutboxEvent]) -> anyhow::Result<()> {
+        let mut lines = String::new();
+        for event in events {
+            let encoded = general_purpose::STANDARD.encode(Event::try_from(event)?.encode_to_vec());
+            lines.push_str(&format!("{}\t{}\t{}\t{}\n", event.sequence, event.event_type, event.asset_id, encoded));
+        }
+        let writer = self.writer.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut writer = writer.lock().map_err(|_| anyhow!("event sink writer is poisoned"))?;
+            writer.write_all(lines.as_bytes())?;
+            writer.flush()?;
+            Ok(())
+        })
+        .await
+        .context("event sink writer stopped")?
+    }
+}
+
+/// Streams the events to the `EventSinkService` of a downstream service, a call per publication.
+/// The events are received once the service acknowledges the last of them.
+#[derive(Debug, Clone)]
+pub struct GrpcStreamSink {
+    client: EventSinkServiceClient<Channel>,
+}
+
+impl GrpcStreamSink {
+    /// The connection is made on the first publication and made again after it fails.
+    /// Connecting and every call fail once they take longer than `timeout`.
+    pub fn connect_lazy(endpoint: &str, timeout: Duration) -> anyhow::Result<Self> {
+        let channel = Endpoint::from_shared(endpoint.to_string())
+            .with_context(|| format!("Invalid event sink endpoint {}", endpoint))?
+            .connect_timeout(timeout)
+            .timeout(timeout)
+            .connect_lazy();
+        Ok(GrpcStreamSink { client: EventSinkServiceClient::new(channel) })
+    }
+}
+
+impl EventSink for GrpcStreamSink {
+    async fn publish(&self, events: &[OutboxEvent]) -> anyhow::Result<()> {
+        let messages = events.iter().map(Event::try_from).collect::<Result<Vec<_>, _>>()?;
+        let Some(last_sequence) = messages.last().map(|event| event.sequence) else {
+            return Ok(());
+        };
+        let response = self.client.clone()
+            .publish_events(futures::stream::iter(messages))
+            .await?
+            .into_inner();
+        if response.last_sequence != last_sequence {
+            return Err(anyhow!("event sink acknowledged up to {} instead of {}", response.last_sequence, last_sequence));
+        }
+        Ok(())
+    }
+}
+
+/// The sink of the configuration
+pub enum ConfiguredSink {
+    Writer(WriterSink),
+    Grpc(GrpcStreamSink),
+}
+
+impl ConfiguredSink {
+    /// `timeout` bounds the calls of the gRPC sink
+    pub fn from_config(config: &EventSinkConfig, timeout: Duration) -> anyhow::Result<Self> {
+        Ok(match config {
+            EventSinkConfig::Stdout => ConfiguredSink::Writer(WriterSink::stdout()),
+            EventSinkConfig::File { path } => ConfiguredSink::Writer(WriterSink::file(path)?),
+            EventSinkConfig::Grpc { endpoint } => ConfiguredSink::Grpc(GrpcStreamSink::connect_lazy(endpoint, timeout)?),
+        })
+    }
+}
+
+impl EventSink for ConfiguredSink {
+    async fn publish(&self, events: &[OutboxEvent]) -> anyhow::Result<()> {
+        match self {
+            ConfiguredSink::Writer(sink) => sink.publish(events).await,
+            ConfiguredSink::Grpc(sink) => sink.publish(events).await,
+        }
+    }
+}
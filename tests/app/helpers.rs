@@ -1,6 +1,9 @@
 use sqlx::{Acquire, ConnectOptions, Connection, Executor, PgConnection, PgPool};
+use std::sync::Arc;
 use uuid::Uuid;
 use xrf1::configs::{load_config, DatabaseConfig};
+use xrf1::core::queries;
+use xrf1::core::repository::PgRepository;
 
 #[derive(Debug, Clone)]
 pub struct TestApp {
@@ -11,6 +14,10 @@ pub struct TestApp {
 }
 
 impl TestApp {
+    pub fn repository(&self) -> PgRepository {
+        PgRepository::new(Arc::new(self.db_pool.clone()))
+    }
+
     pub async fn drop_db(self) {
         // Shut down the connection pool, immediately waking all tasks waiting for a connection.
         // Upon calling this method, any currently waiting or subsequent calls to Pool::acquire and 
@@ -61,8 +68,7 @@ pub async fn configure_database(conn: &mut PgConnection, config: &DatabaseConfig
         .expect("Failed to connect to database.");
 
     // Migrate database
-    sqlx::migrate!("./migrations")
-        .run(&connection_pool)
+    queries::run_migrations(&connection_pool)
         .await
         .expect("Failed to migrate the database");
 
@@ -1,3 +1,5 @@
 mod helpers;
+mod orchestrator;
 mod queries;
+mod repository;
 mod seed;
@@ -0,0 +1,37 @@
+use crate::queries::suit::{run_test_async, TestError};
+use crate::seed::create_and_save_contract;
+use xrf1::core::{orchestrator, queries, verify_inclusion_proof, OrchestrateError};
+
+#[tokio::test]
+async fn test_anchored_certificates_prove_against_anchor_root() {
+    run_test_async(|app| async move {
+        let mut assets = Vec::new();
+        for _ in 0..3 {
+            let asset = create_and_save_contract(app.user_fp.clone(), &app.db_pool)
+                .await
+                .expect("Failed to create and save seed asset");
+            assets.push(asset);
+        }
+        let nfc = queries::get_nfc_by_asset_id(&assets[0].id, &app.db_pool).await?;
+        let result = orchestrator::find_inclusion_proof(&nfc.id, &app.repository()).await;
+        assert!(matches!(result, Err(OrchestrateError::InvalidState(_))), "{:?}", result.err());
+
+        let anchor = orchestrator::anchor_nfc_certificates(&app.db_pool).await?.expect("Missing anchor");
+        assert_eq!(anchor.leaf_count, 3);
+        // every certificate is anchored once
+        assert!(orchestrator::anchor_nfc_certificates(&app.db_pool).await?.is_none());
+
+        for asset in &assets {
+            let nfc = queries::get_nfc_by_asset_id(&asset.id, &app.db_pool).await?;
+            let proof = orchestrator::find_inclusion_proof(&nfc.id, &app.repository()).await?;
+            assert_eq!(proof.anchor.id, anchor.id);
+            assert_eq!(proof.leaf_hash, nfc.anchor_leaf_hash());
+            assert!(verify_inclusion_proof(&proof.leaf_hash, &proof.steps, &anchor.root));
+        }
+
+        let result = orchestrator::find_inclusion_proof("unknown-nfc", &app.repository()).await;
+        assert!(matches!(result, Err(OrchestrateError::NotFoundError(_))), "{:?}", result.err());
+
+        Ok::<_, TestError>(())
+    }).await
+}
@@ -0,0 +1,60 @@
+use crate::queries::suit::{run_test_async, TestError};
+use crate::seed::{create_and_save_contract, create_asset_owner, create_org_id, signer, test_contract};
+use xrf1::core::{orchestrator, queries, CertificateSigner, OrchestrateError, OwnershipClaim};
+
+#[tokio::test]
+async fn test_owner_certificate_is_signed_again_when_it_does_not_verify() {
+    run_test_async(|app| async move {
+        let asset = create_and_save_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create and save seed asset");
+        let (nfc, owner_fp) = orchestrator::find_owner_certificate(&asset.id, &signer(), &app.repository())
+            .await
+            .expect("Failed to find certificate");
+        assert_eq!(owner_fp, app.user_fp);
+        assert!(signer().verify(&OwnershipClaim::for_nfc(&nfc, &owner_fp), &nfc.cert));
+
+        // certificates issued before they were signed, or signed with a previous key
+        queries::update_nfc_cert(&nfc.id, "legacy-random-digest", &app.db_pool).await?;
+        let (resigned, _) = orchestrator::find_owner_certificate(&asset.id, &signer(), &app.repository())
+            .await
+            .expect("Failed to find certificate");
+        assert_eq!(resigned.cert, nfc.cert);
+
+        let rotated_signer = CertificateSigner::generate()?;
+        let (rotated, _) = orchestrator::find_owner_certificate(&asset.id, &rotated_signer, &app.repository())
+            .await
+            .expect("Failed to find certificate");
+        assert!(rotated_signer.verify(&OwnershipClaim::for_nfc(&rotated, &owner_fp), &rotated.cert));
+        let saved = queries::get_nfc_by_asset_id(&asset.id, &app.db_pool).await?;
+        assert_eq!(saved.cert, rotated.cert);
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_transfer_asset_only_under_a_contract_of_its_org() {
+    run_test_async(|app| async move {
+        let asset = create_and_save_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create and save seed asset");
+        let (new_owner, new_org) = (create_asset_owner(), create_org_id());
+
+        // assets are only transferred under a contract
+        let result = orchestrator::transfer_asset(&asset.organization, &asset.id, &new_org, &new_owner, &signer(), &app.repository()).await;
+        assert!(matches!(result, Err(OrchestrateError::NotFoundError(_))), "{:?}", result.err());
+        queries::create_contract(&app.db_pool, test_contract(&asset)?).await?;
+        let result = orchestrator::transfer_asset(&create_org_id(), &asset.id, &new_org, &new_owner, &signer(), &app.repository()).await;
+        assert!(matches!(result, Err(OrchestrateError::NotFoundError(_))), "{:?}", result.err());
+
+        let nfc = orchestrator::transfer_asset(&asset.organization, &asset.id, &new_org, &new_owner, &signer(), &app.repository())
+            .await
+            .expect("Failed to transfer asset");
+        assert!(signer().verify(&OwnershipClaim::for_nfc(&nfc, &new_owner), &nfc.cert));
+        let transferred = queries::find_asset_by_id(&asset.id, &app.db_pool).await?;
+        assert_eq!((transferred.organization, transferred.owner_fp), (new_org, new_owner));
+
+        Ok::<_, TestError>(())
+    }).await
+}
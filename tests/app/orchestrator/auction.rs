@@ -0,0 +1,91 @@
+use crate::queries::suit::{run_test_async, TestError};
+use crate::seed::{create_asset_owner, create_auctioned_asset, create_org_id, fx_policy, hold_ttl, settle_escrow_hold, usd};
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use xrf1::core::{orchestrator, queries, Asset, Auction, AuctionType, Bid, BidStatus, Contract, EscrowStatus, OrchestrateError};
+
+fn english_auction() -> Auction {
+    let start = Utc::now() - Duration::minutes(1);
+    Auction::new(AuctionType::English, start, start + Duration::hours(1), usd("30.00"), usd("5.00"), None)
+        .expect("Failed to create auction")
+}
+
+async fn place_bid(asset: &Asset, contract: &Contract, amount: &str, pg: &PgPool) -> Bid {
+    let bid = Bid::new(asset, contract, create_asset_owner(), create_org_id(), usd(amount), false, None, None)
+        .expect("Failed to create bid");
+    queries::create_bid(pg, &bid).await.expect("Failed to save bid");
+    bid
+}
+
+async fn end_auction(asset: &Asset, pg: &PgPool) {
+    sqlx::query("UPDATE contract_auction SET end_time = now() - interval '1 second' WHERE asset_id = $1")
+        .bind(&asset.id)
+        .execute(pg)
+        .await
+        .expect("Failed to end auction");
+}
+
+#[tokio::test]
+async fn test_close_english_auction_reserves_asset_for_highest_bid() {
+    run_test_async(|app| async move {
+        let (asset, contract) = create_auctioned_asset(app.user_fp.clone(), english_auction(), &app.db_pool)
+            .await
+            .expect("Failed to create auctioned asset");
+        let losing_bid = place_bid(&asset, &contract, "40.00", &app.db_pool).await;
+        let winning_bid = place_bid(&asset, &contract, "45.00", &app.db_pool).await;
+        assert_eq!(winning_bid.expires_at, contract.auction.as_ref().unwrap().end_time);
+
+        // the owner can not pick the winner of a running auction
+        let result = orchestrator::accept_bid(&losing_bid.id, &app.user_fp, &fx_policy(), hold_ttl(), &app.db_pool).await;
+        assert!(matches!(result, Err(OrchestrateError::InvalidState(_))));
+        let result = orchestrator::close_auction(&asset.id, hold_ttl(), &app.db_pool).await;
+        assert!(matches!(result, Err(OrchestrateError::InvalidState(_))));
+
+        end_auction(&asset, &app.db_pool).await;
+        let closed = orchestrator::close_due_auctions(hold_ttl(), &app.db_pool).await.expect("Failed to close auctions");
+        assert_eq!(closed, 1);
+
+        // the winner pays into escrow before the asset is transferred to them
+        let hold = queries::find_escrow_hold_by_bid_id(&winning_bid.id, &app.db_pool).await?;
+        assert_eq!(hold.status, EscrowStatus::Pending);
+        assert_eq!(hold.amount, usd("45.00"));
+        let sale = settle_escrow_hold(&hold, &asset, &app.db_pool).await.expect("Failed to settle hold");
+        assert_eq!(sale.price, usd("45.00"));
+        let sold_asset = queries::find_asset_by_id(&asset.id, &app.db_pool).await?;
+        assert_eq!(sold_asset.owner_fp, winning_bid.bidder_fp);
+        let losing_bid = queries::find_bid_by_id(&losing_bid.id, &app.db_pool).await?;
+        assert_eq!(losing_bid.status, BidStatus::Rejected);
+
+        let contract = queries::find_contract_by_asset_id(&asset.id, &app.db_pool).await?;
+        let auction = contract.auction.expect("auction not found");
+        assert!(auction.is_closed());
+        assert_eq!(auction.winning_bid_id, Some(winning_bid.id));
+
+        // closed auctions are not closed twice
+        let closed = orchestrator::close_due_auctions(hold_ttl(), &app.db_pool).await.expect("Failed to close auctions");
+        assert_eq!(closed, 0);
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_close_auction_without_bids_above_reserve() {
+    run_test_async(|app| async move {
+        let (asset, contract) = create_auctioned_asset(app.user_fp.clone(), english_auction(), &app.db_pool)
+            .await
+            .expect("Failed to create auctioned asset");
+        let bid = place_bid(&asset, &contract, "25.00", &app.db_pool).await;
+
+        end_auction(&asset, &app.db_pool).await;
+        let hold = orchestrator::close_auction(&asset.id, hold_ttl(), &app.db_pool).await.expect("Failed to close auction");
+        assert!(hold.is_none());
+
+        let saved_asset = queries::find_asset_by_id(&asset.id, &app.db_pool).await?;
+        assert_eq!(saved_asset.owner_fp, app.user_fp);
+        let bid = queries::find_bid_by_id(&bid.id, &app.db_pool).await?;
+        assert_eq!(bid.status, BidStatus::Rejected);
+
+        Ok::<_, TestError>(())
+    }).await
+}
@@ -0,0 +1,250 @@
+use crate::queries::suit::{run_test_async, TestError};
+use crate::seed::{
+    create_and_save_contract, create_asset_owner, create_bid, create_org_id, create_tradable_asset_with_contract, currency_info, fx_policy,
+    hold_ttl, settle_escrow_hold, signer, usd,
+};
+use chrono::{Duration, Utc};
+use std::collections::HashSet;
+use xrf1::core::queries::UnitOfWork;
+use xrf1::core::{
+    orchestrator, queries, Bid, BidStatus, Currency, EscrowStatus, FxPolicy, FxRate, Money, OrchestrateError, UpdateContractRequest,
+};
+
+#[tokio::test]
+async fn test_accept_bid_holds_asset_in_escrow_until_transfer() {
+    run_test_async(|app| async move {
+        let asset = create_tradable_asset_with_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create tradable asset");
+        let winning_bid = create_bid(&asset, create_asset_owner(), "50.00").expect("Failed to create bid");
+        let losing_bid = create_bid(&asset, create_asset_owner(), "25.00").expect("Failed to create bid");
+        queries::create_bid(&app.db_pool, &winning_bid).await.expect("Failed to save bid");
+        queries::create_bid(&app.db_pool, &losing_bid).await.expect("Failed to save bid");
+
+        let hold = orchestrator::accept_bid(&winning_bid.id, &app.user_fp, &fx_policy(), hold_ttl(), &app.db_pool)
+            .await
+            .expect("Failed to accept bid");
+        assert_eq!(hold.bid_id, winning_bid.id);
+        assert_eq!(hold.amount, winning_bid.amount);
+        assert_eq!(hold.seller_fp, app.user_fp);
+        assert_eq!(hold.buyer_fp, winning_bid.bidder_fp);
+        assert_eq!(hold.status, EscrowStatus::Pending);
+
+        let winning_bid = queries::find_bid_by_id(&winning_bid.id, &app.db_pool).await?;
+        let losing_bid = queries::find_bid_by_id(&losing_bid.id, &app.db_pool).await?;
+        assert_eq!(winning_bid.status, BidStatus::Accepted);
+        assert_eq!(losing_bid.status, BidStatus::Rejected);
+
+        // nothing is sold until the hold is funded and the asset transferred
+        let saved_asset = queries::find_asset_by_id(&asset.id, &app.db_pool).await?;
+        assert_eq!(saved_asset.owner_fp, app.user_fp);
+        assert!(queries::find_sales_by_asset_id(&asset.id, &app.db_pool).await?.is_empty());
+        let result = orchestrator::transfer_asset(&asset.organization, &asset.id, &hold.buyer_org, &hold.buyer_fp, &signer(), &app.repository()).await;
+        assert!(matches!(result, Err(OrchestrateError::InvalidState(_))), "{:?}", result.err());
+
+        // retrying returns the hold instead of reserving the asset twice
+        let retried_hold = orchestrator::accept_bid(&winning_bid.id, &app.user_fp, &fx_policy(), hold_ttl(), &app.db_pool)
+            .await
+            .expect("Failed to retry accepting bid");
+        assert_eq!(retried_hold.id, hold.id);
+
+        // a funded hold is released to its buyer only
+        orchestrator::fund_escrow_hold(&hold.id, &hold.buyer_fp, &app.db_pool).await.expect("Failed to fund hold");
+        let result = orchestrator::transfer_asset(&asset.organization, &asset.id, &create_org_id(), &create_asset_owner(), &signer(), &app.repository()).await;
+        assert!(matches!(result, Err(OrchestrateError::InvalidState(_))), "{:?}", result.err());
+        let nfc = orchestrator::transfer_asset(&asset.organization, &asset.id, &hold.buyer_org, &hold.buyer_fp, &signer(), &app.repository())
+            .await
+            .expect("Failed to transfer asset");
+        assert_eq!(nfc.id, hold.nfc_id);
+
+        let transferred = queries::find_asset_by_id(&asset.id, &app.db_pool).await?;
+        assert_eq!(transferred.owner_fp, winning_bid.bidder_fp);
+        assert_eq!(transferred.organization, winning_bid.bidder_org);
+        let released = queries::find_escrow_hold_by_id(&hold.id, &app.db_pool).await?;
+        assert_eq!(released.status, EscrowStatus::Released);
+        assert!(released.settled_at.is_some());
+
+        let sale = queries::find_sale_by_bid_id(&winning_bid.id, &app.db_pool).await?;
+        assert_eq!(sale.price, winning_bid.amount);
+        assert_eq!(sale.seller_fp, app.user_fp);
+        assert_eq!(sale.buyer_fp, winning_bid.bidder_fp);
+        assert_eq!(sale.nfc_id, hold.nfc_id);
+
+        let trails = queries::get_nfc_trails_by_nfc_id(&sale.nfc_id, &app.db_pool).await?;
+        assert_eq!(trails.len(), 2);
+
+        // sale is posted to the ledger
+        let buyer_balance = queries::get_account_balance(&sale.buyer_fp, &Currency::USD, &app.db_pool).await?;
+        let seller_balance = queries::get_account_balance(&sale.seller_fp, &Currency::USD, &app.db_pool).await?;
+        assert_eq!(buyer_balance, sale.price.checked_neg()?);
+        assert_eq!(seller_balance, sale.price);
+        let entries = queries::find_journal_entries_by_owner(&sale.buyer_fp, None, 10, 0, &app.db_pool).await?;
+        assert_eq!(entries.len(), 1);
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_accept_bid_only_by_asset_owner() {
+    run_test_async(|app| async move {
+        let asset = create_tradable_asset_with_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create tradable asset");
+        let bid = create_bid(&asset, create_asset_owner(), "50.00").expect("Failed to create bid");
+        queries::create_bid(&app.db_pool, &bid).await.expect("Failed to save bid");
+
+        let result = orchestrator::accept_bid(&bid.id, &create_asset_owner(), &fx_policy(), hold_ttl(), &app.db_pool).await;
+        assert!(matches!(result, Err(OrchestrateError::PermissionDenied(_))));
+
+        // nothing changed
+        let saved_asset = queries::find_asset_by_id(&asset.id, &app.db_pool).await?;
+        assert_eq!(saved_asset.owner_fp, app.user_fp);
+        let saved_bid = queries::find_bid_by_id(&bid.id, &app.db_pool).await?;
+        assert_eq!(saved_bid.status, BidStatus::Open);
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_accept_bid_waits_for_concurrent_contract_update() {
+    run_test_async(|app| async move {
+        let asset = create_tradable_asset_with_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create tradable asset");
+        let bid = create_bid(&asset, create_asset_owner(), "50.00").expect("Failed to create bid");
+        queries::create_bid(&app.db_pool, &bid).await.expect("Failed to save bid");
+
+        // a concurrent update raises the min price above the bid while the contract is locked
+        let mut transaction = UnitOfWork::begin(&app.db_pool).await?;
+        queries::find_contract_by_asset_id_for_update(&asset.id, &mut transaction).await?;
+        sqlx::query("UPDATE contract SET min_price = 60 WHERE asset_id = $1")
+            .bind(&asset.id)
+            .execute(&mut *transaction)
+            .await?;
+        let pool = app.db_pool.clone();
+        let bid_id = bid.id.clone();
+        let seller_fp = app.user_fp.clone();
+        let accepting = tokio::spawn(async move {
+            orchestrator::accept_bid(&bid_id, &seller_fp, &fx_policy(), hold_ttl(), &pool).await
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert!(!accepting.is_finished());
+        transaction.commit().await?;
+
+        // the bid is checked against the updated terms
+        let result = accepting.await?;
+        assert!(matches!(result, Err(OrchestrateError::InvalidState(_))), "{:?}", result);
+        let saved_bid = queries::find_bid_by_id(&bid.id, &app.db_pool).await?;
+        assert_eq!(saved_bid.status, BidStatus::Open);
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_place_bid_waits_for_the_asset_lock() {
+    run_test_async(|app| async move {
+        let asset = create_tradable_asset_with_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create tradable asset");
+        let (bid, _) = orchestrator::place_bid(&asset.id, create_asset_owner(), create_org_id(), usd("50.00"), false, None, &fx_policy(), &app.db_pool)
+            .await
+            .expect("Failed to place bid");
+        assert_eq!(queries::find_bid_by_id(&bid.id, &app.db_pool).await?.status, BidStatus::Open);
+
+        // a concurrent change makes the asset untradable while it is locked
+        let mut transaction = UnitOfWork::begin(&app.db_pool).await?;
+        queries::find_asset_by_id_for_update(&asset.id, &mut transaction).await?;
+        sqlx::query("UPDATE asset SET tradable = false WHERE id = $1")
+            .bind(&asset.id)
+            .execute(&mut *transaction)
+            .await?;
+        let pool = app.db_pool.clone();
+        let asset_id = asset.id.clone();
+        let placing = tokio::spawn(async move {
+            orchestrator::place_bid(&asset_id, create_asset_owner(), create_org_id(), usd("60.00"), false, None, &fx_policy(), &pool).await
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert!(!placing.is_finished());
+        transaction.commit().await?;
+
+        // the bid is checked against the asset once the change is committed
+        let result = placing.await?;
+        assert!(matches!(result, Err(OrchestrateError::InvalidState(_))), "{:?}", result.err());
+        assert_eq!(queries::count_bids_by_asset_id(&asset.id, None, &app.db_pool).await?, 1);
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_accept_bid_rejects_untradable_asset() {
+    run_test_async(|app| async move {
+        let asset = create_and_save_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create and save seed asset");
+        let bid = create_bid(&asset, create_asset_owner(), "50.00").expect("Failed to create bid");
+        queries::create_bid(&app.db_pool, &bid).await.expect("Failed to save bid");
+
+        // asset has no contract and is not tradable
+        let result = orchestrator::accept_bid(&bid.id, &app.user_fp, &fx_policy(), hold_ttl(), &app.db_pool).await;
+        assert!(matches!(result, Err(OrchestrateError::InvalidState(_))));
+
+        let sales = queries::find_sales_by_asset_id(&asset.id, &app.db_pool).await?;
+        assert!(sales.is_empty());
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_accept_bid_in_other_currency_at_current_rate() {
+    run_test_async(|app| async move {
+        let asset = create_tradable_asset_with_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create tradable asset");
+        let accept_btc = UpdateContractRequest {
+            accepted_currency: Some(HashSet::from([Currency::USD, Currency::BTC])),
+            ..Default::default()
+        };
+        let contract = orchestrator::update_contract(&asset.id, &app.user_fp, &accept_btc, &app.repository())
+            .await
+            .expect("Failed to update contract");
+
+        // 0.001 BTC is worth 65 USD, above the 20 USD min price
+        let btc_usd = FxRate::new(Currency::BTC, Currency::USD, 65000.into(), Utc::now() - Duration::minutes(1))?;
+        let mut transaction = UnitOfWork::begin(&app.db_pool).await?;
+        queries::upsert_fx_rate(&mut transaction, &btc_usd).await?;
+        transaction.commit().await?;
+        let fx_rate = orchestrator::find_fx_rate(&Currency::USD, &Currency::BTC, &fx_policy(), &app.db_pool)
+            .await
+            .expect("Failed to find fx rate");
+        assert_eq!(fx_rate.as_ref().map(|rate| &rate.base), Some(&Currency::USD));
+        let bid = Bid::new(&asset, &contract, create_asset_owner(), create_org_id(),
+                           Money::parse("0.001", &currency_info(Currency::BTC, 8))?, false, None, fx_rate.as_ref())?;
+        queries::create_bid(&app.db_pool, &bid).await.expect("Failed to save bid");
+
+        // the rate is checked again when the bid is accepted, a minute old rate is stale under a stricter policy
+        let strict_policy = FxPolicy::new(Duration::hours(1), Duration::seconds(30));
+        let result = orchestrator::accept_bid(&bid.id, &app.user_fp, &strict_policy, hold_ttl(), &app.db_pool).await;
+        assert!(matches!(result, Err(OrchestrateError::InvalidState(_))), "{:?}", result.err());
+
+        let fresh_rate = FxRate::new(Currency::BTC, Currency::USD, 64000.into(), Utc::now())?;
+        let mut transaction = UnitOfWork::begin(&app.db_pool).await?;
+        queries::upsert_fx_rate(&mut transaction, &fresh_rate).await?;
+        transaction.commit().await?;
+        let hold = orchestrator::accept_bid(&bid.id, &app.user_fp, &fx_policy(), hold_ttl(), &app.db_pool)
+            .await
+            .expect("Failed to accept bid");
+        assert_eq!(hold.amount, bid.amount);
+        let sale = settle_escrow_hold(&hold, &asset, &app.db_pool).await.expect("Failed to settle hold");
+        assert_eq!(sale.price, bid.amount);
+        let seller_balance = queries::get_account_balance(&app.user_fp, &Currency::BTC, &app.db_pool).await?;
+        assert_eq!(seller_balance, bid.amount);
+        assert_eq!(sale.price.currency(), &Currency::BTC);
+
+        Ok::<_, TestError>(())
+    }).await
+}
@@ -0,0 +1,130 @@
+use crate::queries::suit::{run_test_async, TestError};
+use crate::seed::{create_org_id, signer, usd};
+use std::collections::HashSet;
+use uuid::Uuid;
+use xrf1::core::queries::{AssetSort, OrderType};
+use xrf1::core::{orchestrator, queries, Asset, AssetFilter, AssetSortField, Collection, ContractTerms, ContractVersion, Currency,
+                 DefaultContract, OrchestrateError, RoyaltySplit, UpdateCollectionRequest};
+
+fn default_contract() -> DefaultContract {
+    DefaultContract::new("limited series".to_string(),
+                         "collection contract".to_string(),
+                         usd("25.00"),
+                         false,
+                         vec![RoyaltySplit { receiver_fp: "artist".to_string(), basis_points: 500 }],
+                         ContractTerms::default(),
+                         HashSet::from([Currency::USD]))
+        .expect("Failed to create default contract")
+}
+
+fn collection(org_id: &str, supply_cap: Option<i32>, user_fp: &str) -> Collection {
+    Collection::new(org_id.to_string(),
+                    format!("series {}", &Uuid::new_v4().to_string()[..8]),
+                    "sty".to_string(),
+                    "paintings of the night sky".to_string(),
+                    supply_cap,
+                    Some(default_contract()),
+                    user_fp.to_string())
+        .expect("Failed to create collection")
+}
+
+fn collection_asset(collection: &Collection, symbol: &str, user_fp: &str) -> Asset {
+    Asset::new(Uuid::new_v4().to_string()[..15].to_string(),
+               symbol.to_string(),
+               user_fp.to_string(),
+               "".to_string(),
+               collection.organization.clone())
+        .expect("Failed to create asset")
+}
+
+#[tokio::test]
+async fn test_collection_assets_inherit_default_contract_up_to_supply_cap() {
+    run_test_async(|app| async move {
+        let collection = collection(&create_org_id(), Some(2), &app.user_fp);
+        orchestrator::create_collection(&collection, &app.db_pool).await.expect("Failed to create collection");
+
+        let saved = queries::find_collection_by_id(&collection.id, &app.db_pool).await?;
+        assert_eq!(saved.symbol_prefix, "STY");
+        assert_eq!(saved.supply_cap, Some(2));
+        assert_eq!(saved.default_contract, Some(default_contract()));
+
+        for symbol in ["STY-01", "STY-02"] {
+            let asset = collection_asset(&collection, symbol, &app.user_fp);
+            orchestrator::create_collection_asset(&asset, &collection.id, &app.user_fp, &signer(), &app.repository())
+                .await
+                .expect("Failed to create collection asset");
+            let contract = queries::find_contract_by_asset_id(&asset.id, &app.db_pool).await?;
+            assert_eq!(contract.version, ContractVersion::V2);
+            assert_eq!(contract.min_price, usd("25.00"));
+            assert_eq!(contract.royalty_splits, default_contract().royalty_splits);
+        }
+
+        let full = collection_asset(&collection, "STY-03", &app.user_fp);
+        let result = orchestrator::create_collection_asset(&full, &collection.id, &app.user_fp, &signer(), &app.repository()).await;
+        assert!(matches!(result, Err(OrchestrateError::InvalidState(_))));
+        assert!(queries::find_asset_by_id(&full.id, &app.db_pool).await.is_err());
+
+        let other_series = collection_asset(&collection, "XRF-01", &app.user_fp);
+        let result = orchestrator::create_collection_asset(&other_series, &collection.id, &app.user_fp, &signer(), &app.repository()).await;
+        assert!(matches!(result, Err(OrchestrateError::InvalidArgument(_))));
+
+        let filter = AssetFilter { collection_id: Some(collection.id.clone()), ..Default::default() };
+        let sort = AssetSort::new(AssetSortField::Symbol, OrderType::Asc);
+        let assets = queries::list_assets(&filter, sort, None, 10, &app.db_pool).await?;
+        let symbols: Vec<&str> = assets.iter().map(|asset| asset.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["STY-01", "STY-02"]);
+        assert_eq!(queries::count_assets(&filter, &app.db_pool).await?, 2);
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_update_and_delete_collection() {
+    run_test_async(|app| async move {
+        let org_id = create_org_id();
+        let collection = collection(&org_id, None, &app.user_fp);
+        orchestrator::create_collection(&collection, &app.db_pool).await.expect("Failed to create collection");
+        let duplicate = Collection { id: Uuid::new_v4().to_string(), ..collection.clone() };
+        let result = orchestrator::create_collection(&duplicate, &app.db_pool).await;
+        assert!(matches!(result, Err(OrchestrateError::InvalidState(_))));
+
+        for symbol in ["STY-01", "STY-02"] {
+            let asset = collection_asset(&collection, symbol, &app.user_fp);
+            orchestrator::create_collection_asset(&asset, &collection.id, &app.user_fp, &signer(), &app.repository())
+                .await
+                .expect("Failed to create collection asset");
+        }
+
+        let lower_cap = UpdateCollectionRequest { supply_cap: Some(Some(1)), ..Default::default() };
+        let result = orchestrator::update_collection(&collection.id, &org_id, "editor", &lower_cap, &app.db_pool).await;
+        assert!(matches!(result, Err(OrchestrateError::InvalidState(_))));
+
+        let update = UpdateCollectionRequest {
+            name: Some("Starry series".to_string()),
+            supply_cap: Some(Some(2)),
+            default_contract: Some(None),
+            ..Default::default()
+        };
+        let result = orchestrator::update_collection(&collection.id, &create_org_id(), "editor", &update, &app.db_pool).await;
+        assert!(matches!(result, Err(OrchestrateError::NotFoundError(_))));
+        orchestrator::update_collection(&collection.id, &org_id, "editor", &update, &app.db_pool)
+            .await
+            .expect("Failed to update collection");
+        let saved = queries::find_collection_by_id(&collection.id, &app.db_pool).await?;
+        assert_eq!(saved.name, "Starry series");
+        assert_eq!(saved.supply_cap, Some(2));
+        assert_eq!(saved.default_contract, None);
+        assert_eq!(saved.updated_by, "editor");
+
+        let result = orchestrator::delete_collection(&collection.id, &org_id, &app.db_pool).await;
+        assert!(matches!(result, Err(OrchestrateError::InvalidState(_))));
+
+        let empty = self::collection(&org_id, None, &app.user_fp);
+        orchestrator::create_collection(&empty, &app.db_pool).await.expect("Failed to create collection");
+        orchestrator::delete_collection(&empty.id, &org_id, &app.db_pool).await.expect("Failed to delete collection");
+        assert!(queries::find_collection_by_id(&empty.id, &app.db_pool).await.is_err());
+
+        Ok::<_, TestError>(())
+    }).await
+}
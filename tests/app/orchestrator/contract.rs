@@ -0,0 +1,158 @@
+use crate::queries::suit::{run_test_async, TestError};
+use crate::seed::{create_asset_owner, create_bid, create_tradable_asset_with_contract, usd};
+use std::collections::HashSet;
+use xrf1::core::{orchestrator, queries, ContractVersion, Currency, OrchestrateError, RoyaltySplit, UpdateContractRequest};
+
+fn min_price_update(min_price: &str) -> UpdateContractRequest {
+    UpdateContractRequest { min_price: Some(usd(min_price)), ..Default::default() }
+}
+
+#[tokio::test]
+async fn test_update_contract_records_history() {
+    run_test_async(|app| async move {
+        let asset = create_tradable_asset_with_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create tradable asset");
+
+        let updated = orchestrator::update_contract(&asset.id, &app.user_fp, &min_price_update("30.00"), &app.repository())
+            .await
+            .expect("Failed to update contract");
+        assert_eq!(updated.update_count, 1);
+        let updated = orchestrator::update_contract(&asset.id, &app.user_fp, &min_price_update("40.00"), &app.repository())
+            .await
+            .expect("Failed to update contract");
+        assert_eq!(updated.update_count, 2);
+
+        let current = queries::find_contract_by_asset_id(&asset.id, &app.db_pool).await?;
+        assert_eq!(current.min_price, usd("40.00"));
+        assert_eq!(current.update_count, 2);
+
+        let history = queries::find_contract_history(&asset.id, 10, 0, &app.db_pool).await?;
+        let update_counts: Vec<i32> = history.iter().map(|c| c.update_count).collect();
+        assert_eq!(update_counts, vec![1, 0]);
+
+        let original = queries::find_contract_at_version(&asset.id, 0, &app.db_pool).await?;
+        assert_eq!(original.min_price, usd("20.00"));
+        let revision = queries::find_contract_at_version(&asset.id, 1, &app.db_pool).await?;
+        assert_eq!(revision.min_price, usd("30.00"));
+        let latest = queries::find_contract_at_version(&asset.id, 2, &app.db_pool).await?;
+        assert_eq!(latest.min_price, usd("40.00"));
+        assert!(queries::find_contract_at_version(&asset.id, 3, &app.db_pool).await.is_err());
+
+        // history is immutable
+        let result = sqlx::query("UPDATE contract_history SET min_price = 1 WHERE asset_id = $1")
+            .bind(&asset.id)
+            .execute(&app.db_pool)
+            .await;
+        assert!(result.is_err());
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_contract_history_is_only_deleted_with_its_contract() {
+    run_test_async(|app| async move {
+        let asset = create_tradable_asset_with_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create tradable asset");
+        orchestrator::update_contract(&asset.id, &app.user_fp, &min_price_update("30.00"), &app.repository())
+            .await
+            .expect("Failed to update contract");
+
+        let result = sqlx::query("DELETE FROM contract_history WHERE asset_id = $1")
+            .bind(&asset.id)
+            .execute(&app.db_pool)
+            .await;
+        assert!(result.is_err());
+        assert_eq!(queries::find_contract_history(&asset.id, 10, 0, &app.db_pool).await?.len(), 1);
+
+        // deleting the asset deletes its contract and the history of the contract
+        assert!(queries::delete_asset_by_id(&asset.id, &app.db_pool).await?);
+        assert!(queries::find_contract_history(&asset.id, 10, 0, &app.db_pool).await?.is_empty());
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_update_contract_only_by_owner_without_open_bids() {
+    run_test_async(|app| async move {
+        let asset = create_tradable_asset_with_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create tradable asset");
+
+        let result = orchestrator::update_contract(&asset.id, &create_asset_owner(), &min_price_update("30.00"), &app.repository()).await;
+        assert!(matches!(result, Err(OrchestrateError::PermissionDenied(_))));
+
+        let bid = create_bid(&asset, create_asset_owner(), "50.00").expect("Failed to create bid");
+        queries::create_bid(&app.db_pool, &bid).await.expect("Failed to save bid");
+        let result = orchestrator::update_contract(&asset.id, &app.user_fp, &min_price_update("30.00"), &app.repository()).await;
+        assert!(matches!(result, Err(OrchestrateError::InvalidState(_))));
+
+        let contract = queries::find_contract_by_asset_id(&asset.id, &app.db_pool).await?;
+        assert_eq!(contract.update_count, 0);
+        let history = queries::find_contract_history(&asset.id, 10, 0, &app.db_pool).await?;
+        assert!(history.is_empty());
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_upgrade_v1_contracts() {
+    run_test_async(|app| async move {
+        let asset = create_tradable_asset_with_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create tradable asset");
+        let royalty = UpdateContractRequest {
+            royalty_splits: Some(vec![RoyaltySplit { receiver_fp: "creator_fp".to_string(), basis_points: 250 }]),
+            ..Default::default()
+        };
+        orchestrator::update_contract(&asset.id, &app.user_fp, &royalty, &app.repository())
+            .await
+            .expect("Failed to update contract");
+
+        let upgraded = orchestrator::upgrade_v1_contracts(10, &app.db_pool).await.expect("Failed to upgrade contracts");
+        assert_eq!(upgraded, 1);
+        let upgraded = orchestrator::upgrade_v1_contracts(10, &app.db_pool).await.expect("Failed to upgrade contracts");
+        assert_eq!(upgraded, 0);
+
+        let contract = queries::find_contract_by_asset_id(&asset.id, &app.db_pool).await?;
+        assert_eq!(contract.version, ContractVersion::V2);
+        assert_eq!(contract.update_count, 2);
+        assert_eq!(contract.royalty_splits, vec![RoyaltySplit { receiver_fp: "creator_fp".to_string(), basis_points: 250 }]);
+
+        // the v1 revision is kept in the history
+        let v1 = queries::find_contract_at_version(&asset.id, 1, &app.db_pool).await?;
+        assert_eq!(v1.version, ContractVersion::V1);
+        assert_eq!(v1.royalty_splits, contract.royalty_splits);
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_update_contract_only_accepts_enabled_currencies() {
+    run_test_async(|app| async move {
+        let asset = create_tradable_asset_with_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create tradable asset");
+        queries::set_currency_enabled(&Currency::EUR, false, &app.db_pool).await?;
+
+        let currencies = UpdateContractRequest {
+            accepted_currency: Some(HashSet::from([Currency::USD, Currency::EUR])),
+            ..Default::default()
+        };
+        let result = orchestrator::update_contract(&asset.id, &app.user_fp, &currencies, &app.repository()).await;
+        assert!(matches!(result, Err(OrchestrateError::InvalidArgument(_))));
+
+        queries::set_currency_enabled(&Currency::EUR, true, &app.db_pool).await?;
+        let updated = orchestrator::update_contract(&asset.id, &app.user_fp, &currencies, &app.repository())
+            .await
+            .expect("Failed to update contract");
+        assert_eq!(updated.accepted_currency, HashSet::from([Currency::USD, Currency::EUR]));
+
+        Ok::<_, TestError>(())
+    }).await
+}
@@ -0,0 +1,134 @@
+use crate::queries::suit::{run_test_async, TestError};
+use crate::seed::{create_asset, create_asset_owner, create_bid, create_org_id, create_tradable_asset_with_contract, fx_policy, hold_ttl,
+                  signer, test_contract};
+use chrono::Utc;
+use sqlx::PgPool;
+use xrf1::core::repository::{AssetRepository, ContractRepository, InMemoryRepository, NfcRepository, Transactional};
+use xrf1::core::{orchestrator, queries, Asset, BidStatus, EscrowHold, EscrowStatus, OrchestrateError, OwnershipClaim};
+
+async fn accept_new_bid(asset: &Asset, seller_fp: &str, pg: &PgPool) -> EscrowHold {
+    let bid = create_bid(asset, create_asset_owner(), "50.00").expect("Failed to create bid");
+    queries::create_bid(pg, &bid).await.expect("Failed to save bid");
+    orchestrator::accept_bid(&bid.id, seller_fp, &fx_policy(), hold_ttl(), pg)
+        .await
+        .expect("Failed to accept bid")
+}
+
+async fn pass_deadline(hold: &EscrowHold, pg: &PgPool) {
+    sqlx::query("UPDATE escrow SET deadline = now() - interval '1 second' WHERE id = $1")
+        .bind(&hold.id)
+        .execute(pg)
+        .await
+        .expect("Failed to pass hold deadline");
+}
+
+#[tokio::test]
+async fn test_fund_escrow_hold_only_by_buyer_before_deadline() {
+    run_test_async(|app| async move {
+        let asset = create_tradable_asset_with_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create tradable asset");
+        let hold = accept_new_bid(&asset, &app.user_fp, &app.db_pool).await;
+
+        let result = orchestrator::fund_escrow_hold(&hold.id, &app.user_fp, &app.db_pool).await;
+        assert!(matches!(result, Err(OrchestrateError::NotFoundError(_))), "{:?}", result.err());
+
+        let funded = orchestrator::fund_escrow_hold(&hold.id, &hold.buyer_fp, &app.db_pool)
+            .await
+            .expect("Failed to fund hold");
+        assert_eq!(funded.status, EscrowStatus::Funded);
+        assert!(funded.funded_at.is_some());
+        let result = orchestrator::fund_escrow_hold(&hold.id, &hold.buyer_fp, &app.db_pool).await;
+        assert!(matches!(result, Err(OrchestrateError::InvalidState(_))), "{:?}", result.err());
+
+        // a funded hold can not be released once its deadline passed
+        pass_deadline(&hold, &app.db_pool).await;
+        let result = orchestrator::transfer_asset(&asset.organization, &asset.id, &hold.buyer_org, &hold.buyer_fp, &signer(), &app.repository()).await;
+        assert!(matches!(result, Err(OrchestrateError::InvalidState(_))), "{:?}", result.err());
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_expired_escrow_holds_are_cancelled_or_refunded() {
+    run_test_async(|app| async move {
+        let unpaid_asset = create_tradable_asset_with_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create tradable asset");
+        let paid_asset = create_tradable_asset_with_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create tradable asset");
+        let unpaid_hold = accept_new_bid(&unpaid_asset, &app.user_fp, &app.db_pool).await;
+        let paid_hold = accept_new_bid(&paid_asset, &app.user_fp, &app.db_pool).await;
+        orchestrator::fund_escrow_hold(&paid_hold.id, &paid_hold.buyer_fp, &app.db_pool).await.expect("Failed to fund hold");
+
+        // holds within their deadline are kept
+        assert_eq!(orchestrator::expire_escrow_holds(&app.db_pool).await.expect("Failed to expire holds"), 0);
+
+        pass_deadline(&unpaid_hold, &app.db_pool).await;
+        pass_deadline(&paid_hold, &app.db_pool).await;
+        let result = orchestrator::fund_escrow_hold(&unpaid_hold.id, &unpaid_hold.buyer_fp, &app.db_pool).await;
+        assert!(matches!(result, Err(OrchestrateError::InvalidState(_))), "{:?}", result.err());
+        assert_eq!(orchestrator::expire_escrow_holds(&app.db_pool).await.expect("Failed to expire holds"), 2);
+
+        let cancelled = queries::find_escrow_hold_by_id(&unpaid_hold.id, &app.db_pool).await?;
+        let refunded = queries::find_escrow_hold_by_id(&paid_hold.id, &app.db_pool).await?;
+        assert_eq!(cancelled.status, EscrowStatus::Cancelled);
+        assert_eq!(refunded.status, EscrowStatus::Refunded);
+        let expired_bid = queries::find_bid_by_id(&unpaid_hold.bid_id, &app.db_pool).await?;
+        assert_eq!(expired_bid.status, BidStatus::Expired);
+
+        // the seller kept the asset and can accept another bid
+        let saved_asset = queries::find_asset_by_id(&paid_asset.id, &app.db_pool).await?;
+        assert_eq!(saved_asset.owner_fp, app.user_fp);
+        assert!(queries::find_sales_by_asset_id(&paid_asset.id, &app.db_pool).await?.is_empty());
+        let next_hold = accept_new_bid(&paid_asset, &app.user_fp, &app.db_pool).await;
+        assert_eq!(next_hold.status, EscrowStatus::Pending);
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_transfer_asset_in_memory_releases_funded_escrow_hold() -> Result<(), TestError> {
+    let repository = InMemoryRepository::default();
+    let seller_fp = create_asset_owner();
+    let asset = create_asset(seller_fp.clone())?;
+    repository.create_asset(&asset, &seller_fp, &signer()).await?;
+    repository.create_contract(test_contract(&asset)?).await?;
+    let nfc = repository.get_nfc_by_asset_id(&asset.id).await?;
+    let bid = create_bid(&asset, create_asset_owner(), "50.00")?;
+    let mut hold = EscrowHold::for_bid(&bid, &asset, nfc.id, hold_ttl())?;
+    repository.insert_bid(bid).await;
+    repository.insert_escrow_hold(hold.clone()).await;
+
+    // the asset is reserved for the buyer, who has not paid yet
+    let result = orchestrator::transfer_asset(&asset.organization, &asset.id, &hold.buyer_org, &hold.buyer_fp, &signer(), &repository).await;
+    assert!(matches!(result, Err(OrchestrateError::InvalidState(_))), "{:?}", result.err());
+    hold.status = EscrowStatus::Funded;
+    hold.funded_at = Some(Utc::now());
+    repository.insert_escrow_hold(hold.clone()).await;
+    let result = orchestrator::transfer_asset(&asset.organization, &asset.id, &create_org_id(), &create_asset_owner(), &signer(), &repository)
+        .await;
+    assert!(matches!(result, Err(OrchestrateError::InvalidState(_))), "{:?}", result.err());
+
+    // the transfer to the buyer completes the sale and releases the hold
+    let nfc = orchestrator::transfer_asset(&asset.organization, &asset.id, &hold.buyer_org, &hold.buyer_fp, &signer(), &repository).await?;
+    assert!(signer().verify(&OwnershipClaim::for_nfc(&nfc, &hold.buyer_fp), &nfc.cert));
+    let sold = repository.find_asset_by_id_and_org_id(&asset.id, &hold.buyer_org).await?;
+    assert_eq!(sold.owner_fp, hold.buyer_fp);
+    let trails = repository.get_nfc_trails_by_asset_id(&asset.id).await?;
+    assert!(trails[1].sale_id.is_some());
+    let mut transaction = repository.begin().await?;
+    assert!(repository.find_active_escrow_hold(&asset.id, &mut transaction).await?.is_none());
+    drop(transaction);
+
+    // without a hold the asset is transferred at once
+    let (new_owner, new_org) = (create_asset_owner(), create_org_id());
+    orchestrator::transfer_asset(&hold.buyer_org, &asset.id, &new_org, &new_owner, &signer(), &repository).await?;
+    let trails = repository.get_nfc_trails_by_asset_id(&asset.id).await?;
+    assert_eq!(trails.len(), 3);
+    assert!(trails[2].sale_id.is_none());
+    Ok(())
+}
@@ -0,0 +1,9 @@
+mod anchor;
+mod asset;
+mod auction;
+mod bid;
+mod collection;
+mod contract;
+mod escrow;
+mod outbox;
+mod trail;
@@ -0,0 +1,282 @@
+use crate::queries::suit::{run_test_async, TestError};
+use crate::seed::{create_and_save_contract, create_asset_owner, create_org_id, signer};
+use base64::engine::general_purpose;
+use base64::Engine;
+use prost::Message;
+use std::collections::HashSet;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status, Streaming};
+use xrf1::core::event_proto::event_sink_service_server::{EventSinkService, EventSinkServiceServer};
+use xrf1::core::event_proto::{Event, PublishEventsResponse};
+use xrf1::core::repository::{AssetRepository, Transactional};
+use xrf1::core::{orchestrator, queries, EventFilter, EventSink, OrchestrateError, OutboxEvent};
+use xrf1::workers::{GrpcStreamSink, OutboxNotifier, WriterSink};
+
+const PUBLISH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Output of a `WriterSink` kept in memory
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+struct FailingSink;
+
+impl EventSink for FailingSink {
+    async fn publish(&self, _events: &[OutboxEvent]) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!("downstream is unavailable"))
+    }
+}
+
+/// A sink that never acknowledges what it receives
+struct StalledSink;
+
+impl EventSink for StalledSink {
+    async fn publish(&self, _events: &[OutboxEvent]) -> anyhow::Result<()> {
+        std::future::pending().await
+    }
+}
+
+/// A sink that signals when it receives events and never acknowledges them
+struct BlockingSink(Arc<Notify>);
+
+impl EventSink for BlockingSink {
+    async fn publish(&self, _events: &[OutboxEvent]) -> anyhow::Result<()> {
+        self.0.notify_one();
+        std::future::pending().await
+    }
+}
+
+/// Event sink service of a downstream service, acknowledging every event it receives
+#[derive(Clone, Default)]
+struct RecordingService {
+    received: Arc<Mutex<Vec<Event>>>,
+}
+
+#[tonic::async_trait]
+impl EventSinkService for RecordingService {
+    async fn publish_events(&self, request: Request<Streaming<Event>>) -> Result<Response<PublishEventsResponse>, Status> {
+        let mut stream = request.into_inner();
+        let mut last_sequence = 0;
+        while let Some(event) = stream.message().await? {
+            last_sequence = event.sequence;
+            self.received.lock().unwrap().push(event);
+        }
+        Ok(Response::new(PublishEventsResponse { last_sequence }))
+    }
+}
+
+#[tokio::test]
+async fn test_relayed_events_are_written_once() {
+    run_test_async(|app| async move {
+        let first = create_and_save_contract(app.user_fp.clone(), &app.db_pool).await.expect("Failed to create seed asset");
+        let second = create_and_save_contract(app.user_fp.clone(), &app.db_pool).await.expect("Failed to create seed asset");
+        let buffer = SharedBuffer::default();
+        let sink = WriterSink::new(Box::new(buffer.clone()));
+
+        assert_eq!(orchestrator::relay_outbox_events(&sink, 1, PUBLISH_TIMEOUT, &app.db_pool).await?, 1);
+        assert_eq!(orchestrator::relay_outbox_events(&sink, 10, PUBLISH_TIMEOUT, &app.db_pool).await?, 1);
+        assert_eq!(orchestrator::relay_outbox_events(&sink, 10, PUBLISH_TIMEOUT, &app.db_pool).await?, 0);
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone())?;
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for (line, asset) in lines.iter().zip([&first, &second]) {
+            let columns: Vec<&str> = line.split('\t').collect();
+            assert_eq!(&columns[1..3], &["asset_created", asset.id.as_str()]);
+            let event = Event::decode(general_purpose::STANDARD.decode(columns[3])?.as_slice())?;
+            assert_eq!(event.sequence.to_string(), columns[0]);
+            assert_eq!(event.owner_fp, asset.owner_fp);
+        }
+        let events = queries::find_outbox_events_by_asset_id(&first.id, &app.db_pool).await?;
+        assert!(events[0].dispatched_at.is_some());
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_events_stay_pending_when_the_sink_fails() {
+    run_test_async(|app| async move {
+        let asset = create_and_save_contract(app.user_fp.clone(), &app.db_pool).await.expect("Failed to create seed asset");
+
+        let result = orchestrator::relay_outbox_events(&FailingSink, 10, PUBLISH_TIMEOUT, &app.db_pool).await;
+        assert!(matches!(result, Err(OrchestrateError::ServerError(_))), "{:?}", result);
+        let events = queries::find_outbox_events_by_asset_id(&asset.id, &app.db_pool).await?;
+        assert!(events[0].dispatched_at.is_none());
+
+        // the next relay publishes them again
+        let sink = WriterSink::new(Box::new(SharedBuffer::default()));
+        assert_eq!(orchestrator::relay_outbox_events(&sink, 10, PUBLISH_TIMEOUT, &app.db_pool).await?, 1);
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_stalled_publication_times_out_and_releases_the_events() {
+    run_test_async(|app| async move {
+        let asset = create_and_save_contract(app.user_fp.clone(), &app.db_pool).await.expect("Failed to create seed asset");
+
+        let result = orchestrator::relay_outbox_events(&StalledSink, 10, Duration::from_millis(100), &app.db_pool).await;
+        assert!(matches!(result, Err(OrchestrateError::ServerError(_))), "{:?}", result);
+
+        // the events are no longer claimed, the next relay publishes them
+        let sink = WriterSink::new(Box::new(SharedBuffer::default()));
+        let relayed = tokio::time::timeout(Duration::from_secs(5), orchestrator::relay_outbox_events(&sink, 10, PUBLISH_TIMEOUT, &app.db_pool))
+            .await??;
+        assert_eq!(relayed, 1);
+        let events = queries::find_outbox_events_by_asset_id(&asset.id, &app.db_pool).await?;
+        assert!(events[0].dispatched_at.is_some());
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_subscriptions_are_not_held_back_while_events_are_published() {
+    run_test_async(|app| async move {
+        create_and_save_contract(app.user_fp.clone(), &app.db_pool).await.expect("Failed to create seed asset");
+        let publishing = Arc::new(Notify::new());
+        let sink = BlockingSink(publishing.clone());
+        let pg_pool = app.db_pool.clone();
+        let relay = tokio::spawn(async move {
+            orchestrator::relay_outbox_events(&sink, 10, Duration::from_secs(60), &pg_pool).await
+        });
+        publishing.notified().await;
+
+        // events recorded while the relay publishes are read by the subscriptions
+        let owner = create_asset_owner();
+        let asset = create_and_save_contract(owner.clone(), &app.db_pool).await.expect("Failed to create seed asset");
+        let filter = EventFilter::OwnerFp(owner);
+        let mut events = vec![];
+        for _ in 0..50 {
+            events = queries::find_outbox_events_after(&filter, 0, 10, &app.db_pool).await?;
+            if !events.is_empty() {
+                break;
+            }
+            // transactions of the tests running alongside hold the events back meanwhile
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        relay.abort();
+        assert_eq!(events.first().map(|event| &event.asset_id), Some(&asset.id));
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_events_are_streamed_to_the_grpc_sink() {
+    run_test_async(|app| async move {
+        let asset = create_and_save_contract(app.user_fp.clone(), &app.db_pool).await.expect("Failed to create seed asset");
+        let repository = app.repository();
+        let mut transaction = repository.begin().await?;
+        repository.transfer_asset(&asset.id, &create_org_id(), &create_asset_owner(), &signer(), &mut transaction).await?;
+        repository.commit(transaction).await?;
+
+        let service = RecordingService::default();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let endpoint = format!("http://{}", listener.local_addr()?);
+        let incoming = async_stream::stream! {
+            loop {
+                yield listener.accept().await.map(|(stream, _)| stream);
+            }
+        };
+        tokio::spawn(Server::builder().add_service(EventSinkServiceServer::new(service.clone())).serve_with_incoming(incoming));
+
+        let sink = GrpcStreamSink::connect_lazy(&endpoint, PUBLISH_TIMEOUT)?;
+        assert_eq!(orchestrator::relay_outbox_events(&sink, 10, PUBLISH_TIMEOUT, &app.db_pool).await?, 2);
+
+        let received = service.received.lock().unwrap().clone();
+        let events = queries::find_outbox_events_by_asset_id(&asset.id, &app.db_pool).await?;
+        assert_eq!(received.iter().map(|event| event.id.clone()).collect::<Vec<_>>(),
+                   events.iter().map(|event| event.id.clone()).collect::<Vec<_>>());
+        assert!(events.iter().all(|event| event.dispatched_at.is_some()));
+        assert!(received[1].payload.as_ref().and_then(|payload| payload.change.as_ref()).is_some());
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_only_the_events_of_the_caller_are_subscribed_to() {
+    run_test_async(|app| async move {
+        let asset = create_and_save_contract(app.user_fp.clone(), &app.db_pool).await.expect("Failed to create seed asset");
+        let other_user = create_asset_owner();
+        let no_admin = HashSet::new();
+
+        let owned = EventFilter::AssetId(asset.id.clone());
+        orchestrator::authorize_event_filter(&owned, &app.user_fp, &no_admin, &app.db_pool).await.expect("Failed to authorize owner");
+        let result = orchestrator::authorize_event_filter(&owned, &other_user, &no_admin, &app.db_pool).await;
+        assert!(matches!(result, Err(OrchestrateError::PermissionDenied(_))), "{:?}", result);
+        let result = orchestrator::authorize_event_filter(&EventFilter::AssetId("unknown-asset".to_string()), &app.user_fp, &no_admin, &app.db_pool).await;
+        assert!(matches!(result, Err(OrchestrateError::NotFoundError(_))), "{:?}", result);
+
+        let organization = EventFilter::Organization(asset.organization.clone());
+        orchestrator::authorize_event_filter(&organization, &app.user_fp, &no_admin, &app.db_pool).await.expect("Failed to authorize member");
+        let result = orchestrator::authorize_event_filter(&organization, &other_user, &no_admin, &app.db_pool).await;
+        assert!(matches!(result, Err(OrchestrateError::PermissionDenied(_))), "{:?}", result);
+        let other_organization = EventFilter::Organization(create_org_id());
+        let result = orchestrator::authorize_event_filter(&other_organization, &app.user_fp, &no_admin, &app.db_pool).await;
+        assert!(matches!(result, Err(OrchestrateError::PermissionDenied(_))), "{:?}", result);
+        let admins = HashSet::from([other_user.clone()]);
+        orchestrator::authorize_event_filter(&organization, &other_user, &admins, &app.db_pool).await.expect("Failed to authorize admin");
+
+        // subscriptions are authorized again once the asset is transferred
+        let repository = app.repository();
+        let mut transaction = repository.begin().await?;
+        repository.transfer_asset(&asset.id, &create_org_id(), &other_user, &signer(), &mut transaction).await?;
+        repository.commit(transaction).await?;
+        let result = orchestrator::authorize_event_filter(&owned, &app.user_fp, &no_admin, &app.db_pool).await;
+        assert!(matches!(result, Err(OrchestrateError::PermissionDenied(_))), "{:?}", result);
+        orchestrator::authorize_event_filter(&owned, &other_user, &no_admin, &app.db_pool).await.expect("Failed to authorize new owner");
+
+        let own_events = EventFilter::OwnerFp(app.user_fp.clone());
+        orchestrator::authorize_event_filter(&own_events, &app.user_fp, &no_admin, &app.db_pool).await.expect("Failed to authorize owner");
+        let result = orchestrator::authorize_event_filter(&own_events, &other_user, &no_admin, &app.db_pool).await;
+        assert!(matches!(result, Err(OrchestrateError::PermissionDenied(_))), "{:?}", result);
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_notifier_broadcasts_the_sequence_of_recorded_events() {
+    run_test_async(|app| async move {
+        let notifier = OutboxNotifier::new(app.db_pool.clone());
+        let mut notifications = notifier.sender().subscribe();
+        let task = tokio::spawn(notifier.run_until_stopped());
+
+        // the notifier listens once it is connected, events recorded before are read by the subscriptions
+        let mut sequences = vec![];
+        for _ in 0..20 {
+            let asset = create_and_save_contract(app.user_fp.clone(), &app.db_pool).await.expect("Failed to create seed asset");
+            let events = queries::find_outbox_events_by_asset_id(&asset.id, &app.db_pool).await?;
+            sequences.push(events[0].sequence);
+            if let Ok(sequence) = tokio::time::timeout(Duration::from_millis(200), notifications.recv()).await {
+                assert!(sequences.contains(&sequence?));
+                break;
+            }
+        }
+
+        let asset = create_and_save_contract(app.user_fp.clone(), &app.db_pool).await.expect("Failed to create seed asset");
+        let events = queries::find_outbox_events_by_asset_id(&asset.id, &app.db_pool).await?;
+        let sequence = tokio::time::timeout(Duration::from_secs(5), notifications.recv()).await??;
+        assert_eq!(sequence, events[0].sequence);
+        task.abort();
+
+        Ok::<_, TestError>(())
+    }).await
+}
@@ -0,0 +1,52 @@
+use crate::queries::suit::{run_test_async, TestError};
+use crate::seed::{create_and_save_contract, create_asset_owner, create_org_id, signer};
+use xrf1::core::queries::UnitOfWork;
+use xrf1::core::{orchestrator, queries, OrchestrateError, TrailBreakKind};
+
+#[tokio::test]
+async fn test_edited_trail_is_reported_at_its_first_break() {
+    run_test_async(|app| async move {
+        let asset = create_and_save_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create and save seed asset");
+        let new_owner = create_asset_owner();
+        let mut transaction = UnitOfWork::begin(&app.db_pool).await?;
+        queries::transfer_asset_query(&create_org_id(), &asset.id, &new_owner, None, &signer(), &mut transaction).await?;
+        transaction.commit().await?;
+
+        let trails = queries::get_nfc_trails_by_asset_id(&asset.id, &app.db_pool).await?;
+        assert_eq!(trails.len(), 2);
+        assert!(trails[0].previous_hash.is_none());
+        assert_eq!(trails[1].previous_hash.as_ref(), Some(&trails[0].hash));
+        let report = orchestrator::verify_trail_integrity(&asset.id, &app.repository()).await?;
+        assert!(report.is_intact());
+        assert_eq!(report.checked_assets, 1);
+
+        // the new owner is swapped for another one directly in the database
+        sqlx::query("UPDATE nfc_asset_trail SET user_fp = $1 WHERE asset_id = $2 AND user_fp = $3")
+            .bind(create_asset_owner())
+            .bind(&asset.id)
+            .bind(&new_owner)
+            .execute(&app.db_pool)
+            .await?;
+        let report = orchestrator::verify_trail_integrity(&asset.id, &app.repository()).await?;
+        assert_eq!(report.breaks.len(), 1);
+        assert_eq!(report.breaks[0].position, 1);
+        assert_eq!(report.breaks[0].kind, TrailBreakKind::ContentChanged);
+
+        // removing the mint breaks the chain at the first remaining row
+        sqlx::query("DELETE FROM nfc_asset_trail WHERE asset_id = $1 AND previous_hash IS NULL")
+            .bind(&asset.id)
+            .execute(&app.db_pool)
+            .await?;
+        let report = orchestrator::verify_all_trails(&app.repository()).await?;
+        let broken = report.breaks.iter().find(|trail_break| trail_break.asset_id == asset.id).expect("Missing break");
+        assert_eq!(broken.position, 0);
+        assert_eq!(broken.kind, TrailBreakKind::ChainBroken);
+
+        let result = orchestrator::verify_trail_integrity("unknown-asset", &app.repository()).await;
+        assert!(matches!(result, Err(OrchestrateError::NotFoundError(_))), "{:?}", result.err());
+
+        Ok::<_, TestError>(())
+    }).await
+}
@@ -1,8 +1,10 @@
 use crate::queries::suit::{run_test_async, TestError};
-use crate::seed::{create_asset, create_asset_owner, create_org_id};
+use crate::seed::{create_asset, create_asset_owner, create_org_id, signer};
 use anyhow::Context;
-use xrf1::core::queries;
-use xrf1::core::queries::{create_new_asset, find_asset_by_id, OrderType};
+use chrono::{Duration, SubsecRound, Utc};
+use xrf1::core::{queries, Asset, AssetCursor, AssetFilter, AssetSearch, AssetSortField, DatabaseError, OwnershipClaim};
+use xrf1::core::repository::AssetRepository;
+use xrf1::core::queries::{find_asset_by_id, AssetSort, OrderType, UnitOfWork};
 
 #[tokio::test]
 async fn test_create_asset() {
@@ -12,7 +14,7 @@ async fn test_create_asset() {
             .context("My custom message: Setup failed during asset creation")?;
 
         // 2. Create asset
-        let result = queries::create_new_asset(&asset, app.user_fp.clone(), &app.db_pool).await;
+        let result = app.repository().create_asset(&asset, &app.user_fp, &signer()).await;
 
         // 3. Assert
         assert!(result.is_ok());
@@ -22,6 +24,32 @@ async fn test_create_asset() {
     }).await;
 }
 
+#[tokio::test]
+async fn test_dropped_unit_of_work_rolls_back_every_step() {
+    run_test_async(|app| async move {
+        let asset = create_asset(app.user_fp.clone())?;
+        let mut transaction = UnitOfWork::begin(&app.db_pool).await?;
+        assert!(queries::create_new_asset(&asset, app.user_fp.clone(), &signer(), &mut transaction).await?);
+        assert_eq!(queries::get_nfc_by_asset_id(&asset.id, &mut *transaction).await?.asset_id, asset.id);
+        drop(transaction);
+
+        // neither the asset, its NFC nor its trail were created
+        assert!(matches!(find_asset_by_id(&asset.id, &app.db_pool).await, Err(DatabaseError::NotFound)));
+        assert!(matches!(queries::get_nfc_by_asset_id(&asset.id, &app.db_pool).await, Err(DatabaseError::NotFound)));
+        assert!(queries::get_nfc_trails_by_asset_id(&asset.id, &app.db_pool).await?.is_empty());
+
+        // a transfer that is not committed leaves the owner and the trail as they were
+        app.repository().create_asset(&asset, &app.user_fp, &signer()).await?;
+        let mut transaction = UnitOfWork::begin(&app.db_pool).await?;
+        queries::transfer_asset_query(&create_org_id(), &asset.id, &create_asset_owner(), None, &signer(), &mut transaction).await?;
+        transaction.rollback().await?;
+        assert_eq!(find_asset_by_id(&asset.id, &app.db_pool).await?.owner_fp, asset.owner_fp);
+        assert_eq!(queries::get_nfc_trails_by_asset_id(&asset.id, &app.db_pool).await?.len(), 1);
+
+        Ok::<(), TestError>(())
+    }).await;
+}
+
 #[tokio::test]
 async fn test_find_asset_by_id_success() {
     run_test_async(|app| async move {
@@ -29,7 +57,7 @@ async fn test_find_asset_by_id_success() {
         let asset = create_asset(app.user_fp.clone()).expect("Failed to create asset object");
 
         // 2. Create asset in db
-        queries::create_new_asset(&asset, app.user_fp.clone(), &app.db_pool).await
+        app.repository().create_asset(&asset, &app.user_fp, &signer()).await
             .expect("Failed to create asset object");
 
         // 3. fetch created asset
@@ -44,87 +72,40 @@ async fn test_find_asset_by_id_success() {
 }
 
 #[tokio::test]
-async fn test_find_asset_by_owner_fp_success() {
-    run_test_async(|app| async move {
-        let user_fp = app.user_fp.clone();
-        let assets = queries::find_assets_by_owner(&user_fp, 0,
-                                                   0,
-                                                   true, OrderType::Asc, &app.db_pool).await;
-        assert!(assets.is_ok());
-        assert_eq!(assets.unwrap().len(), 0);
-
-        // 3. Set up test data
-        let asset = create_asset(user_fp.clone()).expect("Failed to create asset object");
-
-        // 4. Create asset in db
-        create_new_asset(&asset, user_fp.clone(), &app.db_pool).await
-            .expect("Failed to create asset object");
-
-        let assets = queries::find_assets_by_owner(&user_fp, 2,
-                                                   0,
-                                                   true, OrderType::Asc, &app.db_pool).await;
-        // 5. Assert
-        assert!(assets.is_ok());
-        let stored_assets = assets.unwrap();
-        assert_eq!(stored_assets.len(), 1);
-        assert_eq!(stored_assets.get(0).unwrap().id, asset.id);
-
-        Ok::<_, TestError>(())
-    }).await
-}
-
-#[tokio::test]
-async fn test_find_assets_name_like_success() {
+async fn test_list_assets_by_owner_fp_success() {
     run_test_async(|app| async move {
-        // 2. Check that user has no assets
         let user_fp = app.user_fp.clone();
+        let filter = AssetFilter { owner_fp: Some(user_fp.clone()), listable: Some(true), ..Default::default() };
+        let by_symbol = AssetSort::new(AssetSortField::Symbol, OrderType::Desc);
+        let assets = queries::list_assets(&filter, by_symbol, None, 2, &app.db_pool).await?;
+        assert_eq!(assets.len(), 0);
 
-        // 3. Set up test data
         let asset = create_asset(user_fp.clone()).expect("Failed to create asset object");
-
-        // 4. Create asset in db
-        queries::create_new_asset(&asset, user_fp.clone(), &app.db_pool).await
+        app.repository().create_asset(&asset, &user_fp, &signer()).await
             .expect("Failed to create asset object");
 
-        let asset_name = asset.name.clone();
-        let offset = 0;
-        let limit = 8;
-        let assets = queries::find_assets_name_like(&asset_name[..5],
-                                                    offset,
-                                                    limit,
-                                                    OrderType::Asc, &app.db_pool)
-            .await;
-
-        // 5. Assert
-        assert!(assets.is_ok());
-        assert_eq!(assets.unwrap().len(), 1);
+        let assets = queries::list_assets(&filter, by_symbol, None, 2, &app.db_pool).await?;
+        assert_eq!(assets.len(), 1);
+        assert_eq!(assets[0].id, asset.id);
 
         Ok::<_, TestError>(())
     }).await
 }
 
 #[tokio::test]
-async fn test_find_assets_symbol_like_success() {
+async fn test_list_assets_name_contains_success() {
     run_test_async(|app| async move {
-        // 2. Check that user has no assets
         let user_fp = app.user_fp.clone();
-
-        // 3. Set up test data
         let asset = create_asset(user_fp.clone()).expect("Failed to create asset object");
-        // 4. Create asset in db
-        queries::create_new_asset(&asset, user_fp.clone(), &app.db_pool).await
+        app.repository().create_asset(&asset, &user_fp, &signer()).await
             .expect("Failed to create asset object");
 
-        let symbol = asset.symbol.clone();
-        let offset = 0;
-        let limit = 8;
-
-        let assets = queries::find_assets_symbol_like(&symbol[..3],
-                                                      limit, offset, OrderType::Desc, &app.db_pool).await;
-
-        // 5. Assert
-        assert!(assets.is_ok());
-        assert_eq!(assets.unwrap().len(), 1);
+        let filter = AssetFilter { name_contains: Some(asset.name[2..7].to_uppercase()), ..Default::default() };
+        let by_name = AssetSort::new(AssetSortField::Name, OrderType::Desc);
+        let assets = queries::list_assets(&filter, by_name, None, 8, &app.db_pool).await?;
+        assert_eq!(assets.len(), 1);
+        assert_eq!(assets[0].id, asset.id);
+        assert_eq!(queries::count_assets(&filter, &app.db_pool).await?, 1);
 
         Ok::<_, TestError>(())
     }).await
@@ -138,10 +119,12 @@ async fn test_transfer_assets_success() {
         let new_org_id = create_org_id();
         let new_asset_owner = create_asset_owner();
 
-        create_new_asset(&asset, new_asset_owner.clone(), &app.db_pool).await
+        app.repository().create_asset(&asset, &new_asset_owner, &signer()).await
             .expect("Failed to create asset object");
 
-        let result = queries::transfer_asset_query(&new_org_id, &asset.id, &new_asset_owner, &app.db_pool).await;
+        let mut transaction = UnitOfWork::begin(&app.db_pool).await?;
+        let result = queries::transfer_asset_query(&new_org_id, &asset.id, &new_asset_owner, None, &signer(), &mut transaction).await;
+        transaction.commit().await?;
 
         assert!(result.is_ok());
         assert_eq!(result.unwrap().asset_id, asset.id);
@@ -155,6 +138,156 @@ async fn test_transfer_assets_success() {
         assert_eq!(asset_transferred.organization, new_org_id);
         assert_eq!(asset_transferred.owner_fp, new_asset_owner);
 
+        // the certificate is signed again for the new owner
+        let nfc = queries::get_nfc_by_asset_id(&asset.id, &app.db_pool).await?;
+        assert!(signer().verify(&OwnershipClaim::for_nfc(&nfc, &new_asset_owner), &nfc.cert));
+        assert!(!signer().verify(&OwnershipClaim::for_nfc(&nfc, &app.user_fp), &nfc.cert));
+
         Ok::<_, TestError>(())
     }).await
 }
+
+#[tokio::test]
+async fn test_search_assets_ranks_full_text_and_fuzzy_matches() {
+    run_test_async(|app| async move {
+        let org_id = create_org_id();
+        let painting = Asset::new("Starry Night".to_string(),
+                                  "STARRY".to_string(),
+                                  app.user_fp.clone(),
+                                  "oil painting of a village under a swirling sky".to_string(),
+                                  org_id.clone())?;
+        let sculpture = Asset::new("Thinker".to_string(),
+                                   "THINK".to_string(),
+                                   app.user_fp.clone(),
+                                   "bronze sculpture, cast after a painting study".to_string(),
+                                   create_org_id())?;
+        for asset in [&painting, &sculpture] {
+            app.repository().create_asset(asset, &app.user_fp, &signer()).await?;
+        }
+
+        // the name weighs more than the description
+        let search = AssetSearch::new("starry painting", None, None, None, 10, 0)?;
+        let hits = queries::search_assets(&search, &app.db_pool).await?;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].asset.id, painting.id);
+        assert!(hits[0].snippet.contains("<b>"), "{}", hits[0].snippet);
+
+        let search = AssetSearch::new("paintings", None, None, None, 10, 0)?;
+        let hits = queries::search_assets(&search, &app.db_pool).await?;
+        assert_eq!(hits.len(), 2);
+        assert!(hits[0].score >= hits[1].score);
+
+        // a typo in the name still matches
+        let search = AssetSearch::new("Starry Nite", None, None, None, 10, 0)?;
+        let hits = queries::search_assets(&search, &app.db_pool).await?;
+        assert_eq!(hits.first().map(|hit| hit.asset.id.clone()), Some(painting.id.clone()));
+
+        let search = AssetSearch::new("painting", Some(org_id), None, Some(true), 10, 0)?;
+        let hits = queries::search_assets(&search, &app.db_pool).await?;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].asset.id, painting.id);
+
+        let search = AssetSearch::new("painting", None, Some(true), None, 10, 0)?;
+        assert!(queries::search_assets(&search, &app.db_pool).await?.is_empty());
+
+        assert!(AssetSearch::new("  ", None, None, None, 10, 0).is_err());
+        assert!(AssetSearch::new("painting", None, None, None, 0, 0).is_err());
+
+        Ok::<(), TestError>(())
+    }).await;
+}
+
+#[tokio::test]
+async fn test_get_all_assets_pages_after_cursor() {
+    run_test_async(|app| async move {
+        for _ in 0..5 {
+            let asset = create_asset(app.user_fp.clone())?;
+            app.repository().create_asset(&asset, &app.user_fp, &signer()).await?;
+        }
+        assert_eq!(queries::count_assets(&AssetFilter::default(), &app.db_pool).await?, 5);
+
+        let first_page = queries::get_all_assets(&app.db_pool, None, 2, OrderType::Asc).await?;
+        assert_eq!(first_page.len(), 2);
+
+        // an asset inserted before the cursor does not shift the next page
+        let cursor = AssetCursor::decode(&AssetCursor::after(&first_page[1], AssetSortField::Name).encode())?;
+        let mut early = create_asset(app.user_fp.clone())?;
+        early.name = "000-early".to_string();
+        app.repository().create_asset(&early, &app.user_fp, &signer()).await?;
+
+        let second_page = queries::get_all_assets(&app.db_pool, Some(&cursor), 10, OrderType::Asc).await?;
+        assert_eq!(second_page.len(), 3);
+        assert!(second_page.iter().all(|asset| (&asset.name, &asset.id) > (&cursor.key, &cursor.id)));
+
+        let descending = queries::get_all_assets(&app.db_pool, Some(&cursor), 10, OrderType::Desc).await?;
+        assert_eq!(descending.len(), 2);
+        assert_eq!(descending[0].id, first_page[0].id);
+        assert_eq!(descending[1].id, early.id);
+
+        assert!(queries::get_all_assets(&app.db_pool, None, 101, OrderType::Asc).await.is_err());
+
+        Ok::<(), TestError>(())
+    }).await;
+}
+
+#[tokio::test]
+async fn test_list_assets_filters_and_sorts() {
+    run_test_async(|app| async move {
+        let org_id = create_org_id();
+        let now = Utc::now().trunc_subsecs(6);
+        let mut assets = Vec::new();
+        for (i, symbol) in ["ART-1", "ART-2", "ART-3", "BND-1"].into_iter().enumerate() {
+            let mut asset = Asset::new(format!("asset {}", i), symbol.to_string(), app.user_fp.clone(),
+                                       "description".to_string(), org_id.clone())?;
+            asset.created_at = now - Duration::hours(i as i64);
+            asset.updated_at = asset.created_at;
+            asset.listable = i % 2 == 0;
+            app.repository().create_asset(&asset, &app.user_fp, &signer()).await?;
+            assets.push(asset);
+        }
+        let other_org = create_asset(app.user_fp.clone())?;
+        app.repository().create_asset(&other_org, &app.user_fp, &signer()).await?;
+
+        // the symbol prefix is case insensitive and wildcards in it are literal
+        let filter = AssetFilter {
+            organization: Some(org_id.clone()),
+            symbol_prefix: Some("art".to_string()),
+            ..Default::default()
+        };
+        let sort = AssetSort::new(AssetSortField::CreatedAt, OrderType::Asc);
+        let listed = queries::list_assets(&filter, sort, None, 10, &app.db_pool).await?;
+        let ids: Vec<&str> = listed.iter().map(|asset| asset.id.as_str()).collect();
+        assert_eq!(ids, vec![assets[2].id.as_str(), assets[1].id.as_str(), assets[0].id.as_str()]);
+        assert_eq!(queries::count_assets(&filter, &app.db_pool).await?, 3);
+        let wildcard = AssetFilter { symbol_prefix: Some("%".to_string()), ..Default::default() };
+        assert_eq!(queries::count_assets(&wildcard, &app.db_pool).await?, 0);
+
+        // pages continue after a timestamp cursor
+        let first_page = queries::list_assets(&filter, sort, None, 2, &app.db_pool).await?;
+        let cursor = AssetCursor::decode(&AssetCursor::after(&first_page[1], AssetSortField::CreatedAt).encode())?;
+        let second_page = queries::list_assets(&filter, sort, Some(&cursor), 2, &app.db_pool).await?;
+        assert_eq!(second_page.len(), 1);
+        assert_eq!(second_page[0].id, assets[0].id);
+
+        // a cursor of another sort field is rejected
+        let by_name = AssetSort::new(AssetSortField::Name, OrderType::Asc);
+        assert!(queries::list_assets(&filter, by_name, Some(&cursor), 2, &app.db_pool).await.is_err());
+
+        // ranges include their start and exclude their end
+        let filter = AssetFilter {
+            organization: Some(org_id.clone()),
+            listable: Some(true),
+            created_from: Some(now - Duration::hours(2)),
+            created_until: Some(now),
+            ..Default::default()
+        };
+        let listed = queries::list_assets(&filter, AssetSort::new(AssetSortField::Symbol, OrderType::Desc), None, 10, &app.db_pool).await?;
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, assets[2].id);
+
+        let invalid = AssetFilter { updated_from: Some(now), updated_until: Some(now - Duration::hours(1)), ..Default::default() };
+        assert!(invalid.validate().is_err());
+
+        Ok::<(), TestError>(())
+    }).await;
+}
@@ -0,0 +1,94 @@
+use crate::queries::suit::{run_test_async, TestError};
+use crate::seed::{create_asset, signer};
+use chrono::{TimeZone, Utc};
+use uuid::Uuid;
+use xrf1::core::repository::AssetRepository;
+use xrf1::core::queries::{self, AssetSort};
+use xrf1::core::{normalize_tags, AssetAttribute, AssetFilter, AttributeValue, UpdateAssetRequest, MAX_ASSET_ATTRIBUTES};
+
+fn updated_by() -> String {
+    format!("{}{}", Uuid::new_v4(), Uuid::new_v4())
+}
+
+#[tokio::test]
+async fn test_update_asset_tags_and_attributes() {
+    run_test_async(|app| async move {
+        let asset = create_asset(app.user_fp.clone())?;
+        app.repository().create_asset(&asset, &app.user_fp, &signer()).await?;
+        let other = create_asset(app.user_fp.clone())?;
+        app.repository().create_asset(&other, &app.user_fp, &signer()).await?;
+
+        let painted_at = Utc.with_ymd_and_hms(1889, 6, 1, 0, 0, 0).unwrap();
+        let mut update = UpdateAssetRequest::new(None, None, None, None, None, None);
+        update.tags = Some(normalize_tags(&["Painting".to_string(), "Art".to_string()])?);
+        update.set_attributes = vec![
+            AssetAttribute::new("artist", AttributeValue::String("Van Gogh".to_string()))?,
+            AssetAttribute::new("width_cm", AttributeValue::Number(92.1))?,
+            AssetAttribute::new("framed", AttributeValue::Bool(true))?,
+            AssetAttribute::new("painted_at", AttributeValue::Date(painted_at))?,
+        ];
+        assert!(queries::update_asset(&asset.id, &updated_by(), &update, &app.db_pool).await?);
+
+        let metadata = queries::find_asset_metadata(&[asset.id.clone(), other.id.clone()], &app.db_pool).await?;
+        assert!(!metadata.contains_key(&other.id));
+        let metadata = &metadata[&asset.id];
+        assert_eq!(metadata.tags, vec!["art".to_string(), "painting".to_string()]);
+        assert_eq!(metadata.attributes.len(), 4);
+        assert!(metadata.attributes.contains(&AssetAttribute::new("painted_at", AttributeValue::Date(painted_at))?));
+
+        // listings only keep the assets with every tag and attribute of the filter
+        let filter = AssetFilter {
+            tags: vec!["art".to_string()],
+            attributes: vec![AssetAttribute::new("artist", AttributeValue::String("Van Gogh".to_string()))?],
+            ..Default::default()
+        };
+        let listed = queries::list_assets(&filter, AssetSort::default(), None, 10, &app.db_pool).await?;
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, asset.id);
+        let filter = AssetFilter {
+            tags: vec!["art".to_string(), "sculpture".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(queries::count_assets(&filter, &app.db_pool).await?, 0);
+        let filter = AssetFilter {
+            attributes: vec![AssetAttribute::new("width_cm", AttributeValue::Number(50.0))?],
+            ..Default::default()
+        };
+        assert_eq!(queries::count_assets(&filter, &app.db_pool).await?, 0);
+
+        // an attribute can change kind, removed attributes and replaced tags are gone
+        let mut update = UpdateAssetRequest::new(None, None, None, None, None, None);
+        update.tags = Some(vec![]);
+        update.set_attributes = vec![AssetAttribute::new("framed", AttributeValue::String("gilded".to_string()))?];
+        update.remove_attributes = vec!["width_cm".to_string()];
+        assert!(queries::update_asset(&asset.id, &updated_by(), &update, &app.db_pool).await?);
+        let metadata = queries::find_asset_metadata(std::slice::from_ref(&asset.id), &app.db_pool).await?;
+        let metadata = &metadata[&asset.id];
+        assert!(metadata.tags.is_empty());
+        assert_eq!(metadata.attributes.len(), 3);
+        assert!(metadata.attributes.contains(&AssetAttribute::new("framed", AttributeValue::String("gilded".to_string()))?));
+
+        Ok::<(), TestError>(())
+    }).await;
+}
+
+#[tokio::test]
+async fn test_update_asset_rejects_too_many_attributes() {
+    run_test_async(|app| async move {
+        let asset = create_asset(app.user_fp.clone())?;
+        app.repository().create_asset(&asset, &app.user_fp, &signer()).await?;
+
+        let mut update = UpdateAssetRequest::new(None, None, None, None, None, None);
+        update.tags = Some(vec!["art".to_string()]);
+        update.set_attributes = (0..=MAX_ASSET_ATTRIBUTES)
+            .map(|i| AssetAttribute::new(&format!("trait{}", i), AttributeValue::Number(i as f64)))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert!(queries::update_asset(&asset.id, &updated_by(), &update, &app.db_pool).await.is_err());
+
+        // nothing of the update is kept
+        let metadata = queries::find_asset_metadata(std::slice::from_ref(&asset.id), &app.db_pool).await?;
+        assert!(metadata.is_empty());
+
+        Ok::<(), TestError>(())
+    }).await;
+}
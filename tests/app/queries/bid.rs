@@ -0,0 +1,88 @@
+use crate::queries::suit::{run_test_async, TestError};
+use crate::seed::{create_and_save_contract, create_asset_owner, create_bid};
+use xrf1::core::{queries, BidStatus};
+
+#[tokio::test]
+async fn test_create_bid_success() {
+    run_test_async(|app| async move {
+        let asset = create_and_save_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create and save seed asset");
+        let bid = create_bid(&asset, create_asset_owner(), "25.00").expect("Failed to create bid");
+
+        let result = queries::create_bid(&app.db_pool, &bid).await;
+        assert!(result.is_ok());
+        assert!(result.unwrap());
+
+        let saved_bid = queries::find_bid_by_id(&bid.id, &app.db_pool)
+            .await
+            .expect("Failed to find bid");
+        assert_eq!(saved_bid.asset_id, asset.id);
+        assert_eq!(saved_bid.status, BidStatus::Open);
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_find_bids_by_asset_and_bidder() {
+    run_test_async(|app| async move {
+        let asset = create_and_save_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create and save seed asset");
+        let bidder_fp = create_asset_owner();
+        let low_bid = create_bid(&asset, bidder_fp.clone(), "21.00").expect("Failed to create bid");
+        let high_bid = create_bid(&asset, create_asset_owner(), "50.00").expect("Failed to create bid");
+        queries::create_bid(&app.db_pool, &low_bid).await.expect("Failed to save bid");
+        queries::create_bid(&app.db_pool, &high_bid).await.expect("Failed to save bid");
+
+        // highest bids first
+        let bids = queries::find_bids_by_asset_id(&asset.id, None, 10, 0, &app.db_pool)
+            .await
+            .expect("Failed to find bids for asset");
+        assert_eq!(bids.len(), 2);
+        assert_eq!(bids.first().unwrap().id, high_bid.id);
+
+        let bids = queries::find_bids_by_bidder(&bidder_fp, 10, 0, &app.db_pool)
+            .await
+            .expect("Failed to find bids by bidder");
+        assert_eq!(bids.len(), 1);
+        assert_eq!(bids.first().unwrap().id, low_bid.id);
+
+        // totals count every bid, not only those of the page
+        let bids = queries::find_bids_by_asset_id(&asset.id, None, 1, 0, &app.db_pool).await?;
+        assert_eq!(bids.len(), 1);
+        assert_eq!(queries::count_bids_by_asset_id(&asset.id, None, &app.db_pool).await?, 2);
+
+        // the bids of a single bidder are paged and counted on their own, the first page is not empty
+        let bids = queries::find_bids_by_asset_id(&asset.id, Some(&bidder_fp), 1, 0, &app.db_pool).await?;
+        assert_eq!(bids.iter().map(|bid| bid.id.as_str()).collect::<Vec<_>>(), vec![low_bid.id.as_str()]);
+        assert_eq!(queries::count_bids_by_asset_id(&asset.id, Some(&bidder_fp), &app.db_pool).await?, 1);
+        assert_eq!(queries::count_bids_by_bidder(&bidder_fp, &app.db_pool).await?, 1);
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_update_bid_status_only_from_expected_status() {
+    run_test_async(|app| async move {
+        let asset = create_and_save_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create and save seed asset");
+        let bid = create_bid(&asset, create_asset_owner(), "25.00").expect("Failed to create bid");
+        queries::create_bid(&app.db_pool, &bid).await.expect("Failed to save bid");
+
+        let withdrawn = queries::update_bid_status(&bid.id, BidStatus::Open, BidStatus::Withdrawn, &app.db_pool).await;
+        assert!(withdrawn.unwrap());
+
+        // a withdrawn bid can not be withdrawn again
+        let withdrawn = queries::update_bid_status(&bid.id, BidStatus::Open, BidStatus::Withdrawn, &app.db_pool).await;
+        assert!(!withdrawn.unwrap());
+
+        let saved_bid = queries::find_bid_by_id(&bid.id, &app.db_pool).await.expect("Failed to find bid");
+        assert_eq!(saved_bid.status, BidStatus::Withdrawn);
+
+        Ok::<_, TestError>(())
+    }).await
+}
@@ -1,7 +1,8 @@
 use crate::queries::suit::{run_test_async, TestError};
-use crate::seed::create_and_save_contract;
+use crate::seed::{create_and_save_contract, usd};
+use chrono::{Duration, Utc};
 use std::collections::HashSet;
-use xrf1::core::{queries, Contract, Currency, DomainError};
+use xrf1::core::{queries, Contract, ContractTerms, ContractVersion, Currency, DatabaseError, DomainError, ResaleRestriction, RoyaltySplit};
 
 #[tokio::test]
 async fn test_create_contract_success() {
@@ -46,6 +47,62 @@ async fn test_find_contract_by_asset_id() {
     }).await
 }
 
+#[tokio::test]
+async fn test_create_and_find_v2_contract() {
+    run_test_async(|app| async move {
+        let asset = create_and_save_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create and save seed asset");
+        let royalty_splits = vec![
+            RoyaltySplit { receiver_fp: "creator_fp".to_string(), basis_points: 500 },
+            RoyaltySplit { receiver_fp: "collaborator_fp".to_string(), basis_points: 250 },
+        ];
+        let terms = ContractTerms {
+            resale: ResaleRestriction { allowed: true, min_holding_days: 30 },
+            expires_at: Some(Utc::now() + Duration::days(365)),
+        };
+        let contract = Contract::new_v2(asset.id.clone(),
+                                        "details".to_string(),
+                                        "summary".to_string(),
+                                        app.user_fp.clone(),
+                                        usd("10.00"),
+                                        false,
+                                        royalty_splits.clone(),
+                                        terms.clone(),
+                                        HashSet::from([Currency::USD]))
+            .expect("failed to create contract");
+        queries::create_contract(&app.db_pool, contract).await.expect("Failed to create contract");
+
+        let saved = queries::find_contract_by_asset_id(&asset.id, &app.db_pool).await?;
+        assert_eq!(saved.version, ContractVersion::V2);
+        assert_eq!(saved.royalty_splits, royalty_splits);
+        assert_eq!(saved.terms.resale, terms.resale);
+        assert_eq!(saved.terms.expires_at.map(|t| t.timestamp()), terms.expires_at.map(|t| t.timestamp()));
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_find_contract_with_unknown_version_fails() {
+    run_test_async(|app| async move {
+        let asset = create_and_save_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create and save seed asset");
+        let contract = create_test_contract(asset.id.clone()).expect("failed to create contract");
+        queries::create_contract(&app.db_pool, contract).await.expect("Failed to create contract");
+
+        sqlx::query("UPDATE contract SET version = 'v9' WHERE asset_id = $1")
+            .bind(&asset.id)
+            .execute(&app.db_pool)
+            .await?;
+        let result = queries::find_contract_by_asset_id(&asset.id, &app.db_pool).await;
+        assert!(matches!(result, Err(DatabaseError::Decode(_))));
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
 fn create_test_contract(asset_id: String) -> Result<Contract, DomainError> {
     // Create a sample CurrencyList with various currencies
     let currencies = vec![Currency::USD, Currency::EUR, Currency::BTC];
@@ -57,10 +114,9 @@ fn create_test_contract(asset_id: String) -> Result<Contract, DomainError> {
         "11".to_string(),
         "summary".to_string(),
         "user_fp".to_string(),
-        20.0,
+        usd("20.00"),
         false,
-        3.0,
-        "user_fp".to_string(),
+        vec![RoyaltySplit { receiver_fp: "user_fp".to_string(), basis_points: 300 }],
         currency_list,
     )
 }
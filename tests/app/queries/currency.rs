@@ -0,0 +1,56 @@
+use crate::queries::suit::{run_test_async, TestError};
+use std::str::FromStr;
+use xrf1::core::{queries, Currency, Money};
+
+#[tokio::test]
+async fn test_currency_registry_matches_currency_codes() {
+    run_test_async(|app| async move {
+        let registry = queries::find_currency_registry(&app.db_pool).await?;
+        let currencies = registry.list(true);
+        assert_eq!(currencies.len(), 19);
+        for info in currencies {
+            assert!(info.enabled);
+            assert_eq!(info.crypto, info.code.is_crypto());
+            assert_eq!(Currency::from_str(&info.code.to_string()), Ok(info.code.clone()));
+            assert_eq!(registry.resolve(&info.code.to_string()).map(|resolved| &resolved.code), Some(&info.code));
+            for alias in &info.aliases {
+                let resolved = registry.resolve(alias).map(|resolved| &resolved.code);
+                assert_eq!(resolved, Some(&info.code), "alias {} of {}", alias, info.code);
+            }
+        }
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_amounts_have_the_decimals_of_the_currency_table() {
+    run_test_async(|app| async move {
+        let registry = queries::find_currency_registry(&app.db_pool).await?;
+        let jpy = registry.resolve("Japanese Yen").unwrap();
+        assert_eq!(jpy.code, Currency::JPY);
+        assert!(Money::parse("1.5", jpy).is_err());
+
+        sqlx::query("UPDATE currency SET decimals = 2 WHERE code = 'JPY'").execute(&app.db_pool).await?;
+        let registry = queries::find_currency_registry(&app.db_pool).await?;
+        let amount = Money::parse("1.5", registry.resolve("JPY").unwrap())?;
+        assert_eq!((amount.minor_units(), amount.decimals()), (150, 2));
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_disable_currency() {
+    run_test_async(|app| async move {
+        assert!(queries::set_currency_enabled(&Currency::EUR, false, &app.db_pool).await?);
+
+        let registry = queries::find_currency_registry(&app.db_pool).await?;
+        assert!(!registry.is_enabled(&Currency::EUR));
+        assert!(registry.is_enabled(&Currency::USD));
+        assert!(registry.list(false).iter().all(|info| info.code != Currency::EUR));
+        assert_eq!(registry.get(&Currency::EUR).map(|info| info.name.as_str()), Some("Euro"));
+
+        Ok::<_, TestError>(())
+    }).await
+}
@@ -0,0 +1,54 @@
+use crate::queries::suit::{run_test_async, TestError};
+use crate::seed::{create_asset_owner, create_bid, create_tradable_asset_with_contract, hold_ttl};
+use chrono::{Duration, Utc};
+use xrf1::core::queries::UnitOfWork;
+use xrf1::core::{queries, EscrowHold, EscrowStatus};
+
+#[tokio::test]
+async fn test_asset_has_one_active_escrow_hold() {
+    run_test_async(|app| async move {
+        let asset = create_tradable_asset_with_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create tradable asset");
+        let nfc = queries::get_nfc_by_asset_id(&asset.id, &app.db_pool).await?;
+        let bid = create_bid(&asset, create_asset_owner(), "50.00")?;
+        let other_bid = create_bid(&asset, create_asset_owner(), "60.00")?;
+        queries::create_bid(&app.db_pool, &bid).await?;
+        queries::create_bid(&app.db_pool, &other_bid).await?;
+
+        let hold = EscrowHold::for_bid(&bid, &asset, nfc.id.clone(), hold_ttl())?;
+        let mut transaction = UnitOfWork::begin(&app.db_pool).await?;
+        assert!(queries::create_escrow_hold(&mut transaction, &hold).await?);
+        transaction.commit().await?;
+
+        let found = queries::find_active_escrow_hold_for_asset(&asset.id, &app.db_pool).await?.expect("hold not found");
+        assert_eq!(found.id, hold.id);
+        assert_eq!(found.amount, bid.amount);
+        assert_eq!(queries::find_escrow_hold_by_bid_id(&bid.id, &app.db_pool).await?.id, hold.id);
+
+        // a second active hold on the same asset is refused
+        let other_hold = EscrowHold::for_bid(&other_bid, &asset, nfc.id.clone(), hold_ttl())?;
+        let mut transaction = UnitOfWork::begin(&app.db_pool).await?;
+        assert!(queries::create_escrow_hold(&mut transaction, &other_hold).await.is_err());
+        transaction.rollback().await?;
+
+        // status changes only apply from the expected status
+        assert!(!queries::update_escrow_hold_status(&hold.id, EscrowStatus::Funded, EscrowStatus::Released, &app.db_pool).await?);
+        assert!(queries::update_escrow_hold_status(&hold.id, EscrowStatus::Pending, EscrowStatus::Cancelled, &app.db_pool).await?);
+        let cancelled = queries::find_escrow_hold_by_id(&hold.id, &app.db_pool).await?;
+        assert_eq!(cancelled.status, EscrowStatus::Cancelled);
+        assert!(cancelled.funded_at.is_none());
+        assert!(cancelled.settled_at.is_some());
+        assert!(queries::find_active_escrow_hold_for_asset(&asset.id, &app.db_pool).await?.is_none());
+
+        // once settled, the asset can be held for another bid
+        let mut transaction = UnitOfWork::begin(&app.db_pool).await?;
+        assert!(queries::create_escrow_hold(&mut transaction, &other_hold).await?);
+        transaction.commit().await?;
+        assert!(queries::find_expired_escrow_hold_ids(Utc::now(), 10, &app.db_pool).await?.is_empty());
+        let expired = queries::find_expired_escrow_hold_ids(Utc::now() + hold_ttl() + Duration::seconds(1), 10, &app.db_pool).await?;
+        assert_eq!(expired, vec![other_hold.id]);
+
+        Ok::<_, TestError>(())
+    }).await
+}
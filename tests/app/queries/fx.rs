@@ -0,0 +1,31 @@
+use crate::queries::suit::{run_test_async, TestError};
+use chrono::{Duration, Utc};
+use xrf1::core::queries::UnitOfWork;
+use xrf1::core::{queries, Currency, FxRate};
+
+#[tokio::test]
+async fn test_find_latest_fx_rate_in_either_direction() {
+    run_test_async(|app| async move {
+        let now = Utc::now();
+        let older = FxRate::new(Currency::EUR, Currency::USD, "1.05".parse()?, now - Duration::hours(2))?;
+        let latest = FxRate::new(Currency::USD, Currency::EUR, "0.92".parse()?, now - Duration::hours(1))?;
+        let mut transaction = UnitOfWork::begin(&app.db_pool).await?;
+        assert!(queries::upsert_fx_rate(&mut transaction, &older).await?);
+        assert!(queries::upsert_fx_rate(&mut transaction, &latest).await?);
+        transaction.commit().await?;
+
+        let found = queries::find_latest_fx_rate(&Currency::EUR, &Currency::USD, &app.db_pool).await?;
+        assert_eq!(found.map(|rate| (rate.base, rate.as_of.timestamp())), Some((Currency::USD, latest.as_of.timestamp())));
+        assert!(queries::find_latest_fx_rate(&Currency::EUR, &Currency::BTC, &app.db_pool).await?.is_none());
+
+        // a rate of the same pair at the same time is replaced
+        let corrected = FxRate { rate: "0.93".parse()?, ..latest.clone() };
+        let mut transaction = UnitOfWork::begin(&app.db_pool).await?;
+        assert!(queries::upsert_fx_rate(&mut transaction, &corrected).await?);
+        transaction.commit().await?;
+        let found = queries::find_latest_fx_rate(&Currency::USD, &Currency::EUR, &app.db_pool).await?;
+        assert_eq!(found.map(|rate| rate.rate), Some("0.93".parse()?));
+
+        Ok::<_, TestError>(())
+    }).await
+}
@@ -0,0 +1,96 @@
+use crate::queries::suit::{run_test_async, TestError};
+use crate::seed::{create_asset_owner, currency_info, usd};
+use xrf1::core::queries::UnitOfWork;
+use xrf1::core::{queries, Currency, JournalEntry, Money, Posting, PostingKind};
+
+#[tokio::test]
+async fn test_create_journal_entry_updates_balances() {
+    run_test_async(|app| async move {
+        let buyer_fp = create_asset_owner();
+        let seller_fp = create_asset_owner();
+        let postings = vec![
+            Posting::debit(buyer_fp.clone(), &usd("30.00"), PostingKind::Purchase),
+            Posting::credit(seller_fp.clone(), &usd("27.00"), PostingKind::Sale),
+            Posting::credit(app.user_fp.clone(), &usd("3.00"), PostingKind::Royalty),
+        ];
+        let entry = JournalEntry::new("sale-id".to_string(), "sale".to_string(), Currency::USD, postings)?;
+
+        let mut transaction = UnitOfWork::begin(&app.db_pool).await?;
+        let created = queries::create_journal_entry(&mut transaction, &entry).await;
+        assert!(created.unwrap());
+        transaction.commit().await?;
+
+        let buyer_balance = queries::get_account_balance(&buyer_fp, &Currency::USD, &app.db_pool).await?;
+        let seller_balance = queries::get_account_balance(&seller_fp, &Currency::USD, &app.db_pool).await?;
+        let royalty_balance = queries::get_account_balance(&app.user_fp, &Currency::USD, &app.db_pool).await?;
+        let other_currency = queries::get_account_balance(&seller_fp, &Currency::EUR, &app.db_pool).await?;
+        assert_eq!(buyer_balance, usd("-30.00"));
+        assert_eq!(seller_balance, usd("27.00"));
+        assert_eq!(royalty_balance, usd("3.00"));
+        assert_eq!(other_currency, Money::zero(&currency_info(Currency::EUR, 2)));
+
+        let entries = queries::find_journal_entries_by_owner(&seller_fp, None, 10, 0, &app.db_pool).await?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries.first().unwrap().postings.len(), 3);
+        assert!(entries.first().unwrap().is_balanced());
+
+        let entries = queries::find_journal_entries_by_owner(&seller_fp, Some(Currency::EUR), 10, 0, &app.db_pool).await?;
+        assert!(entries.is_empty());
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_unbalanced_postings_are_rejected_by_database() {
+    run_test_async(|app| async move {
+        let account = {
+            let mut transaction = UnitOfWork::begin(&app.db_pool).await?;
+            let account = queries::find_or_create_ledger_account(&app.user_fp, &Currency::USD, &mut transaction).await?;
+            transaction.commit().await?;
+            account
+        };
+
+        // bypass the domain checks and write a single sided entry
+        let mut transaction = UnitOfWork::begin(&app.db_pool).await?;
+        sqlx::query("INSERT INTO journal_entry (id, reference_id, description, currency, created_at) VALUES ('e1', 'r1', 'd', 'USD', now())")
+            .execute(&mut *transaction)
+            .await?;
+        sqlx::query("INSERT INTO journal_posting (entry_id, account_id, amount, kind) VALUES ('e1', $1, 10.0, 'sale')")
+            .bind(&account.id)
+            .execute(&mut *transaction)
+            .await?;
+        assert!(transaction.commit().await.is_err());
+
+        let balance = queries::get_account_balance(&app.user_fp, &Currency::USD, &app.db_pool).await?;
+        assert!(balance.is_zero());
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_amounts_are_stored_exactly() {
+    run_test_async(|app| async move {
+        let buyer_fp = create_asset_owner();
+        // more significant digits than an f64 can hold
+        let registry = queries::find_currency_registry(&app.db_pool).await?;
+        let amount = Money::parse("1234.000000000000000001", registry.get(&Currency::ETH).unwrap())?;
+        let postings = vec![
+            Posting::debit(buyer_fp.clone(), &amount, PostingKind::Purchase),
+            Posting::credit(app.user_fp.clone(), &amount, PostingKind::Sale),
+        ];
+        let entry = JournalEntry::new("sale-id".to_string(), "sale".to_string(), Currency::ETH, postings)?;
+
+        let mut transaction = UnitOfWork::begin(&app.db_pool).await?;
+        queries::create_journal_entry(&mut transaction, &entry).await?;
+        transaction.commit().await?;
+
+        let balance = queries::get_account_balance(&app.user_fp, &Currency::ETH, &app.db_pool).await?;
+        assert_eq!(balance, amount);
+        let entries = queries::find_journal_entries_by_owner(&buyer_fp, None, 10, 0, &app.db_pool).await?;
+        assert_eq!(entries.first().unwrap().postings.first().unwrap().amount, amount.checked_neg()?);
+
+        Ok::<_, TestError>(())
+    }).await
+}
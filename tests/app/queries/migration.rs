@@ -0,0 +1,40 @@
+use crate::queries::suit::{run_test_async, TestError};
+use xrf1::core::queries;
+
+#[tokio::test]
+async fn test_pending_migrations_of_schema_behind_binary() {
+    run_test_async(|app| async move {
+        assert!(queries::find_pending_migrations(&app.db_pool).await?.is_empty());
+
+        let latest = queries::MIGRATOR.iter().map(|migration| migration.version).max().expect("no embedded migration");
+        sqlx::query("DELETE FROM _sqlx_migrations WHERE version = $1")
+            .bind(latest)
+            .execute(&app.db_pool)
+            .await?;
+        assert_eq!(queries::find_pending_migrations(&app.db_pool).await?, vec![latest]);
+
+        sqlx::query("DROP TABLE _sqlx_migrations").execute(&app.db_pool).await?;
+        let pending = queries::find_pending_migrations(&app.db_pool).await?;
+        assert_eq!(pending.len(), queries::MIGRATOR.iter().count());
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_base_schema_applies_to_an_existing_database() {
+    run_test_async(|app| async move {
+        // databases migrated before the base schema existed apply it after every later migration
+        let base = queries::MIGRATOR.iter().map(|migration| migration.version).min().expect("no embedded migration");
+        sqlx::query("DELETE FROM _sqlx_migrations WHERE version = $1")
+            .bind(base)
+            .execute(&app.db_pool)
+            .await?;
+        assert_eq!(queries::find_pending_migrations(&app.db_pool).await?, vec![base]);
+
+        queries::run_migrations(&app.db_pool).await?;
+        assert!(queries::find_pending_migrations(&app.db_pool).await?.is_empty());
+
+        Ok::<_, TestError>(())
+    }).await
+}
@@ -1,4 +1,12 @@
 pub mod contract;
+mod bid;
+mod currency;
+mod escrow;
+mod fx;
+mod ledger;
+mod migration;
 mod nfc;
+mod outbox;
 mod asset;
-mod suit;
+mod attribute;
+pub mod suit;
@@ -1,6 +1,7 @@
 use crate::queries::suit::{run_test_async, TestError};
-use crate::seed::create_and_save_contract;
-use xrf1::core::queries;
+use crate::seed::{create_and_save_contract, create_asset_owner, create_bid, create_org_id, create_tradable_asset_with_contract,
+                  fx_policy, hold_ttl, settle_escrow_hold, signer, usd};
+use xrf1::core::{orchestrator, queries, TransferReason};
 
 #[tokio::test]
 async fn test_nfc_is_created_when_asset_is_created_successfully() {
@@ -44,7 +45,57 @@ async fn test_create_nfc_creates_first_nfc_trail_successfully() {
         assert!(trails.is_ok());
         let trails_list = trails.unwrap();
         assert_eq!(trails_list.len(), 1);
-        assert_eq!(trails_list.get(0).unwrap().nfc_id, nfc_id);
+        let mint = trails_list.first().unwrap();
+        assert_eq!(mint.nfc_id, nfc_id);
+        assert_eq!(mint.reason, TransferReason::Mint);
+        assert_eq!(mint.user_fp, app.user_fp);
+        assert_eq!(mint.organization.as_deref(), Some(asset.organization.as_str()));
+        assert!(mint.previous_owner_fp.is_none());
+
+        Ok::<_, TestError>(())
+    }).await
+}
+
+#[tokio::test]
+async fn test_asset_provenance_follows_transfers_and_sales() {
+    run_test_async(|app| async move {
+        let asset = create_tradable_asset_with_contract(app.user_fp.clone(), &app.db_pool)
+            .await
+            .expect("Failed to create tradable asset");
+
+        // 1. transfer to a new owner in another org
+        let new_owner = create_asset_owner();
+        let new_org = create_org_id();
+        orchestrator::transfer_asset(&asset.organization, &asset.id, &new_org, &new_owner, &signer(), &app.repository())
+            .await
+            .expect("Failed to transfer asset");
+
+        // 2. the new owner sells the asset
+        let asset = queries::find_asset_by_id(&asset.id, &app.db_pool).await.expect("Failed to get asset");
+        let bid = create_bid(&asset, create_asset_owner(), "50.00").expect("Failed to create bid");
+        queries::create_bid(&app.db_pool, &bid).await.expect("Failed to save bid");
+        let hold = orchestrator::accept_bid(&bid.id, &new_owner, &fx_policy(), hold_ttl(), &app.db_pool)
+            .await
+            .expect("Failed to accept bid");
+        let sale = settle_escrow_hold(&hold, &asset, &app.db_pool).await.expect("Failed to settle hold");
+
+        let provenance = queries::find_asset_provenance(&asset.id, &app.db_pool).await?;
+        assert_eq!(provenance.len(), 3);
+        let reasons: Vec<_> = provenance.iter().map(|entry| entry.trail.reason).collect();
+        assert_eq!(reasons, vec![TransferReason::Mint, TransferReason::Transfer, TransferReason::Sale]);
+
+        let transfer = &provenance[1];
+        assert_eq!(transfer.trail.previous_owner_fp.as_deref(), Some(app.user_fp.as_str()));
+        assert_eq!(transfer.trail.user_fp, new_owner);
+        assert_eq!(transfer.trail.organization.as_deref(), Some(new_org.as_str()));
+        assert!(transfer.price.is_none());
+
+        let sold = &provenance[2];
+        assert_eq!(sold.trail.previous_owner_fp.as_deref(), Some(new_owner.as_str()));
+        assert_eq!(sold.trail.previous_org.as_deref(), Some(new_org.as_str()));
+        assert_eq!(sold.trail.user_fp, bid.bidder_fp);
+        assert_eq!(sold.trail.sale_id.as_deref(), Some(sale.id.as_str()));
+        assert_eq!(sold.price, Some(usd("50.00")));
 
         Ok::<_, TestError>(())
     }).await
@@ -0,0 +1,230 @@
+use crate::queries::suit::{run_test_async, TestError};
+use crate::seed::{create_asset, create_asset_owner, create_org_id, signer, test_contract};
+use chrono::Utc;
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+use std::time::Duration;
+use uuid::Uuid;
+use xrf1::core::event_proto::event_payload::Change;
+use xrf1::core::repository::AssetRepository;
+use xrf1::core::queries::{UnitOfWork, OUTBOX_CHANNEL};
+use xrf1::core::{queries, AssetAttribute, AttributeValue, EventFilter, EventType, OutboxEvent, UpdateAssetRequest, MAX_ASSET_ATTRIBUTES};
+
+fn updated_by() -> String {
+    format!("{}{}", Uuid::new_v4(), Uuid::new_v4())
+}
+
+/// Events that follow the sequence, once the transactions of the tests running alongside no longer hold them back
+async fn events_after(filter: &EventFilter, after_sequence: i64, limit: i64, pg_pool: &PgPool) -> Result<Vec<OutboxEvent>, TestError> {
+    for _ in 0..50 {
+        if !queries::has_outbox_events_held_back(filter, after_sequence, pg_pool).await? {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    Ok(queries::find_outbox_events_after(filter, after_sequence, limit, pg_pool).await?)
+}
+
+#[tokio::test]
+async fn test_changes_of_an_asset_record_events_in_order() {
+    run_test_async(|app| async move {
+        let asset = create_asset(app.user_fp.clone())?;
+        app.repository().create_asset(&asset, &app.user_fp, &signer()).await?;
+        assert!(queries::create_contract(&app.db_pool, test_contract(&asset)?).await?);
+        let update = UpdateAssetRequest::new(Some("renamed".to_string()), None, None, None, None, None);
+        assert!(queries::update_asset(&asset.id, &updated_by(), &update, &app.db_pool).await?);
+
+        let (new_org, new_owner) = (create_org_id(), create_asset_owner());
+        let mut transaction = UnitOfWork::begin(&app.db_pool).await?;
+        let nfc = queries::transfer_asset_query(&new_org, &asset.id, &new_owner, None, &signer(), &mut transaction).await?;
+        transaction.commit().await?;
+        assert!(queries::delete_asset_by_id(&asset.id, &app.db_pool).await?);
+
+        let events = queries::find_outbox_events_by_asset_id(&asset.id, &app.db_pool).await?;
+        let types: Vec<EventType> = events.iter().map(|event| event.event_type).collect();
+        assert_eq!(types, vec![EventType::AssetCreated, EventType::ContractCreated, EventType::AssetUpdated,
+                               EventType::AssetTransferred, EventType::AssetDeleted]);
+        assert!(events.windows(2).all(|pair| pair[0].sequence < pair[1].sequence));
+        assert!(events.iter().all(|event| event.dispatched_at.is_none()));
+
+        // events are addressed to the owner of the asset once the change is made
+        assert_eq!((events[2].organization.as_str(), events[2].owner_fp.as_str()), (asset.organization.as_str(), asset.owner_fp.as_str()));
+        assert_eq!((events[3].organization.as_str(), events[3].owner_fp.as_str()), (new_org.as_str(), new_owner.as_str()));
+        assert_eq!(events[4].owner_fp, new_owner);
+
+        let Change::AssetCreated(created) = events[0].change()? else { panic!("not a creation") };
+        assert_eq!(created.nfc_id, nfc.id);
+        let Change::AssetUpdated(updated) = events[2].change()? else { panic!("not an update") };
+        assert_eq!(updated.name.as_deref(), Some("renamed"));
+        let Change::AssetTransferred(transferred) = events[3].change()? else { panic!("not a transfer") };
+        assert_eq!((transferred.from_owner_fp, transferred.to_owner_fp), (asset.owner_fp.clone(), new_owner));
+
+        Ok::<(), TestError>(())
+    }).await;
+}
+
+#[tokio::test]
+async fn test_changes_that_are_rolled_back_record_no_event() {
+    run_test_async(|app| async move {
+        let asset = create_asset(app.user_fp.clone())?;
+        let mut transaction = UnitOfWork::begin(&app.db_pool).await?;
+        assert!(queries::create_new_asset(&asset, app.user_fp.clone(), &signer(), &mut transaction).await?);
+        transaction.rollback().await?;
+        assert!(queries::find_outbox_events_by_asset_id(&asset.id, &app.db_pool).await?.is_empty());
+
+        // an update over the attribute limit is rolled back with its event
+        app.repository().create_asset(&asset, &app.user_fp, &signer()).await?;
+        let mut update = UpdateAssetRequest::new(None, None, None, None, None, None);
+        update.set_attributes = (0..=MAX_ASSET_ATTRIBUTES)
+            .map(|i| AssetAttribute { key: format!("key-{}", i), value: AttributeValue::Bool(true) })
+            .collect();
+        assert!(queries::update_asset(&asset.id, &updated_by(), &update, &app.db_pool).await.is_err());
+        let events = queries::find_outbox_events_by_asset_id(&asset.id, &app.db_pool).await?;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, EventType::AssetCreated);
+
+        // deleting an asset that does not exist records nothing
+        assert!(!queries::delete_asset_by_id("unknown-asset", &app.db_pool).await?);
+        assert!(queries::find_outbox_events_by_asset_id("unknown-asset", &app.db_pool).await?.is_empty());
+
+        Ok::<(), TestError>(())
+    }).await;
+}
+
+#[tokio::test]
+async fn test_pending_events_are_dispatched_once() {
+    run_test_async(|app| async move {
+        for _ in 0..3 {
+            let asset = create_asset(app.user_fp.clone())?;
+            app.repository().create_asset(&asset, &app.user_fp, &signer()).await?;
+        }
+
+        let now = Utc::now();
+        let claimed_until = now + chrono::Duration::minutes(1);
+        let claimed = queries::claim_pending_outbox_events(2, now, claimed_until, &app.db_pool).await?;
+        assert_eq!(claimed.len(), 2);
+        assert!(claimed[0].sequence < claimed[1].sequence);
+        // claimed events are skipped until their claim ends
+        let last = queries::claim_pending_outbox_events(10, now, claimed_until, &app.db_pool).await?;
+        assert_eq!(last.len(), 1);
+        assert!(last[0].sequence > claimed[1].sequence);
+        assert!(queries::claim_pending_outbox_events(10, now, claimed_until, &app.db_pool).await?.is_empty());
+
+        let sequences: Vec<i64> = claimed.iter().map(|event| event.sequence).collect();
+        assert_eq!(queries::mark_outbox_events_dispatched(&sequences, Utc::now(), &app.db_pool).await?, 2);
+        assert_eq!(queries::mark_outbox_events_dispatched(&sequences, Utc::now(), &app.db_pool).await?, 0);
+        // dispatched events are neither released nor claimed again once their claim ends
+        assert_eq!(queries::release_outbox_events(&sequences, &app.db_pool).await?, 0);
+        let reclaimed = queries::claim_pending_outbox_events(10, claimed_until, claimed_until, &app.db_pool).await?;
+        assert_eq!(reclaimed.iter().map(|event| event.sequence).collect::<Vec<_>>(), vec![last[0].sequence]);
+
+        // released events are claimed at once
+        assert_eq!(queries::release_outbox_events(&[last[0].sequence], &app.db_pool).await?, 1);
+        let released = queries::claim_pending_outbox_events(10, now, claimed_until, &app.db_pool).await?;
+        assert_eq!(released.iter().map(|event| event.sequence).collect::<Vec<_>>(), vec![last[0].sequence]);
+
+        Ok::<(), TestError>(())
+    }).await;
+}
+
+#[tokio::test]
+async fn test_events_after_a_sequence_are_those_of_the_filter() {
+    run_test_async(|app| async move {
+        assert_eq!(queries::find_last_outbox_sequence(&app.db_pool).await?, 0);
+        let first = create_asset(app.user_fp.clone())?;
+        app.repository().create_asset(&first, &app.user_fp, &signer()).await?;
+        let second = create_asset(app.user_fp.clone())?;
+        app.repository().create_asset(&second, &app.user_fp, &signer()).await?;
+        let (new_org, new_owner) = (create_org_id(), create_asset_owner());
+        let mut transaction = UnitOfWork::begin(&app.db_pool).await?;
+        queries::transfer_asset_query(&new_org, &first.id, &new_owner, None, &signer(), &mut transaction).await?;
+        transaction.commit().await?;
+
+        let events = events_after(&EventFilter::AssetId(first.id.clone()), 0, 10, &app.db_pool).await?;
+        let last_sequence = queries::find_last_outbox_sequence(&app.db_pool).await?;
+        assert_eq!(events.iter().map(|event| event.event_type).collect::<Vec<_>>(), vec![EventType::AssetCreated, EventType::AssetTransferred]);
+        assert_eq!(events[1].sequence, last_sequence);
+
+        // resuming after the creation only sends the transfer
+        let events = events_after(&EventFilter::AssetId(first.id.clone()), events[0].sequence, 10, &app.db_pool).await?;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, EventType::AssetTransferred);
+
+        // the transfer is addressed to the new owner only
+        let events = events_after(&EventFilter::OwnerFp(app.user_fp.clone()), 0, 10, &app.db_pool).await?;
+        assert_eq!(events.iter().map(|event| event.asset_id.as_str()).collect::<Vec<_>>(), vec![first.id.as_str(), second.id.as_str()]);
+        let events = events_after(&EventFilter::OwnerFp(new_owner), 0, 10, &app.db_pool).await?;
+        assert_eq!(events.len(), 1);
+        let events = events_after(&EventFilter::Organization(new_org), 0, 10, &app.db_pool).await?;
+        assert_eq!(events.len(), 1);
+        let events = events_after(&EventFilter::Organization(second.organization.clone()), 0, 10, &app.db_pool).await?;
+        assert_eq!(events.iter().map(|event| event.asset_id.as_str()).collect::<Vec<_>>(), vec![second.id.as_str()]);
+
+        let events = events_after(&EventFilter::OwnerFp(app.user_fp.clone()), 0, 1, &app.db_pool).await?;
+        assert_eq!(events[0].asset_id, first.id);
+        assert!(events_after(&EventFilter::AssetId(first.id), last_sequence, 10, &app.db_pool).await?.is_empty());
+
+        Ok::<(), TestError>(())
+    }).await;
+}
+
+#[tokio::test]
+async fn test_events_of_a_transaction_that_commits_later_are_not_skipped() {
+    run_test_async(|app| async move {
+        let owner_fp = create_asset_owner();
+        let filter = EventFilter::OwnerFp(owner_fp.clone());
+        let event = |asset_id: &str| OutboxEvent::asset_deleted(asset_id, &create_org_id(), &owner_fp);
+
+        // the transaction that starts first records its event last and commits first
+        let mut first = UnitOfWork::begin(&app.db_pool).await?;
+        sqlx::query("SELECT pg_current_xact_id()").execute(&mut *first).await?;
+        let mut second = UnitOfWork::begin(&app.db_pool).await?;
+        let early_sequence = queries::create_outbox_event(&mut second, &event("early-asset")).await?;
+        let late_sequence = queries::create_outbox_event(&mut first, &event("late-asset")).await?;
+        assert!(early_sequence < late_sequence);
+        first.commit().await?;
+        let events = events_after(&filter, 0, 10, &app.db_pool).await?;
+        assert_eq!(events.iter().map(|event| event.sequence).collect::<Vec<_>>(), vec![late_sequence]);
+
+        // resuming after the late sequence still reads the event of the transaction that committed afterwards
+        second.commit().await?;
+        let events = events_after(&filter, late_sequence, 10, &app.db_pool).await?;
+        assert_eq!(events.iter().map(|event| event.sequence).collect::<Vec<_>>(), vec![early_sequence]);
+        assert!(events_after(&filter, early_sequence, 10, &app.db_pool).await?.is_empty());
+
+        // the events of a transaction are held back while an older transaction is running
+        let mut older = UnitOfWork::begin(&app.db_pool).await?;
+        sqlx::query("SELECT pg_current_xact_id()").execute(&mut *older).await?;
+        let mut younger = UnitOfWork::begin(&app.db_pool).await?;
+        let sequence = queries::create_outbox_event(&mut younger, &event("held-back-asset")).await?;
+        younger.commit().await?;
+        assert!(queries::has_outbox_events_held_back(&filter, early_sequence, &app.db_pool).await?);
+        assert!(queries::find_outbox_events_after(&filter, early_sequence, 10, &app.db_pool).await?.is_empty());
+        older.rollback().await?;
+        let events = events_after(&filter, early_sequence, 10, &app.db_pool).await?;
+        assert_eq!(events.iter().map(|event| event.sequence).collect::<Vec<_>>(), vec![sequence]);
+
+        Ok::<(), TestError>(())
+    }).await;
+}
+
+#[tokio::test]
+async fn test_recorded_events_are_notified_once_committed() {
+    run_test_async(|app| async move {
+        let mut listener = PgListener::connect_with(&app.db_pool).await?;
+        listener.listen(OUTBOX_CHANNEL).await?;
+
+        let asset = create_asset(app.user_fp.clone())?;
+        let mut transaction = UnitOfWork::begin(&app.db_pool).await?;
+        assert!(queries::create_new_asset(&asset, app.user_fp.clone(), &signer(), &mut transaction).await?);
+        transaction.rollback().await?;
+        assert!(tokio::time::timeout(Duration::from_millis(200), listener.recv()).await.is_err(), "a rolled back event was notified");
+
+        app.repository().create_asset(&asset, &app.user_fp, &signer()).await?;
+        let notification = tokio::time::timeout(Duration::from_secs(5), listener.recv()).await??;
+        let events = queries::find_outbox_events_by_asset_id(&asset.id, &app.db_pool).await?;
+        assert_eq!(notification.payload(), events[0].sequence.to_string());
+
+        Ok::<(), TestError>(())
+    }).await;
+}
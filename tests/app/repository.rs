@@ -0,0 +1,229 @@
+use crate::queries::suit::{run_test_async, TestError};
+use crate::seed::{create_asset, create_asset_owner, create_org_id, signer, test_contract, usd};
+use uuid::Uuid;
+use xrf1::core::queries::{AssetSort, OrderType};
+use xrf1::core::repository::{AssetRepository, ContractRepository, InMemoryRepository, NfcRepository};
+use xrf1::core::{AssetAttribute, AssetCursor, AssetFilter, AssetSortField, AttributeValue, Contract, DatabaseError,
+                 OwnershipClaim, TransferReason, UpdateAssetRequest, UpdateContractRequest, MAX_ASSET_ATTRIBUTES};
+
+/// `updated_by` of updates, fingerprints are at least 50 characters
+fn editor() -> String {
+    format!("{}{}", Uuid::new_v4(), Uuid::new_v4())
+}
+
+/// Every scenario runs against Postgres and against memory, both must behave the same
+macro_rules! both_repositories {
+    ($pg_test:ident, $memory_test:ident, $scenario:ident) => {
+        #[tokio::test]
+        async fn $pg_test() {
+            run_test_async(|app| async move { $scenario(&app.repository()).await }).await
+        }
+
+        #[tokio::test]
+        async fn $memory_test() {
+            $scenario(&InMemoryRepository::default()).await.expect("scenario failed in memory");
+        }
+    };
+}
+
+both_repositories!(test_pg_asset_and_nfc_lifecycle, test_memory_asset_and_nfc_lifecycle, asset_and_nfc_lifecycle);
+both_repositories!(test_pg_list_assets_pages, test_memory_list_assets_pages, list_assets_pages);
+both_repositories!(test_pg_failed_changes_roll_back, test_memory_failed_changes_roll_back, failed_changes_roll_back);
+both_repositories!(test_pg_contract_revisions, test_memory_contract_revisions, contract_revisions);
+both_repositories!(test_pg_transfer_asset, test_memory_transfer_asset, transfer_asset);
+
+async fn asset_and_nfc_lifecycle<R>(repository: &R) -> Result<(), TestError>
+where
+    R: AssetRepository + ContractRepository + NfcRepository,
+{
+    let owner = create_asset_owner();
+    let asset = create_asset(owner.clone())?;
+    assert!(repository.create_asset(&asset, &owner, &signer()).await?);
+    let result = repository.create_asset(&asset, &owner, &signer()).await;
+    assert!(matches!(result, Err(DatabaseError::UniqueViolation)));
+
+    let saved = repository.find_asset_by_id(&asset.id).await?;
+    assert_eq!(saved.name, asset.name);
+    assert_eq!(saved.created_at.timestamp_micros(), asset.created_at.timestamp_micros());
+    assert!(!saved.tradable);
+    let result = repository.find_asset_by_id_and_org_id(&asset.id, &create_org_id()).await;
+    assert!(matches!(result, Err(DatabaseError::NotFound)));
+
+    let nfc = repository.get_nfc_by_asset_id(&asset.id).await?;
+    assert_eq!(repository.get_nfc_by_id(&nfc.id).await?.asset_id, asset.id);
+    assert!(signer().verify(&OwnershipClaim::for_nfc(&nfc, &owner), &nfc.cert));
+    let trails = repository.get_nfc_trails_by_nfc_id(&nfc.id).await?;
+    assert_eq!(trails.len(), 1);
+    assert_eq!(trails[0].reason, TransferReason::Mint);
+    assert!(trails[0].previous_hash.is_none());
+    let mut transaction = repository.begin().await?;
+    assert!(repository.update_nfc_cert(&nfc.id, "re-signed", &mut transaction).await?);
+    assert!(!repository.update_nfc_cert("unknown", "re-signed", &mut transaction).await?);
+    repository.commit(transaction).await?;
+    assert_eq!(repository.get_nfc_by_id(&nfc.id).await?.cert, "re-signed");
+
+    repository.create_contract(test_contract(&asset)?).await?;
+    assert!(repository.delete_asset_by_id(&asset.id).await?);
+    assert!(!repository.delete_asset_by_id(&asset.id).await?);
+    assert!(matches!(repository.find_asset_by_id(&asset.id).await, Err(DatabaseError::NotFound)));
+    assert!(matches!(repository.get_nfc_by_id(&nfc.id).await, Err(DatabaseError::NotFound)));
+    assert!(repository.get_nfc_trails_by_asset_id(&asset.id).await?.is_empty());
+    assert!(matches!(repository.find_contract_by_asset_id(&asset.id).await, Err(DatabaseError::NotFound)));
+    Ok(())
+}
+
+async fn list_assets_pages<R: AssetRepository>(repository: &R) -> Result<(), TestError> {
+    let owner = create_asset_owner();
+    let organization = create_org_id();
+    for name in ["Comet", "aurora", "Borealis"] {
+        let asset = xrf1::core::Asset::new(name.to_string(), "XRF-NS".to_string(), owner.clone(), "".to_string(), organization.clone())?;
+        repository.create_asset(&asset, &owner, &signer()).await?;
+    }
+    let filter = AssetFilter { organization: Some(organization.clone()), ..Default::default() };
+    let sort = AssetSort::new(AssetSortField::Name, OrderType::Asc);
+
+    let first = repository.list_assets(&filter, sort, None, 2).await?;
+    let names: Vec<&str> = first.iter().map(|asset| asset.name.as_str()).collect();
+    assert_eq!(names, vec!["Borealis", "Comet"]);
+    let cursor = AssetCursor::after(&first[1], AssetSortField::Name);
+    let second = repository.list_assets(&filter, sort, Some(&cursor), 2).await?;
+    let names: Vec<&str> = second.iter().map(|asset| asset.name.as_str()).collect();
+    assert_eq!(names, vec!["aurora"]);
+    assert_eq!(repository.count_assets(&filter).await?, 3);
+
+    let by_symbol = AssetSort::new(AssetSortField::Symbol, OrderType::Asc);
+    let result = repository.list_assets(&filter, by_symbol, Some(&cursor), 2).await;
+    assert!(matches!(result, Err(DatabaseError::InvalidArgument(_))));
+    let result = repository.list_assets(&filter, sort, None, 0).await;
+    assert!(matches!(result, Err(DatabaseError::InvalidArgument(_))));
+
+    let newest_first = AssetSort::new(AssetSortField::CreatedAt, OrderType::Desc);
+    let newest = repository.list_assets(&filter, newest_first, None, 1).await?;
+    assert_eq!(newest[0].name, "Borealis");
+    let cursor = AssetCursor::after(&newest[0], AssetSortField::CreatedAt);
+    let older = repository.list_assets(&filter, newest_first, Some(&cursor), 10).await?;
+    assert_eq!(older.len(), 2);
+
+    let like = AssetFilter { name_contains: Some("OREAL".to_string()), ..filter.clone() };
+    let names: Vec<String> = repository.list_assets(&like, sort, None, 10).await?.into_iter().map(|asset| asset.name).collect();
+    assert_eq!(names, vec!["Borealis".to_string()]);
+    assert_eq!(repository.count_assets(&like).await?, 1);
+    Ok(())
+}
+
+async fn failed_changes_roll_back<R>(repository: &R) -> Result<(), TestError>
+where
+    R: AssetRepository + ContractRepository,
+{
+    let owner = create_asset_owner();
+    let asset = create_asset(owner.clone())?;
+    repository.create_asset(&asset, &owner, &signer()).await?;
+
+    // too many attributes fail the update after the name was changed, nothing of it is kept
+    let too_many = UpdateAssetRequest {
+        set_attributes: (0..=MAX_ASSET_ATTRIBUTES)
+            .map(|i| AssetAttribute::new(&format!("key-{}", i), AttributeValue::Number(i as f64)))
+            .collect::<Result<_, _>>()?,
+        tags: Some(vec!["rare".to_string()]),
+        ..UpdateAssetRequest::new(Some("Renamed".to_string()), None, None, None, None, None)
+    };
+    let result = repository.update_asset(&asset.id, &editor(), &too_many).await;
+    assert!(matches!(result, Err(DatabaseError::InvalidArgument(_))));
+    assert_eq!(repository.find_asset_by_id(&asset.id).await?.name, asset.name);
+    assert!(repository.find_asset_metadata(std::slice::from_ref(&asset.id)).await?.is_empty());
+
+    let tagged = UpdateAssetRequest {
+        tags: Some(vec!["rare".to_string()]),
+        set_attributes: vec![AssetAttribute::new("year", AttributeValue::Number(1889.0))?],
+        ..UpdateAssetRequest::new(Some("Renamed".to_string()), None, None, None, None, None)
+    };
+    assert!(repository.update_asset(&asset.id, &editor(), &tagged).await?);
+    assert!(!repository.update_asset("unknown", &editor(), &tagged).await?);
+    let metadata = repository.find_asset_metadata(std::slice::from_ref(&asset.id)).await?.remove(&asset.id).unwrap_or_default();
+    assert_eq!(metadata.tags, vec!["rare"]);
+    let filter = AssetFilter { tags: vec!["rare".to_string()], attributes: tagged.set_attributes.clone(), ..Default::default() };
+    assert_eq!(repository.count_assets(&filter).await?, 1);
+
+    let result = repository.create_contract(test_contract(&create_asset(owner.clone())?)?).await;
+    assert!(matches!(result, Err(DatabaseError::ForeignKeyViolation)));
+    repository.create_contract(test_contract(&asset)?).await?;
+    let result = repository.create_contract(test_contract(&asset)?).await;
+    assert!(matches!(result, Err(DatabaseError::RecordExists(_))));
+    Ok(())
+}
+
+async fn contract_revisions<R>(repository: &R) -> Result<(), TestError>
+where
+    R: AssetRepository + ContractRepository,
+{
+    let owner = create_asset_owner();
+    let asset = create_asset(owner.clone())?;
+    repository.create_asset(&asset, &owner, &signer()).await?;
+    repository.create_contract(test_contract(&asset)?).await?;
+
+    let contract = repository.find_contract_by_asset_id(&asset.id).await?;
+    let update = UpdateContractRequest::new(None, None, Some(usd("25.00")), None, None, None, None);
+    let updated = contract.apply_update(&update, &owner)?;
+    let mut transaction = repository.begin().await?;
+    assert!(repository.update_contract(&contract, &updated, &mut transaction).await?);
+    repository.commit(transaction).await?;
+
+    // the revision read before the update is already in the history
+    let stale = contract.apply_update(&update, &owner)?;
+    let mut transaction = repository.begin().await?;
+    let result = repository.update_contract(&contract, &stale, &mut transaction).await;
+    assert!(matches!(result, Err(DatabaseError::UniqueViolation)));
+    drop(transaction);
+    // a revision that was never saved does not replace the current one
+    let unsaved = Contract { update_count: updated.update_count + 1, ..updated.clone() };
+    let next = unsaved.apply_update(&update, &owner)?;
+    let mut transaction = repository.begin().await?;
+    assert!(!repository.update_contract(&unsaved, &next, &mut transaction).await?);
+    drop(transaction);
+
+    let current = repository.find_contract_by_asset_id(&asset.id).await?;
+    assert_eq!(current.update_count, updated.update_count);
+    assert_eq!(current.min_price, usd("25.00"));
+    let history = repository.find_contract_history(&asset.id, 10, 0).await?;
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].min_price, usd("20.00"));
+    let first = repository.find_contract_at_version(&asset.id, contract.update_count).await?;
+    assert_eq!(first.min_price, usd("20.00"));
+    let result = repository.find_contract_at_version(&asset.id, updated.update_count + 1).await;
+    assert!(matches!(result, Err(DatabaseError::NotFound)));
+    Ok(())
+}
+
+async fn transfer_asset<R>(repository: &R) -> Result<(), TestError>
+where
+    R: AssetRepository + ContractRepository + NfcRepository,
+{
+    let owner = create_asset_owner();
+    let asset = create_asset(owner.clone())?;
+    repository.create_asset(&asset, &owner, &signer()).await?;
+    let (new_owner, new_org) = (create_asset_owner(), create_org_id());
+    let mut transaction = repository.begin().await?;
+    let result = repository.transfer_asset("missing-asset", &new_org, &new_owner, &signer(), &mut transaction).await;
+    assert!(result.is_err());
+    drop(transaction);
+
+    let mut transaction = repository.begin().await?;
+    repository.transfer_asset(&asset.id, &new_org, &new_owner, &signer(), &mut transaction).await?;
+    // the transfer is not seen before it commits
+    drop(transaction);
+    assert_eq!(repository.find_asset_by_id(&asset.id).await?.owner_fp, owner);
+    let mut transaction = repository.begin().await?;
+    let nfc = repository.transfer_asset(&asset.id, &new_org, &new_owner, &signer(), &mut transaction).await?;
+    repository.commit(transaction).await?;
+    assert!(signer().verify(&OwnershipClaim::for_nfc(&nfc, &new_owner), &nfc.cert));
+    let transferred = repository.find_asset_by_id_and_org_id(&asset.id, &new_org).await?;
+    assert_eq!(transferred.owner_fp, new_owner);
+
+    let trails = repository.get_nfc_trails_by_asset_id(&asset.id).await?;
+    assert_eq!(trails.len(), 2);
+    assert_eq!(trails[1].reason, TransferReason::Transfer);
+    assert_eq!(trails[1].previous_owner_fp.as_deref(), Some(owner.as_str()));
+    assert_eq!(trails[1].previous_hash.as_deref(), Some(trails[0].hash.as_str()));
+    assert_eq!(trails[1].hash, trails[1].compute_hash());
+    Ok(())
+}
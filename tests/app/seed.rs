@@ -1,6 +1,11 @@
+use chrono::Duration;
 use sqlx::PgPool;
+use std::collections::HashSet;
+use std::sync::{Arc, OnceLock};
 use uuid::Uuid;
-use xrf1::core::{queries, Asset, DomainError};
+use xrf1::core::repository::{AssetRepository, PgRepository};
+use xrf1::core::{orchestrator, queries, Asset, Auction, Bid, CertificateSigner, Contract, Currency, CurrencyInfo, DomainError, EscrowHold, FxPolicy,
+                 Money, Sale, UpdateAssetRequest};
 
 pub async fn create_and_save_contract(
     user_fp: String,
@@ -8,7 +13,7 @@ pub async fn create_and_save_contract(
 ) -> Result<Asset, Box<dyn std::error::Error>> {
     let asset = create_asset(user_fp.clone())?;
 
-    queries::create_new_asset(&asset, user_fp, &pg).await?;
+    PgRepository::new(Arc::new(pg.clone())).create_asset(&asset, &user_fp, &signer()).await?;
 
     Ok(asset)
 }
@@ -23,10 +28,101 @@ pub fn create_asset(owner_fp: String) -> Result<Asset, DomainError> {
     Asset::new(asset_name, symbol, owner_fp, description, org_id)
 }
 
+/// Key signing the certificates of every test, generated once per test run
+pub fn signer() -> CertificateSigner {
+    static SIGNER: OnceLock<CertificateSigner> = OnceLock::new();
+    SIGNER.get_or_init(|| CertificateSigner::generate().expect("Failed to generate signing key")).clone()
+}
+
 pub fn create_asset_owner() -> String {
     Uuid::new_v4().to_string().to_string()
 }
 
 pub fn create_org_id() -> String {
     Uuid::new_v4().to_string().to_string()
-}
\ No newline at end of file
+}
+
+/// Creates an asset that accepts bids: it is tradable and has a contract accepting USD with a min price of 20.0
+pub async fn create_tradable_asset_with_contract(
+    user_fp: String,
+    pg: &PgPool,
+) -> Result<Asset, Box<dyn std::error::Error>> {
+    let asset = save_tradable_asset(user_fp, pg).await?;
+    queries::create_contract(pg, test_contract(&asset)?).await?;
+
+    Ok(asset)
+}
+
+/// Creates a tradable asset whose contract sells it through the given auction
+pub async fn create_auctioned_asset(
+    user_fp: String,
+    auction: Auction,
+    pg: &PgPool,
+) -> Result<(Asset, Contract), Box<dyn std::error::Error>> {
+    let asset = save_tradable_asset(user_fp, pg).await?;
+    let contract = test_contract(&asset)?.with_auction(auction)?;
+    queries::create_contract(pg, contract.clone()).await?;
+
+    Ok((asset, contract))
+}
+
+async fn save_tradable_asset(user_fp: String, pg: &PgPool) -> Result<Asset, Box<dyn std::error::Error>> {
+    let mut asset = create_and_save_contract(user_fp, pg).await?;
+
+    let tradable = UpdateAssetRequest::new(None, None, Some(true), None, None, None);
+    let updated_by = format!("{}{}", Uuid::new_v4(), Uuid::new_v4());
+    queries::update_asset(&asset.id, &updated_by, &tradable, pg).await?;
+    asset.tradable = true;
+
+    Ok(asset)
+}
+
+/// A currency with the decimals it is seeded with in the `currency` table
+pub fn currency_info(code: Currency, decimals: u32) -> CurrencyInfo {
+    CurrencyInfo { name: code.to_string(), aliases: vec![], decimals, crypto: code.is_crypto(), enabled: true, code }
+}
+
+/// Parses a USD amount such as "20.00"
+pub fn usd(amount: &str) -> Money {
+    Money::parse(amount, &currency_info(Currency::USD, 2)).expect("invalid USD amount")
+}
+
+/// Rates are fresh for an hour, or five minutes when a crypto currency is involved
+pub fn fx_policy() -> FxPolicy {
+    FxPolicy::new(Duration::hours(1), Duration::minutes(5))
+}
+
+/// Buyers of accepted bids have an hour to pay
+pub fn hold_ttl() -> Duration {
+    Duration::hours(1)
+}
+
+/// Funds the hold as its buyer and transfers the asset to them, which completes the sale
+pub async fn settle_escrow_hold(hold: &EscrowHold, asset: &Asset, pg: &PgPool) -> Result<Sale, Box<dyn std::error::Error>> {
+    orchestrator::fund_escrow_hold(&hold.id, &hold.buyer_fp, pg).await?;
+    let repository = PgRepository::new(Arc::new(pg.clone()));
+    orchestrator::transfer_asset(&asset.organization, &asset.id, &hold.buyer_org, &hold.buyer_fp, &signer(), &repository).await?;
+    Ok(queries::find_sale_by_bid_id(&hold.bid_id, pg).await?)
+}
+
+pub fn create_bid(asset: &Asset, bidder_fp: String, amount: &str) -> Result<Bid, DomainError> {
+    // bids are only accepted on tradable assets, new assets are not tradable by default
+    let mut tradable_asset = asset.clone();
+    tradable_asset.tradable = true;
+    let contract = test_contract(asset)?;
+
+    Bid::new(&tradable_asset, &contract, bidder_fp, create_org_id(), usd(amount), false, None, None)
+}
+
+pub fn test_contract(asset: &Asset) -> Result<Contract, DomainError> {
+    Contract::new(
+        asset.id.clone(),
+        "details".to_string(),
+        "summary".to_string(),
+        asset.owner_fp.clone(),
+        usd("20.00"),
+        false,
+        vec![],
+        HashSet::from([Currency::USD]),
+    )
+}